@@ -67,9 +67,50 @@ pub fn bench_generative1(c: &mut Criterion) {
     group.finish();
 }
 
+pub fn bench_math(c: &mut Criterion) {
+    let graph = GraphBuilder::new();
+
+    let out1 = graph.add_audio_output();
+
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency").connect(440.0);
+    let saw = graph.add(SawOscillator::default());
+    saw.input("frequency").connect(110.0);
+
+    // a long chain of mixing and scaling, as in a large additive patch
+    let mut mix = sine.clone();
+    for i in 0..64 {
+        mix = if i % 2 == 0 {
+            &mix + &saw
+        } else {
+            &mix * &sine
+        };
+    }
+    let mix = mix.smooth(0.5);
+    mix.output(0).connect(&out1.input(0));
+
+    let mut runtime = graph.build_runtime();
+
+    let mut group = c.benchmark_group(name("math"));
+
+    for &block_size in BLOCK_SIZES {
+        runtime.allocate_for_block_size(SAMPLE_RATE, block_size);
+
+        group.throughput(criterion::Throughput::Elements(block_size as u64));
+        group.bench_function(format!("block_size_{}", block_size), |b| {
+            b.iter(|| {
+                runtime.process().unwrap();
+            });
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     // bench_demo,
-    bench_generative1
+    bench_generative1,
+    bench_math
 );
 criterion_main!(benches);
@@ -0,0 +1,33 @@
+use raug::prelude::*;
+
+fn main() {
+    env_logger::init();
+
+    let graph = GraphBuilder::new();
+
+    let in1 = graph.add_audio_input();
+
+    let out1 = graph.add_audio_output();
+    let out2 = graph.add_audio_output();
+
+    // pass the input through to both outputs at half volume
+    let mix = in1 * 0.5;
+
+    mix.output(0).connect(&out1.input(0));
+    mix.output(0).connect(&out2.input(0));
+
+    let mut runtime = graph.build_runtime();
+
+    let handle = runtime
+        .run_with_input(
+            AudioBackend::Default,
+            AudioDevice::Default,
+            AudioDevice::Default,
+            None,
+        )
+        .unwrap();
+
+    std::io::stdin().read_line(&mut String::new()).unwrap();
+
+    handle.stop();
+}
@@ -16,7 +16,7 @@ fn main() {
     let fft = graph.add(FftGraph::new(512, 64, WindowFunction::Hann).build(|fft| {
         let sine_input = fft.add_audio_input();
         let noise_input = fft.add_audio_input();
-        let saw_input = fft.add_audio_input();
+        let _saw_input = fft.add_audio_input();
         let output = fft.add_audio_output();
 
         let convolved = sine_input * noise_input;
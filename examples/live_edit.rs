@@ -0,0 +1,36 @@
+use raug::prelude::*;
+
+fn main() {
+    env_logger::init();
+
+    let graph = GraphBuilder::new();
+
+    let out1 = graph.add_audio_output();
+    let out2 = graph.add_audio_output();
+
+    let sine = graph.add(SineOscillator::new(440.0));
+    let sine = sine * 0.2;
+
+    sine.output(0).connect(&out1.input(0));
+    sine.output(0).connect(&out2.input(0));
+
+    let mut runtime = graph.build_runtime();
+
+    let handle = runtime
+        .run(AudioBackend::Default, AudioDevice::Default, None)
+        .unwrap();
+
+    std::thread::sleep(Duration::from_secs(1));
+
+    // replace the sine oscillator with a saw oscillator while the graph is playing
+    handle.edit(|graph| {
+        let saw = graph.add_processor(BlSawOscillator::default());
+        let freq = graph.add_processor(Constant::new(220.0 as Float));
+        graph.connect(freq, 0, saw, 0).unwrap();
+        graph.connect(saw, 0, sine.id(), 0).unwrap();
+    });
+
+    std::thread::sleep(Duration::from_secs(1));
+
+    handle.stop();
+}
@@ -18,10 +18,11 @@ fn main() {
 
     let graph = graph.build();
 
-    let ser = serde_json::to_string(&graph).unwrap();
+    let ser = graph.to_json().unwrap();
     println!("{}", ser);
 
-    let graph = serde_json::from_str(&ser).unwrap();
+    graph.save("target/serde.ron").unwrap();
+    let graph = Graph::load("target/serde.ron").unwrap();
     let mut runtime = Runtime::new(graph);
 
     runtime
@@ -0,0 +1,40 @@
+use raug::prelude::*;
+
+fn main() {
+    env_logger::init();
+
+    let graph = GraphBuilder::new();
+
+    let out1 = graph.add_audio_output();
+    let out2 = graph.add_audio_output();
+
+    // a quiet sine buried in noise
+    let sine = graph.add(SineOscillator::new(440.0));
+    let noise = graph.add(NoiseOscillator::new());
+    let noise = (noise * 2.0 - 1.0) * 0.5;
+    let mix = sine * 0.25 + noise;
+
+    let analyze = graph.add(FftAnalyze::new(2048, 512, WindowFunction::Hann));
+    let gate = graph.add(SpectralGate::new(0.05));
+    let synthesize = graph.add(IfftSynthesize::new(2048, 512, WindowFunction::Hann));
+
+    mix.output(0).connect(&analyze.input(0));
+    analyze.output(0).connect(&gate.input(0));
+    gate.output(0).connect(&synthesize.input(0));
+
+    let master = synthesize.output(0);
+
+    out1.input(0).connect(&master);
+    out2.input(0).connect(&master);
+
+    let mut runtime = graph.build_runtime();
+
+    runtime
+        .run_offline_to_file(
+            "target/spectral_gate.wav",
+            Duration::from_secs(5),
+            48000.0,
+            512,
+        )
+        .unwrap();
+}
@@ -0,0 +1,52 @@
+use raug::prelude::*;
+
+// A simple voice module: a sine oscillator with a gain control.
+fn voice() -> SubGraph {
+    SubGraph::build(|graph| {
+        let frequency = graph.add_named_audio_input("frequency");
+        let gain = graph.add_named_audio_input("gain");
+        let out = graph.add_named_audio_output("out");
+
+        let sine = graph.add(SineOscillator::default());
+        sine.input("frequency").connect(frequency.output(0));
+
+        let sine = sine * gain;
+        sine.output(0).connect(&out.input(0));
+    })
+}
+
+fn main() {
+    env_logger::init();
+
+    // sub-graphs can contain other sub-graphs
+    let chord = SubGraph::build(|graph| {
+        let root = graph.add_named_audio_input("root");
+        let out = graph.add_named_audio_output("out");
+
+        let mut mix = graph.constant(0.0);
+        for ratio in [1.0, 1.25, 1.5] {
+            let voice = graph.add(voice());
+            voice.input("frequency").connect(root.clone() * ratio);
+            voice.input("gain").connect(0.1);
+            mix = mix + voice;
+        }
+        mix.output(0).connect(&out.input(0));
+    });
+
+    let graph = GraphBuilder::new();
+
+    let out1 = graph.add_audio_output();
+    let out2 = graph.add_audio_output();
+
+    let chord = graph.add(chord);
+    chord.input("root").connect(220.0);
+
+    chord.output("out").connect(&out1.input(0));
+    chord.output("out").connect(&out2.input(0));
+
+    let mut runtime = graph.build_runtime();
+
+    runtime
+        .run_offline_to_file("target/subgraph.wav", Duration::from_secs(5), 44_100.0, 512)
+        .unwrap();
+}
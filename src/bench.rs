@@ -0,0 +1,149 @@
+//! Tools for measuring how fast a graph processes, for benchmarks and performance regression tests.
+//!
+//! [`measure`] runs a graph offline for a number of blocks and returns a [`BenchReport`] with its throughput.
+//! With the `profiling` feature, the report also breaks the processing time down per node,
+//! and counts the allocations made while processing using the `allocation-counter` crate.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::{graph::Graph, runtime::Runtime, signal::Float};
+
+#[cfg(feature = "profiling")]
+use crate::graph::profile::ProfileReport;
+
+/// The results of running a graph with [`measure`].
+#[derive(Clone, Debug)]
+pub struct BenchReport {
+    /// The number of blocks processed.
+    pub blocks: usize,
+    /// The number of samples in each block.
+    pub block_size: usize,
+    /// The sample rate the graph was processed at.
+    pub sample_rate: Float,
+    /// The total time spent processing.
+    pub elapsed: Duration,
+    /// The longest time spent processing a single block.
+    pub worst_block: Duration,
+    /// The number of allocations made while processing, or `None` without the `profiling` feature.
+    pub allocations: Option<u64>,
+    /// The number of bytes allocated while processing, or `None` without the `profiling` feature.
+    pub allocated_bytes: Option<u64>,
+    /// The processing time of each node, slowest first.
+    #[cfg(feature = "profiling")]
+    pub nodes: ProfileReport,
+}
+
+impl BenchReport {
+    /// Returns the duration of the audio that was processed.
+    pub fn audio_duration(&self) -> Duration {
+        Duration::from_secs_f64((self.blocks * self.block_size) as f64 / self.sample_rate as f64)
+    }
+
+    /// Returns how many times faster than real time the graph was processed.
+    pub fn realtime_factor(&self) -> f64 {
+        self.audio_duration().as_secs_f64() / self.elapsed.as_secs_f64()
+    }
+
+    /// Returns the average time spent processing a block.
+    pub fn average_block(&self) -> Duration {
+        self.elapsed / self.blocks.max(1) as u32
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} blocks of {} samples at {} Hz in {:.2} ms ({:.1}x realtime)",
+            self.blocks,
+            self.block_size,
+            self.sample_rate,
+            self.elapsed.as_secs_f64() * 1e3,
+            self.realtime_factor(),
+        )?;
+        writeln!(
+            f,
+            "block avg: {:.2} us, worst: {:.2} us",
+            self.average_block().as_secs_f64() * 1e6,
+            self.worst_block.as_secs_f64() * 1e6,
+        )?;
+        match (self.allocations, self.allocated_bytes) {
+            (Some(allocations), Some(bytes)) => {
+                write!(f, "allocations: {allocations} ({bytes} bytes)")?
+            }
+            _ => write!(f, "allocations: not counted")?,
+        }
+        #[cfg(feature = "profiling")]
+        write!(f, "\n{}", self.nodes)?;
+        Ok(())
+    }
+}
+
+/// Processes the given graph for the given number of blocks of 512 samples at 48 kHz, and reports how long it took.
+///
+/// See [`measure_with`] for details.
+pub fn measure(graph: &Graph, blocks: usize) -> BenchReport {
+    measure_with(graph, blocks, 48_000.0, 512)
+}
+
+/// Processes the given graph for the given number of blocks at the given sample rate and block size, and reports how long it took.
+///
+/// The graph is processed offline, as fast as possible, with its audio inputs silent and its outputs discarded.
+/// One block is processed before measuring, so allocations made while setting up aren't counted.
+/// Only allocations made on the calling thread are counted, so with the `parallel` feature, allocations made by processors running on the thread pool are missed.
+///
+/// # Panics
+///
+/// Panics if processing the graph fails.
+pub fn measure_with(
+    graph: &Graph,
+    blocks: usize,
+    sample_rate: Float,
+    block_size: usize,
+) -> BenchReport {
+    let mut runtime = Runtime::new(graph.clone());
+    runtime.allocate_for_block_size(sample_rate, block_size);
+    runtime.process().expect("failed to process graph");
+
+    #[cfg(feature = "profiling")]
+    for node in runtime.graph_mut().digraph_mut().node_weights_mut() {
+        *node.profile_mut() = Default::default();
+    }
+
+    #[cfg(feature = "profiling")]
+    let (allocations, (elapsed, worst_block)) = {
+        let mut timings = Default::default();
+        let info = allocation_counter::measure(|| timings = process_blocks(&mut runtime, blocks));
+        (Some((info.count_total, info.bytes_total)), timings)
+    };
+    #[cfg(not(feature = "profiling"))]
+    let (allocations, (elapsed, worst_block)): (Option<(u64, u64)>, _) =
+        (None, process_blocks(&mut runtime, blocks));
+
+    BenchReport {
+        blocks,
+        block_size,
+        sample_rate,
+        elapsed,
+        worst_block,
+        allocations: allocations.map(|(count, _)| count),
+        allocated_bytes: allocations.map(|(_, bytes)| bytes),
+        #[cfg(feature = "profiling")]
+        nodes: runtime.graph().profile_report(),
+    }
+}
+
+// processes the given number of blocks, returning the total time taken and the longest time taken by a single block
+fn process_blocks(runtime: &mut Runtime, blocks: usize) -> (Duration, Duration) {
+    let mut worst_block = Duration::ZERO;
+    let start = Instant::now();
+    for _ in 0..blocks {
+        let block_start = Instant::now();
+        runtime.process().expect("failed to process graph");
+        worst_block = worst_block.max(block_start.elapsed());
+    }
+    (start.elapsed(), worst_block)
+}
@@ -0,0 +1,179 @@
+//! Integration with the [Bevy](https://bevyengine.org) game engine.
+//!
+//! Add a [`RaugPlugin`] to an app to start playing a graph when the app starts. The plugin inserts:
+//!
+//! - A [`RaugRuntime`] resource, which owns the [`RuntimeHandle`] of the running graph.
+//! - A [`RaugParams`] resource, which holds the [`Param`]s of the graph by name.
+//!
+//! Entities with an [`AudioParam`] component send their value to a parameter whenever it changes,
+//! and [`PlayOneShot`] events trigger boolean parameters, e.g. to play a sound effect.
+
+use std::sync::Mutex;
+
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+
+use crate::{
+    graph::Graph,
+    prelude::Param,
+    runtime::{AudioBackend, AudioDevice, Command, MidiPort, Runtime, RuntimeHandle},
+    signal::AnySignal,
+};
+
+/// A Bevy plugin that plays a graph while the app is running.
+///
+/// If the graph fails to start, the error is logged and the resources are not inserted.
+pub struct RaugPlugin {
+    // `Graph` isn't `Sync`, but plugins must be
+    graph: Mutex<Graph>,
+    backend: AudioBackend,
+    device: AudioDevice,
+    midi_port: Option<MidiPort>,
+}
+
+impl RaugPlugin {
+    /// Creates a new [`RaugPlugin`] that plays the given graph on the default device of the default backend.
+    pub fn new(graph: Graph) -> Self {
+        Self {
+            graph: Mutex::new(graph),
+            backend: AudioBackend::Default,
+            device: AudioDevice::Default,
+            midi_port: None,
+        }
+    }
+
+    /// Sets the audio backend to play the graph with.
+    pub fn with_backend(mut self, backend: AudioBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Sets the output device to play the graph on.
+    pub fn with_device(mut self, device: AudioDevice) -> Self {
+        self.device = device;
+        self
+    }
+
+    /// Sets the MIDI port to feed the graph's MIDI inputs from.
+    pub fn with_midi_port(mut self, midi_port: MidiPort) -> Self {
+        self.midi_port = Some(midi_port);
+        self
+    }
+}
+
+impl Plugin for RaugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayOneShot>()
+            .add_systems(Update, (send_audio_params, play_one_shots));
+
+        let graph = self.graph.lock().unwrap().clone();
+        let params = graph
+            .param_iter()
+            .map(|(name, param)| (name.to_string(), param.clone()))
+            .collect();
+
+        let mut runtime = Runtime::new(graph);
+        match runtime.run(
+            self.backend.clone(),
+            self.device.clone(),
+            self.midi_port.clone(),
+        ) {
+            Ok(handle) => {
+                app.insert_resource(RaugRuntime(handle))
+                    .insert_resource(RaugParams(params));
+            }
+            Err(err) => log::error!("Failed to start audio graph: {}", err),
+        }
+    }
+}
+
+/// A resource that owns the [`RuntimeHandle`] of the graph played by a [`RaugPlugin`].
+///
+/// The graph stops when this resource is removed or the app exits.
+#[derive(Resource)]
+pub struct RaugRuntime(pub RuntimeHandle);
+
+impl std::ops::Deref for RaugRuntime {
+    type Target = RuntimeHandle;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A resource that holds the [`Param`]s of the graph played by a [`RaugPlugin`], by name.
+#[derive(Resource, Default)]
+pub struct RaugParams(rustc_hash::FxHashMap<String, Param>);
+
+impl RaugParams {
+    /// Returns the parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&Param> {
+        self.0.get(name)
+    }
+
+    /// Sends a value to the parameter with the given name. Returns `false` if there is no such parameter.
+    pub fn send(&self, name: &str, value: impl crate::signal::Signal) -> bool {
+        let Some(param) = self.get(name) else {
+            return false;
+        };
+        param.send(value);
+        true
+    }
+
+    /// Returns an iterator over the names and parameters.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Param)> + '_ {
+        self.0.iter().map(|(name, param)| (name.as_str(), param))
+    }
+}
+
+/// A component that sends its value to the named [`Param`] whenever the value changes, e.g. to tie a sound's volume to an entity's distance from the camera.
+#[derive(Component, Debug, Clone)]
+pub struct AudioParam {
+    /// The name of the parameter.
+    pub name: String,
+    /// The value to send to the parameter.
+    pub value: AnySignal,
+}
+
+impl AudioParam {
+    /// Creates a new [`AudioParam`] for the parameter with the given name.
+    pub fn new(name: impl Into<String>, value: impl crate::signal::Signal) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into_any_signal(),
+        }
+    }
+}
+
+/// An event that triggers the boolean [`Param`] with the given name for one sample, e.g. to start an envelope that plays a one-shot sound.
+///
+/// See [`Command::Trigger`].
+#[derive(Event, Debug, Clone)]
+pub struct PlayOneShot(pub String);
+
+fn send_audio_params(
+    runtime: Option<Res<RaugRuntime>>,
+    params: Query<&AudioParam, Changed<AudioParam>>,
+) {
+    let Some(runtime) = runtime else {
+        return;
+    };
+    for param in &params {
+        let command = Command::SetParam(param.name.clone(), param.value.clone());
+        if let Err(err) = runtime.send(command) {
+            log::warn!("Failed to set parameter {}: {}", param.name, err);
+        }
+    }
+}
+
+fn play_one_shots(runtime: Option<Res<RaugRuntime>>, mut events: EventReader<PlayOneShot>) {
+    let Some(runtime) = runtime else {
+        events.clear();
+        return;
+    };
+    for PlayOneShot(name) in events.read() {
+        if let Err(err) = runtime.send(Command::Trigger(name.clone())) {
+            log::warn!("Failed to play one-shot {}: {}", name, err);
+        }
+    }
+}
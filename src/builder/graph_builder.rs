@@ -6,12 +6,13 @@ use std::{
 };
 
 use crate::{
-    graph::{asset::Asset, Graph},
-    prelude::{Param, Processor},
+    graph::{asset::Asset, node::ProcessorNode, Graph, GraphConstructionResult, NodeIndex},
+    prelude::{Feedback, Param, Processor, TapReader},
     runtime::Runtime,
+    transport::Transport,
 };
 
-use super::node_builder::{IntoInputIdx, IntoNode, IntoOutputIdx, Node};
+use super::node_builder::{IntoInputIdx, IntoNode, IntoOutput, IntoOutputIdx, Node};
 
 /// A builder for constructing audio graphs.
 #[derive(Clone, Default)]
@@ -41,6 +42,27 @@ impl GraphBuilder {
         })
     }
 
+    /// Returns a handle to the graph's [`Transport`].
+    pub fn transport(&self) -> Transport {
+        self.with_graph(|graph| graph.transport().clone())
+    }
+
+    /// Adds an audio input node with the given name to the graph.
+    pub fn add_named_audio_input(&self, name: impl Into<String>) -> Node {
+        self.with_graph_mut(|graph| Node {
+            graph: self.clone(),
+            node_id: graph.add_named_audio_input(name),
+        })
+    }
+
+    /// Adds an audio output node with the given name to the graph.
+    pub fn add_named_audio_output(&self, name: impl Into<String>) -> Node {
+        self.with_graph_mut(|graph| Node {
+            graph: self.clone(),
+            node_id: graph.add_named_audio_output(name),
+        })
+    }
+
     /// Adds a MIDI input node to the graph.
     pub fn add_midi_input(&self, name: impl Into<String>) -> Node {
         self.with_graph_mut(|graph| Node {
@@ -57,6 +79,38 @@ impl GraphBuilder {
         })
     }
 
+    /// Adds the given processors to the graph, connecting the first output of each one to the first input of the next, and returns the last node, or `None` if no processors are given.
+    ///
+    /// The first input of the first processor is left unconnected. See also [`Node::then`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if any processor but the first has no inputs, or any but the last has no outputs.
+    /// - Panics if the signal types of a connected output and input are not compatible.
+    #[track_caller]
+    pub fn chain(&self, processors: impl IntoIterator<Item = Box<dyn Processor>>) -> Option<Node> {
+        self.try_chain(processors)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Adds the given processors to the graph like [`GraphBuilder::chain`], returning an error instead of panicking if they can't be connected.
+    ///
+    /// None of the processors are added if any of them can't be connected.
+    pub fn try_chain(
+        &self,
+        processors: impl IntoIterator<Item = Box<dyn Processor>>,
+    ) -> GraphConstructionResult<Option<Node>> {
+        let nodes = processors
+            .into_iter()
+            .map(ProcessorNode::new_from_boxed)
+            .collect();
+        let last = self.with_graph_mut(|graph| graph.add_chain(nodes))?;
+        Ok(last.map(|node_id| Node {
+            graph: self.clone(),
+            node_id,
+        }))
+    }
+
     /// Adds an asset to the graph.
     pub fn add_asset(&self, name: impl Into<String>, asset: impl Into<Asset>) {
         self.with_graph_mut(|graph| graph.add_asset(name, asset.into()));
@@ -70,6 +124,46 @@ impl GraphBuilder {
         })
     }
 
+    /// Closes a feedback loop through a [`Feedback`] node, which delays the fed back signal by one block.
+    ///
+    /// The closure receives the `Feedback` node, whose output is the fed back signal, and returns the node whose output is fed back.
+    /// The returned node is also returned from this method.
+    ///
+    /// Unlike an implicit feedback loop, the loop is processed block by block rather than one sample at a time.
+    #[track_caller]
+    pub fn feedback(&self, f: impl FnOnce(Node) -> Node) -> Node {
+        let feedback = self.add(Feedback::default());
+        let out = f(feedback.clone());
+        feedback.input(0).connect(&out);
+        out
+    }
+
+    /// Attaches a [`TruePeak`](crate::builtins::TruePeak) meter to each audio output of the graph, returning their readers.
+    ///
+    /// See [`Graph::monitor_outputs`].
+    pub fn monitor_outputs(&self) -> Vec<TapReader> {
+        self.with_graph_mut(|graph| graph.monitor_outputs())
+    }
+
+    /// Removes every node that can't reach an audio output or a tap, returning the removed nodes.
+    ///
+    /// [`Node`]s referring to removed nodes must not be used afterwards. See [`Graph::garbage_collect`].
+    pub fn garbage_collect(&self) -> Vec<(NodeIndex, ProcessorNode)> {
+        self.with_graph_mut(|graph| graph.garbage_collect())
+    }
+
+    /// Simplifies the graph by removing passthroughs and folding constant math, returning the removed nodes.
+    ///
+    /// [`Node`]s referring to removed nodes must not be used afterwards. See [`Graph::optimize`].
+    pub fn optimize(&self) -> Vec<(NodeIndex, ProcessorNode)> {
+        self.with_graph_mut(|graph| graph.optimize())
+    }
+
+    /// Seeds the random number generators of every node in the graph. See [`Graph::set_seed`].
+    pub fn set_seed(&self, seed: u64) {
+        self.with_graph_mut(|graph| graph.set_seed(seed));
+    }
+
     /// Creates a new [`GraphBuilder`] with the given graph as a starting point.
     pub fn from_graph(graph: Graph) -> Self {
         Self {
@@ -123,12 +217,34 @@ impl GraphBuilder {
         to: impl IntoNode,
         to_input: impl IntoInputIdx,
     ) {
+        self.try_connect(from, from_output, to, to_input)
+            .unwrap_or_else(|err| panic!("{err}"));
+    }
+
+    /// Connects the given output of one node to the given input of another node, returning an error instead of panicking if the connection is invalid.
+    #[inline]
+    pub fn try_connect(
+        &self,
+        from: impl IntoNode,
+        from_output: impl IntoOutputIdx,
+        to: impl IntoNode,
+        to_input: impl IntoInputIdx,
+    ) -> GraphConstructionResult<()> {
         let from = from.into_node(self);
         let to = to.into_node(self);
-        let from_output = from_output.into_output_idx(&from);
-        let to_input = to_input.into_input_idx(&to);
+        let from_output = from_output.try_into_output_idx(&from)?;
+        let to_input = to_input.try_into_input_idx(&to)?;
         self.with_graph_mut(|graph| graph.connect(from.id(), from_output, to.id(), to_input))
-            .unwrap();
+    }
+
+    /// Connects the given output to the [`SIDECHAIN`](crate::builtins::dynamics::SIDECHAIN) input of another node.
+    ///
+    /// See [`Node::sidechain`].
+    #[track_caller]
+    #[inline]
+    pub fn connect_sidechain(&self, from: impl IntoOutput, to: impl IntoNode) -> Node {
+        let to = to.into_node(self);
+        to.sidechain(from)
     }
 
     /// Writes a DOT representation of the graph to the given writer.
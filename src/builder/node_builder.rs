@@ -3,7 +3,7 @@
 use petgraph::prelude::*;
 
 use crate::{
-    graph::GraphConstructionResult,
+    graph::{node::ProcessorNode, GraphConstructionError, GraphConstructionResult},
     prelude::*,
     signal::{Signal, SignalType},
 };
@@ -22,6 +22,16 @@ fn assert_signals_compatible(a: &SignalType, b: &SignalType, op: impl Into<Strin
     );
 }
 
+// like `assert_signals_compatible`, but also allows a float to be combined with a frame, applying to every channel
+fn assert_signals_broadcastable(a: &SignalType, b: &SignalType, op: impl Into<String>) {
+    if !matches!(
+        (a, b),
+        (SignalType::Frame, SignalType::Float) | (SignalType::Float, SignalType::Frame)
+    ) {
+        assert_signals_compatible(a, b, op);
+    }
+}
+
 /// Represents a node in the audio graph. This type is used to build connections between nodes.
 #[derive(Clone)]
 pub struct Node {
@@ -30,8 +40,9 @@ pub struct Node {
 }
 
 impl Node {
+    /// Returns the index of the node in the graph.
     #[inline]
-    pub(crate) fn id(&self) -> NodeIndex {
+    pub fn id(&self) -> NodeIndex {
         self.node_id
     }
 
@@ -48,6 +59,29 @@ impl Node {
             .with_graph(|graph| graph.digraph()[self.id()].name().to_string())
     }
 
+    /// Returns the value of the node's metadata entry with the given key, if it exists.
+    ///
+    /// See [`Graph::node_metadata`](crate::graph::Graph::node_metadata).
+    #[inline]
+    pub fn metadata(&self, key: &str) -> Option<String> {
+        self.graph
+            .with_graph(|graph| graph.node_metadata(self.id(), key).map(str::to_string))
+    }
+
+    /// Sets the node's metadata entry with the given key, returning its previous value.
+    #[inline]
+    pub fn set_metadata(&self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.graph
+            .with_graph_mut(|graph| graph.set_node_metadata(self.id(), key, value))
+    }
+
+    /// Removes the node's metadata entry with the given key, returning its value.
+    #[inline]
+    pub fn remove_metadata(&self, key: &str) -> Option<String> {
+        self.graph
+            .with_graph_mut(|graph| graph.remove_node_metadata(self.id(), key))
+    }
+
     /// Asserts that the node has a single output.
     #[inline]
     #[track_caller]
@@ -67,12 +101,10 @@ impl Node {
         if self.num_outputs() == 1 {
             Ok(())
         } else {
-            Err(
-                crate::graph::GraphConstructionError::NodeHasMultipleOutputs {
-                    op: op.into(),
-                    signal_type: self.name(),
-                },
-            )
+            Err(GraphConstructionError::NodeHasMultipleOutputs {
+                op: op.into(),
+                signal_type: self.name(),
+            })
         }
     }
 
@@ -90,6 +122,20 @@ impl Node {
             .with_graph(|graph| graph.digraph()[self.id()].num_outputs())
     }
 
+    // calls the given function with the node's weight, or returns an error if the node isn't in the graph
+    fn try_with_weight<T>(
+        &self,
+        f: impl FnOnce(&ProcessorNode) -> GraphConstructionResult<T>,
+    ) -> GraphConstructionResult<T> {
+        self.graph.with_graph(|graph| {
+            let weight = graph
+                .digraph()
+                .node_weight(self.id())
+                .ok_or(GraphConstructionError::NodeNotFound(self.id()))?;
+            f(weight)
+        })
+    }
+
     /// Returns the input of the node at the given index.
     ///
     /// # Panics
@@ -254,6 +300,84 @@ impl Node {
         self.clone()
     }
 
+    /// Returns the input of the node at the given index or name, or an error if the node has no such input.
+    #[inline]
+    pub fn try_input(&self, index: impl IntoInputIdx) -> GraphConstructionResult<Input> {
+        Ok(Input {
+            node: self.clone(),
+            input_index: index.try_into_input_idx(self)?,
+        })
+    }
+
+    /// Returns the output of the node at the given index or name, or an error if the node has no such output.
+    #[inline]
+    pub fn try_output(&self, index: impl IntoOutputIdx) -> GraphConstructionResult<Output> {
+        Ok(Output {
+            node: self.clone(),
+            output_index: index.try_into_output_idx(self)?,
+        })
+    }
+
+    /// Connects the output of another node to the input of this node, returning an error instead of panicking if the connection is invalid.
+    ///
+    /// See [`Node::connect_input`].
+    #[inline]
+    pub fn try_connect_input(
+        &self,
+        source: impl IntoNode,
+        source_output: impl IntoOutputIdx,
+        target_input: impl IntoInputIdx,
+    ) -> GraphConstructionResult<Node> {
+        let source = source.into_node(&self.graph);
+        self.try_input(target_input)?
+            .try_connect(source.try_output(source_output)?)
+    }
+
+    /// Connects the output of this node to the input of another node, returning an error instead of panicking if the connection is invalid.
+    ///
+    /// See [`Node::connect_output`].
+    #[inline]
+    pub fn try_connect_output(
+        &self,
+        output: impl IntoOutputIdx,
+        target: impl IntoNode,
+        target_input: impl IntoInputIdx,
+    ) -> GraphConstructionResult<Node> {
+        let target = target.into_node(&self.graph);
+        self.try_output(output)?
+            .try_connect(&target.try_input(target_input)?)
+    }
+
+    /// Returns `true` if the node has a [`SIDECHAIN`] input.
+    #[inline]
+    pub fn has_sidechain(&self) -> bool {
+        self.graph.with_graph(|graph| {
+            graph.digraph()[self.id()]
+                .input_spec()
+                .iter()
+                .any(|spec| spec.name == SIDECHAIN)
+        })
+    }
+
+    /// Connects the given output to the [`SIDECHAIN`] input of this node, so that the node detects the level of `source` instead of its own input.
+    ///
+    /// The source can be any output in the graph, including an audio input or the sum of several nodes, and it keeps feeding any other inputs it is already connected to.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the node does not have a [`SIDECHAIN`] input.
+    /// - Panics if the source signal type does not match the sidechain input's signal type.
+    #[inline]
+    #[track_caller]
+    pub fn sidechain(&self, source: impl IntoOutput) -> Node {
+        assert!(
+            self.has_sidechain(),
+            "sidechain: node {} has no {SIDECHAIN} input",
+            self.name()
+        );
+        self.input(SIDECHAIN).connect(source)
+    }
+
     /// Connects a [`Smooth`] processor to the output of this node.
     ///
     /// The `factor` parameter controls the smoothing factor, where a value of 0.0 means maximum smoothing and 1.0 means no smoothing.
@@ -336,6 +460,29 @@ impl Node {
         self.output(0).len()
     }
 
+    /// Adds the given processor to the graph and connects the first output of this node to its first input, returning the new node.
+    ///
+    /// This makes it easy to build chains of effects, e.g. `osc.then(filter).then(reverb)`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if this node has no outputs, or the processor has no inputs.
+    /// - Panics if the signal types of the output and input are not compatible.
+    #[inline]
+    #[track_caller]
+    pub fn then(&self, processor: impl Processor) -> Node {
+        self.try_then(processor)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Adds the given processor to the graph and connects the first output of this node to its first input, returning an error instead of panicking if they can't be connected.
+    ///
+    /// The processor isn't added if it can't be connected.
+    #[inline]
+    pub fn try_then(&self, processor: impl Processor) -> GraphConstructionResult<Node> {
+        self.try_output(0)?.try_pipe(processor)
+    }
+
     /// Connects a [`Cast`] processor to the output of this node.
     ///
     /// The `signal_type` parameter specifies the type to cast the signal to.
@@ -404,6 +551,21 @@ impl Node {
         self.output(0).finite_or_zero()
     }
 
+    /// Connects a [`DcBlock`] processor to the output of this node.
+    ///
+    /// Useful right before the audio outputs of patches that may produce a DC offset, such as those with feedback or waveshaping.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the node has multiple outputs.
+    /// - Panics if the output signal is not a float.
+    #[inline]
+    #[track_caller]
+    pub fn dc_block(&self) -> Node {
+        self.assert_single_output("dc_block");
+        self.output(0).dc_block()
+    }
+
     /// Connects a [`IsSome`] processor to the output of this node.
     ///
     /// # Panics
@@ -439,6 +601,19 @@ impl Node {
         self.assert_single_output("or_else");
         self.output(0).or_else(default)
     }
+
+    /// Connects a [`Filter`] processor to the output of this node, passing it through only while `pass` is `true`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the node has multiple outputs.
+    /// - Panics if `pass` is not a `Bool` signal.
+    #[inline]
+    #[track_caller]
+    pub fn filter(&self, pass: impl IntoOutput) -> Node {
+        self.assert_single_output("filter");
+        self.output(0).filter(pass)
+    }
 }
 
 /// Represents an input of a [`Node`].
@@ -476,6 +651,50 @@ impl Input {
         self.node.clone()
     }
 
+    /// Connects the input to the output of another node, returning an error if the signal types are incompatible.
+    #[inline]
+    pub fn try_connect(&self, output: impl IntoOutput) -> GraphConstructionResult<Node> {
+        let output = output.into_output(self.node.graph());
+        self.node.graph().with_graph_mut(|graph| {
+            graph.connect(
+                output.node.id(),
+                output.output_index,
+                self.node.id(),
+                self.input_index,
+            )
+        })?;
+        Ok(self.node.clone())
+    }
+
+    /// Connects the input to the output of another node, inserting a [`Cast`] processor in between if their signal types differ.
+    ///
+    /// For example, a `Bool` output connected to a `Float` input reads as `0.0` or `1.0`, and a `Float` output connected to a `Bool` input is `true` when nonzero.
+    /// See [`AnySignal::cast`] for the supported conversions.
+    ///
+    /// The `Float` to `Bool` cast has no threshold: any nonzero sample, however small, and `NaN` read as `true`.
+    /// To gate on an audio signal, which is rarely exactly zero, compare it against a threshold with [`Output::gt`] instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output signal cannot be cast to the input signal type.
+    #[inline]
+    #[track_caller]
+    pub fn connect_cast(&self, output: impl IntoOutput) -> Node {
+        let output = output.into_output(self.node.graph());
+        let (source_type, target_type) = (output.signal_type(), self.signal_type());
+        assert!(
+            source_type.can_cast_to(&target_type),
+            "connect_cast: cannot cast {:?} to {:?}",
+            source_type,
+            target_type
+        );
+        if source_type.is_compatible_with(&target_type) {
+            self.connect(output)
+        } else {
+            self.connect(output.cast(target_type))
+        }
+    }
+
     /// Creates a [`Param`] processor and connects it to the input.
     ///
     /// This can be used to create a parameter that can be controlled externally.
@@ -531,6 +750,59 @@ impl Output {
         self.node.clone()
     }
 
+    /// Connects the output to the input of another node, returning an error if the signal types are incompatible.
+    #[inline]
+    pub fn try_connect(&self, input: &Input) -> GraphConstructionResult<Node> {
+        input.try_connect(self)?;
+        Ok(self.node.clone())
+    }
+
+    /// Connects the output to the input of another node, inserting a [`Cast`] processor in between if their signal types differ.
+    ///
+    /// See [`Input::connect_cast`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output signal cannot be cast to the input signal type.
+    #[inline]
+    #[track_caller]
+    pub fn connect_cast(&self, input: &Input) -> Node {
+        input.connect_cast(self);
+        self.node.clone()
+    }
+
+    /// Adds the given processor to the graph and connects this output to its first input, returning the new node.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the processor has no inputs.
+    /// - Panics if the signal types of the output and input are not compatible.
+    #[inline]
+    #[track_caller]
+    pub fn pipe(&self, processor: impl Processor) -> Node {
+        self.try_pipe(processor)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Adds the given processor to the graph and connects this output to its first input, returning an error instead of panicking if they can't be connected.
+    ///
+    /// The processor isn't added if it can't be connected.
+    #[inline]
+    pub fn try_pipe(&self, processor: impl Processor) -> GraphConstructionResult<Node> {
+        let graph = self.node.graph();
+        let node_id = graph.with_graph_mut(|graph| {
+            graph.add_connected(
+                self.node.id(),
+                self.output_index,
+                ProcessorNode::new(processor),
+            )
+        })?;
+        Ok(Node {
+            graph: graph.clone(),
+            node_id,
+        })
+    }
+
     /// Creates a [`Cast`] processor and connects it to the output.
     ///
     /// The `signal_type` parameter specifies the type to cast the signal to.
@@ -631,7 +903,7 @@ impl Output {
     #[inline]
     pub fn len(&self) -> Node {
         assert!(
-            matches!(self.signal_type(), SignalType::List { .. }),
+            matches!(self.signal_type(), SignalType::List),
             "output signal must be a list"
         );
         let proc = self.node.graph().add(Len);
@@ -704,6 +976,23 @@ impl Output {
         proc
     }
 
+    /// Creates a [`DcBlock`] processor and connects it to the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output signal is not a float.
+    #[inline]
+    #[track_caller]
+    pub fn dc_block(&self) -> Node {
+        assert!(
+            matches!(self.signal_type(), SignalType::Float),
+            "output signal must be a float"
+        );
+        let proc = self.node.graph().add(DcBlock::default());
+        proc.input(0).connect(self);
+        proc
+    }
+
     /// Creates a [`IsSome`] processor and connects it to the output.
     #[inline]
     pub fn is_some(&self) -> Node {
@@ -727,6 +1016,20 @@ impl Output {
         proc.input(0).connect(self);
         proc
     }
+
+    /// Creates a [`Filter`] processor and connects it to the output, passing it through only while `pass` is `true`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pass` is not a `Bool` signal.
+    #[inline]
+    #[track_caller]
+    pub fn filter(&self, pass: impl IntoOutput) -> Node {
+        let proc = self.node.graph().add(Filter::new(self.signal_type()));
+        proc.input(0).connect(self);
+        proc.input(1).connect(pass);
+        proc
+    }
 }
 
 mod sealed {
@@ -857,63 +1160,100 @@ impl IntoNode for &str {
 /// A trait for coercing a value into an output index of a node.
 pub trait IntoOutputIdx: sealed::Sealed {
     /// Converts the value into an output index of the given node.
-    fn into_output_idx(self, node: &Node) -> u32;
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has no such output.
+    #[track_caller]
+    fn into_output_idx(self, node: &Node) -> u32
+    where
+        Self: Sized,
+    {
+        self.try_into_output_idx(node)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Converts the value into an output index of the given node, returning an error if the node has no such output.
+    fn try_into_output_idx(self, node: &Node) -> GraphConstructionResult<u32>;
 }
 
 /// A trait for coercing a value into an input index of a node.
 pub trait IntoInputIdx: sealed::Sealed {
     /// Converts the value into an input index of the given node.
-    fn into_input_idx(self, node: &Node) -> u32;
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has no such input.
+    #[track_caller]
+    fn into_input_idx(self, node: &Node) -> u32
+    where
+        Self: Sized,
+    {
+        self.try_into_input_idx(node)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Converts the value into an input index of the given node, returning an error if the node has no such input.
+    fn try_into_input_idx(self, node: &Node) -> GraphConstructionResult<u32>;
 }
 
 impl IntoOutputIdx for u32 {
     #[inline]
-    fn into_output_idx(self, node: &Node) -> u32 {
-        assert!(
-            self < node.num_outputs() as u32,
-            "output index out of bounds"
-        );
-        self
+    fn try_into_output_idx(self, node: &Node) -> GraphConstructionResult<u32> {
+        node.try_with_weight(|weight| {
+            if self < weight.num_outputs() as u32 {
+                Ok(self)
+            } else {
+                Err(GraphConstructionError::OutputNotFound {
+                    node: weight.name().to_string(),
+                    output: self.to_string(),
+                })
+            }
+        })
     }
 }
 
 impl IntoInputIdx for u32 {
     #[inline]
-    fn into_input_idx(self, node: &Node) -> u32 {
-        assert!(self < node.num_inputs() as u32, "input index out of bounds");
-        self
+    fn try_into_input_idx(self, node: &Node) -> GraphConstructionResult<u32> {
+        node.try_with_weight(|weight| {
+            if self < weight.num_inputs() as u32 {
+                Ok(self)
+            } else {
+                Err(GraphConstructionError::InputNotFound {
+                    node: weight.name().to_string(),
+                    input: self.to_string(),
+                })
+            }
+        })
     }
 }
 
 impl IntoInputIdx for &str {
-    #[track_caller]
     #[inline]
-    fn into_input_idx(self, node: &Node) -> u32 {
-        let Some(idx) = node.graph().with_graph(|graph| {
-            graph.digraph()[node.id()]
-                .input_spec()
-                .iter()
-                .position(|s| s.name == self)
-        }) else {
-            panic!("no input with name {self}")
-        };
-        idx as u32
+    fn try_into_input_idx(self, node: &Node) -> GraphConstructionResult<u32> {
+        node.try_with_weight(|weight| {
+            let idx = weight.input_spec().iter().position(|s| s.name == self);
+            idx.map(|idx| idx as u32)
+                .ok_or_else(|| GraphConstructionError::InputNotFound {
+                    node: weight.name().to_string(),
+                    input: self.to_string(),
+                })
+        })
     }
 }
 
 impl IntoOutputIdx for &str {
-    #[track_caller]
     #[inline]
-    fn into_output_idx(self, node: &Node) -> u32 {
-        let Some(idx) = node.graph().with_graph(|graph| {
-            graph.digraph()[node.id()]
-                .output_spec()
-                .iter()
-                .position(|s| s.name == self)
-        }) else {
-            panic!("no output with name {self}")
-        };
-        idx as u32
+    fn try_into_output_idx(self, node: &Node) -> GraphConstructionResult<u32> {
+        node.try_with_weight(|weight| {
+            let idx = weight.output_spec().iter().position(|s| s.name == self);
+            idx.map(|idx| idx as u32)
+                .ok_or_else(|| GraphConstructionError::OutputNotFound {
+                    node: weight.name().to_string(),
+                    output: self.to_string(),
+                })
+        })
     }
 }
 
@@ -925,14 +1265,16 @@ macro_rules! impl_binary_node_ops {
             pub fn $name(&self, other: impl IntoOutput) -> Node {
                 let other = other.into_output(self.node().graph());
 
-                assert_signals_compatible(
+                assert_signals_broadcastable(
                     &self.signal_type(),
                     &other.signal_type(),
                     stringify!($name),
                 );
 
-                let signal_type = self.signal_type();
-                let node = self.node().graph().add(<math::$proc>::new(signal_type));
+                let node = self.node().graph().add(<math::$proc>::with_types(
+                    self.signal_type(),
+                    other.signal_type(),
+                ));
 
                 node.input(0).connect(self);
                 node.input(1).connect(&other);
@@ -957,14 +1299,16 @@ macro_rules! impl_binary_node_ops {
             pub fn $name(&self, other: impl IntoOutput) -> Node {
                 let other = other.into_output(self.node().graph());
 
-                assert_signals_compatible(
+                assert_signals_broadcastable(
                     &self.signal_type(),
                     &other.signal_type(),
                     stringify!($name),
                 );
 
-                let signal_type = self.signal_type();
-                let node = self.node().graph().add(<math::$proc>::new(signal_type));
+                let node = self.node().graph().add(<math::$proc>::with_types(
+                    self.signal_type(),
+                    other.signal_type(),
+                ));
 
                 node.input(0).connect(self);
                 node.input(1).connect(&other);
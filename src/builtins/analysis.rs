@@ -0,0 +1,775 @@
+//! Processors that analyze signals, such as level meters and pitch detectors.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use raug_macros::iter_proc_io_as;
+
+use crate::prelude::*;
+
+use super::dynamics::time_to_coeff;
+
+/// A handle for reading the latest level measured by an [`Rms`], [`Peak`], or [`Crest`] processor from another thread.
+///
+/// The level is updated once per block. Reading it never blocks the audio thread.
+#[derive(Clone, Debug, Default)]
+pub struct LevelHandle {
+    bits: Arc<AtomicU64>,
+}
+
+impl LevelHandle {
+    /// Returns the latest level.
+    pub fn get(&self) -> Float {
+        f64::from_bits(self.bits.load(Ordering::Relaxed)) as Float
+    }
+
+    fn set(&self, value: Float) {
+        self.bits.store((value as f64).to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// A processor that measures the RMS (root mean square) amplitude of its input, averaged over a time window.
+///
+/// The latest level can also be read from other threads through [`Rms::handle`].
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `window` | `Float` | The averaging time in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The RMS amplitude. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rms {
+    mean_square: Float,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    handle: LevelHandle,
+
+    /// The averaging time in seconds.
+    pub window: Float,
+}
+
+impl Rms {
+    /// Creates a new `Rms` processor with the given averaging time in seconds.
+    pub fn new(window: Float) -> Self {
+        Self {
+            mean_square: 0.0,
+            handle: LevelHandle::default(),
+            window,
+        }
+    }
+
+    /// Returns a handle for reading the latest level from another thread.
+    ///
+    /// Clones of this processor share the same handle.
+    pub fn handle(&self) -> LevelHandle {
+        self.handle.clone()
+    }
+}
+
+impl Default for Rms {
+    fn default() -> Self {
+        Self::new(0.3)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Rms {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("window", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn is_tap(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let mut coeff = time_to_coeff(self.window, sample_rate);
+
+        for (in_signal, window, out) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(window) = window {
+                if *window != self.window {
+                    self.window = *window;
+                    coeff = time_to_coeff(self.window, sample_rate);
+                }
+            }
+            let x = in_signal.unwrap_or(0.0);
+
+            self.mean_square = x * x + coeff * (self.mean_square - x * x);
+
+            *out = Some(self.mean_square.sqrt());
+        }
+
+        self.handle.set(self.mean_square.sqrt());
+
+        Ok(())
+    }
+}
+
+/// A processor that measures the peak amplitude of its input, holding each peak and letting it fall back over the release time.
+///
+/// The latest level can also be read from other threads through [`Peak::handle`].
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `release` | `Float` | The release time in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The peak amplitude. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Peak {
+    peak: Float,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    handle: LevelHandle,
+
+    /// The release time in seconds.
+    pub release: Float,
+}
+
+impl Peak {
+    /// Creates a new `Peak` processor with the given release time in seconds.
+    pub fn new(release: Float) -> Self {
+        Self {
+            peak: 0.0,
+            handle: LevelHandle::default(),
+            release,
+        }
+    }
+
+    /// Returns a handle for reading the latest level from another thread.
+    ///
+    /// Clones of this processor share the same handle.
+    pub fn handle(&self) -> LevelHandle {
+        self.handle.clone()
+    }
+}
+
+impl Default for Peak {
+    fn default() -> Self {
+        Self::new(0.3)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Peak {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("release", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn is_tap(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let mut coeff = time_to_coeff(self.release, sample_rate);
+
+        for (in_signal, release, out) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(release) = release {
+                if *release != self.release {
+                    self.release = *release;
+                    coeff = time_to_coeff(self.release, sample_rate);
+                }
+            }
+            let x = in_signal.unwrap_or(0.0).abs();
+
+            self.peak = x.max(self.peak * coeff);
+
+            *out = Some(self.peak);
+        }
+
+        self.handle.set(self.peak);
+
+        Ok(())
+    }
+}
+
+/// A processor that measures the crest factor of its input: the ratio of its peak amplitude to its RMS amplitude.
+///
+/// Both levels are measured over the same time window, as by [`Peak`] and [`Rms`].
+/// A sine wave has a crest factor of about `1.41`, while heavily compressed signals approach `1.0` and percussive ones are much higher.
+/// Silence has a crest factor of `0.0`.
+///
+/// The latest crest factor can also be read from other threads through [`Crest::handle`].
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `window` | `Float` | The averaging and release time in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The crest factor, as a linear ratio. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Crest {
+    peak: Float,
+    mean_square: Float,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    handle: LevelHandle,
+
+    /// The averaging and release time in seconds.
+    pub window: Float,
+}
+
+impl Crest {
+    /// Creates a new `Crest` processor with the given averaging and release time in seconds.
+    pub fn new(window: Float) -> Self {
+        Self {
+            peak: 0.0,
+            mean_square: 0.0,
+            handle: LevelHandle::default(),
+            window,
+        }
+    }
+
+    /// Returns a handle for reading the latest crest factor from another thread.
+    ///
+    /// Clones of this processor share the same handle.
+    pub fn handle(&self) -> LevelHandle {
+        self.handle.clone()
+    }
+
+    fn crest(&self) -> Float {
+        let rms = self.mean_square.sqrt();
+        if rms > 0.0 {
+            self.peak / rms
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Default for Crest {
+    fn default() -> Self {
+        Self::new(0.3)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Crest {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("window", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn is_tap(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let mut coeff = time_to_coeff(self.window, sample_rate);
+
+        for (in_signal, window, out) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(window) = window {
+                if *window != self.window {
+                    self.window = *window;
+                    coeff = time_to_coeff(self.window, sample_rate);
+                }
+            }
+            let x = in_signal.unwrap_or(0.0);
+
+            self.peak = x.abs().max(self.peak * coeff);
+            self.mean_square = x * x + coeff * (self.mean_square - x * x);
+
+            *out = Some(self.crest());
+        }
+
+        self.handle.set(self.crest());
+
+        Ok(())
+    }
+}
+
+/// A processor that estimates the fundamental frequency of a monophonic signal, using the YIN algorithm.
+///
+/// Every `hop` samples, the last two periods of the lowest detectable frequency are searched for the shortest period that repeats well enough, and the estimate is held until the next analysis.
+/// Each analysis takes time proportional to the square of the longest period, so a lower `min_freq` is more expensive.
+///
+/// The confidence is how closely the signal repeats at the detected period, from `0.0` (not at all) to `1.0` (exactly).
+/// When no period repeats closely enough (as set by the threshold), such as for noise or silence, the frequency output is `None`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `threshold` | `Float` | How much the signal may differ from itself over one period for it to be detected, typically `0.1` to `0.2`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `freq` | `Float` | The estimated frequency in Hz, or `None` if no pitch was detected. |
+/// | `1` | `confidence` | `Float` | How confident the estimate is. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitchDetect {
+    // the range of periods searched, in samples at the current sample rate
+    min_period: usize,
+    max_period: usize,
+    // the last `2 * max_period` input samples as a ring buffer, and a copy of them in order for the analysis
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Vec<Float>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    frame: Vec<Float>,
+    // the cumulative mean normalized difference for each period
+    #[cfg_attr(feature = "serde", serde(skip))]
+    difference: Vec<Float>,
+    head: usize,
+    // the number of samples until the next analysis
+    countdown: usize,
+    frequency: Option<Float>,
+    confidence: Float,
+
+    /// The lowest detectable frequency in Hz.
+    pub min_freq: Float,
+
+    /// The highest detectable frequency in Hz.
+    pub max_freq: Float,
+
+    /// How much the signal may differ from itself over one period for it to be detected.
+    pub threshold: Float,
+
+    /// The number of samples between analyses.
+    pub hop: usize,
+}
+
+impl PitchDetect {
+    /// Creates a new `PitchDetect` processor that detects frequencies between the given bounds in Hz.
+    pub fn new(min_freq: Float, max_freq: Float) -> Self {
+        Self {
+            min_period: 0,
+            max_period: 0,
+            history: Vec::new(),
+            frame: Vec::new(),
+            difference: Vec::new(),
+            head: 0,
+            countdown: 0,
+            frequency: None,
+            confidence: 0.0,
+            min_freq,
+            max_freq,
+            threshold: 0.15,
+            hop: 256,
+        }
+    }
+
+    /// Sets how much the signal may differ from itself over one period for it to be detected.
+    pub fn with_threshold(mut self, threshold: Float) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the number of samples between analyses.
+    pub fn with_hop(mut self, hop: usize) -> Self {
+        self.hop = hop.max(1);
+        self
+    }
+
+    /// Returns the most recently estimated frequency in Hz, or `None` if no pitch was detected.
+    pub fn frequency(&self) -> Option<Float> {
+        self.frequency
+    }
+
+    /// Returns the confidence of the most recent estimate.
+    pub fn confidence(&self) -> Float {
+        self.confidence
+    }
+
+    fn analyze(&mut self, sample_rate: Float) {
+        let len = self.max_period * 2;
+        let (older, newer) = self.history[..len].split_at(self.head);
+        self.frame[..newer.len()].copy_from_slice(newer);
+        self.frame[newer.len()..len].copy_from_slice(older);
+
+        // the squared difference between the frame and itself shifted by each period, normalized by the mean difference of the shorter periods
+        let window = self.max_period;
+        let frame = &self.frame[..len];
+        self.difference[0] = 1.0;
+        let mut sum = 0.0;
+        for period in 1..=self.max_period {
+            let difference: Float = frame[..window]
+                .iter()
+                .zip(&frame[period..period + window])
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum();
+            sum += difference;
+            self.difference[period] = if sum > 0.0 {
+                difference * period as Float / sum
+            } else {
+                1.0
+            };
+        }
+
+        let difference = &self.difference[..=self.max_period];
+        let candidate = (self.min_period..=self.max_period)
+            .find(|&period| difference[period] < self.threshold)
+            .map(|mut period| {
+                // move on to the bottom of the dip
+                while period < self.max_period && difference[period + 1] < difference[period] {
+                    period += 1;
+                }
+                period
+            });
+
+        let Some(period) = candidate else {
+            let best = difference[self.min_period..]
+                .iter()
+                .fold(1.0, |best: Float, &d| best.min(d));
+            self.frequency = None;
+            self.confidence = (1.0 - best).clamp(0.0, 1.0);
+            return;
+        };
+
+        // refine the period between samples with a parabola through its neighbors
+        let mut refined = period as Float;
+        if period > 1 && period < self.max_period {
+            let (a, b, c) = (
+                difference[period - 1],
+                difference[period],
+                difference[period + 1],
+            );
+            let curvature = a - 2.0 * b + c;
+            if curvature.abs() > Float::EPSILON {
+                refined += 0.5 * (a - c) / curvature;
+            }
+        }
+
+        self.frequency = Some(sample_rate / refined);
+        self.confidence = (1.0 - difference[period]).clamp(0.0, 1.0);
+    }
+}
+
+impl Default for PitchDetect {
+    fn default() -> Self {
+        Self::new(50.0, 2000.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for PitchDetect {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("threshold", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("freq", SignalType::Float),
+            SignalSpec::new("confidence", SignalType::Float),
+        ]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        let max_period = (sample_rate / self.min_freq).ceil() as usize;
+        self.history = vec![0.0; max_period * 2];
+        self.frame = vec![0.0; max_period * 2];
+        self.difference = vec![0.0; max_period + 1];
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        let max_period =
+            ((sample_rate / self.min_freq).ceil() as usize).min(self.difference.len().max(1) - 1);
+        self.min_period =
+            ((sample_rate / self.max_freq).floor() as usize).clamp(2, max_period.max(2));
+        if max_period != self.max_period {
+            self.max_period = max_period;
+            self.history.fill(0.0);
+            self.head = 0;
+            self.countdown = 0;
+            self.frequency = None;
+            self.confidence = 0.0;
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        for (in_signal, threshold, freq, confidence) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float, Float]
+        ) {
+            self.threshold = threshold.unwrap_or(self.threshold);
+
+            if self.max_period > self.min_period {
+                self.history[self.head] = in_signal.unwrap_or(0.0);
+                self.head = (self.head + 1) % (self.max_period * 2);
+
+                if self.countdown == 0 {
+                    self.countdown = self.hop.max(1);
+                    self.analyze(sample_rate);
+                }
+                self.countdown -= 1;
+            }
+
+            *freq = self.frequency;
+            *confidence = Some(self.confidence);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that measures how often its input crosses zero, averaged over a time window.
+///
+/// The rate is in crossings per second, so a sine wave has a rate of twice its frequency.
+/// Noisy and bright sounds have much higher rates than tonal and dark ones, which makes this a cheap way to tell them apart.
+/// Crossings are counted the same way as by [`ZeroCrossing`].
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `window` | `Float` | The averaging time in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `rate` | `Float` | The zero crossing rate in crossings per second. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZeroCrossingRate {
+    last: Float,
+    // the average number of crossings per sample
+    rate: Float,
+
+    /// The averaging time in seconds.
+    pub window: Float,
+}
+
+impl ZeroCrossingRate {
+    /// Creates a new `ZeroCrossingRate` processor with the given averaging time in seconds.
+    pub fn new(window: Float) -> Self {
+        Self {
+            last: 0.0,
+            rate: 0.0,
+            window,
+        }
+    }
+}
+
+impl Default for ZeroCrossingRate {
+    fn default() -> Self {
+        Self::new(0.05)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for ZeroCrossingRate {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("window", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("rate", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let mut coeff = time_to_coeff(self.window, sample_rate);
+
+        for (in_signal, window, out) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(window) = window {
+                if *window != self.window {
+                    self.window = *window;
+                    coeff = time_to_coeff(self.window, sample_rate);
+                }
+            }
+            let x = in_signal.unwrap_or(0.0);
+
+            let crossed = (self.last < 0.0 && x >= 0.0) || (self.last > 0.0 && x <= 0.0);
+            let crossing = if crossed { 1.0 } else { 0.0 };
+            self.rate = crossing + coeff * (self.rate - crossing);
+            self.last = x;
+
+            *out = Some(self.rate * sample_rate);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that measures the correlation between two channels of a stereo signal, for checking phase and mono compatibility.
+///
+/// The correlation is `1.0` when the channels are identical (mono), around `0.0` when they are unrelated (very wide), and `-1.0` when one is the inverse of the other, which cancels out when summed to mono.
+/// Silence has a correlation of `0.0`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+/// | `2` | `window` | `Float` | The averaging time in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `correlation` | `Float` | The correlation coefficient, from `-1.0` to `1.0`. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Correlation {
+    // the averaged products of the channels
+    left_right: Float,
+    left_left: Float,
+    right_right: Float,
+
+    /// The averaging time in seconds.
+    pub window: Float,
+}
+
+impl Correlation {
+    /// Creates a new `Correlation` processor with the given averaging time in seconds.
+    pub fn new(window: Float) -> Self {
+        Self {
+            left_right: 0.0,
+            left_left: 0.0,
+            right_right: 0.0,
+            window,
+        }
+    }
+
+    /// Returns the most recently measured correlation.
+    pub fn correlation(&self) -> Float {
+        let power = (self.left_left * self.right_right).sqrt();
+        if power > Float::EPSILON {
+            (self.left_right / power).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Default for Correlation {
+    fn default() -> Self {
+        Self::new(0.3)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Correlation {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+            SignalSpec::new("window", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("correlation", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let mut coeff = time_to_coeff(self.window, sample_rate);
+
+        for (left, right, window, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(window) = window {
+                if *window != self.window {
+                    self.window = *window;
+                    coeff = time_to_coeff(self.window, sample_rate);
+                }
+            }
+            let left = left.unwrap_or(0.0);
+            let right = right.unwrap_or(0.0);
+
+            self.left_right = left * right + coeff * (self.left_right - left * right);
+            self.left_left = left * left + coeff * (self.left_left - left * left);
+            self.right_right = right * right + coeff * (self.right_right - right * right);
+
+            *out = Some(self.correlation());
+        }
+
+        Ok(())
+    }
+}
@@ -108,6 +108,8 @@ macro_rules! comparison_op {
             #[doc = concat!("Creates a new `", stringify!($name), "` processor for the given type.")]
             pub fn new(signal_type: SignalType) -> Self {
                 assert!(!matches!(signal_type, SignalType::List { .. }), "List comparison not supported");
+                assert!(!matches!(signal_type, SignalType::Spectrum), "Spectrum comparison not supported");
+                assert!(!matches!(signal_type, SignalType::Frame), "Frame comparison not supported");
                 Self {
                     a: AnySignal::default_of_type(&signal_type),
                     b: AnySignal::default_of_type(&signal_type),
@@ -188,6 +190,12 @@ macro_rules! comparison_op {
                         (AnySignal::List(Some(_)), AnySignal::List(Some(_))) => {
                             unimplemented!("List comparison not supported");
                         }
+                        (AnySignal::Spectrum(Some(_)), AnySignal::Spectrum(Some(_))) => {
+                            unimplemented!("Spectrum comparison not supported");
+                        }
+                        (AnySignal::Frame(Some(_)), AnySignal::Frame(Some(_))) => {
+                            unimplemented!("Frame comparison not supported");
+                        }
                         _ => unreachable!(),
                     }
                 }
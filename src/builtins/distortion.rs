@@ -0,0 +1,273 @@
+//! Distortion processors, such as waveshapers.
+
+use super::{
+    filters::{dot_product, windowed_sinc},
+    iter_list_floats,
+};
+use crate::prelude::*;
+
+// the length of the halfband filters used for oversampling, long enough for about 60 dB of alias rejection
+const HALFBAND_TAPS: usize = 31;
+
+// the longest user curve a waveshaper can hold
+const WAVESHAPER_MAX_TABLE: usize = 4096;
+
+/// The transfer curve of a [`Waveshaper`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShaperCurve {
+    /// Hyperbolic tangent saturation.
+    #[default]
+    Tanh,
+    /// Cubic soft clipping, which is linear near zero and flat beyond `-1.0` and `1.0`.
+    SoftClip,
+    /// Triangle wavefolding, which reflects the signal back whenever it passes `-1.0` or `1.0`.
+    Fold,
+    /// Hard clipping at `-1.0` and `1.0`.
+    HardClip,
+    /// A curve given by the `table` input, whose elements are evenly spaced over inputs from `-1.0` to `1.0` and linearly interpolated.
+    Table,
+}
+
+// a halfband lowpass filter, which passes the lower half of the spectrum for resampling by a factor of 2
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Halfband {
+    // the history is stored twice, so the most recent samples are always contiguous
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Vec<Float>,
+    head: usize,
+}
+
+impl Halfband {
+    fn allocate(&mut self) {
+        self.history = vec![0.0; 2 * HALFBAND_TAPS];
+        self.head = 0;
+    }
+
+    #[inline]
+    fn push(&mut self, x: Float) {
+        self.head = (self.head + 1) % HALFBAND_TAPS;
+        self.history[self.head] = x;
+        self.history[self.head + HALFBAND_TAPS] = x;
+    }
+
+    // the kernel is symmetric, so the order of the history doesn't matter
+    #[inline]
+    fn output(&self, kernel: &[Float]) -> Float {
+        dot_product(
+            &self.history[self.head + 1..self.head + 1 + HALFBAND_TAPS],
+            kernel,
+        )
+    }
+
+    #[inline]
+    fn upsample(&mut self, x: Float, kernel: &[Float]) -> [Float; 2] {
+        // zero-stuffing halves the signal's energy, so make it up with a gain of 2
+        self.push(x);
+        let first = 2.0 * self.output(kernel);
+        self.push(0.0);
+        let second = 2.0 * self.output(kernel);
+        [first, second]
+    }
+
+    #[inline]
+    fn downsample(&mut self, [first, second]: [Float; 2], kernel: &[Float]) -> Float {
+        self.push(first);
+        self.push(second);
+        self.output(kernel)
+    }
+}
+
+/// A waveshaper, which distorts a signal by passing it through a transfer curve.
+///
+/// The signal is multiplied by the `drive` input before it is shaped, so more drive pushes it further into the curve.
+/// Distortion adds harmonics that can alias above the Nyquist frequency, so the waveshaper can oversample the signal by 2x or 4x with halfband filters while shaping it.
+/// Oversampling delays the signal by a few samples, which is reported by [`Processor::latency`].
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `drive` | `Float` | The gain applied to the signal before shaping. |
+/// | `2` | `table` | `List` | The user curve, used by [`ShaperCurve::Table`]. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The shaped signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Waveshaper {
+    curve: ShaperCurve,
+    oversampling: usize,
+    table: Vec<Float>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    kernel: Vec<Float>,
+    // one pair of halfband filters for each doubling of the sample rate
+    upsamplers: Vec<Halfband>,
+    downsamplers: Vec<Halfband>,
+
+    /// The gain applied to the signal before shaping.
+    pub drive: Float,
+}
+
+impl Waveshaper {
+    /// Creates a new `Waveshaper` processor with the given curve, without oversampling.
+    pub fn new(curve: ShaperCurve) -> Self {
+        Self {
+            curve,
+            oversampling: 1,
+            table: Vec::new(),
+            kernel: Vec::new(),
+            upsamplers: Vec::new(),
+            downsamplers: Vec::new(),
+            drive: 1.0,
+        }
+    }
+
+    /// Creates a new `Waveshaper` processor with the given user curve, whose values are evenly spaced over inputs from `-1.0` to `1.0`.
+    pub fn table(table: &[Float]) -> Self {
+        let mut this = Self::new(ShaperCurve::Table);
+        this.table = table[..table.len().min(WAVESHAPER_MAX_TABLE)].to_vec();
+        this
+    }
+
+    /// Sets the oversampling factor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the factor is not 1, 2, or 4.
+    pub fn with_oversampling(mut self, factor: usize) -> Self {
+        assert!(
+            matches!(factor, 1 | 2 | 4),
+            "oversampling factor must be 1, 2, or 4"
+        );
+        let stages = factor.trailing_zeros() as usize;
+        self.oversampling = factor;
+        self.upsamplers = vec![Halfband::default(); stages];
+        self.downsamplers = vec![Halfband::default(); stages];
+        self
+    }
+
+    /// Sets the gain applied to the signal before shaping.
+    pub fn with_drive(mut self, drive: Float) -> Self {
+        self.drive = drive;
+        self
+    }
+
+    /// Returns the transfer curve.
+    pub fn curve(&self) -> ShaperCurve {
+        self.curve
+    }
+
+    /// Returns the oversampling factor.
+    pub fn oversampling(&self) -> usize {
+        self.oversampling
+    }
+
+    #[inline]
+    fn shape(&self, x: Float) -> Float {
+        match self.curve {
+            ShaperCurve::Tanh => x.tanh(),
+            ShaperCurve::SoftClip => {
+                if x.abs() >= 1.0 {
+                    x.signum()
+                } else {
+                    1.5 * (x - x * x * x / 3.0)
+                }
+            }
+            ShaperCurve::Fold => 4.0 * (((x - 1.0) * 0.25).rem_euclid(1.0) - 0.5).abs() - 1.0,
+            ShaperCurve::HardClip => x.clamp(-1.0, 1.0),
+            ShaperCurve::Table => {
+                let len = self.table.len();
+                if len < 2 {
+                    return self.table.first().copied().unwrap_or(x);
+                }
+                let position = (x.clamp(-1.0, 1.0) + 1.0) * 0.5 * (len - 1) as Float;
+                let index = (position.floor() as usize).min(len - 2);
+                let frac = position - index as Float;
+                self.table[index] + (self.table[index + 1] - self.table[index]) * frac
+            }
+        }
+    }
+
+    // shapes one sample at the given oversampling stage, recursing through the faster stages
+    fn shape_oversampled(&mut self, x: Float, stage: usize) -> Float {
+        if stage == self.upsamplers.len() {
+            return self.shape(x);
+        }
+
+        let upsampled = self.upsamplers[stage].upsample(x, &self.kernel);
+        let shaped = upsampled.map(|x| self.shape_oversampled(x, stage + 1));
+        self.downsamplers[stage].downsample(shaped, &self.kernel)
+    }
+}
+
+impl Default for Waveshaper {
+    fn default() -> Self {
+        Self::new(ShaperCurve::default())
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Waveshaper {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("drive", SignalType::Float),
+            SignalSpec::new("table", SignalType::List),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, _sample_rate: Float, _max_block_size: usize) {
+        self.kernel = windowed_sinc(0.25, HALFBAND_TAPS);
+        for halfband in self.upsamplers.iter_mut().chain(&mut self.downsamplers) {
+            halfband.allocate();
+        }
+        self.table
+            .reserve_exact(WAVESHAPER_MAX_TABLE - self.table.len());
+    }
+
+    fn latency(&self) -> usize {
+        // each filter delays the signal by half its length, at twice the sample rate of the stage before it
+        let delay: Float = (0..self.upsamplers.len())
+            .map(|stage| (HALFBAND_TAPS - 1) as Float / (2 << stage) as Float)
+            .sum();
+        delay as usize
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, drive, table, out) in iter_proc_io_as!(
+            inputs as [Float, Float, List],
+            outputs as [Float]
+        ) {
+            self.drive = drive.unwrap_or(self.drive);
+            if let Some(table) = table {
+                self.table.clear();
+                self.table
+                    .extend(iter_list_floats(table).take(WAVESHAPER_MAX_TABLE));
+            }
+
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                continue;
+            };
+
+            *out = Some(self.shape_oversampled(in_signal * self.drive, 0));
+        }
+
+        Ok(())
+    }
+}
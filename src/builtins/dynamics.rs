@@ -1,9 +1,24 @@
 //! Dynamics processors, such as compressors and limiters.
 
+use std::collections::VecDeque;
+
+use smallvec::SmallVec;
+
 use crate::prelude::*;
 
+/// The name of the input that dynamics processors detect the level of, when it should differ from the signal being processed.
+///
+/// By convention, every dynamics processor in this module has an input with this name.
+/// If it is left unconnected (or receives `None`), the processor detects the level of its `in` input instead.
+/// Use [`Node::sidechain`](crate::builder::node_builder::Node::sidechain) to connect a signal to it.
+pub const SIDECHAIN: &str = "sidechain";
+
 /// A simple peak limiter.
 ///
+/// By default, the limiter reacts to peaks as they arrive, so some of each peak's attack passes through above the threshold.
+/// A limiter created with [`PeakLimiter::with_lookahead`] instead delays its signal by the lookahead time (reported by [`Processor::latency`]) and ramps its gain down over that time before each peak arrives.
+/// This makes it a true brick-wall limiter, whose output never exceeds the threshold, without the distortion of clipping; the `attack` input is then unused.
+///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
@@ -12,6 +27,7 @@ use crate::prelude::*;
 /// | `1` | `threshold` | `Float` | The amplitude threshold of the limiter. |
 /// | `2` | `attack` | `Float` | The attack factor of the limiter. |
 /// | `3` | `release` | `Float` | The release factor of the limiter. |
+/// | `4` | `sidechain` | `Float` | The signal to detect the level of, if different from the input signal. |
 ///
 /// # Outputs
 ///
@@ -24,6 +40,23 @@ pub struct PeakLimiter {
     gain: Float,
     envelope: Float,
 
+    // the lookahead time in seconds, and in samples at the current sample rate
+    lookahead: Float,
+    lookahead_samples: usize,
+    // the gain needed over the lookahead window, slowed down by the release
+    recovering_gain: Float,
+    // the delayed input signal, and the smoothed gains being averaged, as ring buffers
+    #[cfg_attr(feature = "serde", serde(skip))]
+    delayed: Vec<Float>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    held_gains: Vec<Float>,
+    held_gains_sum: Float,
+    head: usize,
+    // the gains needed over the lookahead window as (sample, gain) pairs, in increasing order of gain, for a running minimum
+    #[cfg_attr(feature = "serde", serde(skip))]
+    needed_gains: VecDeque<(u64, Float)>,
+    sample: u64,
+
     /// The amplitude threshold of the limiter.
     pub threshold: Float,
 
@@ -44,6 +77,68 @@ impl PeakLimiter {
             ..Default::default()
         }
     }
+
+    /// Sets the lookahead time in seconds, making the limiter a brick-wall limiter that delays its signal by this amount.
+    pub fn with_lookahead(mut self, lookahead: Float) -> Self {
+        self.lookahead = lookahead.max(0.0);
+        self
+    }
+
+    /// Returns the lookahead time in seconds.
+    pub fn lookahead(&self) -> Float {
+        self.lookahead
+    }
+
+    #[inline]
+    fn process_lookahead(&mut self, in_signal: Float, detector: Float) -> Float {
+        let len = self.lookahead_samples;
+
+        let needed_gain = if detector.abs() > self.threshold {
+            self.threshold / detector.abs()
+        } else {
+            1.0
+        };
+
+        // the lowest gain needed by any sample in the lookahead window (including the one being output)
+        while self
+            .needed_gains
+            .front()
+            .is_some_and(|&(sample, _)| sample + (len as u64) < self.sample)
+        {
+            self.needed_gains.pop_front();
+        }
+        while self
+            .needed_gains
+            .back()
+            .is_some_and(|&(_, gain)| gain >= needed_gain)
+        {
+            self.needed_gains.pop_back();
+        }
+        self.needed_gains.push_back((self.sample, needed_gain));
+        let held_gain = self.needed_gains.front().map_or(1.0, |&(_, gain)| gain);
+        self.sample += 1;
+
+        // drop instantly, recover at the release rate
+        self.recovering_gain =
+            held_gain.min(self.recovering_gain * self.release + held_gain * (1.0 - self.release));
+
+        // averaging over the window ramps the gain down before each peak, and never above the gain the peak needs
+        let oldest = self.held_gains[self.head];
+        self.held_gains[self.head] = self.recovering_gain;
+        self.held_gains_sum += self.recovering_gain - oldest;
+        self.gain = self.held_gains_sum / len as Float;
+
+        let out = self.delayed[self.head];
+        self.delayed[self.head] = in_signal;
+        self.head += 1;
+        if self.head == len {
+            self.head = 0;
+            // keep rounding errors in the running sum from accumulating
+            self.held_gains_sum = self.held_gains.iter().sum();
+        }
+
+        out * self.gain
+    }
 }
 
 impl Default for PeakLimiter {
@@ -51,6 +146,15 @@ impl Default for PeakLimiter {
         Self {
             gain: 1.0,
             envelope: 0.0,
+            lookahead: 0.0,
+            lookahead_samples: 0,
+            recovering_gain: 1.0,
+            delayed: vec![],
+            held_gains: vec![],
+            held_gains_sum: 0.0,
+            head: 0,
+            needed_gains: VecDeque::new(),
+            sample: 0,
             // -0.1 dBFS
             threshold: 0.9885530946569389,
             attack: 0.9,
@@ -67,6 +171,7 @@ impl Processor for PeakLimiter {
             SignalSpec::new("threshold", SignalType::Float),
             SignalSpec::new("attack", SignalType::Float),
             SignalSpec::new("release", SignalType::Float),
+            SignalSpec::new(SIDECHAIN, SignalType::Float),
         ]
     }
 
@@ -74,13 +179,37 @@ impl Processor for PeakLimiter {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        let len = (self.lookahead * sample_rate).round() as usize;
+        self.delayed = vec![0.0; len];
+        self.held_gains = vec![1.0; len];
+        self.needed_gains = VecDeque::with_capacity(len + 1);
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        let len = ((self.lookahead * sample_rate).round() as usize).min(self.delayed.len());
+        if len != self.lookahead_samples {
+            self.lookahead_samples = len;
+            self.delayed[..len].fill(0.0);
+            self.held_gains[..len].fill(1.0);
+            self.held_gains_sum = len as Float;
+            self.head = 0;
+            self.needed_gains.clear();
+            self.recovering_gain = 1.0;
+        }
+    }
+
+    fn latency(&self) -> usize {
+        self.lookahead_samples
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, threshold, attack, release, out) in iter_proc_io_as!(
-            inputs as [Float, Float, Float, Float],
+        for (in_signal, threshold, attack, release, sidechain, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float, Float],
             outputs as [Float]
         ) {
             self.threshold = threshold.unwrap_or(self.threshold);
@@ -92,7 +221,14 @@ impl Processor for PeakLimiter {
                 continue;
             };
 
-            self.envelope = in_signal.abs().max(self.envelope * self.release);
+            let detector = sidechain.unwrap_or(*in_signal);
+
+            if self.lookahead_samples > 0 {
+                *out = Some(self.process_lookahead(*in_signal, detector));
+                continue;
+            }
+
+            self.envelope = detector.abs().max(self.envelope * self.release);
 
             let target_gain = if self.envelope > self.threshold {
                 self.threshold / self.envelope
@@ -109,44 +245,59 @@ impl Processor for PeakLimiter {
     }
 }
 
-/// A simple compressor.
+/// A feed-forward compressor with a soft knee and an optional sidechain input.
+///
+/// The compressor's level detector listens to the [`SIDECHAIN`] input if it is connected, and to the `in` input otherwise.
+/// This allows, for example, ducking a pad whenever a kick drum plays by connecting the kick to the sidechain.
+///
+/// The threshold, knee, and makeup gain are in decibels, and the attack and release times are in seconds.
 ///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
 /// | `0` | `in` | `Float` | The input signal. |
-/// | `1` | `threshold` | `Float` | The amplitude threshold of the compressor. |
-/// | `2` | `ratio` | `Float` | The compression ratio of the compressor. |
-/// | `3` | `attack` | `Float` | The attack factor of the compressor. |
-/// | `4` | `release` | `Float` | The release factor of the compressor. |
+/// | `1` | `sidechain` | `Float` | The signal to detect the level of, if different from the input signal. |
+/// | `2` | `threshold` | `Float` | The level above which the signal is compressed, in dBFS. |
+/// | `3` | `ratio` | `Float` | The compression ratio (e.g. `4` for 4:1). |
+/// | `4` | `knee` | `Float` | The width of the soft knee around the threshold, in dB. |
+/// | `5` | `attack` | `Float` | The attack time in seconds. |
+/// | `6` | `release` | `Float` | The release time in seconds. |
+/// | `7` | `makeup` | `Float` | The gain applied after compression, in dB. |
 ///
 /// # Outputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
 /// | `0` | `out` | `Float` | The output signal. |
+/// | `1` | `reduction` | `Float` | The current gain reduction in dB (positive when compressing). |
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Compressor {
-    gain: Float,
-    envelope: Float,
+    // the smoothed gain reduction in dB
+    reduction: Float,
 
-    /// The amplitude threshold of the compressor.
+    /// The level above which the signal is compressed, in dBFS.
     pub threshold: Float,
 
-    /// The compression ratio of the compressor.
+    /// The compression ratio.
     pub ratio: Float,
 
-    /// The attack factor of the compressor.
+    /// The width of the soft knee in dB.
+    pub knee: Float,
+
+    /// The attack time in seconds.
     pub attack: Float,
 
-    /// The release factor of the compressor.
+    /// The release time in seconds.
     pub release: Float,
+
+    /// The gain applied after compression, in dB.
+    pub makeup: Float,
 }
 
 impl Compressor {
-    /// Creates a new `Compressor` processor with the given threshold, ratio, attack, and release.
+    /// Creates a new `Compressor` processor with the given threshold (in dBFS), ratio, attack, and release (in seconds).
     pub fn new(threshold: Float, ratio: Float, attack: Float, release: Float) -> Self {
         Self {
             threshold,
@@ -156,19 +307,68 @@ impl Compressor {
             ..Default::default()
         }
     }
+
+    /// Sets the width of the soft knee in dB.
+    pub fn with_knee(mut self, knee: Float) -> Self {
+        self.knee = knee;
+        self
+    }
+
+    /// Sets the makeup gain in dB.
+    pub fn with_makeup(mut self, makeup: Float) -> Self {
+        self.makeup = makeup;
+        self
+    }
+
+    /// Returns the gain reduction in dB for a signal at the given level in dBFS, before smoothing.
+    pub fn static_reduction(&self, level: Float) -> Float {
+        let ratio = self.ratio.max(1.0);
+        let knee = self.knee.max(0.0);
+        let over = level - self.threshold;
+
+        let compressed = if 2.0 * over < -knee {
+            level
+        } else if 2.0 * over.abs() <= knee && knee > 0.0 {
+            level + (1.0 / ratio - 1.0) * (over + knee / 2.0).powi(2) / (2.0 * knee)
+        } else {
+            self.threshold + over / ratio
+        };
+
+        level - compressed
+    }
+
+    /// Returns the current gain reduction in dB.
+    pub fn reduction(&self) -> Float {
+        self.reduction
+    }
+
+    #[inline]
+    fn compress(&mut self, in_signal: Float, detector: Float, sample_rate: Float) -> Float {
+        let target = self.static_reduction(amp_to_db(detector.abs()));
+
+        let time = if target > self.reduction {
+            self.attack
+        } else {
+            self.release
+        };
+        let coeff = time_to_coeff(time, sample_rate);
+        self.reduction = coeff * self.reduction + (1.0 - coeff) * target;
+
+        in_signal * db_to_amp(self.makeup - self.reduction)
+    }
 }
 
 impl Default for Compressor {
     fn default() -> Self {
         Self {
-            gain: 1.0,
-            envelope: 0.0,
-            // -0.1 dBFS
-            threshold: 0.9885530946569389,
+            reduction: 0.0,
+            threshold: -12.0,
             // 4:1
             ratio: 4.0,
-            attack: 0.9,
-            release: 0.9995,
+            knee: 6.0,
+            attack: 0.01,
+            release: 0.1,
+            makeup: 0.0,
         }
     }
 }
@@ -178,15 +378,21 @@ impl Processor for Compressor {
     fn input_spec(&self) -> Vec<SignalSpec> {
         vec![
             SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new(SIDECHAIN, SignalType::Float),
             SignalSpec::new("threshold", SignalType::Float),
             SignalSpec::new("ratio", SignalType::Float),
+            SignalSpec::new("knee", SignalType::Float),
             SignalSpec::new("attack", SignalType::Float),
             SignalSpec::new("release", SignalType::Float),
+            SignalSpec::new("makeup", SignalType::Float),
         ]
     }
 
     fn output_spec(&self) -> Vec<SignalSpec> {
-        vec![SignalSpec::new("out", SignalType::Float)]
+        vec![
+            SignalSpec::new("out", SignalType::Float),
+            SignalSpec::new("reduction", SignalType::Float),
+        ]
     }
 
     fn process(
@@ -194,31 +400,238 @@ impl Processor for Compressor {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, threshold, ratio, attack, release, out) in iter_proc_io_as!(
-            inputs as [Float, Float, Float, Float, Float],
-            outputs as [Float]
+        let sample_rate = inputs.sample_rate();
+
+        for (
+            in_signal,
+            sidechain,
+            threshold,
+            ratio,
+            knee,
+            attack,
+            release,
+            makeup,
+            out,
+            reduction,
+        ) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float, Float, Float, Float, Float],
+            outputs as [Float, Float]
         ) {
             self.threshold = threshold.unwrap_or(self.threshold);
             self.ratio = ratio.unwrap_or(self.ratio);
+            self.knee = knee.unwrap_or(self.knee);
             self.attack = attack.unwrap_or(self.attack);
             self.release = release.unwrap_or(self.release);
+            self.makeup = makeup.unwrap_or(self.makeup);
 
             let Some(in_signal) = in_signal else {
                 *out = None;
+                *reduction = None;
                 continue;
             };
 
-            self.envelope = in_signal.abs().max(self.envelope * self.release);
+            let detector = sidechain.unwrap_or(*in_signal);
+            *out = Some(self.compress(*in_signal, detector, sample_rate));
+            *reduction = Some(self.reduction);
+        }
 
-            let target_gain = if self.envelope > self.threshold {
-                self.threshold + (self.envelope - self.threshold) / self.ratio
-            } else {
-                self.envelope
-            };
+        Ok(())
+    }
+}
 
-            self.gain = self.gain * self.attack + target_gain * (1.0 - self.attack);
+/// A multiband compressor, which splits a signal into 3 or 4 frequency bands with a [`Crossover`], compresses each band with its own [`Compressor`], and sums them back together.
+///
+/// Each band has its own threshold, ratio, attack, release, and makeup inputs, named after the band (e.g. `threshold0` for the lowest band).
+/// The knee of each band is set with [`MultibandCompressor::with_band`].
+/// The [`SIDECHAIN`] input is split into the same bands, so each band detects the level of its own part of the sidechain.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `sidechain` | `Float` | The signal to detect the level of, if different from the input signal. |
+/// | `2..2+N` | `frequency0..` | `Float` | The frequencies to split the signal at, in ascending order. |
+/// | | `threshold{band}` | `Float` | The level above which the band is compressed, in dBFS. |
+/// | | `ratio{band}` | `Float` | The compression ratio of the band. |
+/// | | `attack{band}` | `Float` | The attack time of the band in seconds. |
+/// | | `release{band}` | `Float` | The release time of the band in seconds. |
+/// | | `makeup{band}` | `Float` | The gain applied to the band after compression, in dB. |
+///
+/// The per-band inputs follow the frequencies, in the order above for each band in turn.
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The output signal. |
+/// | `1..` | `reduction{band}` | `Float` | The current gain reduction of each band in dB. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultibandCompressor {
+    crossover: Crossover,
+    sidechain_crossover: Crossover,
+    bands: Vec<Compressor>,
+}
 
-            *out = Some(in_signal * self.gain);
+// the settings of each band that have inputs, in input order
+const MULTIBAND_INPUTS: [&str; 5] = ["threshold", "ratio", "attack", "release", "makeup"];
+
+impl MultibandCompressor {
+    /// Creates a new `MultibandCompressor` that splits the signal at the given frequencies, with a default [`Compressor`] for each band.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not 2 or 3 frequencies.
+    pub fn new(frequencies: &[Float]) -> Self {
+        assert!(
+            (2..=3).contains(&frequencies.len()),
+            "multiband compressor must have 2 or 3 frequencies"
+        );
+        let crossover = Crossover::new(frequencies);
+        Self {
+            bands: vec![Compressor::default(); crossover.num_bands()],
+            sidechain_crossover: crossover.clone(),
+            crossover,
+        }
+    }
+
+    /// Sets the compressor settings of the given band, from lowest to highest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the band is out of range.
+    pub fn with_band(mut self, band: usize, compressor: Compressor) -> Self {
+        self.bands[band] = compressor;
+        self
+    }
+
+    /// Returns the number of bands the signal is split into.
+    pub fn num_bands(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// Returns the compressor of the given band, from lowest to highest.
+    pub fn band(&self, band: usize) -> Option<&Compressor> {
+        self.bands.get(band)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MultibandCompressor {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new(SIDECHAIN, SignalType::Float),
+        ];
+        for i in 0..self.num_bands() - 1 {
+            spec.push(SignalSpec::new(
+                format!("frequency{}", i),
+                SignalType::Float,
+            ));
+        }
+        for band in 0..self.num_bands() {
+            for name in MULTIBAND_INPUTS {
+                spec.push(SignalSpec::new(
+                    format!("{}{}", name, band),
+                    SignalType::Float,
+                ));
+            }
+        }
+        spec
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = vec![SignalSpec::new("out", SignalType::Float)];
+        for band in 0..self.num_bands() {
+            spec.push(SignalSpec::new(
+                format!("reduction{}", band),
+                SignalType::Float,
+            ));
+        }
+        spec
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, block_size: usize) {
+        self.crossover.resize_buffers(sample_rate, block_size);
+        self.sidechain_crossover
+            .resize_buffers(sample_rate, block_size);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let num_bands = self.num_bands();
+        let num_splits = num_bands - 1;
+
+        let mut sidechain = inputs.iter_input_as_floats(1)?;
+        let mut frequencies = (2..2 + num_splits)
+            .map(|i| inputs.iter_input_as_floats(i))
+            .collect::<Result<SmallVec<[_; 3]>, _>>()?;
+        let mut settings = (2 + num_splits..2 + num_splits + num_bands * MULTIBAND_INPUTS.len())
+            .map(|i| inputs.iter_input_as_floats(i))
+            .collect::<Result<SmallVec<[_; 20]>, _>>()?;
+
+        for (sample_index, in_signal) in inputs
+            .iter_input_as_floats(0)?
+            .take(inputs.block_size())
+            .enumerate()
+        {
+            let sidechain = sidechain.next().flatten();
+            for (split, frequency) in frequencies.iter_mut().enumerate() {
+                if let Some(Some(frequency)) = frequency.next() {
+                    self.crossover.set_frequency(split, frequency);
+                    self.sidechain_crossover.set_frequency(split, frequency);
+                }
+            }
+            for (band, settings) in self
+                .bands
+                .iter_mut()
+                .zip(settings.chunks_mut(MULTIBAND_INPUTS.len()))
+            {
+                let [threshold, ratio, attack, release, makeup] = settings else {
+                    unreachable!()
+                };
+                band.threshold = threshold.next().flatten().unwrap_or(band.threshold);
+                band.ratio = ratio.next().flatten().unwrap_or(band.ratio);
+                band.attack = attack.next().flatten().unwrap_or(band.attack);
+                band.release = release.next().flatten().unwrap_or(band.release);
+                band.makeup = makeup.next().flatten().unwrap_or(band.makeup);
+            }
+
+            let Some(in_signal) = in_signal else {
+                for output in 0..=num_bands {
+                    outputs.output(output).set_none(sample_index);
+                }
+                continue;
+            };
+
+            let mut bands = [0.0; 4];
+            let bands = &mut bands[..num_bands];
+            self.crossover.split(in_signal, bands);
+
+            let mut detectors = [0.0; 4];
+            let detectors = &mut detectors[..num_bands];
+            // keep the sidechain's filters running even while it's disconnected, so they don't click when it reconnects
+            self.sidechain_crossover
+                .split(sidechain.unwrap_or_default(), detectors);
+
+            let mut out = 0.0;
+            for (i, compressor) in self.bands.iter_mut().enumerate() {
+                let detector = if sidechain.is_some() {
+                    detectors[i]
+                } else {
+                    bands[i]
+                };
+                out += compressor.compress(bands[i], detector, sample_rate);
+                outputs
+                    .output(i + 1)
+                    .set_as::<Float>(sample_index, compressor.reduction);
+            }
+            outputs.output(0).set_as::<Float>(sample_index, out);
         }
 
         Ok(())
@@ -237,6 +650,7 @@ impl Processor for Compressor {
 /// | `3` | `attack` | `Float` | The attack factor of the compressor. |
 /// | `4` | `release` | `Float` | The release factor of the compressor. |
 /// | `5` | `window_size` | `Float` | The window size of the RMS detector in seconds. |
+/// | `6` | `sidechain` | `Float` | The signal to detect the level of, if different from the input signal. |
 ///
 /// # Outputs
 ///
@@ -315,6 +729,7 @@ impl Processor for RmsCompressor {
             SignalSpec::new("attack", SignalType::Float),
             SignalSpec::new("release", SignalType::Float),
             SignalSpec::new("window_size", SignalType::Int),
+            SignalSpec::new(SIDECHAIN, SignalType::Float),
         ]
     }
 
@@ -336,8 +751,8 @@ impl Processor for RmsCompressor {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, threshold, ratio, attack, release, window_size, out) in iter_proc_io_as!(
-            inputs as [Float, Float, Float, Float, Float, Float],
+        for (in_signal, threshold, ratio, attack, release, window_size, sidechain, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float, Float, Float, Float],
             outputs as [Float]
         ) {
             self.threshold = threshold.unwrap_or(self.threshold);
@@ -352,7 +767,7 @@ impl Processor for RmsCompressor {
             };
 
             self.window.rotate_left(1);
-            self.window[0] = in_signal.powi(2);
+            self.window[0] = sidechain.unwrap_or(*in_signal).powi(2);
 
             let window_size = (self.window_size * inputs.sample_rate()) as usize;
 
@@ -375,3 +790,219 @@ impl Processor for RmsCompressor {
         Ok(())
     }
 }
+
+// the release time of a gate's level detector in seconds, short enough to follow the signal but long enough not to follow each cycle of it
+const GATE_DETECTOR_RELEASE: Float = 0.01;
+
+/// A noise gate, which silences a signal while its level is below a threshold.
+///
+/// The gate opens when the detected level rises above the threshold, and closes when it falls below the threshold minus the hysteresis and has stayed there for the hold time, so it doesn't chatter on signals hovering around the threshold.
+/// While closed, the signal is attenuated by the range, so a small range makes the gate a gentle downward expander.
+///
+/// The gate's level detector listens to the [`SIDECHAIN`] input if it is connected, and to the `in` input otherwise.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `sidechain` | `Float` | The signal to detect the level of, if different from the input signal. |
+/// | `2` | `threshold` | `Float` | The level above which the gate opens, in dBFS. |
+/// | `3` | `hysteresis` | `Float` | How far below the threshold the level must fall for the gate to close, in dB. |
+/// | `4` | `attack` | `Float` | The time the gate takes to open, in seconds. |
+/// | `5` | `hold` | `Float` | The time the level must stay low before the gate closes, in seconds. |
+/// | `6` | `release` | `Float` | The time the gate takes to close, in seconds. |
+/// | `7` | `range` | `Float` | The attenuation of the signal while the gate is closed, in dB. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The output signal. |
+/// | `1` | `open` | `Bool` | Whether the gate is open. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gate {
+    // the detected level, as a linear amplitude
+    level: Float,
+    open: bool,
+    // the number of samples the level has been low for while the gate is open
+    held: Float,
+    // the smoothed attenuation in dB
+    attenuation: Float,
+
+    /// The level above which the gate opens, in dBFS.
+    pub threshold: Float,
+
+    /// How far below the threshold the level must fall for the gate to close, in dB.
+    pub hysteresis: Float,
+
+    /// The time the gate takes to open, in seconds.
+    pub attack: Float,
+
+    /// The time the level must stay low before the gate closes, in seconds.
+    pub hold: Float,
+
+    /// The time the gate takes to close, in seconds.
+    pub release: Float,
+
+    /// The attenuation of the signal while the gate is closed, in dB.
+    pub range: Float,
+}
+
+impl Gate {
+    /// Creates a new `Gate` processor with the given threshold (in dBFS), attack, hold, and release (in seconds).
+    pub fn new(threshold: Float, attack: Float, hold: Float, release: Float) -> Self {
+        Self {
+            threshold,
+            attack,
+            hold,
+            release,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the hysteresis in dB.
+    pub fn with_hysteresis(mut self, hysteresis: Float) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Sets the attenuation while closed in dB.
+    pub fn with_range(mut self, range: Float) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Returns whether the gate is open.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+impl Default for Gate {
+    fn default() -> Self {
+        Self {
+            level: 0.0,
+            open: false,
+            held: 0.0,
+            attenuation: 80.0,
+            threshold: -40.0,
+            hysteresis: 6.0,
+            attack: 0.001,
+            hold: 0.05,
+            release: 0.1,
+            range: 80.0,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Gate {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new(SIDECHAIN, SignalType::Float),
+            SignalSpec::new("threshold", SignalType::Float),
+            SignalSpec::new("hysteresis", SignalType::Float),
+            SignalSpec::new("attack", SignalType::Float),
+            SignalSpec::new("hold", SignalType::Float),
+            SignalSpec::new("release", SignalType::Float),
+            SignalSpec::new("range", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("out", SignalType::Float),
+            SignalSpec::new("open", SignalType::Bool),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let detector_coeff = time_to_coeff(GATE_DETECTOR_RELEASE, sample_rate);
+
+        for (
+            in_signal,
+            sidechain,
+            threshold,
+            hysteresis,
+            attack,
+            hold,
+            release,
+            range,
+            out,
+            open,
+        ) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float, Float, Float, Float, Float],
+            outputs as [Float, bool]
+        ) {
+            self.threshold = threshold.unwrap_or(self.threshold);
+            self.hysteresis = hysteresis.unwrap_or(self.hysteresis).max(0.0);
+            self.attack = attack.unwrap_or(self.attack);
+            self.hold = hold.unwrap_or(self.hold);
+            self.release = release.unwrap_or(self.release);
+            self.range = range.unwrap_or(self.range).max(0.0);
+
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                *open = None;
+                continue;
+            };
+
+            let detector = sidechain.unwrap_or(*in_signal);
+            self.level = detector.abs().max(self.level * detector_coeff);
+            let level = amp_to_db(self.level);
+
+            if level > self.threshold {
+                self.open = true;
+                self.held = 0.0;
+            } else if self.open && level < self.threshold - self.hysteresis {
+                self.held += 1.0;
+                if self.held > self.hold * sample_rate {
+                    self.open = false;
+                }
+            }
+
+            let (target, time) = if self.open {
+                (0.0, self.attack)
+            } else {
+                (self.range, self.release)
+            };
+            let coeff = time_to_coeff(time, sample_rate);
+            self.attenuation = coeff * self.attenuation + (1.0 - coeff) * target;
+
+            *out = Some(in_signal * db_to_amp(-self.attenuation));
+            *open = Some(self.open);
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a gain in decibels to a linear amplitude.
+#[inline]
+pub(crate) fn db_to_amp(db: Float) -> Float {
+    Float::powf(10.0, db / 20.0)
+}
+
+/// Converts a linear amplitude to a gain in decibels, clamped to -200 dB for silence.
+#[inline]
+pub(crate) fn amp_to_db(amp: Float) -> Float {
+    20.0 * amp.max(1e-10).log10()
+}
+
+/// Returns the coefficient of a one-pole smoothing filter with the given time constant in seconds.
+#[inline]
+pub(crate) fn time_to_coeff(time: Float, sample_rate: Float) -> Float {
+    if time <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / (time * sample_rate)).exp()
+    }
+}
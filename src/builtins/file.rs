@@ -0,0 +1,417 @@
+//! Processors that stream audio from files on disk.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{prelude::*, processor::ProcessMode};
+
+// the number of frames read from the file at a time
+const CHUNK_FRAMES: usize = 4096;
+
+// the number of chunks the prefetch thread reads ahead of the audio thread
+const PREFETCH_CHUNKS: usize = 4;
+
+type WavReader = hound::WavReader<BufReader<File>>;
+
+// a block of interleaved frames read by the prefetch thread
+#[derive(Debug)]
+struct Chunk {
+    // incremented every time the stream is restarted, so stale chunks can be discarded
+    generation: u64,
+    samples: Vec<Float>,
+    // whether this is the last chunk of the file (when not looping)
+    end: bool,
+}
+
+// the audio thread's side of a running prefetch thread
+#[derive(Debug)]
+struct DiskStream {
+    chunk_rx: Receiver<Chunk>,
+    free_tx: Sender<Vec<Float>>,
+    // the generation the prefetch thread should be reading, which it polls between chunks
+    restart: Arc<AtomicU64>,
+    // wakes the prefetch thread up when it is waiting at the end of the file
+    wake_tx: Sender<()>,
+    generation: u64,
+    chunk: Option<Chunk>,
+    position: usize,
+    finished: bool,
+    _thread: JoinHandle<()>,
+}
+
+impl DiskStream {
+    fn spawn(reader: WavReader, loop_points: Option<(u32, u32)>) -> Self {
+        let (chunk_tx, chunk_rx) = crossbeam_channel::bounded(PREFETCH_CHUNKS);
+        let (free_tx, free_rx) = crossbeam_channel::bounded(PREFETCH_CHUNKS + 1);
+        let (wake_tx, wake_rx) = crossbeam_channel::bounded(1);
+        let restart = Arc::new(AtomicU64::new(0));
+
+        let thread = std::thread::Builder::new()
+            .name("raug-disk-stream".into())
+            .spawn({
+                let restart = restart.clone();
+                move || prefetch(reader, loop_points, chunk_tx, free_rx, restart, wake_rx)
+            })
+            .expect("failed to spawn disk streaming thread");
+
+        Self {
+            chunk_rx,
+            free_tx,
+            restart,
+            wake_tx,
+            generation: 0,
+            chunk: None,
+            position: 0,
+            finished: false,
+            _thread: thread,
+        }
+    }
+
+    fn restart(&mut self) {
+        self.generation += 1;
+        self.restart.store(self.generation, Ordering::Release);
+        // if the channel is full, the prefetch thread has a wake-up pending already
+        self.wake_tx.try_send(()).ok();
+
+        self.recycle();
+        self.finished = false;
+
+        // discard anything read before the restart, making room for the prefetch thread to continue
+        while let Ok(chunk) = self.chunk_rx.try_recv() {
+            self.free_tx.try_send(chunk.samples).ok();
+        }
+    }
+
+    fn recycle(&mut self) {
+        if let Some(chunk) = self.chunk.take() {
+            self.free_tx.try_send(chunk.samples).ok();
+        }
+        self.position = 0;
+    }
+
+    // returns the next frame, or `None` if the file has ended or the prefetch thread has fallen behind
+    fn next_frame(&mut self, channels: usize) -> Option<&[Float]> {
+        if self.finished {
+            return None;
+        }
+
+        if self
+            .chunk
+            .as_ref()
+            .is_some_and(|chunk| self.position >= chunk.samples.len())
+        {
+            let end = self.chunk.as_ref().is_some_and(|chunk| chunk.end);
+            self.recycle();
+            if end {
+                self.finished = true;
+                return None;
+            }
+        }
+
+        while self.chunk.is_none() {
+            let chunk = self.chunk_rx.try_recv().ok()?;
+            if chunk.generation == self.generation {
+                self.chunk = Some(chunk);
+            } else {
+                self.free_tx.try_send(chunk.samples).ok();
+            }
+        }
+
+        let chunk = self.chunk.as_ref()?;
+        let frame = chunk.samples.get(self.position..self.position + channels)?;
+        self.position += channels;
+        Some(frame)
+    }
+}
+
+fn prefetch(
+    mut reader: WavReader,
+    loop_points: Option<(u32, u32)>,
+    chunk_tx: Sender<Chunk>,
+    free_rx: Receiver<Vec<Float>>,
+    restart: Arc<AtomicU64>,
+    wake_rx: Receiver<()>,
+) {
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+    let length = reader.duration();
+    let loop_points = loop_points
+        .map(|(start, end)| (start.min(length), end.min(length)))
+        .filter(|(start, end)| start < end);
+
+    let mut generation = 0;
+    let mut position = 0;
+
+    loop {
+        let requested = restart.load(Ordering::Acquire);
+        if requested != generation {
+            generation = requested;
+            position = 0;
+            if let Err(e) = reader.seek(0) {
+                log::error!("Error seeking in WAV file: {}", e);
+                return;
+            }
+        }
+
+        let mut samples = free_rx
+            .try_recv()
+            .unwrap_or_else(|_| Vec::with_capacity(CHUNK_FRAMES * channels));
+        samples.clear();
+
+        let mut end = false;
+        while samples.len() < CHUNK_FRAMES * channels {
+            let stop = loop_points.map_or(length, |(_, end)| end);
+
+            if position >= stop {
+                if let Some((start, _)) = loop_points {
+                    position = start;
+                    if let Err(e) = reader.seek(start) {
+                        log::error!("Error seeking in WAV file: {}", e);
+                        return;
+                    }
+                    continue;
+                } else {
+                    end = true;
+                    break;
+                }
+            }
+
+            let remaining = (CHUNK_FRAMES - samples.len() / channels) as u32;
+            let frames = (stop - position).min(remaining);
+            if let Err(e) = read_frames(&mut reader, frames as usize * channels, &mut samples) {
+                log::error!("Error reading WAV file: {}", e);
+                return;
+            }
+            position += frames;
+        }
+
+        let chunk = Chunk {
+            generation,
+            samples,
+            end,
+        };
+
+        if chunk_tx.send(chunk).is_err() {
+            // the processor was dropped
+            return;
+        }
+
+        if end {
+            // wait for the stream to be restarted, which the next iteration seeks for
+            while restart.load(Ordering::Acquire) == generation {
+                if wake_rx.recv().is_err() {
+                    // the processor was dropped
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn read_frames(
+    reader: &mut WavReader,
+    num_samples: usize,
+    samples: &mut Vec<Float>,
+) -> Result<(), hound::Error> {
+    let spec = reader.spec();
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for sample in reader.samples::<f32>().take(num_samples) {
+                samples.push(sample? as Float);
+            }
+        }
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as Float;
+            for sample in reader.samples::<i32>().take(num_samples) {
+                samples.push(sample? as Float / scale);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A processor that streams a WAV file from disk.
+///
+/// Unlike loading the file into a [`Buffer`], only a few blocks of the file are kept in memory at a time.
+/// The file is read ahead of playback on a background thread, which is started when the processor is allocated.
+/// If the background thread falls behind, the missing samples are output as `None`.
+///
+/// The file is played back at its own sample rate, without resampling.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `restart` | `Bool` | Whether to restart playback from the beginning of the file. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0..` | `0..` | `Float` | The channels of the file. |
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WavFileIn {
+    path: PathBuf,
+    channels: usize,
+    loop_points: Option<(u32, u32)>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    stream: Option<DiskStream>,
+}
+
+impl WavFileIn {
+    /// Creates a new [`WavFileIn`] processor that streams the WAV file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, hound::Error> {
+        let path = path.as_ref().to_path_buf();
+        let reader = hound::WavReader::open(&path)?;
+
+        Ok(Self {
+            path,
+            channels: reader.spec().channels as usize,
+            loop_points: None,
+            stream: None,
+        })
+    }
+
+    /// Loops playback between the given start and end frames.
+    ///
+    /// Playback starts from the beginning of the file, and jumps back to the start frame whenever it reaches the end frame.
+    pub fn with_loop(mut self, start: u32, end: u32) -> Self {
+        self.loop_points = Some((start, end));
+        self
+    }
+
+    /// Returns the number of channels in the file.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+}
+
+impl Clone for WavFileIn {
+    fn clone(&self) -> Self {
+        // the clone starts its own stream when it is allocated
+        Self {
+            path: self.path.clone(),
+            channels: self.channels,
+            loop_points: self.loop_points,
+            stream: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for WavFileIn {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("restart", SignalType::Bool)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        (0..self.channels)
+            .map(|i| SignalSpec::new(format!("{}", i), SignalType::Float))
+            .collect()
+    }
+
+    fn allocate(&mut self, _sample_rate: Float, _max_block_size: usize) {
+        if self.stream.is_some() {
+            return;
+        }
+
+        match hound::WavReader::open(&self.path) {
+            Ok(reader) => self.stream = Some(DiskStream::spawn(reader, self.loop_points)),
+            Err(e) => log::error!("Error opening WAV file {}: {}", self.path.display(), e),
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let (offset, num_samples) = match inputs.mode {
+            ProcessMode::Block => (0, inputs.block_size()),
+            ProcessMode::Sample(sample_index) => (sample_index, 1),
+        };
+
+        let restarts = inputs.iter_input_as::<bool>(0)?.take(num_samples);
+
+        for (i, restart) in restarts.enumerate() {
+            let sample_index = offset + i;
+
+            let frame = match &mut self.stream {
+                Some(stream) => {
+                    if restart.unwrap_or(false) {
+                        stream.restart();
+                    }
+                    stream.next_frame(self.channels)
+                }
+                None => None,
+            };
+
+            for (channel, output) in outputs.outputs.iter_mut().enumerate() {
+                if let SignalBuffer::Float(output) = output {
+                    output[sample_index] = frame.map(|frame| frame[channel]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    // reads the next frame's first sample, waiting for the prefetch thread if it has fallen behind
+    fn next_sample(stream: &mut DiskStream) -> Option<Float> {
+        let started = Instant::now();
+        loop {
+            if let Some(frame) = stream.next_frame(1) {
+                return Some(frame[0]);
+            }
+            if stream.finished || started.elapsed() > Duration::from_secs(5) {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn restart_after_end_plays_from_the_beginning() {
+        let path = std::env::temp_dir().join(format!("raug-{}-restart.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..10 {
+            writer.write_sample(i as f32).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut stream = DiskStream::spawn(hound::WavReader::open(&path).unwrap(), None);
+        for _ in 0..2 {
+            let samples: Vec<_> = std::iter::from_fn(|| next_sample(&mut stream)).collect();
+            assert_eq!(samples, (0..10).map(|i| i as Float).collect::<Vec<_>>());
+            // the prefetch thread is waiting at the end of the file until it is restarted
+            stream.restart();
+        }
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -2,6 +2,10 @@
 
 use crate::{prelude::*, signal::PI};
 
+use smallvec::SmallVec;
+
+use super::{iter_list_floats, lerp};
+
 const THERMAL: Float = 0.000025;
 
 /// A 4-pole Moog ladder lowpass filter.
@@ -676,3 +680,1236 @@ impl Processor for OnePole {
         Ok(())
     }
 }
+
+/// A state variable filter, with simultaneous lowpass, highpass, bandpass, and notch outputs.
+///
+/// The filter uses the topology-preserving transform (zero-delay feedback) structure, so it stays stable and in tune when its cutoff and resonance are modulated at audio rate.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `cutoff` | `Float` | The cutoff frequency of the filter. |
+/// | `2` | `resonance` | `Float` | The resonance of the filter, between `0.0` and `1.0`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `lowpass` | `Float` | The lowpass output. |
+/// | `1` | `highpass` | `Float` | The highpass output. |
+/// | `2` | `bandpass` | `Float` | The bandpass output. |
+/// | `3` | `notch` | `Float` | The notch output. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Svf {
+    // integrator states
+    ic1eq: Float,
+    ic2eq: Float,
+
+    // coefficients
+    k: Float,
+    a1: Float,
+    a2: Float,
+    a3: Float,
+    sample_rate: Float,
+
+    /// The cutoff frequency of the filter.
+    pub cutoff: Float,
+
+    /// The resonance of the filter.
+    pub resonance: Float,
+}
+
+impl Default for Svf {
+    fn default() -> Self {
+        Self::new(1000.0, 0.1)
+    }
+}
+
+impl Svf {
+    /// Creates a new `Svf` filter with the given cutoff frequency and resonance.
+    pub fn new(cutoff: Float, resonance: Float) -> Self {
+        Self {
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+            k: 2.0,
+            a1: 1.0,
+            a2: 0.0,
+            a3: 0.0,
+            sample_rate: 0.0,
+            cutoff,
+            resonance,
+        }
+    }
+
+    // https://cytomic.com/files/dsp/SvfLinearTrapOptimised2.pdf
+    #[inline]
+    fn set_coefficients(&mut self, sample_rate: Float) {
+        self.sample_rate = sample_rate;
+        let cutoff = self.cutoff.clamp(0.0, sample_rate * 0.49);
+        let resonance = self.resonance.clamp(0.0, 1.0);
+
+        let g = Float::tan(PI * cutoff / sample_rate);
+        // full resonance is just short of self-oscillation, which would never decay
+        self.k = 2.0 - 1.99 * resonance;
+        self.a1 = 1.0 / (1.0 + g * (g + self.k));
+        self.a2 = g * self.a1;
+        self.a3 = g * self.a2;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Svf {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("cutoff", SignalType::Float),
+            SignalSpec::new("resonance", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("lowpass", SignalType::Float),
+            SignalSpec::new("highpass", SignalType::Float),
+            SignalSpec::new("bandpass", SignalType::Float),
+            SignalSpec::new("notch", SignalType::Float),
+        ]
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        self.set_coefficients(sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, cutoff, resonance, lowpass, highpass, bandpass, notch) in iter_proc_io_as!(
+            inputs as [Float, Float, Float],
+            outputs as [Float, Float, Float, Float]
+        ) {
+            let cutoff = cutoff.unwrap_or(self.cutoff);
+            let resonance = resonance.unwrap_or(self.resonance);
+
+            if cutoff != self.cutoff
+                || resonance != self.resonance
+                || inputs.sample_rate() != self.sample_rate
+            {
+                self.cutoff = cutoff;
+                self.resonance = resonance;
+                self.set_coefficients(inputs.sample_rate());
+            }
+
+            let Some(v0) = in_signal else {
+                *lowpass = None;
+                *highpass = None;
+                *bandpass = None;
+                *notch = None;
+                continue;
+            };
+
+            let v3 = v0 - self.ic2eq;
+            let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+            let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+            self.ic1eq = 2.0 * v1 - self.ic1eq;
+            self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+            let low = v2;
+            let high = v0 - self.k * v1 - v2;
+
+            *lowpass = Some(low);
+            *highpass = Some(high);
+            *bandpass = Some(v1);
+            *notch = Some(low + high);
+        }
+
+        Ok(())
+    }
+}
+
+// thermal voltage of the ladder transistors, scaled so that full-scale signals saturate gently at unity drive
+const LADDER_VT: Float = 1.0;
+
+/// A 4-pole ladder lowpass filter with saturating stages and a drive input.
+///
+/// Unlike [`MoogLadder`], every stage saturates, so driving the filter harder adds warmth and distortion, and it self-oscillates at full resonance.
+/// The input gain rises with the resonance to partially compensate for the loss of passband volume.
+///
+/// Based on "An Improved Virtual Analog Model of the Moog Ladder Filter" by D'Angelo and Välimäki.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `cutoff` | `Float` | The cutoff frequency of the filter. |
+/// | `2` | `resonance` | `Float` | The resonance of the filter, between `0.0` and `1.0`. |
+/// | `3` | `drive` | `Float` | The gain applied to the input before the first stage. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The output signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LadderFilter {
+    // stage voltages, their derivatives, and their saturated values
+    v: [Float; 4],
+    dv: [Float; 4],
+    tv: [Float; 4],
+
+    /// The cutoff frequency of the filter.
+    pub cutoff: Float,
+
+    /// The resonance of the filter.
+    pub resonance: Float,
+
+    /// The gain applied to the input.
+    pub drive: Float,
+}
+
+impl Default for LadderFilter {
+    fn default() -> Self {
+        Self::new(1000.0, 0.1, 1.0)
+    }
+}
+
+impl LadderFilter {
+    /// Creates a new `LadderFilter` with the given cutoff frequency, resonance, and drive.
+    pub fn new(cutoff: Float, resonance: Float, drive: Float) -> Self {
+        Self {
+            v: [0.0; 4],
+            dv: [0.0; 4],
+            tv: [0.0; 4],
+            cutoff,
+            resonance,
+            drive,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for LadderFilter {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("cutoff", SignalType::Float),
+            SignalSpec::new("resonance", SignalType::Float),
+            SignalSpec::new("drive", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        // oversampled by a factor of 2
+        let sample_rate = inputs.sample_rate() * 2.0;
+        let vt2 = 2.0 * LADDER_VT;
+
+        for (in_signal, cutoff, resonance, drive, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(cutoff) = cutoff {
+                self.cutoff = *cutoff;
+            }
+            if let Some(resonance) = resonance {
+                self.resonance = *resonance;
+            }
+            if let Some(drive) = drive {
+                self.drive = *drive;
+            }
+
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                continue;
+            };
+
+            let cutoff = self.cutoff.clamp(0.0, inputs.sample_rate() * 0.45);
+            let x = PI * cutoff / sample_rate;
+            let g = 4.0 * PI * LADDER_VT * cutoff * (1.0 - x) / (1.0 + x);
+            let k = 4.0 * self.resonance.clamp(0.0, 1.0);
+            let input = in_signal * self.drive * (1.0 + 0.5 * k);
+
+            for _ in 0..2 {
+                let dv0 = -g * (Float::tanh((input + k * self.v[3]) / vt2) + self.tv[0]);
+                self.v[0] += (dv0 + self.dv[0]) / (2.0 * sample_rate);
+                self.dv[0] = dv0;
+                self.tv[0] = Float::tanh(self.v[0] / vt2);
+
+                for i in 1..4 {
+                    let dv = g * (self.tv[i - 1] - self.tv[i]);
+                    self.v[i] += (dv + self.dv[i]) / (2.0 * sample_rate);
+                    self.dv[i] = dv;
+                    self.tv[i] = Float::tanh(self.v[i] / vt2);
+                }
+            }
+
+            // the stages invert the signal
+            *out = Some(-self.v[3]);
+        }
+
+        Ok(())
+    }
+}
+
+/// The structure of a [`CombFilter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CombType {
+    /// The delayed input is added to the input, producing evenly spaced notches.
+    FeedForward,
+    /// The delayed output is fed back into the input, producing evenly spaced resonant peaks.
+    Feedback,
+}
+
+/// A comb filter, which adds a delayed copy of a signal to itself.
+///
+/// The delayed signal passes through a 1-pole lowpass filter controlled by the `damping` input, which makes feedback combs decay faster at high frequencies, as in Schroeder and Freeverb-style reverbs.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `delay` | `Float` | The delay time in seconds. |
+/// | `2` | `feedback` | `Float` | The gain of the delayed signal, between `-1.0` and `1.0`. |
+/// | `3` | `damping` | `Float` | The amount of high frequency damping of the delayed signal, between `0.0` and `1.0`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The output signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CombFilter {
+    comb_type: CombType,
+    max_delay: Float,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ring_buffer: Vec<Float>,
+    head: usize,
+    // state of the damping filter
+    damped: Float,
+
+    /// The delay time in seconds.
+    pub delay: Float,
+
+    /// The gain of the delayed signal.
+    pub feedback: Float,
+
+    /// The amount of damping of the delayed signal.
+    pub damping: Float,
+}
+
+impl CombFilter {
+    /// Creates a new `CombFilter` of the given type, which can delay signals by up to `max_delay` seconds.
+    pub fn new(comb_type: CombType, max_delay: Float) -> Self {
+        Self {
+            comb_type,
+            max_delay,
+            ring_buffer: vec![],
+            head: 0,
+            damped: 0.0,
+            delay: max_delay,
+            feedback: 0.5,
+            damping: 0.0,
+        }
+    }
+
+    /// Creates a new feedforward `CombFilter`, which can delay signals by up to `max_delay` seconds.
+    pub fn feedforward(max_delay: Float) -> Self {
+        Self::new(CombType::FeedForward, max_delay)
+    }
+
+    /// Creates a new feedback `CombFilter`, which can delay signals by up to `max_delay` seconds.
+    pub fn feedback(max_delay: Float) -> Self {
+        Self::new(CombType::Feedback, max_delay)
+    }
+
+    /// Returns the type of comb filter this is.
+    pub fn comb_type(&self) -> CombType {
+        self.comb_type
+    }
+
+    /// Returns the maximum delay time in seconds.
+    pub fn max_delay(&self) -> Float {
+        self.max_delay
+    }
+
+    #[inline]
+    fn read(&self, delay: Float) -> Float {
+        let len = self.ring_buffer.len();
+        let delay = delay.clamp(1.0, (len - 2) as Float);
+        let delay_floor = delay.floor();
+        let frac = delay - delay_floor;
+        let index = (self.head + len - delay_floor as usize) % len;
+        let prev_index = (index + len - 1) % len;
+        lerp(self.ring_buffer[index], self.ring_buffer[prev_index], frac)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for CombFilter {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("delay", SignalType::Float),
+            SignalSpec::new("feedback", SignalType::Float),
+            SignalSpec::new("damping", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        let len = (self.max_delay * sample_rate).ceil() as usize + 3;
+        self.ring_buffer.resize(len, 0.0);
+        self.head %= len;
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, delay, feedback, damping, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(delay) = delay {
+                self.delay = delay.clamp(0.0, self.max_delay);
+            }
+            if let Some(feedback) = feedback {
+                self.feedback = feedback.clamp(-1.0, 1.0);
+            }
+            if let Some(damping) = damping {
+                self.damping = damping.clamp(0.0, 1.0);
+            }
+
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                continue;
+            };
+
+            let delayed = self.read(self.delay * inputs.sample_rate());
+            self.damped = lerp(delayed, self.damped, self.damping);
+            let filtered = in_signal + self.feedback * self.damped;
+
+            self.ring_buffer[self.head] = match self.comb_type {
+                CombType::FeedForward => *in_signal,
+                CombType::Feedback => filtered,
+            };
+            self.head = (self.head + 1) % self.ring_buffer.len();
+
+            *out = Some(filtered);
+        }
+
+        Ok(())
+    }
+}
+
+/// A DC blocking filter, which removes any constant offset from a signal.
+///
+/// This is a 1-pole, 1-zero highpass filter with a very low cutoff frequency, cheap enough to put before every audio output.
+/// See also [`Node::dc_block`](crate::builder::node_builder::Node::dc_block).
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The output signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DcBlock {
+    cutoff: Float,
+    r: Float,
+    x1: Float,
+    y1: Float,
+}
+
+impl Default for DcBlock {
+    fn default() -> Self {
+        Self::new(10.0)
+    }
+}
+
+impl DcBlock {
+    /// Creates a new `DcBlock` filter with the given cutoff frequency.
+    pub fn new(cutoff: Float) -> Self {
+        Self {
+            cutoff,
+            r: 0.995,
+            x1: 0.0,
+            y1: 0.0,
+        }
+    }
+
+    /// Returns the cutoff frequency of the filter.
+    pub fn cutoff(&self) -> Float {
+        self.cutoff
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for DcBlock {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        self.r = Float::exp(-2.0 * PI * self.cutoff / sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, out) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                continue;
+            };
+
+            let filtered = in_signal - self.x1 + self.r * self.y1;
+            self.x1 = *in_signal;
+            self.y1 = filtered;
+
+            *out = Some(filtered);
+        }
+
+        Ok(())
+    }
+}
+
+// a second-order section in transposed direct form II
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct SecondOrderSection {
+    b0: Float,
+    b1: Float,
+    b2: Float,
+    a1: Float,
+    a2: Float,
+    z1: Float,
+    z2: Float,
+}
+
+impl SecondOrderSection {
+    // Butterworth lowpass, highpass, and allpass coefficients, from the RBJ Audio EQ Cookbook
+    fn set_lowpass(&mut self, frequency: Float, sample_rate: Float) {
+        self.set_coefficients(frequency, sample_rate, |cos_w0, _| {
+            ((1.0 - cos_w0) / 2.0, 1.0 - cos_w0, (1.0 - cos_w0) / 2.0)
+        });
+    }
+
+    fn set_highpass(&mut self, frequency: Float, sample_rate: Float) {
+        self.set_coefficients(frequency, sample_rate, |cos_w0, _| {
+            ((1.0 + cos_w0) / 2.0, -(1.0 + cos_w0), (1.0 + cos_w0) / 2.0)
+        });
+    }
+
+    fn set_allpass(&mut self, frequency: Float, sample_rate: Float) {
+        self.set_coefficients(frequency, sample_rate, |cos_w0, alpha| {
+            (1.0 - alpha, -2.0 * cos_w0, 1.0 + alpha)
+        });
+    }
+
+    fn set_coefficients(
+        &mut self,
+        frequency: Float,
+        sample_rate: Float,
+        numerator: impl FnOnce(Float, Float) -> (Float, Float, Float),
+    ) {
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / Float::sqrt(2.0);
+        let a0 = 1.0 + alpha;
+
+        let (b0, b1, b2) = numerator(cos_w0, alpha);
+
+        self.b0 = b0 / a0;
+        self.b1 = b1 / a0;
+        self.b2 = b2 / a0;
+        self.a1 = -2.0 * cos_w0 / a0;
+        self.a2 = (1.0 - alpha) / a0;
+    }
+
+    // sets coefficients that are already normalized, so that `a0` is 1
+    pub(crate) fn set_normalized(&mut self, [b0, b1, b2]: [Float; 3], [a1, a2]: [Float; 2]) {
+        self.b0 = b0;
+        self.b1 = b1;
+        self.b2 = b2;
+        self.a1 = a1;
+        self.a2 = a2;
+    }
+
+    #[inline]
+    pub(crate) fn process(&mut self, x: Float) -> Float {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+// one split point of a crossover
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct CrossoverSplit {
+    frequency: Float,
+    // two cascaded Butterworth sections each make a 4th-order Linkwitz-Riley filter
+    lowpass: [SecondOrderSection; 2],
+    highpass: [SecondOrderSection; 2],
+    // allpass sections that match the phase of the lower bands to this split
+    allpass: Vec<SecondOrderSection>,
+}
+
+impl CrossoverSplit {
+    fn set_coefficients(&mut self, sample_rate: Float) {
+        let frequency = self.frequency.clamp(1.0, sample_rate * 0.49);
+        for section in &mut self.lowpass {
+            section.set_lowpass(frequency, sample_rate);
+        }
+        for section in &mut self.highpass {
+            section.set_highpass(frequency, sample_rate);
+        }
+        for section in &mut self.allpass {
+            section.set_allpass(frequency, sample_rate);
+        }
+    }
+
+    #[inline]
+    fn split(&mut self, x: Float) -> (Float, Float) {
+        let [lp0, lp1] = &mut self.lowpass;
+        let [hp0, hp1] = &mut self.highpass;
+        (lp1.process(lp0.process(x)), hp1.process(hp0.process(x)))
+    }
+}
+
+/// A Linkwitz-Riley crossover, which splits a signal into 2 to 4 frequency bands.
+///
+/// Each split point uses 4th-order (24 dB/octave) Linkwitz-Riley filters, and the lower bands are phase-compensated for the higher split points, so the bands sum back to a flat (allpassed) signal.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1..=N` | `frequency0..` | `Float` | The frequencies to split the signal at, in ascending order. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0..=N` | `band0..` | `Float` | The frequency bands, from lowest to highest. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Crossover {
+    splits: Vec<CrossoverSplit>,
+    sample_rate: Float,
+}
+
+impl Crossover {
+    /// Creates a new `Crossover` that splits the signal at the given frequencies, producing one more band than there are frequencies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not between 1 and 3 frequencies.
+    pub fn new(frequencies: &[Float]) -> Self {
+        assert!(
+            (1..=3).contains(&frequencies.len()),
+            "crossover must have between 1 and 3 frequencies"
+        );
+        let splits = frequencies
+            .iter()
+            .enumerate()
+            .map(|(i, &frequency)| CrossoverSplit {
+                frequency,
+                // every band below this split needs an allpass to match its phase
+                allpass: vec![SecondOrderSection::default(); i],
+                ..Default::default()
+            })
+            .collect();
+        Self {
+            splits,
+            sample_rate: 0.0,
+        }
+    }
+
+    /// Returns the number of bands the signal is split into.
+    pub fn num_bands(&self) -> usize {
+        self.splits.len() + 1
+    }
+
+    // moves the given split point, if it has changed
+    #[inline]
+    pub(crate) fn set_frequency(&mut self, split: usize, frequency: Float) {
+        let split = &mut self.splits[split];
+        if frequency != split.frequency {
+            split.frequency = frequency;
+            split.set_coefficients(self.sample_rate);
+        }
+    }
+
+    // splits one sample of the signal into `bands`, which must have `num_bands` elements
+    #[inline]
+    pub(crate) fn split(&mut self, mut rest: Float, bands: &mut [Float]) {
+        let num_splits = self.splits.len();
+        for (band, value) in bands[..num_splits].iter_mut().enumerate() {
+            let (mut low, high) = self.splits[band].split(rest);
+            rest = high;
+
+            // match the phase of this band to the splits above it
+            for higher in &mut self.splits[band + 1..] {
+                low = higher.allpass[band].process(low);
+            }
+
+            *value = low;
+        }
+        bands[num_splits] = rest;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Crossover {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = vec![SignalSpec::new("in", SignalType::Float)];
+        for i in 0..self.splits.len() {
+            spec.push(SignalSpec::new(
+                format!("frequency{}", i),
+                SignalType::Float,
+            ));
+        }
+        spec
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        (0..self.num_bands())
+            .map(|i| SignalSpec::new(format!("band{}", i), SignalType::Float))
+            .collect()
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        self.sample_rate = sample_rate;
+        for split in &mut self.splits {
+            split.set_coefficients(sample_rate);
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let mut frequencies = (1..=self.splits.len())
+            .map(|i| inputs.iter_input_as_floats(i))
+            .collect::<Result<SmallVec<[_; 3]>, _>>()?;
+
+        for (sample_index, in_signal) in inputs
+            .iter_input_as_floats(0)?
+            .take(inputs.block_size())
+            .enumerate()
+        {
+            for (split, frequency) in frequencies.iter_mut().enumerate() {
+                if let Some(Some(frequency)) = frequency.next() {
+                    self.set_frequency(split, frequency);
+                }
+            }
+
+            let Some(in_signal) = in_signal else {
+                for band in 0..self.num_bands() {
+                    outputs.output(band).set_none(sample_index);
+                }
+                continue;
+            };
+
+            let mut bands = [0.0; 4];
+            let bands = &mut bands[..self.num_bands()];
+            self.split(in_signal, bands);
+            for (band, value) in bands.iter().enumerate() {
+                outputs.output(band).set_as::<Float>(sample_index, *value);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The kernel of a [`FirFilter`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FirDesign {
+    /// The given coefficients, used as-is.
+    Coefficients(Vec<Float>),
+    /// A windowed-sinc lowpass filter.
+    LowPass {
+        /// The cutoff frequency.
+        cutoff: Float,
+        /// The number of taps. Rounded up to an odd number.
+        taps: usize,
+    },
+    /// A windowed-sinc highpass filter.
+    HighPass {
+        /// The cutoff frequency.
+        cutoff: Float,
+        /// The number of taps. Rounded up to an odd number.
+        taps: usize,
+    },
+    /// A windowed-sinc bandpass filter.
+    BandPass {
+        /// The lower cutoff frequency.
+        low: Float,
+        /// The upper cutoff frequency.
+        high: Float,
+        /// The number of taps. Rounded up to an odd number.
+        taps: usize,
+    },
+}
+
+impl FirDesign {
+    /// Generates the filter kernel for the given sample rate.
+    pub fn kernel(&self, sample_rate: Float) -> Vec<Float> {
+        match *self {
+            FirDesign::Coefficients(ref coefficients) => coefficients.clone(),
+            FirDesign::LowPass { cutoff, taps } => windowed_sinc(cutoff / sample_rate, taps),
+            FirDesign::HighPass { cutoff, taps } => {
+                let mut kernel = windowed_sinc(cutoff / sample_rate, taps);
+                // spectral inversion
+                for x in kernel.iter_mut() {
+                    *x = -*x;
+                }
+                let center = kernel.len() / 2;
+                kernel[center] += 1.0;
+                kernel
+            }
+            FirDesign::BandPass { low, high, taps } => {
+                let mut kernel = windowed_sinc(high / sample_rate, taps);
+                let low = windowed_sinc(low / sample_rate, taps);
+                for (x, low) in kernel.iter_mut().zip(low) {
+                    *x -= low;
+                }
+                kernel
+            }
+        }
+    }
+}
+
+// a Blackman-windowed sinc lowpass kernel with unity gain at DC, for a cutoff given as a fraction of the sample rate
+pub(crate) fn windowed_sinc(cutoff: Float, taps: usize) -> Vec<Float> {
+    let taps = taps.max(1) | 1;
+    let cutoff = cutoff.clamp(0.0, 0.5);
+    let center = (taps / 2) as Float;
+
+    let mut kernel = apodize::blackman_iter(taps)
+        .enumerate()
+        .map(|(i, window)| {
+            let x = i as Float - center;
+            let sinc = if x == 0.0 {
+                2.0 * cutoff
+            } else {
+                Float::sin(2.0 * PI * cutoff * x) / (PI * x)
+            };
+            sinc * window as Float
+        })
+        .collect::<Vec<_>>();
+
+    let sum = kernel.iter().sum::<Float>();
+    if sum != 0.0 {
+        for x in kernel.iter_mut() {
+            *x /= sum;
+        }
+    }
+    kernel
+}
+
+// split into independent accumulators, so the compiler can vectorize the loop
+#[inline]
+pub(crate) fn dot_product(a: &[Float], b: &[Float]) -> Float {
+    const LANES: usize = 8;
+
+    let mut sums = [0.0; LANES];
+    let a_chunks = a.chunks_exact(LANES);
+    let b_chunks = b.chunks_exact(LANES);
+    let remainder = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(a, b)| a * b)
+        .sum::<Float>();
+
+    for (a, b) in a_chunks.zip(b_chunks) {
+        for i in 0..LANES {
+            sums[i] += a[i] * b[i];
+        }
+    }
+
+    sums.iter().sum::<Float>() + remainder
+}
+
+/// A finite impulse response filter, which convolves a signal with a fixed kernel.
+///
+/// The kernel is either given directly, or designed from a [`FirDesign`] when the filter is allocated.
+/// Designed kernels are symmetric, so they have a linear phase response, with a latency of half their length.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The filtered signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirFilter {
+    design: FirDesign,
+
+    // the kernel, reversed so it lines up with the history
+    #[cfg_attr(feature = "serde", serde(skip))]
+    kernel: Vec<Float>,
+    // the input history, stored twice in a row so the latest samples are always contiguous
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Vec<Float>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    head: usize,
+}
+
+impl FirFilter {
+    /// Creates a new `FirFilter` with the given kernel design.
+    pub fn new(design: FirDesign) -> Self {
+        Self {
+            design,
+            kernel: vec![],
+            history: vec![],
+            head: 0,
+        }
+    }
+
+    /// Creates a new `FirFilter` with the given coefficients.
+    pub fn with_coefficients(coefficients: impl Into<Vec<Float>>) -> Self {
+        Self::new(FirDesign::Coefficients(coefficients.into()))
+    }
+
+    /// Creates a new lowpass `FirFilter` with the given cutoff frequency and number of taps.
+    pub fn lowpass(cutoff: Float, taps: usize) -> Self {
+        Self::new(FirDesign::LowPass { cutoff, taps })
+    }
+
+    /// Creates a new highpass `FirFilter` with the given cutoff frequency and number of taps.
+    pub fn highpass(cutoff: Float, taps: usize) -> Self {
+        Self::new(FirDesign::HighPass { cutoff, taps })
+    }
+
+    /// Creates a new bandpass `FirFilter` with the given cutoff frequencies and number of taps.
+    pub fn bandpass(low: Float, high: Float, taps: usize) -> Self {
+        Self::new(FirDesign::BandPass { low, high, taps })
+    }
+
+    /// Returns the design of the filter's kernel.
+    pub fn design(&self) -> &FirDesign {
+        &self.design
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for FirFilter {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.kernel = self.design.kernel(sample_rate);
+        self.kernel.reverse();
+        if self.kernel.is_empty() {
+            self.kernel.push(0.0);
+        }
+        self.history = vec![0.0; self.kernel.len() * 2];
+        self.head = 0;
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let len = self.kernel.len();
+
+        for (in_signal, out) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                continue;
+            };
+
+            self.history[self.head] = *in_signal;
+            self.history[self.head + len] = *in_signal;
+
+            let window = &self.history[self.head + 1..self.head + 1 + len];
+            *out = Some(dot_product(window, &self.kernel));
+
+            self.head = (self.head + 1) % len;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Resonator {
+    frequency: Float,
+    gain: Float,
+    decay: Float,
+
+    // coefficients
+    b0: Float,
+    a1: Float,
+    a2: Float,
+
+    // input and output state
+    x1: Float,
+    x2: Float,
+    y1: Float,
+    y2: Float,
+}
+
+impl Default for Resonator {
+    fn default() -> Self {
+        Self {
+            frequency: 440.0,
+            gain: 1.0,
+            decay: 1.0,
+            b0: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+}
+
+impl Resonator {
+    fn set_coefficients(&mut self, sample_rate: Float) {
+        if sample_rate <= 0.0 {
+            return;
+        }
+        let frequency = self.frequency.clamp(0.0, sample_rate * 0.49);
+        let w = 2.0 * PI * frequency / sample_rate;
+        // the pole radius at which the resonator decays by 60 dB in `decay` seconds
+        let r = if self.decay > 0.0 {
+            Float::exp(-6.907755 / (self.decay * sample_rate))
+        } else {
+            0.0
+        };
+        // zeros at DC and Nyquist, normalized to unity gain at the resonant frequency
+        self.b0 = (1.0 - r * r) * 0.5 * self.gain;
+        self.a1 = -2.0 * r * w.cos();
+        self.a2 = r * r;
+    }
+
+    #[inline]
+    fn process(&mut self, x: Float) -> Float {
+        let y = self.b0 * (x - self.x2) - self.a1 * self.y1 - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// A bank of tuned resonators, each a 2-pole bandpass filter, for modal synthesis and vocoder-like effects.
+///
+/// The frequencies, gains, and decay times of the resonators are set from lists of floats, where the first element sets the first resonator, and so on.
+/// The number of frequencies sets how many resonators are active, up to the maximum the bank was created with.
+/// Resonators whose gain or decay time isn't given keep their previous value.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The excitation signal. |
+/// | `1` | `frequencies` | `List` | The resonant frequencies of the resonators. |
+/// | `2` | `gains` | `List` | The gains of the resonators. |
+/// | `3` | `decays` | `List` | The times the resonators take to decay by 60 dB, in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The sum of the resonators' outputs. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResonatorBank {
+    resonators: Vec<Resonator>,
+    num_active: usize,
+    sample_rate: Float,
+}
+
+impl ResonatorBank {
+    /// Creates a new `ResonatorBank` with up to the given number of resonators, all initially inactive.
+    pub fn new(max_resonators: usize) -> Self {
+        Self {
+            resonators: vec![Resonator::default(); max_resonators],
+            num_active: 0,
+            sample_rate: 0.0,
+        }
+    }
+
+    /// Sets the frequencies of the resonators, activating one resonator for each frequency.
+    pub fn with_frequencies(mut self, frequencies: &[Float]) -> Self {
+        self.set_frequencies(frequencies.iter().copied());
+        self
+    }
+
+    /// Sets the gains of the resonators.
+    pub fn with_gains(mut self, gains: &[Float]) -> Self {
+        self.set_gains(gains.iter().copied());
+        self
+    }
+
+    /// Sets the decay times of the resonators, in seconds.
+    pub fn with_decays(mut self, decays: &[Float]) -> Self {
+        self.set_decays(decays.iter().copied());
+        self
+    }
+
+    /// Returns the maximum number of resonators.
+    pub fn max_resonators(&self) -> usize {
+        self.resonators.len()
+    }
+
+    /// Returns the number of active resonators.
+    pub fn num_active(&self) -> usize {
+        self.num_active
+    }
+
+    fn set_frequencies(&mut self, frequencies: impl Iterator<Item = Float>) {
+        let mut num_active = 0;
+        for (resonator, frequency) in self.resonators.iter_mut().zip(frequencies) {
+            if resonator.frequency != frequency {
+                resonator.frequency = frequency;
+                resonator.set_coefficients(self.sample_rate);
+            }
+            num_active += 1;
+        }
+        // silence resonators as they're deactivated, so they don't ring when they come back
+        for resonator in &mut self.resonators[num_active..self.num_active.max(num_active)] {
+            resonator.x1 = 0.0;
+            resonator.x2 = 0.0;
+            resonator.y1 = 0.0;
+            resonator.y2 = 0.0;
+        }
+        self.num_active = num_active;
+    }
+
+    fn set_gains(&mut self, gains: impl Iterator<Item = Float>) {
+        for (resonator, gain) in self.resonators.iter_mut().zip(gains) {
+            if resonator.gain != gain {
+                resonator.gain = gain;
+                resonator.set_coefficients(self.sample_rate);
+            }
+        }
+    }
+
+    fn set_decays(&mut self, decays: impl Iterator<Item = Float>) {
+        for (resonator, decay) in self.resonators.iter_mut().zip(decays) {
+            if resonator.decay != decay {
+                resonator.decay = decay;
+                resonator.set_coefficients(self.sample_rate);
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for ResonatorBank {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("frequencies", SignalType::List),
+            SignalSpec::new("gains", SignalType::List),
+            SignalSpec::new("decays", SignalType::List),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        self.sample_rate = sample_rate;
+        for resonator in &mut self.resonators {
+            resonator.set_coefficients(sample_rate);
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, frequencies, gains, decays, out) in iter_proc_io_as!(
+            inputs as [Float, List, List, List],
+            outputs as [Float]
+        ) {
+            if let Some(frequencies) = frequencies {
+                self.set_frequencies(iter_list_floats(frequencies));
+            }
+            if let Some(gains) = gains {
+                self.set_gains(iter_list_floats(gains));
+            }
+            if let Some(decays) = decays {
+                self.set_decays(iter_list_floats(decays));
+            }
+
+            let Some(in_signal) = in_signal else {
+                *out = None;
+                continue;
+            };
+
+            let mut sum = 0.0;
+            for resonator in &mut self.resonators[..self.num_active] {
+                sum += resonator.process(*in_signal);
+            }
+
+            *out = Some(sum);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+//! Processors for multichannel [`Frame`] signals.
+
+use crate::{builder::node_builder::IntoOutput, prelude::*};
+
+/// A processor that merges separate channels into a single multichannel [`Frame`] signal.
+///
+/// Unconnected channels are silent.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0..N` | `in0..inN` | `Float` | The channels to merge. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Frame` | The merged frame. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameMerge {
+    channels: usize,
+}
+
+impl FrameMerge {
+    /// Creates a new [`FrameMerge`] processor with the given number of channels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channels` is greater than [`MAX_CHANNELS`].
+    pub fn new(channels: usize) -> Self {
+        assert!(
+            channels <= MAX_CHANNELS,
+            "frames can have at most {MAX_CHANNELS} channels, got {channels}"
+        );
+        Self { channels }
+    }
+
+    /// Returns the number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+}
+
+impl Default for FrameMerge {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for FrameMerge {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        (0..self.channels)
+            .map(|i| SignalSpec::new(format!("in{}", i), SignalType::Float))
+            .collect()
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Frame)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for frame in outputs.iter_output_mut_as::<Frame>(0)? {
+            *frame = Some(Frame::new(self.channels));
+        }
+
+        for channel in 0..self.channels {
+            let samples = inputs.iter_input_as_floats(channel)?;
+            for (frame, sample) in outputs.iter_output_mut_as::<Frame>(0)?.zip(samples) {
+                let frame = frame.as_mut().unwrap();
+                frame[channel] = sample.unwrap_or(0.0);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GraphBuilder {
+    /// Adds a node that merges the given left and right channels into a stereo [`Frame`] signal.
+    pub fn stereo(&self, left: impl IntoOutput, right: impl IntoOutput) -> Node {
+        let node = self.add(FrameMerge::new(2));
+        node.input(0).connect(left);
+        node.input(1).connect(right);
+        node
+    }
+}
+
+/// A processor that splits a multichannel [`Frame`] signal into separate channels.
+///
+/// Channels missing from the input frame are silent.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Frame` | The frame to split. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0..N` | `out0..outN` | `Float` | The channels of the frame, or `None` if the frame is `None`. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameSplit {
+    channels: usize,
+}
+
+impl FrameSplit {
+    /// Creates a new [`FrameSplit`] processor with the given number of channels.
+    pub fn new(channels: usize) -> Self {
+        Self { channels }
+    }
+
+    /// Returns the number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+}
+
+impl Default for FrameSplit {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for FrameSplit {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Frame)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        (0..self.channels)
+            .map(|i| SignalSpec::new(format!("out{}", i), SignalType::Float))
+            .collect()
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for channel in 0..self.channels {
+            let frames = inputs.iter_input_as::<Frame>(0)?;
+            for (out, frame) in outputs.iter_output_mut_as_floats(channel)?.zip(frames) {
+                *out = frame
+                    .as_ref()
+                    .map(|frame| frame.get(channel).copied().unwrap_or(0.0));
+            }
+        }
+
+        Ok(())
+    }
+}
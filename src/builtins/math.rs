@@ -1,6 +1,10 @@
 //! Mathematical processors.
 
-use crate::{prelude::*, processor::ProcessorError, signal::AnySignalMut};
+use crate::{
+    prelude::*,
+    processor::ProcessorError,
+    signal::{AnySignalMut, AnySignalRef},
+};
 use std::ops::{
     Add as AddOp, Div as DivOp, Mul as MulOp, Neg as NegOp, Rem as RemOp, Sub as SubOp,
 };
@@ -32,6 +36,11 @@ impl Constant {
     pub fn new_any(value: AnySignal) -> Self {
         Self { value }
     }
+
+    /// Returns the value this `Constant` outputs.
+    pub fn value(&self) -> &AnySignal {
+        &self.value
+    }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde)]
@@ -89,6 +98,10 @@ impl Processor for MidiToFreq {
         vec![SignalSpec::new("freq", SignalType::Float)]
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
@@ -96,7 +109,7 @@ impl Processor for MidiToFreq {
     ) -> Result<(), ProcessorError> {
         for (note, freq) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
             let note = note.unwrap_or_default();
-            *freq = Some(Float::powf(2.0, (note - 69.0) / 12.0) * 440.0);
+            *freq = Some(note_to_freq(note));
         }
 
         Ok(())
@@ -130,6 +143,10 @@ impl Processor for FreqToMidi {
         vec![SignalSpec::new("note", SignalType::Float)]
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
@@ -144,6 +161,276 @@ impl Processor for FreqToMidi {
     }
 }
 
+// converts a (possibly fractional) MIDI note number to a frequency in Hz
+#[inline]
+fn note_to_freq(note: Float) -> Float {
+    Float::powf(2.0, (note - 69.0) / 12.0) * 440.0
+}
+
+/// The quality of a [`Chord`], which determines the intervals of its notes above the root.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChordType {
+    /// A major triad (root, major third, perfect fifth).
+    #[default]
+    Major,
+    /// A minor triad (root, minor third, perfect fifth).
+    Minor,
+    /// A diminished triad (root, minor third, diminished fifth).
+    Diminished,
+    /// An augmented triad (root, major third, augmented fifth).
+    Augmented,
+    /// A suspended second chord (root, major second, perfect fifth).
+    Sus2,
+    /// A suspended fourth chord (root, perfect fourth, perfect fifth).
+    Sus4,
+    /// A major seventh chord.
+    Major7,
+    /// A minor seventh chord.
+    Minor7,
+    /// A dominant seventh chord.
+    Dominant7,
+    /// A half-diminished (minor seven flat five) chord.
+    HalfDiminished7,
+    /// A diminished seventh chord.
+    Diminished7,
+    /// A minor-major seventh chord.
+    MinorMajor7,
+    /// A major ninth chord.
+    Major9,
+    /// A minor ninth chord.
+    Minor9,
+    /// A dominant ninth chord.
+    Dominant9,
+    /// A power chord (root and perfect fifth).
+    Power,
+}
+
+impl ChordType {
+    const ALL: [Self; 16] = [
+        Self::Major,
+        Self::Minor,
+        Self::Diminished,
+        Self::Augmented,
+        Self::Sus2,
+        Self::Sus4,
+        Self::Major7,
+        Self::Minor7,
+        Self::Dominant7,
+        Self::HalfDiminished7,
+        Self::Diminished7,
+        Self::MinorMajor7,
+        Self::Major9,
+        Self::Minor9,
+        Self::Dominant9,
+        Self::Power,
+    ];
+
+    /// Returns the chord type with the given index, as used by the `chord` input of a [`Chord`], in the order the types are declared (`0` is major, `1` is minor, and so on).
+    pub fn from_index(index: i64) -> Option<Self> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| Self::ALL.get(index).copied())
+    }
+
+    /// Returns the intervals of the chord's notes above the root, in semitones, from lowest to highest.
+    pub fn intervals(self) -> &'static [Float] {
+        match self {
+            Self::Major => &[0.0, 4.0, 7.0],
+            Self::Minor => &[0.0, 3.0, 7.0],
+            Self::Diminished => &[0.0, 3.0, 6.0],
+            Self::Augmented => &[0.0, 4.0, 8.0],
+            Self::Sus2 => &[0.0, 2.0, 7.0],
+            Self::Sus4 => &[0.0, 5.0, 7.0],
+            Self::Major7 => &[0.0, 4.0, 7.0, 11.0],
+            Self::Minor7 => &[0.0, 3.0, 7.0, 10.0],
+            Self::Dominant7 => &[0.0, 4.0, 7.0, 10.0],
+            Self::HalfDiminished7 => &[0.0, 3.0, 6.0, 10.0],
+            Self::Diminished7 => &[0.0, 3.0, 6.0, 9.0],
+            Self::MinorMajor7 => &[0.0, 3.0, 7.0, 11.0],
+            Self::Major9 => &[0.0, 4.0, 7.0, 11.0, 14.0],
+            Self::Minor9 => &[0.0, 3.0, 7.0, 10.0, 14.0],
+            Self::Dominant9 => &[0.0, 4.0, 7.0, 10.0, 14.0],
+            Self::Power => &[0.0, 7.0],
+        }
+    }
+}
+
+// the most notes any chord type has
+const CHORD_MAX_NOTES: usize = 5;
+
+/// A processor that generates the frequencies of the notes of a chord, for playing chords on a bank of oscillators or voices.
+///
+/// Each inversion moves the lowest note of the chord up an octave.
+/// The notes are output as a list of frequencies, and also on one output per voice.
+/// Voices beyond the number of notes in the chord double its notes in the octaves above, so every voice always plays a note of the chord.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `root` | `Float` | The root of the chord, as a MIDI note number. |
+/// | `1` | `chord` | `Int` | The index of the chord type (see [`ChordType::from_index`]). |
+/// | `2` | `inversion` | `Int` | The inversion of the chord. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `freqs` | `List` | The frequencies of the chord's notes, from lowest to highest. |
+/// | `1..=N` | `voice0..` | `Float` | The frequency of each voice. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chord {
+    num_voices: usize,
+
+    /// The root of the chord, as a MIDI note number.
+    pub root: Float,
+    /// The chord type.
+    pub chord_type: ChordType,
+    /// The inversion of the chord.
+    pub inversion: i64,
+
+    // the chord the outputs were last computed for, and its notes as MIDI note numbers and as a list of frequencies
+    #[cfg_attr(feature = "serde", serde(skip))]
+    computed: Option<(Float, ChordType, i64)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    notes: [Float; CHORD_MAX_NOTES],
+    #[cfg_attr(feature = "serde", serde(skip))]
+    freqs: Option<List>,
+}
+
+impl Chord {
+    /// Creates a new `Chord` processor of the given type, with the given number of voice outputs.
+    pub fn new(chord_type: ChordType, num_voices: usize) -> Self {
+        Self {
+            num_voices,
+            root: 60.0,
+            chord_type,
+            inversion: 0,
+            computed: None,
+            notes: [0.0; CHORD_MAX_NOTES],
+            freqs: None,
+        }
+    }
+
+    /// Sets the root of the chord, as a MIDI note number.
+    pub fn with_root(mut self, root: Float) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// Sets the inversion of the chord.
+    pub fn with_inversion(mut self, inversion: i64) -> Self {
+        self.inversion = inversion;
+        self
+    }
+
+    /// Returns the number of voice outputs.
+    pub fn num_voices(&self) -> usize {
+        self.num_voices
+    }
+
+    // recomputes the notes if the chord has changed since they were last computed
+    fn update(&mut self) {
+        let chord = (self.root, self.chord_type, self.inversion);
+        if self.computed == Some(chord) {
+            return;
+        }
+        self.computed = Some(chord);
+
+        let intervals = self.chord_type.intervals();
+        let len = intervals.len();
+        let inversion = self.inversion.max(0) as usize;
+        for (i, note) in self.notes[..len].iter_mut().enumerate() {
+            // the notes of an inversion are the notes of the root position, starting from a later note
+            let index = i + inversion;
+            *note = self.root + intervals[index % len] + 12.0 * (index / len) as Float;
+        }
+
+        let notes = &self.notes[..len];
+        match &mut self.freqs {
+            Some(freqs) if freqs.len() == len => {
+                for (slot, &note) in freqs.iter_mut().zip(notes) {
+                    *slot = AnySignal::Float(Some(note_to_freq(note)));
+                }
+            }
+            // only reallocate when the number of notes changes
+            freqs => *freqs = Some(List::new(notes.iter().map(|&note| note_to_freq(note)))),
+        }
+    }
+
+    // the frequency of the given voice
+    fn voice(&self, voice: usize) -> Float {
+        let len = self.chord_type.intervals().len();
+        note_to_freq(self.notes[voice % len] + 12.0 * (voice / len) as Float)
+    }
+}
+
+impl Default for Chord {
+    fn default() -> Self {
+        Self::new(ChordType::Major, 3)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Chord {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("root", SignalType::Float),
+            SignalSpec::new("chord", SignalType::Int),
+            SignalSpec::new("inversion", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = vec![SignalSpec::new("freqs", SignalType::List)];
+        spec.extend(
+            (0..self.num_voices).map(|i| SignalSpec::new(format!("voice{}", i), SignalType::Float)),
+        );
+        spec
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let mut chord_types = inputs.iter_input_as_ints(1)?;
+        let mut inversions = inputs.iter_input_as_ints(2)?;
+
+        for (sample_index, root) in inputs
+            .iter_input_as_floats(0)?
+            .take(inputs.block_size())
+            .enumerate()
+        {
+            if let Some(root) = root {
+                self.root = root;
+            }
+            if let Some(chord_type) = chord_types.next().flatten().and_then(ChordType::from_index) {
+                self.chord_type = chord_type;
+            }
+            if let Some(inversion) = inversions.next().flatten() {
+                self.inversion = inversion;
+            }
+
+            self.update();
+
+            outputs
+                .output(0)
+                .set(sample_index, AnySignalRef::List(&self.freqs));
+            for voice in 0..self.num_voices {
+                let freq = self.voice(voice);
+                outputs
+                    .output(voice + 1)
+                    .set_as::<Float>(sample_index, freq);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// A processor that executes an arbitrary mathematical expression using the [`evalexpr`] crate.
 ///
 /// This processor is currently limited to only [`Float`] inputs, and the expression must evaluate to a single [`Float`] output.
@@ -245,6 +532,178 @@ impl Processor for Expr {
     }
 }
 
+// the number of samples the block kernels below process at a time
+const LANES: usize = 8;
+
+// reads a chunk of an input as plain values if every sample is `Some`, or repeats the held value if the input is unconnected
+#[inline(always)]
+fn load_lanes<T: Copy + Default>(
+    chunk: Option<&[Option<T>]>,
+    held: Option<T>,
+) -> Option<[T; LANES]> {
+    match chunk {
+        Some(chunk) => {
+            if chunk.len() != LANES || chunk.iter().any(Option::is_none) {
+                return None;
+            }
+            Some(std::array::from_fn(|i| chunk[i].unwrap_or_default()))
+        }
+        None => Some([held.unwrap_or_default(); LANES]),
+    }
+}
+
+// applies a binary operation to a block, holding each input's last value through `None`s like the per-sample path does
+// chunks of `LANES` samples without any `None`s are processed as plain arrays, which the compiler can vectorize
+// when processing in place, `a` is ignored and its values are read from `out` instead
+#[allow(clippy::too_many_arguments)]
+fn binary_kernel<T: Copy + Default>(
+    out: &mut [Option<T>],
+    a: Option<&[Option<T>]>,
+    b: Option<&[Option<T>]>,
+    held_a: &mut Option<T>,
+    held_b: &mut Option<T>,
+    in_place: bool,
+    op: impl Fn(T, T) -> T,
+) {
+    let a = if in_place { None } else { a };
+
+    if !in_place && a.is_none() && b.is_none() {
+        let value = match (*held_a, *held_b) {
+            (None, None) => None,
+            (a, b) => Some(op(a.unwrap_or_default(), b.unwrap_or_default())),
+        };
+        out.fill(value);
+        return;
+    }
+
+    let len = [a.map(<[_]>::len), b.map(<[_]>::len)]
+        .into_iter()
+        .flatten()
+        .fold(out.len(), usize::min);
+
+    for (chunk_index, out) in out[..len].chunks_mut(LANES).enumerate() {
+        let start = chunk_index * LANES;
+        let end = start + out.len();
+        let in_place_a: [Option<T>; LANES] = std::array::from_fn(|i| out.get(i).copied().flatten());
+        let a = if in_place {
+            Some(&in_place_a[..out.len()])
+        } else {
+            a.map(|a| &a[start..end])
+        };
+        let b = b.map(|b| &b[start..end]);
+
+        if let (Some(a_lanes), Some(b_lanes)) = (load_lanes(a, *held_a), load_lanes(b, *held_b)) {
+            let mut values = [T::default(); LANES];
+            for i in 0..LANES {
+                values[i] = op(a_lanes[i], b_lanes[i]);
+            }
+            for (out, value) in out.iter_mut().zip(values) {
+                *out = Some(value);
+            }
+            if a.is_some() {
+                *held_a = Some(a_lanes[LANES - 1]);
+            }
+            if b.is_some() {
+                *held_b = Some(b_lanes[LANES - 1]);
+            }
+            continue;
+        }
+
+        for (i, out) in out.iter_mut().enumerate() {
+            if let Some(value) = a.and_then(|a| a[i]) {
+                *held_a = Some(value);
+            }
+            if let Some(value) = b.and_then(|b| b[i]) {
+                *held_b = Some(value);
+            }
+            *out = match (*held_a, *held_b) {
+                (None, None) => None,
+                (a, b) => Some(op(a.unwrap_or_default(), b.unwrap_or_default())),
+            };
+        }
+    }
+}
+
+// applies a unary operation to a block, holding the input's last value through `None`s like the per-sample path does
+// when processing in place, `a` is ignored and its values are read from `out` instead
+fn unary_kernel<T: Copy + Default>(
+    out: &mut [Option<T>],
+    a: Option<&[Option<T>]>,
+    held: &mut Option<T>,
+    in_place: bool,
+    op: impl Fn(T) -> T,
+) {
+    let a = match (in_place, a) {
+        (true, _) => None,
+        (false, Some(a)) => Some(a),
+        (false, None) => {
+            out.fill(held.map(op));
+            return;
+        }
+    };
+
+    let len = a.map_or(out.len(), |a| a.len().min(out.len()));
+
+    for (chunk_index, out) in out[..len].chunks_mut(LANES).enumerate() {
+        let start = chunk_index * LANES;
+        let in_place_a: [Option<T>; LANES] = std::array::from_fn(|i| out.get(i).copied().flatten());
+        let a = match a {
+            Some(a) => &a[start..start + out.len()],
+            None => &in_place_a[..out.len()],
+        };
+
+        if let Some(lanes) = load_lanes(Some(a), *held) {
+            let mut values = [T::default(); LANES];
+            for i in 0..LANES {
+                values[i] = op(lanes[i]);
+            }
+            for (out, value) in out.iter_mut().zip(values) {
+                *out = Some(value);
+            }
+            *held = Some(lanes[LANES - 1]);
+            continue;
+        }
+
+        for (out, a) in out.iter_mut().zip(a) {
+            if a.is_some() {
+                *held = *a;
+            }
+            *out = held.map(&op);
+        }
+    }
+}
+
+// the value of a frame operand on the given channel: frames apply per channel, floats are broadcast to every channel, and anything missing is silent
+fn frame_operand(signal: &AnySignal, channel: usize) -> Float {
+    match signal {
+        AnySignal::Frame(Some(frame)) => frame.get(channel).copied().unwrap_or(0.0),
+        AnySignal::Float(Some(value)) => *value,
+        _ => 0.0,
+    }
+}
+
+// applies `op` to each channel of two frame operands (see `frame_operand`), outputting as many channels as the widest frame
+fn frame_binary_op(
+    out: &mut Option<Frame>,
+    a: &AnySignal,
+    b: &AnySignal,
+    op: impl Fn(Float, Float) -> Float,
+) {
+    let channels = |signal: &AnySignal| match signal {
+        AnySignal::Frame(Some(frame)) => Some(frame.channels()),
+        _ => None,
+    };
+    let Some(channels) = channels(a).max(channels(b)) else {
+        *out = None;
+        return;
+    };
+    let mut frame = Frame::new(channels);
+    for (i, x) in frame.iter_mut().enumerate() {
+        *x = op(frame_operand(a, i), frame_operand(b, i));
+    }
+    *out = Some(frame);
+}
+
 macro_rules! impl_binary_proc {
     ($name:ident, $method:ident, ($($data:ident = $ty:ty),*), $doc:literal) => {
         #[derive(Clone, Debug)]
@@ -258,9 +717,42 @@ macro_rules! impl_binary_proc {
         impl $name {
             #[doc = concat!("Creates a new `", stringify!($name), "` processor.")]
             pub fn new(signal_type: SignalType) -> Self {
+                Self::with_types(signal_type, signal_type)
+            }
+
+            #[doc = concat!("Creates a new `", stringify!($name), "` processor whose inputs have the given types.")]
+            ///
+            /// The types must be the same, except that a `Float` input can be combined with a `Frame` input, in which case it applies to every channel and the output is a `Frame`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the types can't be combined.
+            #[track_caller]
+            pub fn with_types(a: SignalType, b: SignalType) -> Self {
+                assert!(
+                    a.is_compatible_with(&b)
+                        || matches!(
+                            (a, b),
+                            (SignalType::Frame, SignalType::Float)
+                                | (SignalType::Float, SignalType::Frame)
+                        ),
+                    "{}: cannot combine {:?} and {:?} inputs",
+                    stringify!($name),
+                    a,
+                    b
+                );
                 Self {
-                    a: AnySignal::default_of_type(&signal_type),
-                    b: AnySignal::default_of_type(&signal_type),
+                    a: AnySignal::default_of_type(&a),
+                    b: AnySignal::default_of_type(&b),
+                }
+            }
+
+            // the type of the output, which is a frame if either input is
+            fn output_type(&self) -> SignalType {
+                if self.b.signal_type() == SignalType::Frame {
+                    SignalType::Frame
+                } else {
+                    self.a.signal_type()
                 }
             }
         }
@@ -275,14 +767,35 @@ macro_rules! impl_binary_proc {
             }
 
             fn output_spec(&self) -> Vec<SignalSpec> {
-                vec![SignalSpec::new("out", self.a.signal_type())]
+                vec![SignalSpec::new("out", self.output_type())]
+            }
+
+            fn can_process_in_place(&self) -> bool {
+                $(self.output_type() == <$ty>::signal_type())||*
+            }
+
+            fn is_pure(&self) -> bool {
+                true
             }
 
             fn process(
                 &mut self,
                 inputs: ProcessorInputs,
-                outputs: ProcessorOutputs,
+                mut outputs: ProcessorOutputs,
             ) -> Result<(), ProcessorError> {
+                // plain numeric signals are processed a whole block at a time
+                $(if self.output_type() == <$ty>::signal_type() {
+                    let a = inputs.input_as_slice::<$ty>(0)?;
+                    let b = inputs.input_as_slice::<$ty>(1)?;
+                    let out = outputs.output_as_slice_mut::<$ty>(0)?;
+                    let held_a = self.a.as_type_mut::<$ty>().unwrap();
+                    let held_b = self.b.as_type_mut::<$ty>().unwrap();
+                    binary_kernel(out, a, b, held_a, held_b, inputs.in_place, |a: $ty, b: $ty| {
+                        a.$method(b)
+                    });
+                    return Ok(());
+                })*
+
                 for (in1, in2, sample) in iter_proc_io_as!(inputs as [Any, Any], outputs as [Any]) {
                     if let Some(in1) = in1 {
                         if in1.signal_type() != self.a.signal_type() {
@@ -329,10 +842,13 @@ macro_rules! impl_binary_proc {
                                 }
                             }
                         })*
+                        AnySignalMut::Frame(sample) => {
+                            frame_binary_op(sample, &self.a, &self.b, |a: Float, b: Float| a.$method(b));
+                        }
                         sample => {
                             return Err(ProcessorError::OutputSpecMismatch {
                                 index: 0,
-                                expected: self.a.signal_type(),
+                                expected: self.output_type(),
                                 actual: sample.signal_type(),
                             });
                         }
@@ -434,11 +950,28 @@ macro_rules! impl_unary_proc {
                 vec![SignalSpec::new("out", self.a.signal_type())]
             }
 
+            fn can_process_in_place(&self) -> bool {
+                $(self.a.signal_type() == <$ty>::signal_type())||*
+            }
+
+            fn is_pure(&self) -> bool {
+                true
+            }
+
             fn process(
                 &mut self,
                 inputs: ProcessorInputs,
-                outputs: ProcessorOutputs,
+                mut outputs: ProcessorOutputs,
             ) -> Result<(), ProcessorError> {
+                // plain numeric signals are processed a whole block at a time
+                $(if self.a.signal_type() == <$ty>::signal_type() {
+                    let a = inputs.input_as_slice::<$ty>(0)?;
+                    let out = outputs.output_as_slice_mut::<$ty>(0)?;
+                    let held = self.a.as_type_mut::<$ty>().unwrap();
+                    unary_kernel(out, a, held, inputs.in_place, |a: $ty| a.$method());
+                    return Ok(());
+                })*
+
                 for (a, sample) in iter_proc_io_as!(inputs as [Any], outputs as [Any]) {
                     if let Some(a) = a {
                         if a.signal_type() != self.a.signal_type() {
@@ -461,6 +994,10 @@ macro_rules! impl_unary_proc {
                             let a = self.a.as_type::<$ty>().unwrap().unwrap();
                             *sample = Some(a.$method());
                         })*
+                        AnySignalMut::Frame(sample) => {
+                            let a = self.a.as_type::<Frame>().unwrap().unwrap();
+                            *sample = Some(a.map(|a: Float| a.$method()));
+                        }
                         sample => {
                             return Err(ProcessorError::OutputSpecMismatch {
                                 index: 0,
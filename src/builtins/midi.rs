@@ -232,3 +232,279 @@ impl Processor for MidiChannel {
         Ok(())
     }
 }
+
+/// A processor that outputs the value of a MIDI control change (CC) message for a specific controller.
+///
+/// The output holds the last received value, normalized to the range `[0, 1]`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `midi` | `Midi` | The input MIDI message. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `value` | `Float` | The normalized value of the controller. |
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidiCC {
+    /// The controller number to listen for.
+    pub controller: u8,
+    value: Float,
+}
+
+impl MidiCC {
+    /// Creates a new `MidiCC` processor listening for the given controller number.
+    pub fn new(controller: u8) -> Self {
+        Self {
+            controller,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MidiCC {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("midi", SignalType::Midi)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("value", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (midi, out) in iter_proc_io_as!(inputs as [MidiMessage], outputs as [Float]) {
+            if let Some(msg) = midi {
+                if msg.is_control_change() && msg.data1() == self.controller {
+                    self.value = msg.data2() as Float / 127.0;
+                }
+            }
+
+            *out = Some(self.value);
+        }
+        Ok(())
+    }
+}
+
+/// A processor that outputs the value of MIDI pitch bend messages.
+///
+/// The output holds the last received value, normalized to the range `[-1, 1]`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `midi` | `Midi` | The input MIDI message. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `bend` | `Float` | The normalized pitch bend value. |
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidiPitchBend {
+    bend: Float,
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MidiPitchBend {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("midi", SignalType::Midi)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("bend", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (midi, out) in iter_proc_io_as!(inputs as [MidiMessage], outputs as [Float]) {
+            if let Some(msg) = midi {
+                if msg.is_pitch_bend() {
+                    let bend = msg.pitch_bend() as Float - 8192.0;
+                    self.bend = (bend / 8191.0).clamp(-1.0, 1.0);
+                }
+            }
+
+            *out = Some(self.bend);
+        }
+        Ok(())
+    }
+}
+
+/// A processor that turns a gate, a note number and a velocity into MIDI note on/off messages.
+///
+/// A note on message is output when the gate opens, and a note off message for the same note when it closes.
+/// On all other samples, the output is `None`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `gate` | `Bool` | The gate signal. |
+/// | `1` | `note` | `Float` | The note number, rounded to the nearest note. |
+/// | `2` | `velocity` | `Float` | The velocity in the range `[0, 1]`. Defaults to `1`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `midi` | `Midi` | The note on/off messages. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidiNoteMessage {
+    /// The MIDI channel to send on, in the range `0..16`.
+    pub channel: u8,
+    note: Float,
+    velocity: Float,
+    held_note: Option<u8>,
+}
+
+impl MidiNoteMessage {
+    /// Creates a new `MidiNoteMessage` processor sending on the given channel.
+    pub fn new(channel: u8) -> Self {
+        Self {
+            channel,
+            note: 60.0,
+            velocity: 1.0,
+            held_note: None,
+        }
+    }
+}
+
+impl Default for MidiNoteMessage {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MidiNoteMessage {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("gate", SignalType::Bool),
+            SignalSpec::new("note", SignalType::Float),
+            SignalSpec::new("velocity", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("midi", SignalType::Midi)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (gate, note, velocity, out) in iter_proc_io_as!(
+            inputs as [bool, Float, Float],
+            outputs as [MidiMessage]
+        ) {
+            if let Some(note) = note {
+                self.note = *note;
+            }
+            if let Some(velocity) = velocity {
+                self.velocity = *velocity;
+            }
+
+            *out = None;
+            match (gate, self.held_note) {
+                (Some(true), None) => {
+                    let note = self.note.round().clamp(0.0, 127.0) as u8;
+                    // a velocity of 0 would read as a note off
+                    let velocity = (self.velocity * 127.0).round().clamp(1.0, 127.0) as u8;
+                    *out = Some(MidiMessage::note_on(self.channel, note, velocity));
+                    self.held_note = Some(note);
+                }
+                (Some(false), Some(note)) => {
+                    *out = Some(MidiMessage::note_off(self.channel, note));
+                    self.held_note = None;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A processor that turns a value into MIDI control change (CC) messages for a specific controller.
+///
+/// A message is output whenever the value, scaled to the range `0..=127`, changes. On all other samples, the output is `None`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `value` | `Float` | The normalized value of the controller, in the range `[0, 1]`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `midi` | `Midi` | The control change messages. |
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidiCCMessage {
+    /// The controller number to send.
+    pub controller: u8,
+    /// The MIDI channel to send on, in the range `0..16`.
+    pub channel: u8,
+    last_value: Option<u8>,
+}
+
+impl MidiCCMessage {
+    /// Creates a new `MidiCCMessage` processor sending the given controller number on the given channel.
+    pub fn new(controller: u8, channel: u8) -> Self {
+        Self {
+            controller,
+            channel,
+            last_value: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MidiCCMessage {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("value", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("midi", SignalType::Midi)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (value, out) in iter_proc_io_as!(inputs as [Float], outputs as [MidiMessage]) {
+            *out = None;
+            if let Some(value) = value {
+                let value = (value * 127.0).round().clamp(0.0, 127.0) as u8;
+                if self.last_value != Some(value) {
+                    *out = Some(MidiMessage::control_change(
+                        self.channel,
+                        self.controller,
+                        value,
+                    ));
+                    self.last_value = Some(value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,32 +1,54 @@
 //! Built-in processors and utilities for the audio graph.
 
+pub mod analysis;
 pub mod control;
+pub mod distortion;
 pub mod dynamics;
+pub mod file;
 pub mod filters;
+pub mod frame;
 pub mod list;
 pub mod math;
 pub mod midi;
 pub mod oscillators;
+pub mod poly;
+pub mod spatial;
 pub mod storage;
+pub mod tap;
 pub mod time;
 pub mod util;
 
+#[cfg(feature = "rubato")]
+pub mod resample;
 #[cfg(feature = "fft")]
 pub mod simple_fft;
+#[cfg(feature = "fft")]
+pub mod spectral;
 
+pub use analysis::*;
 pub use control::*;
+pub use distortion::*;
 pub use dynamics::*;
+pub use file::*;
 pub use filters::*;
+pub use frame::*;
 pub use list::*;
 pub use math::*;
 pub use midi::*;
 pub use oscillators::*;
+pub use poly::*;
+pub use spatial::*;
 pub use storage::*;
+pub use tap::*;
 pub use time::*;
 pub use util::*;
 
+#[cfg(feature = "rubato")]
+pub use resample::*;
 #[cfg(feature = "fft")]
 pub use simple_fft::*;
+#[cfg(feature = "fft")]
+pub use spectral::*;
 
 use crate::{prelude::*, runtime::RuntimeError};
 
@@ -38,8 +60,22 @@ pub fn lerp(a: Float, b: Float, t: Float) -> Float {
     a + (b - a) * t
 }
 
+// reads a list's elements as floats, converting integers and treating anything else as 0
+pub(crate) fn iter_list_floats(list: &List) -> impl Iterator<Item = Float> + '_ {
+    list.iter().map(|signal| match signal {
+        AnySignal::Float(value) => value.unwrap_or_default(),
+        AnySignal::Int(value) => value.unwrap_or_default() as Float,
+        _ => 0.0,
+    })
+}
+
 /// A processor that runs a sub-graph.
 ///
+/// The audio inputs and outputs of the sub-graph become the inputs and outputs of the processor, and keep the names they were given with [`Graph::add_named_audio_input`] and [`Graph::add_named_audio_output`].
+/// Sub-graphs can be nested, cloned, and (with the `serde` feature) serialized along with the rest of the graph.
+///
+/// Unconnected inputs are passed to the sub-graph as `None`, and the sub-graph's [`Transport`] follows the parent graph's.
+///
 /// # Inputs
 ///
 /// The inputs of the sub-graph.
@@ -61,6 +97,7 @@ impl SubGraph {
         }
     }
 
+    /// Creates a new [`SubGraph`] processor, building the sub-graph with the given closure.
     pub fn build<F>(f: F) -> Self
     where
         F: FnOnce(&GraphBuilder),
@@ -69,22 +106,31 @@ impl SubGraph {
         f(&builder);
         Self::new(builder.build())
     }
+
+    /// Returns a reference to the sub-graph.
+    pub fn graph(&self) -> &Graph {
+        self.rt.graph()
+    }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl Processor for SubGraph {
     fn input_spec(&self) -> Vec<SignalSpec> {
+        let graph = self.rt.graph();
         let mut spec = vec![];
-        for (i, _input) in self.rt.graph().input_indices().iter().enumerate() {
-            spec.push(SignalSpec::new(format!("{}", i), SignalType::Float));
+        for i in 0..graph.num_audio_inputs() {
+            let name = graph.audio_input_name(i).unwrap();
+            spec.push(SignalSpec::new(name, SignalType::Float));
         }
         spec
     }
 
     fn output_spec(&self) -> Vec<SignalSpec> {
+        let graph = self.rt.graph();
         let mut spec = vec![];
-        for (i, _output) in self.rt.graph().output_indices().iter().enumerate() {
-            spec.push(SignalSpec::new(format!("{}", i), SignalType::Float));
+        for i in 0..graph.num_audio_outputs() {
+            let name = graph.audio_output_name(i).unwrap();
+            spec.push(SignalSpec::new(name, SignalType::Float));
         }
         spec
     }
@@ -97,6 +143,10 @@ impl Processor for SubGraph {
         self.rt.set_block_size(block_size).unwrap();
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rt.graph_mut().set_seed(seed);
+    }
+
     fn num_inputs(&self) -> usize {
         self.rt.graph().input_indices().len()
     }
@@ -110,15 +160,25 @@ impl Processor for SubGraph {
         inputs: ProcessorInputs,
         mut outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
+        // the parent may be processing part of a block, see `Runtime::process`
+        self.rt
+            .set_block_len(inputs.block_size())
+            .map_err(|_| ProcessorError::Other)?;
+
         for i in 0..self.num_inputs() {
-            let signal = inputs.input(i).ok_or(ProcessorError::NumInputsMismatch)?;
             let input = self
                 .rt
                 .get_input_mut(i)
                 .ok_or(ProcessorError::NumInputsMismatch)?;
-            input.clone_from(signal);
+            if let Some(signal) = inputs.input(i) {
+                input.clone_from(signal);
+            } else {
+                input.fill_default();
+            }
         }
 
+        self.rt.transport().sync_to(inputs.transport());
+
         match self.rt.process() {
             Ok(()) => {}
             Err(RuntimeError::GraphRunError(e)) => {
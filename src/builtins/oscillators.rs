@@ -4,10 +4,12 @@ use std::collections::VecDeque;
 
 use crate::{
     prelude::*,
-    processor::ProcessorOutputs,
+    processor::{ProcessMode, ProcessorOutputs},
     signal::{PI, TAU},
 };
 
+use super::{iter_list_floats, lerp};
+
 /// A processor that accumulates a phase value.
 ///
 /// The phase value will be incremented by the `increment` input signal each sample, and can be reset to 0 by the `reset` input signal.
@@ -269,7 +271,9 @@ impl Processor for SawOscillator {
 ///
 /// # Inputs
 ///
-/// None.
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible noise. |
 ///
 /// # Outputs
 ///
@@ -278,12 +282,24 @@ impl Processor for SawOscillator {
 /// | `0` | `out` | `Float` | The white noise value. |
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct NoiseOscillator;
+pub struct NoiseOscillator {
+    #[cfg_attr(feature = "serde", serde(default = "NoiseRng::from_entropy"))]
+    rng: NoiseRng,
+}
 
 impl NoiseOscillator {
-    /// Creates a new [`NoiseOscillator`] processor.
+    /// Creates a new [`NoiseOscillator`] processor with a random seed.
     pub fn new() -> Self {
-        Self
+        Self {
+            rng: NoiseRng::from_entropy(),
+        }
+    }
+
+    /// Creates a new [`NoiseOscillator`] processor with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: NoiseRng::new(seed),
+        }
     }
 }
 
@@ -296,185 +312,336 @@ impl Default for NoiseOscillator {
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl Processor for NoiseOscillator {
     fn input_spec(&self) -> Vec<SignalSpec> {
-        vec![]
+        vec![SignalSpec::new("seed", SignalType::Int)]
     }
 
     fn output_spec(&self) -> Vec<SignalSpec> {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
     fn process(
         &mut self,
-        _inputs: ProcessorInputs,
-        mut outputs: ProcessorOutputs,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        use rand::distributions::Distribution;
-        let mut rng = rand::thread_rng();
-        let dist = rand::distributions::Uniform::new(0.0, 1.0);
-        for out in outputs.iter_output_mut_as_floats(0)? {
-            // generate a random number
-            *out = Some(dist.sample(&mut rng));
+        for (seed, out) in iter_proc_io_as!(inputs as [i64], outputs as [Float]) {
+            self.rng.update_seed(*seed);
+            *out = Some(self.rng.next_unipolar());
         }
 
         Ok(())
     }
 }
 
-/// A processor that generates a band-limited sawtooth wave.
+// a small, fast random number generator (xorshift64*) whose state can be serialized, for reproducible noise
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct NoiseRng {
+    state: u64,
+    // the last seed received on the `seed` input
+    seed: Option<i64>,
+}
+
+impl NoiseRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        let mut this = Self {
+            state: 0,
+            seed: None,
+        };
+        this.reseed(seed);
+        this
+    }
+
+    pub(crate) fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        // scramble the seed with splitmix64, since xorshift can't have a state of 0
+        let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        self.state = (z ^ (z >> 31)).max(1);
+    }
+
+    // reseeds the generator when the `seed` input changes, returning whether it did
+    #[inline]
+    pub(crate) fn update_seed(&mut self, seed: Option<i64>) -> bool {
+        match seed {
+            Some(value) if seed != self.seed => {
+                self.seed = seed;
+                self.reseed(value as u64);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    // a uniformly distributed number in `[0, 1)`
+    #[inline]
+    pub(crate) fn next_unipolar(&mut self) -> Float {
+        // 24 bits are exactly representable as an `f32`, so this never rounds up to 1
+        (self.next_u64() >> 40) as Float / (1u64 << 24) as Float
+    }
+
+    // a uniformly distributed number in `[-1, 1)`
+    #[inline]
+    pub(crate) fn next_bipolar(&mut self) -> Float {
+        self.next_unipolar() * 2.0 - 1.0
+    }
+
+    // a normally distributed number with a mean of 0 and a standard deviation of 1, using the Box-Muller transform
+    #[inline]
+    pub(crate) fn next_gaussian(&mut self) -> Float {
+        let radius = (-2.0 * (1.0 - self.next_unipolar()).ln()).sqrt();
+        radius * (TAU * self.next_unipolar()).cos()
+    }
+}
+
+/// A processor that generates bipolar pink noise, which has equal power per octave.
+///
+/// White noise is filtered with Paul Kellet's refined pinking filter, which is accurate to within 0.05 dB above 9 Hz.
 ///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `frequency` | `Float` | The frequency of the sawtooth wave. |
+/// | `0` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible noise. |
 ///
 /// # Outputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `out` | `Float` | The sawtooth wave value. |
+/// | `0` | `out` | `Float` | The pink noise value. |
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct BlSawOscillator {
-    p: Float,
-    dp: Float,
-    saw: Float,
-
-    /// The frequency of the sawtooth wave.
-    pub frequency: Float,
+pub struct PinkNoise {
+    rng: NoiseRng,
+    b: [Float; 7],
 }
 
-impl Default for BlSawOscillator {
-    fn default() -> Self {
+impl PinkNoise {
+    /// Creates a new [`PinkNoise`] processor with a random seed.
+    pub fn new() -> Self {
         Self {
-            p: 0.0,
-            dp: 1.0,
-            saw: 0.0,
-            frequency: 0.0,
+            rng: NoiseRng::from_entropy(),
+            b: [0.0; 7],
         }
     }
-}
 
-impl BlSawOscillator {
-    /// Creates a new [`BlSawOscillator`] processor with the given frequency.
-    pub fn new(frequency: Float) -> Self {
+    /// Creates a new [`PinkNoise`] processor with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
         Self {
-            frequency,
-            ..Default::default()
+            rng: NoiseRng::new(seed),
+            b: [0.0; 7],
         }
     }
 }
 
+impl Default for PinkNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg_attr(feature = "serde", typetag::serde)]
-impl Processor for BlSawOscillator {
+impl Processor for PinkNoise {
     fn input_spec(&self) -> Vec<SignalSpec> {
-        vec![SignalSpec::new("frequency", SignalType::Float)]
+        vec![SignalSpec::new("seed", SignalType::Int)]
     }
 
     fn output_spec(&self) -> Vec<SignalSpec> {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        // algorithm courtesy of https://www.musicdsp.org/en/latest/Synthesis/12-bandlimited-waveforms.html
-        for (frequency, out) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
-            self.frequency = frequency.unwrap_or(self.frequency);
-            if self.frequency <= 0.0 {
-                *out = None;
-                continue;
-            }
+        // http://www.firstpr.com.au/dsp/pink-noise/
+        for (seed, out) in iter_proc_io_as!(inputs as [i64], outputs as [Float]) {
+            self.rng.update_seed(*seed);
+
+            let white = self.rng.next_bipolar();
+            let b = &mut self.b;
+            b[0] = 0.99886 * b[0] + white * 0.0555179;
+            b[1] = 0.99332 * b[1] + white * 0.0750759;
+            b[2] = 0.96900 * b[2] + white * 0.1538520;
+            b[3] = 0.86650 * b[3] + white * 0.3104856;
+            b[4] = 0.55000 * b[4] + white * 0.5329522;
+            b[5] = -0.7616 * b[5] - white * 0.0168980;
+            let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+            b[6] = white * 0.115926;
+
+            // scale to roughly unity peak amplitude
+            *out = Some(pink * 0.11);
+        }
 
-            let pmax = 0.5 * inputs.sample_rate() / self.frequency;
-            let dc = -0.498 / pmax;
+        Ok(())
+    }
+}
 
-            self.p += self.dp;
-            if self.p < 0.0 {
-                self.p = -self.p;
-                self.dp = -self.dp;
-            } else if self.p > pmax {
-                self.p = 2.0 * pmax - self.p;
-                self.dp = -self.dp;
-            }
+/// A processor that generates bipolar brown (Brownian) noise, whose power falls by 6 dB per octave.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible noise. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The brown noise value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrownNoise {
+    rng: NoiseRng,
+    last: Float,
+}
 
-            let mut x = PI * self.p;
-            if x < 0.00001 {
-                x = 0.00001;
-            }
+impl BrownNoise {
+    /// Creates a new [`BrownNoise`] processor with a random seed.
+    pub fn new() -> Self {
+        Self {
+            rng: NoiseRng::from_entropy(),
+            last: 0.0,
+        }
+    }
 
-            self.saw = 0.995 * self.saw + dc + x.sin() / x;
+    /// Creates a new [`BrownNoise`] processor with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: NoiseRng::new(seed),
+            last: 0.0,
+        }
+    }
+}
 
-            *out = Some(self.saw);
+impl Default for BrownNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for BrownNoise {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("seed", SignalType::Int)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (seed, out) in iter_proc_io_as!(inputs as [i64], outputs as [Float]) {
+            self.rng.update_seed(*seed);
+
+            // a leaky integrator, so the output doesn't drift away from zero
+            let white = self.rng.next_bipolar();
+            self.last = (self.last + 0.02 * white) / 1.02;
+
+            // scale to roughly unity peak amplitude
+            *out = Some(self.last * 3.5);
         }
 
         Ok(())
     }
 }
 
-const BL_SQUARE_MAX_HARMONICS: usize = 512;
-
-/// A processor that generates a band-limited square/pulse wave.
+/// A processor that generates velvet noise: sparse impulses of random sign at random positions, one in each period of the given density.
+///
+/// Velvet noise sounds smoother than white noise at high densities, and is useful for decorrelation and efficient reverbs.
 ///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `frequency` | `Float` | The frequency of the square wave. |
-/// | `1` | `pulse_width` | `Float` | The pulse width of the square wave. |
-/// | `2` | `reset` | `Bool` | Whether to reset the phase accumulator to 0. |
+/// | `0` | `density` | `Float` | The number of impulses per second. |
+/// | `1` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible noise. |
 ///
 /// # Outputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `out` | `Float` | The square wave value. |
+/// | `0` | `out` | `Float` | The velvet noise value (`-1.0`, `0.0`, or `1.0`). |
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct BlSquareOscillator {
-    // phase accumulator
-    t: Float,
-    // phase increment per sample
-    t_step: Float,
-
-    // band-limited square wave coefficients
-    coeff: Box<[Float]>,
-
-    /// The frequency of the square wave.
-    pub frequency: Float,
-
-    /// The pulse width of the square wave (0.0 to 1.0).
-    pub pulse_width: Float,
+pub struct VelvetNoise {
+    rng: NoiseRng,
+    // the position in the current period, in samples
+    position: Float,
+    // where in the current period the impulse falls, in samples
+    impulse_at: Float,
+    impulse_sign: Float,
+    fired: bool,
+
+    /// The number of impulses per second.
+    pub density: Float,
 }
 
-impl Default for BlSquareOscillator {
-    fn default() -> Self {
-        Self::new(0.0, 0.5)
+impl VelvetNoise {
+    /// Creates a new [`VelvetNoise`] processor with the given density and a random seed.
+    pub fn new(density: Float) -> Self {
+        Self::with_rng(density, NoiseRng::from_entropy())
     }
-}
 
-impl BlSquareOscillator {
-    /// Creates a new [`BlSquareOscillator`] processor with the given frequency and pulse width.
-    pub fn new(frequency: Float, pulse_width: Float) -> Self {
+    /// Creates a new [`VelvetNoise`] processor with the given density and seed.
+    pub fn with_seed(density: Float, seed: u64) -> Self {
+        Self::with_rng(density, NoiseRng::new(seed))
+    }
+
+    fn with_rng(density: Float, rng: NoiseRng) -> Self {
         Self {
-            frequency,
-            pulse_width,
-            t: 0.0,
-            t_step: 0.0,
-            coeff: Box::new([0.0; BL_SQUARE_MAX_HARMONICS]),
+            rng,
+            position: Float::INFINITY,
+            impulse_at: 0.0,
+            impulse_sign: 1.0,
+            fired: true,
+            density,
         }
     }
 }
 
+impl Default for VelvetNoise {
+    fn default() -> Self {
+        Self::new(2000.0)
+    }
+}
+
 #[cfg_attr(feature = "serde", typetag::serde)]
-impl Processor for BlSquareOscillator {
+impl Processor for VelvetNoise {
     fn input_spec(&self) -> Vec<SignalSpec> {
         vec![
-            SignalSpec::new("frequency", SignalType::Float),
-            SignalSpec::new("pulse_width", SignalType::Float),
-            SignalSpec::new("reset", SignalType::Bool),
+            SignalSpec::new("density", SignalType::Float),
+            SignalSpec::new("seed", SignalType::Int),
         ]
     }
 
@@ -482,104 +649,121 @@ impl Processor for BlSquareOscillator {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (frequency, pulse_width, reset, out) in iter_proc_io_as!(
-            inputs as [Float, Float, bool],
+        for (density, seed, out) in iter_proc_io_as!(
+            inputs as [Float, i64],
             outputs as [Float]
         ) {
-            self.frequency = frequency.unwrap_or(self.frequency);
-            if self.frequency <= 0.0 {
-                *out = None;
-                continue;
+            if self.rng.update_seed(*seed) {
+                // start a new period, so the same seed always produces the same impulses
+                self.position = Float::INFINITY;
             }
 
-            if reset.unwrap_or(false) {
-                self.t = 0.0;
+            self.density = density.unwrap_or(self.density);
+            if self.density <= 0.0 {
+                *out = Some(0.0);
+                continue;
             }
 
-            self.pulse_width = pulse_width.unwrap_or(self.pulse_width);
-
-            self.t_step = self.frequency / inputs.sample_rate();
-
-            let n_harm = (inputs.sample_rate() / (self.frequency * 4.0)) as usize;
-            self.coeff[0] = self.pulse_width - 0.5;
-            for i in 1..n_harm + 1 {
-                self.coeff[i] =
-                    Float::sin(i as Float * PI * self.pulse_width) * 2.0 / (i as Float * PI);
+            let period = (inputs.sample_rate() / self.density).max(1.0);
+            if self.position >= period {
+                self.position = 0.0;
+                self.impulse_at = (self.rng.next_unipolar() * period).floor();
+                self.impulse_sign = if self.rng.next_u64() & 1 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                self.fired = false;
             }
 
-            let theta = self.t * TAU;
-
-            let mut square = 0.0;
-            for i in 0..n_harm + 1 {
-                square += self.coeff[i] * (theta * i as Float).cos();
+            if !self.fired && self.position >= self.impulse_at {
+                self.fired = true;
+                *out = Some(self.impulse_sign);
+            } else {
+                *out = Some(0.0);
             }
 
-            self.t += self.t_step;
-
-            *out = Some(square);
+            self.position += 1.0;
         }
 
         Ok(())
     }
 }
 
-/// A processor that models a physical string vibrating at a given frequency using the Karplus-Strong algorithm.
+/// A processor that generates random impulses ("dust"), for crackle and texture, or as random triggers.
+///
+/// The `chaos` input blends between evenly spaced impulses of full amplitude (`0.0`) and impulses at random times with random amplitudes (`1.0`).
 ///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `trig` | `bool` | Triggers the pluck. |
-/// | `1` | `frequency` | `Float` | The frequency of the string. |
-/// | `2` | `damping` | `Float` | The damping factor of the string. |
+/// | `0` | `rate` | `Float` | The average number of impulses per second. |
+/// | `1` | `chaos` | `Float` | The randomness of the impulses' timing and amplitude (0.0 to 1.0). |
+/// | `2` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible impulses. |
 ///
 /// # Outputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `out` | `Float` | The string value. |
+/// | `0` | `out` | `Float` | The impulse value, which is `0.0` between impulses. |
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct KarplusStrong {
-    // delay line
-    ringbuf: VecDeque<Float>,
+pub struct Dust {
+    rng: NoiseRng,
+    // samples until the next impulse
+    countdown: Float,
 
-    /// The damping factor of the string.
-    pub damping: Float,
+    /// The average number of impulses per second.
+    pub rate: Float,
 
-    /// The frequency of the string.
-    pub frequency: Float,
+    /// The randomness of the impulses (0.0 to 1.0).
+    pub chaos: Float,
 }
 
-impl KarplusStrong {
-    /// Creates a new [`KarplusStrong`] processor with the given frequency, damping factor, and pluck position.
-    pub fn new(frequency: Float, damping: Float) -> Self {
+impl Dust {
+    /// Creates a new [`Dust`] processor with the given rate, chaos, and a random seed.
+    pub fn new(rate: Float, chaos: Float) -> Self {
+        Self::with_rng(rate, chaos, NoiseRng::from_entropy())
+    }
+
+    /// Creates a new [`Dust`] processor with the given rate, chaos, and seed.
+    pub fn with_seed(rate: Float, chaos: Float, seed: u64) -> Self {
+        Self::with_rng(rate, chaos, NoiseRng::new(seed))
+    }
+
+    fn with_rng(rate: Float, chaos: Float, rng: NoiseRng) -> Self {
         Self {
-            ringbuf: VecDeque::new(),
-            damping,
-            frequency,
+            rng,
+            countdown: 0.0,
+            rate,
+            chaos,
         }
     }
 }
 
-impl Default for KarplusStrong {
+impl Default for Dust {
     fn default() -> Self {
-        Self::new(0.0, 0.5)
+        Self::new(10.0, 1.0)
     }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde)]
-impl Processor for KarplusStrong {
+impl Processor for Dust {
     fn input_spec(&self) -> Vec<SignalSpec> {
         vec![
-            SignalSpec::new("trig", SignalType::Bool),
-            SignalSpec::new("frequency", SignalType::Float),
-            SignalSpec::new("damping", SignalType::Float),
+            SignalSpec::new("rate", SignalType::Float),
+            SignalSpec::new("chaos", SignalType::Float),
+            SignalSpec::new("seed", SignalType::Int),
         ]
     }
 
@@ -587,8 +771,8 @@ impl Processor for KarplusStrong {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
-    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
-        self.ringbuf = VecDeque::with_capacity(sample_rate as usize / 2);
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
     }
 
     fn process(
@@ -596,26 +780,1742 @@ impl Processor for KarplusStrong {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (trig, frequency, damping, out) in iter_proc_io_as!(
-            inputs as [bool, Float, Float],
+        for (rate, chaos, seed, out) in iter_proc_io_as!(
+            inputs as [Float, Float, i64],
             outputs as [Float]
         ) {
-            self.frequency = frequency.unwrap_or(self.frequency);
-            if self.frequency <= 0.0 {
-                *out = None;
-                continue;
+            if self.rng.update_seed(*seed) {
+                self.countdown = 0.0;
             }
 
-            self.damping = damping.unwrap_or(self.damping);
+            self.rate = rate.unwrap_or(self.rate);
+            self.chaos = chaos.unwrap_or(self.chaos).clamp(0.0, 1.0);
 
-            if trig.unwrap_or(false) {
-                // calculate the delay line index
-                let delay_time = (inputs.sample_rate() / self.frequency) as usize;
+            if self.rate <= 0.0 {
+                *out = Some(0.0);
+                continue;
+            }
 
-                // initialize the delay line with noise
+            self.countdown -= 1.0;
+            if self.countdown > 0.0 {
+                *out = Some(0.0);
+                continue;
+            }
+
+            // exponentially distributed intervals make the impulses a Poisson process
+            let period = inputs.sample_rate() / self.rate;
+            let random_interval = -(1.0 - self.rng.next_unipolar()).ln();
+            self.countdown += period * (1.0 - self.chaos + self.chaos * random_interval);
+
+            let amplitude = 1.0 - self.chaos * self.rng.next_unipolar();
+            *out = Some(amplitude);
+        }
+
+        Ok(())
+    }
+}
+
+/// The distribution of the steps taken by a [`RandomWalk`] or [`Drunk`] processor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StepDistribution {
+    /// Steps are equally likely to be any size up to the maximum step.
+    #[default]
+    Uniform,
+    /// Steps follow a normal distribution whose standard deviation is the step size, so small steps are common and large ones are rare.
+    Gaussian,
+}
+
+impl StepDistribution {
+    /// Returns the distribution with the given index, as used by the `distribution` inputs (`0` is uniform and `1` is gaussian).
+    pub fn from_index(index: i64) -> Option<Self> {
+        match index {
+            0 => Some(Self::Uniform),
+            1 => Some(Self::Gaussian),
+            _ => None,
+        }
+    }
+
+    // a random step of the given size
+    #[inline]
+    fn sample(self, rng: &mut NoiseRng, step: Float) -> Float {
+        match self {
+            Self::Uniform => rng.next_bipolar() * step,
+            Self::Gaussian => rng.next_gaussian() * step,
+        }
+    }
+
+    // a random whole-numbered step of the given size
+    #[inline]
+    fn sample_whole(self, rng: &mut NoiseRng, step: i64) -> i64 {
+        match self {
+            Self::Uniform => (rng.next_unipolar() * (2 * step + 1) as Float) as i64 - step,
+            Self::Gaussian => (rng.next_gaussian() * step as Float).round() as i64,
+        }
+    }
+}
+
+// reflects a value that has stepped past a bound back into the range, so walks don't stick to the bounds
+#[inline]
+fn reflect<T>(value: T, min: T, max: T) -> T
+where
+    T: Copy + PartialOrd + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+{
+    let value = if value > max {
+        max - (value - max)
+    } else if value < min {
+        min + (min - value)
+    } else {
+        value
+    };
+    // steps larger than the range can still land outside it
+    if value > max {
+        max
+    } else if value < min {
+        min
+    } else {
+        value
+    }
+}
+
+/// A processor that generates a random walk, which takes random steps at a steady rate and glides between them, for slowly wandering, organic modulation.
+///
+/// The walk stays between its bounds, bouncing off them when a step would take it past one.
+/// Each glide takes the slew time, or is instant if the slew time is `0.0`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `rate` | `Float` | The number of steps per second. |
+/// | `1` | `step` | `Float` | The largest step (or with [`StepDistribution::Gaussian`], the typical step). |
+/// | `2` | `slew` | `Float` | The time taken to glide to each step, in seconds. |
+/// | `3` | `min` | `Float` | The lower bound of the walk. |
+/// | `4` | `max` | `Float` | The upper bound of the walk. |
+/// | `5` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible walks. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The value of the walk. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RandomWalk {
+    rng: NoiseRng,
+    distribution: StepDistribution,
+    // samples until the next step
+    countdown: Float,
+    // the value the walk is gliding to, and how far it moves per sample
+    target: Float,
+    value: Float,
+    increment: Float,
+
+    /// The number of steps per second.
+    pub rate: Float,
+    /// The largest (or typical) step.
+    pub step: Float,
+    /// The time taken to glide to each step, in seconds.
+    pub slew: Float,
+    /// The lower bound of the walk.
+    pub min: Float,
+    /// The upper bound of the walk.
+    pub max: Float,
+}
+
+impl RandomWalk {
+    /// Creates a new [`RandomWalk`] processor with the given rate and step, bounded to `-1.0` to `1.0`, with a random seed.
+    pub fn new(rate: Float, step: Float) -> Self {
+        Self::with_rng(rate, step, NoiseRng::from_entropy())
+    }
+
+    /// Creates a new [`RandomWalk`] processor with the given rate, step, and seed, bounded to `-1.0` to `1.0`.
+    pub fn with_seed(rate: Float, step: Float, seed: u64) -> Self {
+        Self::with_rng(rate, step, NoiseRng::new(seed))
+    }
+
+    fn with_rng(rate: Float, step: Float, rng: NoiseRng) -> Self {
+        Self {
+            rng,
+            distribution: StepDistribution::Uniform,
+            countdown: 0.0,
+            target: 0.0,
+            value: 0.0,
+            increment: 0.0,
+            rate,
+            step,
+            slew: 0.0,
+            min: -1.0,
+            max: 1.0,
+        }
+    }
+
+    /// Sets the distribution of the steps.
+    pub fn with_distribution(mut self, distribution: StepDistribution) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
+    /// Sets the time taken to glide to each step, in seconds.
+    pub fn with_slew(mut self, slew: Float) -> Self {
+        self.slew = slew;
+        self
+    }
+
+    /// Sets the bounds of the walk, which starts halfway between them.
+    pub fn with_bounds(mut self, min: Float, max: Float) -> Self {
+        self.min = min;
+        self.max = max;
+        self.value = (min + max) * 0.5;
+        self.target = self.value;
+        self
+    }
+
+    /// Returns the distribution of the steps.
+    pub fn distribution(&self) -> StepDistribution {
+        self.distribution
+    }
+}
+
+impl Default for RandomWalk {
+    fn default() -> Self {
+        Self::new(10.0, 0.1)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for RandomWalk {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("rate", SignalType::Float),
+            SignalSpec::new("step", SignalType::Float),
+            SignalSpec::new("slew", SignalType::Float),
+            SignalSpec::new("min", SignalType::Float),
+            SignalSpec::new("max", SignalType::Float),
+            SignalSpec::new("seed", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        for (rate, step, slew, min, max, seed, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float, Float, i64],
+            outputs as [Float]
+        ) {
+            if self.rng.update_seed(*seed) {
+                self.countdown = 0.0;
+            }
+
+            self.rate = rate.unwrap_or(self.rate);
+            self.step = step.unwrap_or(self.step).max(0.0);
+            self.slew = slew.unwrap_or(self.slew).max(0.0);
+            self.min = min.unwrap_or(self.min);
+            self.max = max.unwrap_or(self.max).max(self.min);
+
+            self.countdown -= 1.0;
+            if self.countdown <= 0.0 && self.rate > 0.0 {
+                self.countdown += sample_rate / self.rate;
+                // keep stepping from the current target, so a long slew doesn't hold the walk back
+                let step = self.distribution.sample(&mut self.rng, self.step);
+                self.target = reflect(self.target + step, self.min, self.max);
+                let glide_samples = (self.slew * sample_rate).max(1.0);
+                self.increment = (self.target - self.value) / glide_samples;
+            }
+
+            // the bounds may have moved since the last step
+            self.target = self.target.clamp(self.min, self.max);
+            if (self.target - self.value).abs() <= self.increment.abs() {
+                self.value = self.target;
+            } else {
+                self.value += self.increment;
+            }
+
+            *out = Some(self.value.clamp(self.min, self.max));
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that takes a random step up or down each time it's triggered, like a drunk stumbling between whole numbers, for wandering melodies and sequences.
+///
+/// The value stays between its bounds, bouncing off them when a step would take it past one.
+/// Steps are rounded to whole numbers, so with a step of `1` the value moves by at most one each time (for example, one note of a scale).
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `trig` | `Bool` | Takes a step when `true`. |
+/// | `1` | `step` | `Int` | The largest step (or with [`StepDistribution::Gaussian`], the typical step). |
+/// | `2` | `min` | `Int` | The lower bound of the value. |
+/// | `3` | `max` | `Int` | The upper bound of the value. |
+/// | `4` | `seed` | `Int` | Reseeds the noise generator when it changes, for reproducible steps. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Int` | The current value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Drunk {
+    rng: NoiseRng,
+    distribution: StepDistribution,
+    value: i64,
+
+    /// The largest (or typical) step.
+    pub step: i64,
+    /// The lower bound of the value.
+    pub min: i64,
+    /// The upper bound of the value.
+    pub max: i64,
+}
+
+impl Drunk {
+    /// Creates a new [`Drunk`] processor with the given step and bounds, starting halfway between them, with a random seed.
+    pub fn new(step: i64, min: i64, max: i64) -> Self {
+        Self::with_rng(step, min, max, NoiseRng::from_entropy())
+    }
+
+    /// Creates a new [`Drunk`] processor with the given step, bounds, and seed, starting halfway between the bounds.
+    pub fn with_seed(step: i64, min: i64, max: i64, seed: u64) -> Self {
+        Self::with_rng(step, min, max, NoiseRng::new(seed))
+    }
+
+    fn with_rng(step: i64, min: i64, max: i64, rng: NoiseRng) -> Self {
+        Self {
+            rng,
+            distribution: StepDistribution::Uniform,
+            value: min + (max - min) / 2,
+            step,
+            min,
+            max,
+        }
+    }
+
+    /// Sets the distribution of the steps.
+    pub fn with_distribution(mut self, distribution: StepDistribution) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
+    /// Returns the distribution of the steps.
+    pub fn distribution(&self) -> StepDistribution {
+        self.distribution
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+}
+
+impl Default for Drunk {
+    fn default() -> Self {
+        Self::new(1, 0, 127)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Drunk {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("trig", SignalType::Bool),
+            SignalSpec::new("step", SignalType::Int),
+            SignalSpec::new("min", SignalType::Int),
+            SignalSpec::new("max", SignalType::Int),
+            SignalSpec::new("seed", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Int)]
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (trig, step, min, max, seed, out) in iter_proc_io_as!(
+            inputs as [bool, i64, i64, i64, i64],
+            outputs as [i64]
+        ) {
+            self.rng.update_seed(*seed);
+
+            self.step = step.unwrap_or(self.step).max(0);
+            self.min = min.unwrap_or(self.min);
+            self.max = max.unwrap_or(self.max).max(self.min);
+
+            if let Some(true) = trig {
+                let step = self.distribution.sample_whole(&mut self.rng, self.step);
+                self.value = reflect(self.value + step, self.min, self.max);
+            }
+
+            self.value = self.value.clamp(self.min, self.max);
+            *out = Some(self.value);
+        }
+
+        Ok(())
+    }
+}
+
+/// The waveform of an [`Lfo`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LfoShape {
+    /// A sine wave, starting at `0.0` and rising.
+    #[default]
+    Sine,
+    /// A triangle wave, starting at `0.0` and rising.
+    Triangle,
+    /// A rising sawtooth wave.
+    Saw,
+    /// A square wave, high for the first half of each cycle.
+    Square,
+    /// A new random value every cycle, held for the whole cycle.
+    SampleAndHold,
+    /// A new random value every cycle, glided to smoothly over the following cycle.
+    SmoothRandom,
+}
+
+impl LfoShape {
+    /// Returns the shape with the given index, as used by the `shape` input of an [`Lfo`], in the order the shapes are declared (`0` is sine, `1` is triangle, and so on).
+    pub fn from_index(index: i64) -> Option<Self> {
+        match index {
+            0 => Some(Self::Sine),
+            1 => Some(Self::Triangle),
+            2 => Some(Self::Saw),
+            3 => Some(Self::Square),
+            4 => Some(Self::SampleAndHold),
+            5 => Some(Self::SmoothRandom),
+            _ => None,
+        }
+    }
+}
+
+/// A low-frequency oscillator with a choice of shapes, for modulating other processors.
+///
+/// An LFO created with [`Lfo::synced`] measures its period in beats of the graph's [`Transport`] instead of running at a frequency, so it stays in time with the music and only moves while the transport is playing.
+///
+/// The output is bipolar (`-1.0` to `1.0`) by default, or unipolar (`0.0` to `1.0`) with [`Lfo::with_unipolar`].
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `rate` | `Float` | The frequency in Hz (or the period in beats, if synced). |
+/// | `1` | `shape` | `Int` | The index of the shape (see [`LfoShape::from_index`]). |
+/// | `2` | `phase` | `Float` | The phase offset, as a fraction of a cycle. |
+/// | `3` | `retrigger` | `Bool` | Restarts the cycle when `true`. |
+/// | `4` | `seed` | `Int` | Reseeds the noise generator of the random shapes when it changes. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The LFO value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lfo {
+    rng: NoiseRng,
+    // whether the rate is a period in beats of the transport
+    synced: bool,
+    unipolar: bool,
+    // the phase before the offset is applied
+    phase: Float,
+    // subtracted from the transport's position, so retriggering a synced LFO restarts its cycle
+    sync_offset: Float,
+    // the phase (with the offset) of the previous sample, to detect the start of each cycle
+    last_phase: Option<Float>,
+    // the random values of the previous and current cycle
+    random: (Float, Float),
+
+    /// The frequency in Hz (or the period in beats, if synced).
+    pub rate: Float,
+    /// The shape of the LFO.
+    pub shape: LfoShape,
+    /// The phase offset, as a fraction of a cycle.
+    pub phase_offset: Float,
+}
+
+impl Lfo {
+    /// Creates a new [`Lfo`] processor with the given shape and frequency in Hz.
+    pub fn new(shape: LfoShape, frequency: Float) -> Self {
+        Self {
+            rng: NoiseRng::from_entropy(),
+            synced: false,
+            unipolar: false,
+            phase: 0.0,
+            sync_offset: 0.0,
+            last_phase: None,
+            random: (0.0, 0.0),
+            rate: frequency,
+            shape,
+            phase_offset: 0.0,
+        }
+    }
+
+    /// Creates a new [`Lfo`] processor with the given shape, which completes a cycle every given number of beats of the graph's [`Transport`].
+    pub fn synced(shape: LfoShape, beats: Float) -> Self {
+        Self {
+            synced: true,
+            ..Self::new(shape, beats)
+        }
+    }
+
+    /// Sets the phase offset, as a fraction of a cycle.
+    pub fn with_phase(mut self, phase: Float) -> Self {
+        self.phase_offset = phase;
+        self
+    }
+
+    /// Sets whether the output is unipolar (`0.0` to `1.0`) instead of bipolar (`-1.0` to `1.0`).
+    pub fn with_unipolar(mut self, unipolar: bool) -> Self {
+        self.unipolar = unipolar;
+        self
+    }
+
+    /// Seeds the noise generator of the random shapes, for reproducible modulation.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = NoiseRng::new(seed);
+        self
+    }
+
+    /// Returns `true` if the LFO is synced to the transport.
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Returns `true` if the output is unipolar.
+    pub fn is_unipolar(&self) -> bool {
+        self.unipolar
+    }
+
+    #[inline]
+    fn shape_at(&self, phase: Float) -> Float {
+        match self.shape {
+            LfoShape::Sine => (TAU * phase).sin(),
+            LfoShape::Triangle => 4.0 * ((phase + 0.75).fract() - 0.5).abs() - 1.0,
+            LfoShape::Saw => phase * 2.0 - 1.0,
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SampleAndHold => self.random.1,
+            LfoShape::SmoothRandom => {
+                let (from, to) = self.random;
+                let t = 0.5 - 0.5 * (PI * phase).cos();
+                from + (to - from) * t
+            }
+        }
+    }
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Self::new(LfoShape::Sine, 1.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Lfo {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("rate", SignalType::Float),
+            SignalSpec::new("shape", SignalType::Int),
+            SignalSpec::new("phase", SignalType::Float),
+            SignalSpec::new("retrigger", SignalType::Bool),
+            SignalSpec::new("seed", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let transport = *inputs.transport();
+        let offset = match inputs.mode {
+            ProcessMode::Block => 0,
+            ProcessMode::Sample(sample_index) => sample_index,
+        };
+
+        for (i, (rate, shape, phase, retrigger, seed, out)) in iter_proc_io_as!(
+            inputs as [Float, i64, Float, bool, i64],
+            outputs as [Float]
+        )
+        .enumerate()
+        {
+            if self.rng.update_seed(*seed) {
+                self.last_phase = None;
+            }
+
+            self.rate = rate.unwrap_or(self.rate);
+            if let Some(shape) = shape.and_then(LfoShape::from_index) {
+                self.shape = shape;
+            }
+            self.phase_offset = phase.unwrap_or(self.phase_offset);
+
+            let retrigger = retrigger.unwrap_or(false);
+            if self.synced {
+                let position = if self.rate > 0.0 {
+                    transport.beat_at(offset + i, sample_rate) / self.rate
+                } else {
+                    0.0
+                };
+                if retrigger {
+                    self.sync_offset = position;
+                }
+                self.phase = (position - self.sync_offset).rem_euclid(1.0);
+            } else if retrigger {
+                self.phase = 0.0;
+            }
+
+            let phase = (self.phase + self.phase_offset).rem_euclid(1.0);
+            let new_cycle = match self.last_phase {
+                Some(last_phase) => retrigger || phase < last_phase,
+                None => {
+                    self.random.1 = self.rng.next_bipolar();
+                    true
+                }
+            };
+            if new_cycle {
+                self.random = (self.random.1, self.rng.next_bipolar());
+            }
+            self.last_phase = Some(phase);
+
+            let value = self.shape_at(phase);
+            *out = Some(if self.unipolar {
+                (value + 1.0) * 0.5
+            } else {
+                value
+            });
+
+            if !self.synced {
+                self.phase = (self.phase + self.rate / sample_rate).rem_euclid(1.0);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// integrates a 3-dimensional system of ODEs by one step using the 4th-order Runge-Kutta method
+#[inline]
+fn rk4_step(
+    state: [Float; 3],
+    dt: Float,
+    derivative: impl Fn([Float; 3]) -> [Float; 3],
+) -> [Float; 3] {
+    let offset = |a: [Float; 3], b: [Float; 3], t: Float| {
+        [a[0] + b[0] * t, a[1] + b[1] * t, a[2] + b[2] * t]
+    };
+    let k1 = derivative(state);
+    let k2 = derivative(offset(state, k1, dt * 0.5));
+    let k3 = derivative(offset(state, k2, dt * 0.5));
+    let k4 = derivative(offset(state, k3, dt));
+    [0, 1, 2].map(|i| state[i] + dt / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+}
+
+// the largest time step the chaotic oscillators take per sample, to keep the integration stable
+const CHAOTIC_MAX_STEP: Float = 0.02;
+
+/// A processor that simulates the Lorenz attractor, a chaotic system, for use as a modulation or texture source.
+///
+/// The `chaos` input sets the attractor's `rho` parameter, from a stable spiral (`0.0`) to the classic chaotic butterfly (`1.0`).
+/// The outputs are scaled to roughly the range `-1.0` to `1.0`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `rate` | `Float` | The speed of the simulation, roughly the number of orbits per second. |
+/// | `1` | `chaos` | `Float` | The amount of chaos (0.0 to 1.0). |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `x` | `Float` | The x coordinate of the system. |
+/// | `1` | `y` | `Float` | The y coordinate of the system. |
+/// | `2` | `z` | `Float` | The z coordinate of the system. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LorenzOscillator {
+    state: [Float; 3],
+
+    /// The speed of the simulation.
+    pub rate: Float,
+
+    /// The amount of chaos (0.0 to 1.0).
+    pub chaos: Float,
+}
+
+impl LorenzOscillator {
+    /// Creates a new [`LorenzOscillator`] processor with the given rate and chaos.
+    pub fn new(rate: Float, chaos: Float) -> Self {
+        Self {
+            state: [0.1, 0.0, 0.0],
+            rate,
+            chaos,
+        }
+    }
+}
+
+impl Default for LorenzOscillator {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for LorenzOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("rate", SignalType::Float),
+            SignalSpec::new("chaos", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("x", SignalType::Float),
+            SignalSpec::new("y", SignalType::Float),
+            SignalSpec::new("z", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        const SIGMA: Float = 10.0;
+        const BETA: Float = 8.0 / 3.0;
+        // a single orbit around one of the attractor's lobes takes about this long
+        const ORBIT_TIME: Float = 0.75;
+
+        for (rate, chaos, x, y, z) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float, Float, Float]
+        ) {
+            self.rate = rate.unwrap_or(self.rate);
+            self.chaos = chaos.unwrap_or(self.chaos).clamp(0.0, 1.0);
+
+            let rho = 10.0 + 18.0 * self.chaos;
+            let dt = (self.rate * ORBIT_TIME / inputs.sample_rate()).clamp(0.0, CHAOTIC_MAX_STEP);
+
+            self.state = rk4_step(self.state, dt, |[x, y, z]| {
+                [SIGMA * (y - x), x * (rho - z) - y, x * y - BETA * z]
+            });
+
+            *x = Some(self.state[0] / 20.0);
+            *y = Some(self.state[1] / 27.0);
+            *z = Some(self.state[2] / 25.0 - 1.0);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that simulates the Rössler attractor, a chaotic system with a smoother, more periodic character than the Lorenz attractor.
+///
+/// The `chaos` input sets the attractor's `c` parameter, from a simple periodic orbit (`0.0`) to the classic chaotic band (`1.0`).
+/// The outputs are scaled to roughly the range `-1.0` to `1.0`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `rate` | `Float` | The speed of the simulation, roughly the number of orbits per second. |
+/// | `1` | `chaos` | `Float` | The amount of chaos (0.0 to 1.0). |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `x` | `Float` | The x coordinate of the system. |
+/// | `1` | `y` | `Float` | The y coordinate of the system. |
+/// | `2` | `z` | `Float` | The z coordinate of the system. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosslerOscillator {
+    state: [Float; 3],
+
+    /// The speed of the simulation.
+    pub rate: Float,
+
+    /// The amount of chaos (0.0 to 1.0).
+    pub chaos: Float,
+}
+
+impl RosslerOscillator {
+    /// Creates a new [`RosslerOscillator`] processor with the given rate and chaos.
+    pub fn new(rate: Float, chaos: Float) -> Self {
+        Self {
+            state: [1.0, 0.0, 0.0],
+            rate,
+            chaos,
+        }
+    }
+}
+
+impl Default for RosslerOscillator {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for RosslerOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("rate", SignalType::Float),
+            SignalSpec::new("chaos", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("x", SignalType::Float),
+            SignalSpec::new("y", SignalType::Float),
+            SignalSpec::new("z", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        const A: Float = 0.2;
+        const B: Float = 0.2;
+        const ORBIT_TIME: Float = TAU;
+
+        for (rate, chaos, x, y, z) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float, Float, Float]
+        ) {
+            self.rate = rate.unwrap_or(self.rate);
+            self.chaos = chaos.unwrap_or(self.chaos).clamp(0.0, 1.0);
+
+            let c = 2.0 + 3.7 * self.chaos;
+            let dt = (self.rate * ORBIT_TIME / inputs.sample_rate()).clamp(0.0, CHAOTIC_MAX_STEP);
+
+            self.state = rk4_step(self.state, dt, |[x, y, z]| {
+                [-y - z, x + A * y, B + z * (x - c)]
+            });
+
+            *x = Some(self.state[0] / 12.0);
+            *y = Some(self.state[1] / 12.0);
+            *z = Some(self.state[2] / 12.0 - 1.0);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that generates a band-limited sawtooth wave.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The frequency of the sawtooth wave. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The sawtooth wave value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlSawOscillator {
+    p: Float,
+    dp: Float,
+    saw: Float,
+
+    /// The frequency of the sawtooth wave.
+    pub frequency: Float,
+}
+
+impl Default for BlSawOscillator {
+    fn default() -> Self {
+        Self {
+            p: 0.0,
+            dp: 1.0,
+            saw: 0.0,
+            frequency: 0.0,
+        }
+    }
+}
+
+impl BlSawOscillator {
+    /// Creates a new [`BlSawOscillator`] processor with the given frequency.
+    pub fn new(frequency: Float) -> Self {
+        Self {
+            frequency,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for BlSawOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("frequency", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        // algorithm courtesy of https://www.musicdsp.org/en/latest/Synthesis/12-bandlimited-waveforms.html
+        for (frequency, out) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            if self.frequency <= 0.0 {
+                *out = None;
+                continue;
+            }
+
+            let pmax = 0.5 * inputs.sample_rate() / self.frequency;
+            let dc = -0.498 / pmax;
+
+            self.p += self.dp;
+            if self.p < 0.0 {
+                self.p = -self.p;
+                self.dp = -self.dp;
+            } else if self.p > pmax {
+                self.p = 2.0 * pmax - self.p;
+                self.dp = -self.dp;
+            }
+
+            let mut x = PI * self.p;
+            if x < 0.00001 {
+                x = 0.00001;
+            }
+
+            self.saw = 0.995 * self.saw + dc + x.sin() / x;
+
+            *out = Some(self.saw);
+        }
+
+        Ok(())
+    }
+}
+
+const BL_SQUARE_MAX_HARMONICS: usize = 512;
+
+/// A processor that generates a band-limited square/pulse wave.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The frequency of the square wave. |
+/// | `1` | `pulse_width` | `Float` | The pulse width of the square wave. |
+/// | `2` | `reset` | `Bool` | Whether to reset the phase accumulator to 0. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The square wave value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlSquareOscillator {
+    // phase accumulator
+    t: Float,
+    // phase increment per sample
+    t_step: Float,
+
+    // band-limited square wave coefficients
+    coeff: Box<[Float]>,
+
+    /// The frequency of the square wave.
+    pub frequency: Float,
+
+    /// The pulse width of the square wave (0.0 to 1.0).
+    pub pulse_width: Float,
+}
+
+impl Default for BlSquareOscillator {
+    fn default() -> Self {
+        Self::new(0.0, 0.5)
+    }
+}
+
+impl BlSquareOscillator {
+    /// Creates a new [`BlSquareOscillator`] processor with the given frequency and pulse width.
+    pub fn new(frequency: Float, pulse_width: Float) -> Self {
+        Self {
+            frequency,
+            pulse_width,
+            t: 0.0,
+            t_step: 0.0,
+            coeff: Box::new([0.0; BL_SQUARE_MAX_HARMONICS]),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for BlSquareOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("pulse_width", SignalType::Float),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (frequency, pulse_width, reset, out) in iter_proc_io_as!(
+            inputs as [Float, Float, bool],
+            outputs as [Float]
+        ) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            if self.frequency <= 0.0 {
+                *out = None;
+                continue;
+            }
+
+            if reset.unwrap_or(false) {
+                self.t = 0.0;
+            }
+
+            self.pulse_width = pulse_width.unwrap_or(self.pulse_width);
+
+            self.t_step = self.frequency / inputs.sample_rate();
+
+            let n_harm = (inputs.sample_rate() / (self.frequency * 4.0)) as usize;
+            self.coeff[0] = self.pulse_width - 0.5;
+            for i in 1..n_harm + 1 {
+                self.coeff[i] =
+                    Float::sin(i as Float * PI * self.pulse_width) * 2.0 / (i as Float * PI);
+            }
+
+            let theta = self.t * TAU;
+
+            let mut square = 0.0;
+            for i in 0..n_harm + 1 {
+                square += self.coeff[i] * (theta * i as Float).cos();
+            }
+
+            self.t += self.t_step;
+
+            *out = Some(square);
+        }
+
+        Ok(())
+    }
+}
+
+// the polyBLEP residual of a unit step at phase 0, for a phase `t` advancing by `dt` per sample
+#[inline]
+fn poly_blep(t: Float, dt: Float) -> Float {
+    if t < dt {
+        let t = t / dt;
+        2.0 * t - t * t - 1.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
+    }
+}
+
+// the polyBLAMP residual of a unit change in slope (per sample) at phase 0
+#[inline]
+fn poly_blamp(t: Float, dt: Float) -> Float {
+    if t < dt {
+        let t = t / dt - 1.0;
+        -t * t * t / 3.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt + 1.0;
+        t * t * t / 3.0
+    } else {
+        0.0
+    }
+}
+
+/// A processor that generates a band-limited pulse wave using polyBLEP, with a pulse width that can be modulated at audio rate.
+///
+/// This is much cheaper than the [`BlSquareOscillator`], at the cost of slightly more aliasing at high frequencies.
+/// A pulse width of `0.5` produces a square wave.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The frequency of the pulse wave. |
+/// | `1` | `pulse_width` | `Float` | The pulse width of the pulse wave (0.0 to 1.0). |
+/// | `2` | `reset` | `Bool` | Whether to reset the phase to 0. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The pulse wave value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlPulseOscillator {
+    phase: Float,
+
+    /// The frequency of the pulse wave.
+    pub frequency: Float,
+
+    /// The pulse width of the pulse wave (0.0 to 1.0).
+    pub pulse_width: Float,
+}
+
+impl Default for BlPulseOscillator {
+    fn default() -> Self {
+        Self::new(0.0, 0.5)
+    }
+}
+
+impl BlPulseOscillator {
+    /// Creates a new [`BlPulseOscillator`] processor with the given frequency and pulse width.
+    pub fn new(frequency: Float, pulse_width: Float) -> Self {
+        Self {
+            phase: 0.0,
+            frequency,
+            pulse_width,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for BlPulseOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("pulse_width", SignalType::Float),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (frequency, pulse_width, reset, out) in iter_proc_io_as!(
+            inputs as [Float, Float, bool],
+            outputs as [Float]
+        ) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            if self.frequency <= 0.0 {
+                *out = None;
+                continue;
+            }
+
+            if reset.unwrap_or(false) {
+                self.phase = 0.0;
+            }
+
+            self.pulse_width = pulse_width.unwrap_or(self.pulse_width);
+
+            let dt = (self.frequency / inputs.sample_rate()).min(0.5);
+            // keep both edges at least a sample apart
+            let pulse_width = self.pulse_width.clamp(dt, 1.0 - dt);
+
+            let mut pulse = if self.phase < pulse_width { 1.0 } else { -1.0 };
+            pulse += poly_blep(self.phase, dt);
+            pulse -= poly_blep((self.phase - pulse_width).rem_euclid(1.0), dt);
+
+            self.phase = (self.phase + dt).fract();
+
+            *out = Some(pulse);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that generates a band-limited triangle wave using polyBLAMP.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The frequency of the triangle wave. |
+/// | `1` | `reset` | `Bool` | Whether to reset the phase to 0. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The triangle wave value. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlTriangleOscillator {
+    phase: Float,
+
+    /// The frequency of the triangle wave.
+    pub frequency: Float,
+}
+
+impl BlTriangleOscillator {
+    /// Creates a new [`BlTriangleOscillator`] processor with the given frequency.
+    pub fn new(frequency: Float) -> Self {
+        Self {
+            phase: 0.0,
+            frequency,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for BlTriangleOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (frequency, reset, out) in iter_proc_io_as!(
+            inputs as [Float, bool],
+            outputs as [Float]
+        ) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            if self.frequency <= 0.0 {
+                *out = None;
+                continue;
+            }
+
+            if reset.unwrap_or(false) {
+                self.phase = 0.0;
+            }
+
+            let dt = (self.frequency / inputs.sample_rate()).min(0.5);
+
+            // peaks at phase 0 and troughs at phase 0.5, where the slope changes by 8 per cycle
+            let mut triangle = 2.0 * (2.0 * self.phase - 1.0).abs() - 1.0;
+            triangle -= 8.0 * dt * poly_blamp(self.phase, dt);
+            triangle += 8.0 * dt * poly_blamp((self.phase + 0.5).fract(), dt);
+
+            self.phase = (self.phase + dt).fract();
+
+            *out = Some(triangle);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Partial {
+    amplitude: Float,
+    // in cents, relative to the harmonic
+    detune: Float,
+    // the partial's phase, as a point on the unit circle
+    re: Float,
+    im: Float,
+    // the rotation applied to the phase each sample
+    cos: Float,
+    sin: Float,
+}
+
+impl Default for Partial {
+    fn default() -> Self {
+        Self {
+            amplitude: 0.0,
+            detune: 0.0,
+            re: 1.0,
+            im: 0.0,
+            cos: 1.0,
+            sin: 0.0,
+        }
+    }
+}
+
+/// A processor that sums sine partials with individually controlled amplitudes and detunes.
+///
+/// The `n`th element of the `amplitudes` list sets the amplitude of the `n + 1`th harmonic of the fundamental frequency, and its length sets how many partials are active, up to the maximum the oscillator was created with.
+/// The `detunes` list offsets each partial from its harmonic, in cents; partials without a detune keep their previous value.
+/// Partials above the Nyquist frequency are silenced.
+///
+/// The partials are generated by rotating phasors, so their coefficients are only recomputed when the frequency or detunes change.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The fundamental frequency. |
+/// | `1` | `amplitudes` | `List` | The amplitudes of the partials. |
+/// | `2` | `detunes` | `List` | The detunes of the partials, in cents. |
+/// | `3` | `reset` | `Bool` | Whether to reset the phases of the partials to 0. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The sum of the partials. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdditiveOscillator {
+    partials: Vec<Partial>,
+    num_active: usize,
+    sample_rate: Float,
+    // whether the rotations need to be recomputed
+    dirty: bool,
+
+    /// The fundamental frequency.
+    pub frequency: Float,
+}
+
+impl AdditiveOscillator {
+    /// Creates a new [`AdditiveOscillator`] processor with the given frequency and up to the given number of partials, all initially inactive.
+    pub fn new(frequency: Float, max_partials: usize) -> Self {
+        Self {
+            partials: vec![Partial::default(); max_partials],
+            num_active: 0,
+            sample_rate: 0.0,
+            dirty: true,
+            frequency,
+        }
+    }
+
+    /// Sets the amplitudes of the partials, activating one partial for each amplitude.
+    pub fn with_amplitudes(mut self, amplitudes: &[Float]) -> Self {
+        self.set_amplitudes(amplitudes.iter().copied());
+        self
+    }
+
+    /// Sets the detunes of the partials, in cents.
+    pub fn with_detunes(mut self, detunes: &[Float]) -> Self {
+        self.set_detunes(detunes.iter().copied());
+        self
+    }
+
+    /// Returns the maximum number of partials.
+    pub fn max_partials(&self) -> usize {
+        self.partials.len()
+    }
+
+    /// Returns the number of active partials.
+    pub fn num_active(&self) -> usize {
+        self.num_active
+    }
+
+    fn set_amplitudes(&mut self, amplitudes: impl Iterator<Item = Float>) {
+        let mut num_active = 0;
+        for (partial, amplitude) in self.partials.iter_mut().zip(amplitudes) {
+            partial.amplitude = amplitude;
+            num_active += 1;
+        }
+        if num_active > self.num_active {
+            // newly activated partials need their rotations computed
+            self.dirty = true;
+        }
+        self.num_active = num_active;
+    }
+
+    fn set_detunes(&mut self, detunes: impl Iterator<Item = Float>) {
+        for (partial, detune) in self.partials.iter_mut().zip(detunes) {
+            if partial.detune != detune {
+                partial.detune = detune;
+                self.dirty = true;
+            }
+        }
+    }
+
+    fn set_rotations(&mut self) {
+        let nyquist = self.sample_rate * 0.5;
+        for (i, partial) in self.partials[..self.num_active].iter_mut().enumerate() {
+            let frequency =
+                self.frequency * (i + 1) as Float * (2.0 as Float).powf(partial.detune / 1200.0);
+            if self.sample_rate <= 0.0 || frequency.abs() >= nyquist {
+                partial.cos = 1.0;
+                partial.sin = 0.0;
+                // a partial that doesn't rotate would output a constant, so it's muted in `process`
+                continue;
+            }
+            let (sin, cos) = (TAU * frequency / self.sample_rate).sin_cos();
+            partial.cos = cos;
+            partial.sin = sin;
+        }
+        self.dirty = false;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for AdditiveOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("amplitudes", SignalType::List),
+            SignalSpec::new("detunes", SignalType::List),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        self.sample_rate = sample_rate;
+        self.dirty = true;
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (frequency, amplitudes, detunes, reset, out) in iter_proc_io_as!(
+            inputs as [Float, List, List, bool],
+            outputs as [Float]
+        ) {
+            if let Some(frequency) = frequency {
+                if *frequency != self.frequency {
+                    self.frequency = *frequency;
+                    self.dirty = true;
+                }
+            }
+            if let Some(amplitudes) = amplitudes {
+                self.set_amplitudes(iter_list_floats(amplitudes));
+            }
+            if let Some(detunes) = detunes {
+                self.set_detunes(iter_list_floats(detunes));
+            }
+            if self.dirty {
+                self.set_rotations();
+            }
+
+            if reset.unwrap_or(false) {
+                for partial in &mut self.partials {
+                    partial.re = 1.0;
+                    partial.im = 0.0;
+                }
+            }
+
+            let mut sum = 0.0;
+            for partial in &mut self.partials[..self.num_active] {
+                if partial.sin == 0.0 {
+                    continue;
+                }
+                sum += partial.amplitude * partial.im;
+                let re = partial.re * partial.cos - partial.im * partial.sin;
+                let im = partial.re * partial.sin + partial.im * partial.cos;
+                partial.re = re;
+                partial.im = im;
+            }
+
+            *out = Some(sum);
+        }
+
+        // keep the phasors on the unit circle, as rounding errors accumulate
+        for partial in &mut self.partials[..self.num_active] {
+            let magnitude = (partial.re * partial.re + partial.im * partial.im).sqrt();
+            if magnitude > 0.0 {
+                partial.re /= magnitude;
+                partial.im /= magnitude;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// the phase distortion transfer curves, each the identity when `amount` is 0
+#[inline]
+fn pd_saw(phase: Float, amount: Float) -> Float {
+    let knee = 0.5 - 0.49 * amount;
+    if phase < knee {
+        0.5 * phase / knee
+    } else {
+        0.5 + 0.5 * (phase - knee) / (1.0 - knee)
+    }
+}
+
+#[inline]
+fn pd_square(phase: Float, amount: Float) -> Float {
+    let width = 1.0 - 0.98 * amount;
+    if phase < 0.5 {
+        (phase / width).min(0.5)
+    } else {
+        0.5 + ((phase - 0.5) / width).min(0.5)
+    }
+}
+
+#[inline]
+fn pd_pulse(phase: Float, amount: Float) -> Float {
+    let width = 1.0 - 0.98 * amount;
+    (phase / width).min(1.0)
+}
+
+/// A processor that generates a phase distortion waveform, in the style of the Casio CZ synthesizers.
+///
+/// A cosine wave is read with a warped phase, which adds harmonics as the `amount` input increases, much like opening a filter.
+/// The `curve` input morphs the shape of the warp from a sawtooth-like (`0.0`) to a square-like (`0.5`) to a pulse-like (`1.0`) waveform.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The frequency of the waveform. |
+/// | `1` | `amount` | `Float` | The amount of phase distortion (0.0 to 1.0). |
+/// | `2` | `curve` | `Float` | The shape of the phase distortion (0.0 to 1.0). |
+/// | `3` | `reset` | `Bool` | Whether to reset the phase to 0. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The waveform value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhaseDistortionOscillator {
+    phase: Float,
+
+    /// The frequency of the waveform.
+    pub frequency: Float,
+
+    /// The amount of phase distortion (0.0 to 1.0).
+    pub amount: Float,
+
+    /// The shape of the phase distortion (0.0 to 1.0).
+    pub curve: Float,
+}
+
+impl PhaseDistortionOscillator {
+    /// Creates a new [`PhaseDistortionOscillator`] processor with the given frequency, amount, and curve.
+    pub fn new(frequency: Float, amount: Float, curve: Float) -> Self {
+        Self {
+            phase: 0.0,
+            frequency,
+            amount,
+            curve,
+        }
+    }
+
+    fn warp(&self, phase: Float) -> Float {
+        if self.curve < 0.5 {
+            let t = self.curve * 2.0;
+            lerp(pd_saw(phase, self.amount), pd_square(phase, self.amount), t)
+        } else {
+            let t = self.curve * 2.0 - 1.0;
+            lerp(
+                pd_square(phase, self.amount),
+                pd_pulse(phase, self.amount),
+                t,
+            )
+        }
+    }
+}
+
+impl Default for PhaseDistortionOscillator {
+    fn default() -> Self {
+        Self::new(440.0, 0.5, 0.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for PhaseDistortionOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("amount", SignalType::Float),
+            SignalSpec::new("curve", SignalType::Float),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (frequency, amount, curve, reset, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, bool],
+            outputs as [Float]
+        ) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            self.amount = amount.unwrap_or(self.amount).clamp(0.0, 1.0);
+            self.curve = curve.unwrap_or(self.curve).clamp(0.0, 1.0);
+
+            if reset.unwrap_or(false) {
+                self.phase = 0.0;
+            }
+
+            *out = Some(-(TAU * self.warp(self.phase)).cos());
+
+            self.phase += self.frequency / inputs.sample_rate();
+            self.phase = self.phase.rem_euclid(1.0);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that models a physical string vibrating at a given frequency using the Karplus-Strong algorithm.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `trig` | `bool` | Triggers the pluck. |
+/// | `1` | `frequency` | `Float` | The frequency of the string. |
+/// | `2` | `damping` | `Float` | The damping factor of the string. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The string value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KarplusStrong {
+    // delay line
+    ringbuf: VecDeque<Float>,
+    // fills the delay line with noise on each pluck
+    #[cfg_attr(feature = "serde", serde(default = "NoiseRng::from_entropy"))]
+    rng: NoiseRng,
+
+    /// The damping factor of the string.
+    pub damping: Float,
+
+    /// The frequency of the string.
+    pub frequency: Float,
+}
+
+impl KarplusStrong {
+    /// Creates a new [`KarplusStrong`] processor with the given frequency, damping factor, and pluck position.
+    pub fn new(frequency: Float, damping: Float) -> Self {
+        Self {
+            ringbuf: VecDeque::new(),
+            rng: NoiseRng::from_entropy(),
+            damping,
+            frequency,
+        }
+    }
+
+    /// Seeds the noise generator that excites the string, for reproducible plucks.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = NoiseRng::new(seed);
+        self
+    }
+}
+
+impl Default for KarplusStrong {
+    fn default() -> Self {
+        Self::new(0.0, 0.5)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for KarplusStrong {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("trig", SignalType::Bool),
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("damping", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.ringbuf = VecDeque::with_capacity(sample_rate as usize / 2);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (trig, frequency, damping, out) in iter_proc_io_as!(
+            inputs as [bool, Float, Float],
+            outputs as [Float]
+        ) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            if self.frequency <= 0.0 {
+                *out = None;
+                continue;
+            }
+
+            self.damping = damping.unwrap_or(self.damping);
+
+            if trig.unwrap_or(false) {
+                // calculate the delay line index
+                let delay_time = (inputs.sample_rate() / self.frequency) as usize;
+
+                // initialize the delay line with noise
                 self.ringbuf.clear();
                 for _ in 0..delay_time {
-                    self.ringbuf.push_back(rand::random::<Float>() * 2.0 - 1.0);
+                    self.ringbuf.push_back(self.rng.next_bipolar());
                 }
             }
 
@@ -632,3 +2532,275 @@ impl Processor for KarplusStrong {
         Ok(())
     }
 }
+
+/// A set of single-cycle waveforms ("frames") for a [`WavetableOscillator`], along with band-limited copies of each frame.
+///
+/// Each frame is stored as a series of mipmaps, one per octave, where each mipmap contains half as many harmonics as the one before it.
+/// The oscillator picks the mipmap with as many harmonics as possible without exceeding the Nyquist frequency, which avoids aliasing at high pitches.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wavetable {
+    frame_length: usize,
+    frames: Vec<Box<[Float]>>,
+
+    // the band-limited copies of each frame, indexed by `[frame][octave]`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    mipmaps: Vec<Vec<Box<[Float]>>>,
+}
+
+impl Wavetable {
+    /// Creates a new wavetable from the given frames, which must all have the same length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no frames, if the frames are empty, or if the frames have different lengths.
+    pub fn new<F>(frames: impl IntoIterator<Item = F>) -> Self
+    where
+        F: AsRef<[Float]>,
+    {
+        let frames: Vec<Box<[Float]>> = frames
+            .into_iter()
+            .map(|frame| frame.as_ref().into())
+            .collect();
+
+        assert!(!frames.is_empty(), "Wavetable: no frames given");
+        let frame_length = frames[0].len();
+        assert!(frame_length > 0, "Wavetable: frames must not be empty");
+        assert!(
+            frames.iter().all(|frame| frame.len() == frame_length),
+            "Wavetable: all frames must have the same length"
+        );
+
+        let mut table = Self {
+            frame_length,
+            frames,
+            mipmaps: Vec::new(),
+        };
+        table.build_mipmaps();
+        table
+    }
+
+    /// Creates a new wavetable with a single frame.
+    pub fn from_slice(frame: &[Float]) -> Self {
+        Self::new([frame])
+    }
+
+    /// Loads a wavetable from a WAV file.
+    ///
+    /// If `frame_length` is `None`, the whole file is used as a single frame.
+    /// Otherwise, the file is split into consecutive frames of the given length, and any leftover samples at the end of the file are ignored.
+    /// Only the first channel of the file is used.
+    pub fn load_wav(
+        path: impl AsRef<std::path::Path>,
+        frame_length: Option<usize>,
+    ) -> Result<Self, hound::Error> {
+        let buffer = Buffer::load_wav(path)?;
+        let samples: Vec<Float> = buffer.iter().map(|s| s.unwrap_or_default()).collect();
+
+        let frame_length = frame_length.unwrap_or(samples.len()).max(1);
+        if samples.len() < frame_length {
+            return Err(hound::Error::FormatError(
+                "file is shorter than one wavetable frame",
+            ));
+        }
+
+        Ok(Self::new(samples.chunks_exact(frame_length)))
+    }
+
+    /// Returns the number of samples in each frame.
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+
+    /// Returns the number of frames.
+    pub fn num_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the number of mipmaps (octaves) stored for each frame.
+    pub fn num_mipmaps(&self) -> usize {
+        (self.frame_length / 2).max(1).ilog2() as usize + 1
+    }
+
+    fn build_mipmaps(&mut self) {
+        let n = self.frame_length;
+        let max_harmonic = n / 2;
+
+        let cos: Vec<Float> = (0..n)
+            .map(|i| (TAU * i as Float / n as Float).cos())
+            .collect();
+        let sin: Vec<Float> = (0..n)
+            .map(|i| (TAU * i as Float / n as Float).sin())
+            .collect();
+
+        self.mipmaps = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            // find the frame's harmonics with a DFT
+            let dc = frame.iter().sum::<Float>() / n as Float;
+            let mut harmonics = Vec::with_capacity(max_harmonic);
+            for h in 1..=max_harmonic {
+                let (mut re, mut im) = (0.0, 0.0);
+                for (i, sample) in frame.iter().enumerate() {
+                    let index = (h * i) % n;
+                    re += sample * cos[index];
+                    im += sample * sin[index];
+                }
+                // the Nyquist harmonic only appears once in the spectrum
+                let scale = if 2 * h == n { 1.0 } else { 2.0 } / n as Float;
+                harmonics.push((re * scale, im * scale));
+            }
+
+            // resynthesize the frame with half as many harmonics for each octave
+            let mut mipmaps = Vec::with_capacity(self.num_mipmaps());
+            for octave in 0..self.num_mipmaps() {
+                let limit = (max_harmonic >> octave).max(1);
+                let mipmap = (0..n)
+                    .map(|i| {
+                        let mut sample = dc;
+                        for (h, (re, im)) in harmonics.iter().enumerate().take(limit) {
+                            let index = ((h + 1) * i) % n;
+                            sample += re * cos[index] + im * sin[index];
+                        }
+                        sample
+                    })
+                    .collect();
+                mipmaps.push(mipmap);
+            }
+            self.mipmaps.push(mipmaps);
+        }
+    }
+
+    // reads the given frame's mipmap at the given phase (0 to 1) with linear interpolation
+    #[inline]
+    fn read(&self, frame: usize, octave: usize, phase: Float) -> Float {
+        let table = &self.mipmaps[frame][octave];
+        let position = phase * self.frame_length as Float;
+        let index = position.floor() as usize % self.frame_length;
+        let next = (index + 1) % self.frame_length;
+        let t = position - position.floor();
+        table[index] + (table[next] - table[index]) * t
+    }
+}
+
+/// A processor that plays back a [`Wavetable`], morphing between its frames.
+///
+/// The `position` input selects which frame to play, from `0` (the first frame) to `1` (the last frame).
+/// Positions between two frames are crossfaded between them.
+///
+/// The wavetable's mipmaps are used to avoid aliasing at high frequencies.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `frequency` | `Float` | The frequency of the oscillator. |
+/// | `1` | `position` | `Float` | The position in the wavetable (0 to 1). |
+/// | `2` | `reset` | `Bool` | Whether to reset the phase accumulator to 0. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The oscillator value. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WavetableOscillator {
+    table: Wavetable,
+
+    // phase accumulator (0 to 1)
+    t: Float,
+
+    /// The frequency of the oscillator.
+    pub frequency: Float,
+
+    /// The position in the wavetable (0 to 1).
+    pub position: Float,
+}
+
+impl WavetableOscillator {
+    /// Creates a new [`WavetableOscillator`] processor that plays the given wavetable at the given frequency.
+    pub fn new(table: Wavetable, frequency: Float) -> Self {
+        Self {
+            table,
+            t: 0.0,
+            frequency,
+            position: 0.0,
+        }
+    }
+
+    /// Returns the wavetable being played.
+    pub fn table(&self) -> &Wavetable {
+        &self.table
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for WavetableOscillator {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("frequency", SignalType::Float),
+            SignalSpec::new("position", SignalType::Float),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, _sample_rate: Float, _max_block_size: usize) {
+        // mipmaps aren't serialized, so rebuild them after deserializing
+        if self.table.mipmaps.is_empty() {
+            self.table.build_mipmaps();
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let max_harmonic = self.table.frame_length / 2;
+        let last_octave = self.table.num_mipmaps() - 1;
+        let last_frame = self.table.num_frames() - 1;
+
+        for (frequency, position, reset, out) in iter_proc_io_as!(
+            inputs as [Float, Float, bool],
+            outputs as [Float]
+        ) {
+            self.frequency = frequency.unwrap_or(self.frequency);
+            self.position = position.unwrap_or(self.position).clamp(0.0, 1.0);
+
+            if reset.unwrap_or(false) {
+                self.t = 0.0;
+            }
+
+            // pick the octave with the most harmonics that stay below the Nyquist frequency
+            let harmonics = (inputs.sample_rate() * 0.5 / self.frequency.abs().max(1e-6)) as usize;
+            let mut octave = 0;
+            while octave < last_octave && (max_harmonic >> octave) > harmonics {
+                octave += 1;
+            }
+
+            let frame = self.position * last_frame as Float;
+            let index = (frame.floor() as usize).min(last_frame);
+            let next = (index + 1).min(last_frame);
+            let mix = frame - index as Float;
+
+            let a = self.table.read(index, octave, self.t);
+            let sample = if mix > 0.0 {
+                let b = self.table.read(next, octave, self.t);
+                a + (b - a) * mix
+            } else {
+                a
+            };
+
+            *out = Some(sample);
+
+            self.t += self.frequency / inputs.sample_rate();
+            self.t -= self.t.floor();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+//! Built-in processors for polyphonic voice allocation.
+
+use crate::{graph::derive_seed, prelude::*, runtime::RuntimeError};
+
+// the MIDI controller that MPE controllers send slide (forward/backward movement on a key) on
+const MPE_SLIDE_CC: u8 = 74;
+
+// the pitch bend range of the MPE master channel, in semitones
+const MPE_MASTER_BEND_RANGE: Float = 2.0;
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct VoiceState {
+    // the note this voice is playing (or was last playing, if released)
+    note: Option<u8>,
+    // the MIDI channel of the note
+    channel: u8,
+    // the normalized velocity of the note, or 0 if the note has been released
+    gate: Float,
+    // when the voice was last triggered or released, used to pick which voice to reuse
+    last_event: u64,
+    // the expression of the note's channel, frozen once the note is released
+    expression: ChannelExpression,
+}
+
+// the latest per-note expression received on a MIDI channel
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct ChannelExpression {
+    // normalized to `[-1, 1]`
+    bend: Float,
+    // normalized to `[0, 1]`
+    pressure: Float,
+    // normalized to `[0, 1]`
+    slide: Float,
+}
+
+/// A processor that runs multiple copies ("voices") of a graph, allocating incoming MIDI notes to free voices and summing their outputs.
+///
+/// The voice graph's first two audio inputs receive the voice's note number and gate, respectively.
+/// The gate is the normalized velocity of the note while it is held, and `0` once it is released.
+/// Any further audio inputs of the voice graph are exposed as inputs of this processor, and are passed to every voice.
+///
+/// When a note is played and no voices are free, the voice that was triggered the longest ago is stolen.
+///
+/// Note that [`Param`]s in the voice graph are shared between all voices, so each value sent to one will only be received by a single voice.
+///
+/// # MPE
+///
+/// With [`PolyVoice::with_mpe`], the MIDI input is treated as coming from an MPE (MIDI Polyphonic Expression) controller, which plays each note on its own channel.
+/// The voice graph's next three audio inputs then receive the per-note expression of the voice's note:
+///
+/// | Index | Description |
+/// | --- | --- |
+/// | `2` | The pitch bend of the note, in semitones. This includes the pitch bend of the master channel (channel 1), with a range of ±2 semitones. |
+/// | `3` | The pressure (channel aftertouch) of the note, normalized to `[0, 1]`. |
+/// | `4` | The slide (CC 74) of the note, normalized to `[0, 1]`. |
+///
+/// Any further audio inputs of the voice graph are exposed as inputs of this processor, as usual.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `midi` | `Midi` | The MIDI note on/off messages to allocate to voices. |
+/// | `1..` | `2..` | `Float` | The remaining inputs of the voice graph (`5..` with MPE). |
+///
+/// # Outputs
+///
+/// The outputs of the voice graph, summed across all voices.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolyVoice {
+    voices: Vec<Runtime>,
+    states: Vec<VoiceState>,
+    num_inputs: usize,
+    num_outputs: usize,
+    event_counter: u64,
+    // the pitch bend range of the MPE member channels in semitones, if MPE is enabled
+    mpe_bend_range: Option<Float>,
+    channels: [ChannelExpression; 16],
+}
+
+impl PolyVoice {
+    /// Creates a new `PolyVoice` processor with the given number of voices, each running a copy of the given graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph has fewer than two audio inputs.
+    pub fn new(num_voices: usize, voice: Graph) -> Self {
+        assert!(
+            voice.num_audio_inputs() >= 2,
+            "PolyVoice: voice graph must have at least 2 audio inputs (note and gate)"
+        );
+
+        let num_inputs = voice.num_audio_inputs();
+        let num_outputs = voice.num_audio_outputs();
+
+        Self {
+            voices: (0..num_voices)
+                .map(|_| Runtime::new(voice.clone()))
+                .collect(),
+            states: vec![VoiceState::default(); num_voices],
+            num_inputs,
+            num_outputs,
+            event_counter: 0,
+            mpe_bend_range: None,
+            channels: [ChannelExpression::default(); 16],
+        }
+    }
+
+    /// Enables MPE, with the given pitch bend range of the member channels in semitones (usually 48). See the [MPE](#mpe) section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the voice graph has fewer than five audio inputs.
+    pub fn with_mpe(mut self, bend_range: Float) -> Self {
+        assert!(
+            self.num_inputs >= 5,
+            "PolyVoice: voice graph must have at least 5 audio inputs (note, gate, bend, pressure and slide) to use MPE"
+        );
+        self.mpe_bend_range = Some(bend_range);
+        self
+    }
+
+    /// Creates a new `PolyVoice` processor with the given number of voices, building the voice graph with the given closure.
+    pub fn build<F>(num_voices: usize, f: F) -> Self
+    where
+        F: FnOnce(&GraphBuilder),
+    {
+        let builder = GraphBuilder::new();
+        f(&builder);
+        Self::new(num_voices, builder.build())
+    }
+
+    /// Returns the number of voices.
+    pub fn num_voices(&self) -> usize {
+        self.voices.len()
+    }
+
+    // the number of voice graph inputs that are fed by the processor rather than exposed
+    fn num_voice_inputs(&self) -> usize {
+        if self.mpe_bend_range.is_some() {
+            5
+        } else {
+            2
+        }
+    }
+
+    // whether a voice is playing the given note, taking the channel into account with MPE
+    fn is_playing(&self, state: &VoiceState, note: u8, channel: u8) -> bool {
+        state.note == Some(note)
+            && state.gate > 0.0
+            && (self.mpe_bend_range.is_none() || state.channel == channel)
+    }
+
+    fn note_on(&mut self, note: u8, channel: u8, velocity: u8) {
+        // a note that is already held keeps its voice
+        if let Some(voice) = self
+            .states
+            .iter()
+            .position(|state| self.is_playing(state, note, channel))
+        {
+            let state = &mut self.states[voice];
+            state.gate = velocity as Float / 127.0;
+            return;
+        }
+
+        self.event_counter += 1;
+
+        // prefer a released voice that was released the longest ago, otherwise steal the oldest held voice
+        let voice = self
+            .states
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, state)| (state.gate > 0.0, state.last_event))
+            .map(|(i, _)| i);
+
+        if let Some(voice) = voice {
+            let state = &mut self.states[voice];
+            state.note = Some(note);
+            state.channel = channel;
+            state.gate = velocity as Float / 127.0;
+            state.last_event = self.event_counter;
+        }
+    }
+
+    fn note_off(&mut self, note: u8, channel: u8) {
+        self.event_counter += 1;
+
+        for voice in 0..self.states.len() {
+            if self.is_playing(&self.states[voice], note, channel) {
+                let state = &mut self.states[voice];
+                state.gate = 0.0;
+                state.last_event = self.event_counter;
+            }
+        }
+    }
+
+    fn expression(&mut self, msg: &MidiMessage) {
+        let channel = &mut self.channels[msg.channel() as usize];
+        if msg.is_pitch_bend() {
+            let bend = msg.pitch_bend() as Float - 8192.0;
+            channel.bend = (bend / 8191.0).clamp(-1.0, 1.0);
+        } else if msg.is_channel_pressure() {
+            channel.pressure = msg.data1() as Float / 127.0;
+        } else if msg.is_control_change() && msg.data1() == MPE_SLIDE_CC {
+            channel.slide = msg.data2() as Float / 127.0;
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for PolyVoice {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = vec![SignalSpec::new("midi", SignalType::Midi)];
+        for i in self.num_voice_inputs()..self.num_inputs {
+            spec.push(SignalSpec::new(format!("{}", i), SignalType::Float));
+        }
+        spec
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = vec![];
+        for i in 0..self.num_outputs {
+            spec.push(SignalSpec::new(format!("{}", i), SignalType::Float));
+        }
+        spec
+    }
+
+    fn allocate(&mut self, sample_rate: Float, max_block_size: usize) {
+        for voice in &mut self.voices {
+            voice.allocate_for_block_size(sample_rate, max_block_size);
+        }
+    }
+
+    fn resize_buffers(&mut self, _sample_rate: Float, block_size: usize) {
+        for voice in &mut self.voices {
+            voice.set_block_size(block_size).unwrap();
+        }
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        // give each voice its own seed, so they don't all play the same random sequence
+        for (i, voice) in self.voices.iter_mut().enumerate() {
+            voice.graph_mut().set_seed(derive_seed(seed, i as u64));
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        // allocate notes to voices, writing each voice's note and gate (and expression, with MPE) for every sample
+        for (sample_index, midi) in inputs.iter_input_as::<MidiMessage>(0)?.enumerate() {
+            if sample_index >= inputs.block_size() {
+                break;
+            }
+
+            if let Some(msg) = midi {
+                if msg.is_note_on() {
+                    self.note_on(msg.data1(), msg.channel(), msg.data2());
+                } else if msg.is_note_off() {
+                    self.note_off(msg.data1(), msg.channel());
+                } else if self.mpe_bend_range.is_some() {
+                    self.expression(msg);
+                }
+            }
+
+            for (voice, state) in self.voices.iter_mut().zip(&mut self.states) {
+                if let Some(SignalBuffer::Float(note)) = voice.get_input_mut(0) {
+                    note[sample_index] = state.note.map(|note| note as Float);
+                }
+                if let Some(SignalBuffer::Float(gate)) = voice.get_input_mut(1) {
+                    gate[sample_index] = Some(state.gate);
+                }
+
+                let Some(bend_range) = self.mpe_bend_range else {
+                    continue;
+                };
+                if state.gate > 0.0 {
+                    state.expression = self.channels[state.channel as usize];
+                }
+                let master = self.channels[0];
+                let expression = state.expression;
+                let bend = if state.channel == 0 {
+                    master.bend * MPE_MASTER_BEND_RANGE
+                } else {
+                    expression.bend * bend_range + master.bend * MPE_MASTER_BEND_RANGE
+                };
+                let values = [bend, expression.pressure, expression.slide];
+                for (i, value) in values.into_iter().enumerate() {
+                    if let Some(SignalBuffer::Float(buffer)) = voice.get_input_mut(i + 2) {
+                        buffer[sample_index] = Some(value);
+                    }
+                }
+            }
+        }
+
+        let num_voice_inputs = self.num_voice_inputs();
+        for voice in &mut self.voices {
+            voice.transport().sync_to(inputs.transport());
+
+            for i in num_voice_inputs..self.num_inputs {
+                let Some(signal) = inputs.input(i - num_voice_inputs + 1) else {
+                    continue;
+                };
+                let input = voice
+                    .get_input_mut(i)
+                    .ok_or(ProcessorError::NumInputsMismatch)?;
+                input.clone_from(signal);
+            }
+
+            match voice.process() {
+                Ok(()) => {}
+                Err(RuntimeError::GraphRunError(e)) => {
+                    return Err(ProcessorError::SubGraph(Box::new(e)))
+                }
+                Err(_) => {
+                    return Err(ProcessorError::Other);
+                }
+            }
+        }
+
+        for i in 0..self.num_outputs {
+            for (sample_index, out) in outputs.iter_output_mut_as::<Float>(i)?.enumerate() {
+                let mut sum = 0.0;
+                for voice in &self.voices {
+                    if let Some(SignalBuffer::Float(buffer)) = voice.get_output(i) {
+                        sum += buffer[sample_index].unwrap_or_default();
+                    }
+                }
+                *out = Some(sum);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+//! Sample rate conversion using [`rubato`].
+
+use std::collections::VecDeque;
+
+use rubato::{
+    Resampler, SincFixedOut, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+
+use crate::{prelude::*, processor::ProcessMode, runtime::RuntimeError};
+
+// the number of frames the resampler outputs at a time
+const CHUNK_FRAMES: usize = 64;
+
+/// Returns the interpolation parameters used for resampling.
+pub(crate) fn sinc_parameters() -> SincInterpolationParameters {
+    let sinc_len = 128;
+    let window = WindowFunction::BlackmanHarris2;
+    SincInterpolationParameters {
+        sinc_len,
+        f_cutoff: rubato::calculate_cutoff(sinc_len, window),
+        oversampling_factor: 128,
+        interpolation: SincInterpolationType::Cubic,
+        window,
+    }
+}
+
+struct ResampleState {
+    resampler: SincFixedOut<Float>,
+    // the inner graph's outputs for the frames the resampler asked for
+    input: Vec<Vec<Float>>,
+    // the resampler's output for one chunk
+    output: Vec<Vec<Float>>,
+    // resampled frames that haven't been output yet
+    queue: Vec<VecDeque<Float>>,
+}
+
+/// A processor that runs a graph at a different sample rate, converting its outputs to the sample rate of the parent graph.
+///
+/// This can be used to play back sources at their native sample rate (for example, a [`WavFileIn`] streaming a 44.1 kHz file) in a graph running at another rate without changing their pitch.
+///
+/// The conversion uses a windowed sinc interpolator, which delays the outputs by a few dozen samples.
+/// The inner graph must not have any audio inputs.
+///
+/// # Outputs
+///
+/// The outputs of the inner graph, at the sample rate of the parent graph.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Resample {
+    rt: Runtime,
+    sample_rate: Float,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: Option<ResampleState>,
+}
+
+impl Resample {
+    /// Creates a new [`Resample`] processor that runs the given graph at the given sample rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph has any audio inputs.
+    pub fn new(sample_rate: Float, graph: Graph) -> Self {
+        assert_eq!(
+            graph.num_audio_inputs(),
+            0,
+            "Resample: the inner graph must not have any audio inputs"
+        );
+
+        Self {
+            rt: Runtime::new(graph),
+            sample_rate,
+            state: None,
+        }
+    }
+
+    /// Creates a new [`Resample`] processor that runs a graph at the given sample rate, building the graph with the given closure.
+    pub fn build<F>(sample_rate: Float, f: F) -> Self
+    where
+        F: FnOnce(&GraphBuilder),
+    {
+        let builder = GraphBuilder::new();
+        f(&builder);
+        Self::new(sample_rate, builder.build())
+    }
+
+    /// Returns the sample rate the inner graph runs at.
+    pub fn sample_rate(&self) -> Float {
+        self.sample_rate
+    }
+}
+
+impl Clone for Resample {
+    fn clone(&self) -> Self {
+        // the clone creates its own resampler when it is allocated
+        Self {
+            rt: self.rt.clone(),
+            sample_rate: self.sample_rate,
+            state: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Resample {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        let graph = self.rt.graph();
+        let mut spec = vec![];
+        for i in 0..graph.num_audio_outputs() {
+            let name = graph.audio_output_name(i).unwrap();
+            spec.push(SignalSpec::new(name, SignalType::Float));
+        }
+        spec
+    }
+
+    fn allocate(&mut self, sample_rate: Float, max_block_size: usize) {
+        let num_channels = self.rt.graph().num_audio_outputs();
+        let ratio = sample_rate as f64 / self.sample_rate as f64;
+
+        let resampler = match SincFixedOut::new(
+            ratio,
+            1.0,
+            sinc_parameters(),
+            CHUNK_FRAMES,
+            num_channels.max(1),
+        ) {
+            Ok(resampler) => resampler,
+            Err(e) => {
+                log::error!("Error creating resampler: {}", e);
+                self.state = None;
+                return;
+            }
+        };
+
+        let max_input = resampler.input_frames_max();
+        let max_output = resampler.output_frames_max();
+
+        self.rt.allocate_for_block_size(self.sample_rate, max_input);
+
+        self.state = Some(ResampleState {
+            input: vec![vec![0.0; max_input]; num_channels.max(1)],
+            output: vec![vec![0.0; max_output]; num_channels.max(1)],
+            queue: vec![VecDeque::with_capacity(max_block_size + max_output); num_channels],
+            resampler,
+        });
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let Some(state) = &mut self.state else {
+            return Ok(());
+        };
+
+        let (offset, num_samples) = match inputs.mode {
+            ProcessMode::Block => (0, inputs.block_size()),
+            ProcessMode::Sample(sample_index) => (sample_index, 1),
+        };
+
+        let num_channels = state.queue.len();
+
+        // run the inner graph until enough frames have been resampled
+        while num_channels > 0 && state.queue[0].len() < num_samples {
+            let frames = state.resampler.input_frames_next();
+
+            self.rt
+                .set_block_size(frames)
+                .map_err(|_| ProcessorError::Other)?;
+
+            match self.rt.process() {
+                Ok(()) => {}
+                Err(RuntimeError::GraphRunError(e)) => {
+                    return Err(ProcessorError::SubGraph(Box::new(e)))
+                }
+                Err(_) => {
+                    return Err(ProcessorError::Other);
+                }
+            }
+
+            for (channel, input) in state.input.iter_mut().enumerate() {
+                let Some(SignalBuffer::Float(output)) = self.rt.get_output(channel) else {
+                    return Err(ProcessorError::NumOutputsMismatch);
+                };
+                for (input, output) in input[..frames].iter_mut().zip(output.iter()) {
+                    *input = output.unwrap_or_default();
+                }
+            }
+
+            let (_, resampled) = state
+                .resampler
+                .process_into_buffer(&state.input, &mut state.output, None)
+                .map_err(|_| ProcessorError::Other)?;
+
+            for (queue, output) in state.queue.iter_mut().zip(&state.output) {
+                queue.extend(&output[..resampled]);
+            }
+        }
+
+        for (index, (queue, output)) in state
+            .queue
+            .iter_mut()
+            .zip(outputs.outputs.iter_mut())
+            .enumerate()
+        {
+            let SignalBuffer::Float(output) = output else {
+                return Err(ProcessorError::OutputSpecMismatch {
+                    index,
+                    expected: SignalType::Float,
+                    actual: output.signal_type(),
+                });
+            };
+
+            for sample in output[offset..offset + num_samples].iter_mut() {
+                *sample = queue.pop_front();
+            }
+        }
+
+        Ok(())
+    }
+}
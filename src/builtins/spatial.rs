@@ -0,0 +1,861 @@
+//! Processors for placing signals in space, such as panners and ambisonic encoders, and for processing stereo signals.
+
+use raug_macros::iter_proc_io_as;
+
+use crate::{
+    prelude::*,
+    processor::ProcessMode,
+    signal::{PI, TAU},
+};
+
+// equal-power gains for the left and right channels at the given pan position (-1.0 to 1.0)
+#[inline]
+pub(crate) fn pan_gains(pan: Float) -> (Float, Float) {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * PI * 0.25;
+    (angle.cos(), angle.sin())
+}
+
+/// How a panner splits a signal between the left and right channels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PanLaw {
+    /// Each channel is -3 dB in the center, so the total power stays the same wherever the signal is panned.
+    #[default]
+    EqualPower,
+    /// Each channel is -6 dB in the center, so the channels always sum to the input, which suits signals that will be summed to mono.
+    Linear,
+    /// Each channel is -4.5 dB in the center, halfway between [`PanLaw::EqualPower`] and [`PanLaw::Linear`].
+    Compromise,
+}
+
+impl PanLaw {
+    /// Returns the pan law with the given index, as used by the `law` input of a [`Pan`] or [`AutoPan`], in the order the laws are declared (`0` is equal power, `1` is linear, and so on).
+    pub fn from_index(index: i64) -> Option<Self> {
+        match index {
+            0 => Some(Self::EqualPower),
+            1 => Some(Self::Linear),
+            2 => Some(Self::Compromise),
+            _ => None,
+        }
+    }
+
+    /// Returns the gains of the left and right channels at the given pan position, from `-1.0` (left) to `1.0` (right).
+    #[inline]
+    pub fn gains(&self, pan: Float) -> (Float, Float) {
+        let pan = pan.clamp(-1.0, 1.0);
+        let linear = || ((1.0 - pan) * 0.5, (1.0 + pan) * 0.5);
+        match self {
+            PanLaw::EqualPower => pan_gains(pan),
+            PanLaw::Linear => linear(),
+            PanLaw::Compromise => {
+                let (left, right) = linear();
+                let (power_left, power_right) = pan_gains(pan);
+                ((left * power_left).sqrt(), (right * power_right).sqrt())
+            }
+        }
+    }
+}
+
+/// A processor that places a mono signal in the stereo field.
+///
+/// A panner made with [`Pan::framed`] outputs a single stereo [`Frame`] instead of separate channels.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `pan` | `Float` | The pan position, from `-1.0` (left) to `1.0` (right). |
+/// | `2` | `law` | `Int` | The index of the pan law (see [`PanLaw::from_index`]). |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+///
+/// If framed, there is a single output instead:
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Frame` | The stereo signal. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pan {
+    #[cfg_attr(feature = "serde", serde(default))]
+    framed: bool,
+
+    /// The pan position, from `-1.0` (left) to `1.0` (right).
+    pub pan: Float,
+    /// The pan law.
+    pub law: PanLaw,
+}
+
+impl Pan {
+    /// Creates a new [`Pan`] processor with the given pan position, from `-1.0` (left) to `1.0` (right).
+    pub fn new(pan: Float) -> Self {
+        Self {
+            framed: false,
+            pan,
+            law: PanLaw::EqualPower,
+        }
+    }
+
+    /// Sets the pan law.
+    pub fn with_law(mut self, law: PanLaw) -> Self {
+        self.law = law;
+        self
+    }
+
+    /// Makes the panner output a single stereo [`Frame`] instead of separate left and right channels.
+    pub fn framed(mut self) -> Self {
+        self.framed = true;
+        self
+    }
+
+    /// Returns `true` if the panner outputs a stereo [`Frame`].
+    pub fn is_framed(&self) -> bool {
+        self.framed
+    }
+
+    // updates the settings from the given inputs and pans the given sample
+    #[inline]
+    fn pan_sample(
+        &mut self,
+        in_signal: Option<Float>,
+        pan: Option<Float>,
+        law: Option<i64>,
+    ) -> Option<(Float, Float)> {
+        self.pan = pan.unwrap_or(self.pan);
+        if let Some(law) = law.and_then(PanLaw::from_index) {
+            self.law = law;
+        }
+
+        let in_signal = in_signal?;
+        let (left_gain, right_gain) = self.law.gains(self.pan);
+        Some((in_signal * left_gain, in_signal * right_gain))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Pan {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("pan", SignalType::Float),
+            SignalSpec::new("law", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        if self.framed {
+            return vec![SignalSpec::new("out", SignalType::Frame)];
+        }
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        if self.framed {
+            for (in_signal, pan, law, out) in iter_proc_io_as!(
+                inputs as [Float, Float, i64],
+                outputs as [Frame]
+            ) {
+                *out = self
+                    .pan_sample(*in_signal, *pan, *law)
+                    .map(|(left, right)| Frame::stereo(left, right));
+            }
+
+            return Ok(());
+        }
+
+        for (in_signal, pan, law, left, right) in iter_proc_io_as!(
+            inputs as [Float, Float, i64],
+            outputs as [Float, Float]
+        ) {
+            let panned = self.pan_sample(*in_signal, *pan, *law);
+            *left = panned.map(|(left, _)| left);
+            *right = panned.map(|(_, right)| right);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that moves a mono signal back and forth across the stereo field with a built-in sine LFO.
+///
+/// An auto-panner created with [`AutoPan::synced`] measures its period in beats of the graph's [`Transport`] instead of running at a frequency, like a synced [`Lfo`].
+/// For other modulation shapes, drive a [`Pan`] with an [`Lfo`] instead.
+///
+/// An auto-panner made with [`AutoPan::framed`] outputs a single stereo [`Frame`] instead of separate channels.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `rate` | `Float` | The frequency in Hz (or the period in beats, if synced). |
+/// | `2` | `depth` | `Float` | How far the signal moves from the center, from `0.0` to `1.0` (hard left to hard right). |
+/// | `3` | `law` | `Int` | The index of the pan law (see [`PanLaw::from_index`]). |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+///
+/// If framed, there is a single output instead:
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Frame` | The stereo signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutoPan {
+    // whether the rate is a period in beats of the transport
+    synced: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    framed: bool,
+    phase: Float,
+
+    /// The frequency in Hz (or the period in beats, if synced).
+    pub rate: Float,
+    /// How far the signal moves from the center, from `0.0` to `1.0`.
+    pub depth: Float,
+    /// The pan law.
+    pub law: PanLaw,
+}
+
+impl AutoPan {
+    /// Creates a new [`AutoPan`] processor with the given frequency in Hz and depth.
+    pub fn new(frequency: Float, depth: Float) -> Self {
+        Self {
+            synced: false,
+            framed: false,
+            phase: 0.0,
+            rate: frequency,
+            depth,
+            law: PanLaw::EqualPower,
+        }
+    }
+
+    /// Creates a new [`AutoPan`] processor with the given depth, which completes a cycle every given number of beats of the graph's [`Transport`].
+    pub fn synced(beats: Float, depth: Float) -> Self {
+        Self {
+            synced: true,
+            ..Self::new(beats, depth)
+        }
+    }
+
+    /// Sets the pan law.
+    pub fn with_law(mut self, law: PanLaw) -> Self {
+        self.law = law;
+        self
+    }
+
+    /// Makes the auto-panner output a single stereo [`Frame`] instead of separate left and right channels.
+    pub fn framed(mut self) -> Self {
+        self.framed = true;
+        self
+    }
+
+    /// Returns `true` if the auto-panner is synced to the transport.
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Returns `true` if the auto-panner outputs a stereo [`Frame`].
+    pub fn is_framed(&self) -> bool {
+        self.framed
+    }
+
+    // updates the settings from the given inputs, advances the LFO by one sample and pans the given sample
+    #[inline]
+    fn pan_sample(
+        &mut self,
+        in_signal: Option<Float>,
+        rate: Option<Float>,
+        depth: Option<Float>,
+        law: Option<i64>,
+        beat: Float,
+        sample_rate: Float,
+    ) -> Option<(Float, Float)> {
+        self.rate = rate.unwrap_or(self.rate);
+        self.depth = depth.unwrap_or(self.depth);
+        if let Some(law) = law.and_then(PanLaw::from_index) {
+            self.law = law;
+        }
+
+        if self.synced {
+            self.phase = if self.rate > 0.0 {
+                (beat / self.rate).rem_euclid(1.0)
+            } else {
+                0.0
+            };
+        }
+
+        let pan = self.depth.clamp(0.0, 1.0) * (TAU * self.phase).sin();
+        let (left_gain, right_gain) = self.law.gains(pan);
+
+        if !self.synced {
+            self.phase = (self.phase + self.rate / sample_rate).rem_euclid(1.0);
+        }
+
+        let in_signal = in_signal?;
+        Some((in_signal * left_gain, in_signal * right_gain))
+    }
+}
+
+impl Default for AutoPan {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for AutoPan {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("rate", SignalType::Float),
+            SignalSpec::new("depth", SignalType::Float),
+            SignalSpec::new("law", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        if self.framed {
+            return vec![SignalSpec::new("out", SignalType::Frame)];
+        }
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let transport = *inputs.transport();
+        let offset = match inputs.mode {
+            ProcessMode::Block => 0,
+            ProcessMode::Sample(sample_index) => sample_index,
+        };
+        let beat = |i: usize| transport.beat_at(offset + i, sample_rate);
+
+        if self.framed {
+            for (i, (in_signal, rate, depth, law, out)) in iter_proc_io_as!(
+                inputs as [Float, Float, Float, i64],
+                outputs as [Frame]
+            )
+            .enumerate()
+            {
+                *out = self
+                    .pan_sample(*in_signal, *rate, *depth, *law, beat(i), sample_rate)
+                    .map(|(left, right)| Frame::stereo(left, right));
+            }
+
+            return Ok(());
+        }
+
+        for (i, (in_signal, rate, depth, law, left, right)) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, i64],
+            outputs as [Float, Float]
+        )
+        .enumerate()
+        {
+            let panned = self.pan_sample(*in_signal, *rate, *depth, *law, beat(i), sample_rate);
+            *left = panned.map(|(left, _)| left);
+            *right = panned.map(|(_, right)| right);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that converts a stereo signal into mid (the sum of the channels) and side (their difference) signals.
+///
+/// The mid and side signals can be processed separately (for example, to EQ or compress only the center of a mix) and converted back with a [`MidSideDecode`].
+/// Both are halved, so that decoding restores the original channels exactly.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `mid` | `Float` | The mid signal. |
+/// | `1` | `side` | `Float` | The side signal. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidSideEncode;
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MidSideEncode {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("mid", SignalType::Float),
+            SignalSpec::new("side", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (left, right, mid, side) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float, Float]
+        ) {
+            let left = left.unwrap_or(0.0);
+            let right = right.unwrap_or(0.0);
+
+            *mid = Some((left + right) * 0.5);
+            *side = Some((left - right) * 0.5);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that converts mid and side signals (as made by a [`MidSideEncode`]) back into a stereo signal.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `mid` | `Float` | The mid signal. |
+/// | `1` | `side` | `Float` | The side signal. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidSideDecode;
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MidSideDecode {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("mid", SignalType::Float),
+            SignalSpec::new("side", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (mid, side, left, right) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float, Float]
+        ) {
+            let mid = mid.unwrap_or(0.0);
+            let side = side.unwrap_or(0.0);
+
+            *left = Some(mid + side);
+            *right = Some(mid - side);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that narrows or widens a stereo signal by scaling its side signal.
+///
+/// A width of `0.0` collapses the signal to mono, `1.0` leaves it unchanged, and values above `1.0` exaggerate the differences between the channels.
+///
+/// A processor made with [`StereoWidth::framed`] takes and outputs a single stereo [`Frame`] instead of separate channels.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+/// | `2` | `width` | `Float` | The stereo width. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left channel. |
+/// | `1` | `right` | `Float` | The right channel. |
+///
+/// If framed, the inputs and outputs are instead:
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Frame` | The stereo input signal. |
+/// | `1` | `width` | `Float` | The stereo width. |
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Frame` | The stereo output signal. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StereoWidth {
+    #[cfg_attr(feature = "serde", serde(default))]
+    framed: bool,
+
+    /// The stereo width.
+    pub width: Float,
+}
+
+impl StereoWidth {
+    /// Creates a new [`StereoWidth`] processor with the given width.
+    pub fn new(width: Float) -> Self {
+        Self {
+            framed: false,
+            width,
+        }
+    }
+
+    /// Makes the processor take and output a single stereo [`Frame`] instead of separate left and right channels.
+    pub fn framed(mut self) -> Self {
+        self.framed = true;
+        self
+    }
+
+    /// Returns `true` if the processor takes and outputs stereo [`Frame`]s.
+    pub fn is_framed(&self) -> bool {
+        self.framed
+    }
+
+    // updates the width from the given input and applies it to the given channels
+    #[inline]
+    fn widen(&mut self, left: Float, right: Float, width: Option<Float>) -> (Float, Float) {
+        self.width = width.unwrap_or(self.width);
+
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5 * self.width.max(0.0);
+        (mid + side, mid - side)
+    }
+}
+
+impl Default for StereoWidth {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for StereoWidth {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        if self.framed {
+            return vec![
+                SignalSpec::new("in", SignalType::Frame),
+                SignalSpec::new("width", SignalType::Float),
+            ];
+        }
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+            SignalSpec::new("width", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        if self.framed {
+            return vec![SignalSpec::new("out", SignalType::Frame)];
+        }
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        if self.framed {
+            for (in_frame, width, out) in iter_proc_io_as!(
+                inputs as [Frame, Float],
+                outputs as [Frame]
+            ) {
+                let (left, right) = in_frame
+                    .as_ref()
+                    .map_or((0.0, 0.0), |frame| (frame.left(), frame.right()));
+                let (left, right) = self.widen(left, right, *width);
+                *out = Some(Frame::stereo(left, right));
+            }
+
+            return Ok(());
+        }
+
+        for (left, right, width, out_left, out_right) in iter_proc_io_as!(
+            inputs as [Float, Float, Float],
+            outputs as [Float, Float]
+        ) {
+            let (left, right) = self.widen(left.unwrap_or(0.0), right.unwrap_or(0.0), *width);
+            *out_left = Some(left);
+            *out_right = Some(right);
+        }
+
+        Ok(())
+    }
+}
+
+// the unit vector pointing at the given azimuth and elevation in degrees, as (x, y, z) with x to the front, y to the left, and z up
+#[inline]
+fn direction(azimuth: Float, elevation: Float) -> (Float, Float, Float) {
+    let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+    (
+        azimuth.cos() * elevation.cos(),
+        azimuth.sin() * elevation.cos(),
+        elevation.sin(),
+    )
+}
+
+/// A processor that encodes a mono signal into first-order ambisonics (B-format), placing it at the given direction on a sphere around the listener.
+///
+/// The output uses the AmbiX convention: the channels are in ACN order (`w`, `y`, `z`, `x`) with SN3D normalization.
+/// Several encoded signals can be mixed by adding their channels together, and the result turned into speaker feeds with an [`AmbiDecode`].
+///
+/// Angles are in degrees. The azimuth is `0.0` in front of the listener and increases counterclockwise (`90.0` is to the left), and the elevation is `90.0` straight up.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `azimuth` | `Float` | The horizontal angle in degrees. |
+/// | `2` | `elevation` | `Float` | The vertical angle in degrees. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `w` | `Float` | The omnidirectional channel. |
+/// | `1` | `y` | `Float` | The left-right channel. |
+/// | `2` | `z` | `Float` | The up-down channel. |
+/// | `3` | `x` | `Float` | The front-back channel. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmbiEncode {
+    /// The horizontal angle in degrees.
+    pub azimuth: Float,
+    /// The vertical angle in degrees.
+    pub elevation: Float,
+}
+
+impl AmbiEncode {
+    /// Creates a new [`AmbiEncode`] processor with the given azimuth and elevation in degrees.
+    pub fn new(azimuth: Float, elevation: Float) -> Self {
+        Self { azimuth, elevation }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for AmbiEncode {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("azimuth", SignalType::Float),
+            SignalSpec::new("elevation", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("w", SignalType::Float),
+            SignalSpec::new("y", SignalType::Float),
+            SignalSpec::new("z", SignalType::Float),
+            SignalSpec::new("x", SignalType::Float),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let mut gains = direction(self.azimuth, self.elevation);
+
+        for (in_signal, azimuth, elevation, w, y, z, x) in iter_proc_io_as!(
+            inputs as [Float, Float, Float],
+            outputs as [Float, Float, Float, Float]
+        ) {
+            let azimuth = azimuth.unwrap_or(self.azimuth);
+            let elevation = elevation.unwrap_or(self.elevation);
+            if azimuth != self.azimuth || elevation != self.elevation {
+                self.azimuth = azimuth;
+                self.elevation = elevation;
+                gains = direction(azimuth, elevation);
+            }
+
+            let in_signal = in_signal.unwrap_or(0.0);
+            let (gain_x, gain_y, gain_z) = gains;
+            *w = Some(in_signal);
+            *y = Some(in_signal * gain_y);
+            *z = Some(in_signal * gain_z);
+            *x = Some(in_signal * gain_x);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that decodes first-order ambisonics (B-format, as made by an [`AmbiEncode`]) into feeds for a layout of speakers.
+///
+/// [`AmbiDecode::new`] creates a basic (sampling) decoder for speakers at the given directions, which should be spread evenly around the listener.
+/// If all the speakers are at an elevation of `0.0`, the layout is treated as horizontal-only and the height channel is ignored.
+///
+/// [`AmbiDecode::stereo`] instead points a pair of virtual cardioid microphones to the left and right, for listening on headphones or stereo speakers.
+/// This gives a stable stereo image, but not the externalized sound of a true binaural (HRTF-based) decode.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `w` | `Float` | The omnidirectional channel. |
+/// | `1` | `y` | `Float` | The left-right channel. |
+/// | `2` | `z` | `Float` | The up-down channel. |
+/// | `3` | `x` | `Float` | The front-back channel. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0..N` | `out0..outN` | `Float` | The feed of each speaker, in the order they were given. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmbiDecode {
+    // the gain of each input channel (in ACN order) in each output
+    gains: Vec<[Float; 4]>,
+}
+
+impl AmbiDecode {
+    /// Creates a new [`AmbiDecode`] processor for speakers at the given directions, as `(azimuth, elevation)` pairs in degrees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no speakers are given.
+    pub fn new(speakers: &[(Float, Float)]) -> Self {
+        assert!(!speakers.is_empty(), "at least one speaker is required");
+        let horizontal = speakers.iter().all(|&(_, elevation)| elevation == 0.0);
+        // the weight of the directional channels that best reconstructs the sound field
+        let directional = if horizontal { 2.0 } else { 3.0 };
+        let scale = 1.0 / speakers.len() as Float;
+        let gains = speakers
+            .iter()
+            .map(|&(azimuth, elevation)| {
+                let (x, y, z) = direction(azimuth, elevation);
+                let z = if horizontal { 0.0 } else { z };
+                [
+                    scale,
+                    scale * directional * y,
+                    scale * directional * z,
+                    scale * directional * x,
+                ]
+            })
+            .collect();
+        Self { gains }
+    }
+
+    /// Creates a new [`AmbiDecode`] processor with two outputs (left and right) from virtual cardioid microphones pointing to either side.
+    pub fn stereo() -> Self {
+        let gains = [90.0, -90.0]
+            .into_iter()
+            .map(|azimuth| {
+                let (x, y, _) = direction(azimuth, 0.0);
+                [0.5, 0.5 * y, 0.0, 0.5 * x]
+            })
+            .collect();
+        Self { gains }
+    }
+
+    /// Returns the number of speaker feeds.
+    pub fn num_speakers(&self) -> usize {
+        self.gains.len()
+    }
+}
+
+impl Default for AmbiDecode {
+    fn default() -> Self {
+        Self::stereo()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for AmbiDecode {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("w", SignalType::Float),
+            SignalSpec::new("y", SignalType::Float),
+            SignalSpec::new("z", SignalType::Float),
+            SignalSpec::new("x", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        (0..self.gains.len())
+            .map(|i| SignalSpec::new(format!("out{}", i), SignalType::Float))
+            .collect()
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let mut channels = [
+            inputs.iter_input_as_floats(0)?,
+            inputs.iter_input_as_floats(1)?,
+            inputs.iter_input_as_floats(2)?,
+            inputs.iter_input_as_floats(3)?,
+        ];
+
+        for sample_index in 0..inputs.block_size() {
+            let mut frame: [Float; 4] = [0.0; 4];
+            for (value, channel) in frame.iter_mut().zip(&mut channels) {
+                *value = channel.next().flatten().unwrap_or(0.0);
+            }
+
+            for (speaker, gains) in self.gains.iter().enumerate() {
+                let feed = gains.iter().zip(&frame).map(|(g, x)| g * x).sum::<Float>();
+                outputs.output(speaker).set_as::<Float>(sample_index, feed);
+            }
+        }
+
+        Ok(())
+    }
+}
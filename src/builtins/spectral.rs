@@ -0,0 +1,691 @@
+//! Processors for short-time Fourier transform (STFT) processing in the audio graph.
+//!
+//! [`FftAnalyze`] splits a signal into overlapping windowed frames and outputs the [`Spectrum`] of each frame.
+//! Spectra can then be modified by other processors (such as [`SpectralGate`] or [`SpectralMul`]) before being turned back into a signal by [`IfftSynthesize`].
+//! Spectra can also be measured, for example by [`SpectralCentroid`] and [`SpectralFlatness`].
+
+use std::sync::Arc;
+
+use num::Complex;
+
+use crate::{fft::FftError, prelude::*};
+
+// the FFT plan and working buffers of an `FftAnalyze`
+struct AnalyzeState {
+    plan: Arc<dyn realfft::RealToComplex<Float>>,
+    window: Box<[Float]>,
+    // the most recent `fft_length` input samples, as a ring buffer
+    history: Box<[Float]>,
+    write_index: usize,
+    // the number of samples since the last frame was output
+    hop_counter: usize,
+    frame: Box<[Float]>,
+    spectrum: Spectrum,
+    scratch: Box<[Complex<Float>]>,
+    // spectra taken out of the output on samples without a frame, reused for later frames so the audio thread doesn't allocate
+    spares: Vec<Spectrum>,
+}
+
+/// A processor that outputs the spectrum of overlapping, windowed frames of its input.
+///
+/// Every `hop_length` samples, the last `fft_length` samples of the input are windowed and transformed, and the resulting [`Spectrum`] is output.
+/// On all other samples, the output is `None`.
+///
+/// The spectrum is scaled so that a sine wave with an amplitude of `1` has a peak magnitude of about `1`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The spectrum of the most recent frame, output once per hop. |
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FftAnalyze {
+    fft_length: usize,
+    hop_length: usize,
+    window_function: WindowFunction,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: Option<AnalyzeState>,
+}
+
+impl FftAnalyze {
+    /// Creates a new [`FftAnalyze`] processor with the given FFT length, hop length, and window function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the FFT length is not even, or if the hop length is zero or greater than the FFT length.
+    pub fn new(fft_length: usize, hop_length: usize, window_function: WindowFunction) -> Self {
+        assert_stft_lengths(fft_length, hop_length);
+        Self {
+            fft_length,
+            hop_length,
+            window_function,
+            state: None,
+        }
+    }
+
+    /// Returns the FFT length.
+    pub fn fft_length(&self) -> usize {
+        self.fft_length
+    }
+
+    /// Returns the hop length.
+    pub fn hop_length(&self) -> usize {
+        self.hop_length
+    }
+}
+
+impl Clone for FftAnalyze {
+    fn clone(&self) -> Self {
+        // the clone plans its own FFT when it is allocated
+        Self {
+            fft_length: self.fft_length,
+            hop_length: self.hop_length,
+            window_function: self.window_function.clone(),
+            state: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for FftAnalyze {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn allocate(&mut self, _sample_rate: Float, max_block_size: usize) {
+        if self.state.is_some() {
+            return;
+        }
+
+        let plan = realfft::RealFftPlanner::new().plan_fft_forward(self.fft_length);
+        let window = self.window_function.generate(self.fft_length);
+
+        // scale the window so a full-scale sine has a peak magnitude of about 1
+        let gain = 2.0 / window.iter().sum::<Float>();
+        let window = window.iter().map(|w| w * gain).collect();
+
+        self.state = Some(AnalyzeState {
+            window,
+            history: vec![0.0; self.fft_length].into_boxed_slice(),
+            write_index: 0,
+            hop_counter: 0,
+            frame: plan.make_input_vec().into_boxed_slice(),
+            spectrum: Spectrum::new(self.fft_length),
+            scratch: plan.make_scratch_vec().into_boxed_slice(),
+            // enough for a frame on every hop of the largest block
+            spares: (0..max_block_size.div_ceil(self.hop_length) + 1)
+                .map(|_| Spectrum::new(self.fft_length))
+                .collect(),
+            plan,
+        });
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let Some(state) = &mut self.state else {
+            return Ok(());
+        };
+
+        for (input, out) in iter_proc_io_as!(inputs as [Float], outputs as [Spectrum]) {
+            state.history[state.write_index] = input.unwrap_or_default();
+            state.write_index = (state.write_index + 1) % self.fft_length;
+            state.hop_counter += 1;
+
+            if state.hop_counter < self.hop_length {
+                if let Some(spectrum) = out.take() {
+                    if state.spares.len() < state.spares.capacity() {
+                        state.spares.push(spectrum);
+                    }
+                }
+                continue;
+            }
+            state.hop_counter = 0;
+
+            // unroll the ring buffer, oldest sample first
+            for (i, sample) in state.frame.iter_mut().enumerate() {
+                let index = (state.write_index + i) % self.fft_length;
+                *sample = state.history[index] * state.window[i];
+            }
+
+            state
+                .plan
+                .process_with_scratch(&mut state.frame, &mut state.spectrum, &mut state.scratch)
+                .map_err(FftError::from)?;
+
+            if out.is_none() {
+                *out = state.spares.pop();
+            }
+            match out {
+                Some(spectrum) => spectrum.clone_from(&state.spectrum),
+                None => *out = Some(state.spectrum.clone()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// the FFT plan and working buffers of an `IfftSynthesize`
+struct SynthesizeState {
+    plan: Arc<dyn realfft::ComplexToReal<Float>>,
+    window: Box<[Float]>,
+    spectrum: Box<[Complex<Float>]>,
+    frame: Box<[Float]>,
+    scratch: Box<[Complex<Float>]>,
+    // the overlap-added output, as a ring buffer
+    accum: Box<[Float]>,
+    read_index: usize,
+}
+
+/// A processor that turns a stream of spectra back into a signal, using windowed overlap-add.
+///
+/// This is the inverse of [`FftAnalyze`], and should be created with the same FFT length, hop length, and window function as the analyzer that produced its input.
+/// Unmodified spectra are reconstructed into the original signal, delayed by `fft_length - 1` samples.
+///
+/// A new frame is added to the output every time a spectrum is received.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The spectra to synthesize. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The synthesized signal. |
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IfftSynthesize {
+    fft_length: usize,
+    hop_length: usize,
+    window_function: WindowFunction,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: Option<SynthesizeState>,
+}
+
+impl IfftSynthesize {
+    /// Creates a new [`IfftSynthesize`] processor with the given FFT length, hop length, and window function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the FFT length is not even, or if the hop length is zero or greater than the FFT length.
+    pub fn new(fft_length: usize, hop_length: usize, window_function: WindowFunction) -> Self {
+        assert_stft_lengths(fft_length, hop_length);
+        Self {
+            fft_length,
+            hop_length,
+            window_function,
+            state: None,
+        }
+    }
+
+    /// Returns the FFT length.
+    pub fn fft_length(&self) -> usize {
+        self.fft_length
+    }
+
+    /// Returns the hop length.
+    pub fn hop_length(&self) -> usize {
+        self.hop_length
+    }
+}
+
+impl Clone for IfftSynthesize {
+    fn clone(&self) -> Self {
+        // the clone plans its own FFT when it is allocated
+        Self {
+            fft_length: self.fft_length,
+            hop_length: self.hop_length,
+            window_function: self.window_function.clone(),
+            state: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for IfftSynthesize {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, _sample_rate: Float, _max_block_size: usize) {
+        if self.state.is_some() {
+            return;
+        }
+
+        let plan = realfft::RealFftPlanner::new().plan_fft_inverse(self.fft_length);
+        let window = self.window_function.generate(self.fft_length);
+
+        // undo the analyzer's scaling and the unnormalized inverse FFT, then compensate for the overlapping synthesis windows
+        let window_sum = window.iter().sum::<Float>();
+        let window_power = window.iter().map(|w| w * w).sum::<Float>();
+        let gain = window_sum / (2.0 * self.fft_length as Float)
+            * (self.hop_length as Float / window_power);
+        let window = window.iter().map(|w| w * gain).collect();
+
+        self.state = Some(SynthesizeState {
+            window,
+            spectrum: plan.make_input_vec().into_boxed_slice(),
+            frame: plan.make_output_vec().into_boxed_slice(),
+            scratch: plan.make_scratch_vec().into_boxed_slice(),
+            accum: vec![0.0; self.fft_length].into_boxed_slice(),
+            read_index: 0,
+            plan,
+        });
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let Some(state) = &mut self.state else {
+            return Ok(());
+        };
+
+        for (spectrum, out) in iter_proc_io_as!(inputs as [Spectrum], outputs as [Float]) {
+            if let Some(spectrum) = spectrum {
+                if spectrum.len() != state.spectrum.len() {
+                    return Err(ProcessorError::InvalidValue(
+                        "spectrum length does not match the FFT length",
+                    ));
+                }
+
+                state.spectrum.copy_from_slice(spectrum);
+
+                // the DC and Nyquist bins of a real signal have no imaginary part
+                let nyquist = state.spectrum.len() - 1;
+                state.spectrum[0].im = 0.0;
+                state.spectrum[nyquist].im = 0.0;
+
+                state
+                    .plan
+                    .process_with_scratch(&mut state.spectrum, &mut state.frame, &mut state.scratch)
+                    .map_err(FftError::from)?;
+
+                for (i, sample) in state.frame.iter().enumerate() {
+                    let index = (state.read_index + i) % self.fft_length;
+                    state.accum[index] += sample * state.window[i];
+                }
+            }
+
+            *out = Some(state.accum[state.read_index]);
+            state.accum[state.read_index] = 0.0;
+            state.read_index = (state.read_index + 1) % self.fft_length;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that silences the bins of a spectrum whose magnitude is below a threshold.
+///
+/// This can be placed between an [`FftAnalyze`] and an [`IfftSynthesize`] to remove quiet, noisy parts of a signal while keeping its louder partials.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The input spectrum. |
+/// | `1` | `threshold` | `Float` | The magnitude below which bins are silenced. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The gated spectrum. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpectralGate {
+    threshold: Float,
+}
+
+impl SpectralGate {
+    /// Creates a new [`SpectralGate`] processor with the given initial threshold.
+    pub fn new(threshold: Float) -> Self {
+        Self { threshold }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SpectralGate {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("spectrum", SignalType::Spectrum),
+            SignalSpec::new("threshold", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (spectrum, threshold, out) in
+            iter_proc_io_as!(inputs as [Spectrum, Float], outputs as [Spectrum])
+        {
+            if let Some(threshold) = threshold {
+                self.threshold = *threshold;
+            }
+
+            let Some(spectrum) = spectrum else {
+                *out = None;
+                continue;
+            };
+
+            match out {
+                Some(out) if out.len() == spectrum.len() => out.copy_from_slice(spectrum),
+                _ => *out = Some(spectrum.clone()),
+            }
+
+            if let Some(out) = out {
+                for bin in out.iter_mut() {
+                    if bin.norm() < self.threshold {
+                        *bin = Complex::default();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that multiplies two spectra bin by bin.
+///
+/// Multiplying spectra convolves the signals they came from, which can be used for filtering with the spectrum of an impulse response, or for cross-synthesis.
+/// Multiplying a spectrum by the conjugate of another (see [`SpectralConj`]) correlates them instead.
+///
+/// A spectrum is output only when both inputs receive one on the same sample. Bins missing from `b` are treated as zero.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `a` | `Spectrum` | The first spectrum. |
+/// | `1` | `b` | `Spectrum` | The second spectrum. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The product of the spectra, with as many bins as `a`. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpectralMul;
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SpectralMul {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("a", SignalType::Spectrum),
+            SignalSpec::new("b", SignalType::Spectrum),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (a, b, out) in iter_proc_io_as!(inputs as [Spectrum, Spectrum], outputs as [Spectrum]) {
+            let (Some(a), Some(b)) = (a, b) else {
+                *out = None;
+                continue;
+            };
+
+            match out {
+                Some(out) if out.len() == a.len() => out.copy_from_slice(a),
+                _ => *out = Some(a.clone()),
+            }
+
+            if let Some(out) = out {
+                for (i, bin) in out.iter_mut().enumerate() {
+                    *bin *= b.get(i).copied().unwrap_or_default();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that outputs the complex conjugate of each bin of a spectrum, negating its phase.
+///
+/// The conjugate of a spectrum is the spectrum of the signal circularly reversed in time.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The input spectrum. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The conjugated spectrum. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpectralConj;
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SpectralConj {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (spectrum, out) in iter_proc_io_as!(inputs as [Spectrum], outputs as [Spectrum]) {
+            let Some(spectrum) = spectrum else {
+                *out = None;
+                continue;
+            };
+
+            match out {
+                Some(out) if out.len() == spectrum.len() => out.copy_from_slice(spectrum),
+                _ => *out = Some(spectrum.clone()),
+            }
+
+            if let Some(out) = out {
+                for bin in out.iter_mut() {
+                    *bin = bin.conj();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that measures the spectral centroid of a spectrum: the magnitude-weighted mean frequency of its bins.
+///
+/// The centroid follows the perceived "brightness" of a sound.
+/// It is updated every time a spectrum is received and held in between, and is `0.0` for silence.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The input spectrum. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `centroid` | `Float` | The spectral centroid in Hz. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpectralCentroid {
+    centroid: Float,
+}
+
+impl SpectralCentroid {
+    /// Returns the most recently measured centroid in Hz.
+    pub fn centroid(&self) -> Float {
+        self.centroid
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SpectralCentroid {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("centroid", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        for (spectrum, out) in iter_proc_io_as!(inputs as [Spectrum], outputs as [Float]) {
+            if let Some(spectrum) = spectrum {
+                let mut weighted = 0.0;
+                let mut total = 0.0;
+                for (bin, value) in spectrum.iter().enumerate() {
+                    let magnitude = value.norm();
+                    weighted += magnitude * spectrum.bin_frequency(bin, sample_rate);
+                    total += magnitude;
+                }
+                self.centroid = if total > 0.0 { weighted / total } else { 0.0 };
+            }
+
+            *out = Some(self.centroid);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that measures the spectral flatness of a spectrum: the ratio of the geometric mean of its power to the arithmetic mean.
+///
+/// The flatness is close to `1.0` for noise-like sounds with an even spectrum, and close to `0.0` for tonal sounds with a few strong partials.
+/// The DC bin is ignored.
+/// It is updated every time a spectrum is received and held in between, and is `0.0` for silence.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `spectrum` | `Spectrum` | The input spectrum. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `flatness` | `Float` | The spectral flatness, from `0.0` to `1.0`. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpectralFlatness {
+    flatness: Float,
+}
+
+impl SpectralFlatness {
+    /// Returns the most recently measured flatness.
+    pub fn flatness(&self) -> Float {
+        self.flatness
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SpectralFlatness {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("spectrum", SignalType::Spectrum)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("flatness", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (spectrum, out) in iter_proc_io_as!(inputs as [Spectrum], outputs as [Float]) {
+            if let Some(spectrum) = spectrum {
+                let bins = spectrum.get(1..).unwrap_or_default();
+                let mut log_sum = 0.0;
+                let mut sum = 0.0;
+                for value in bins {
+                    // keeps empty bins from making the geometric mean zero
+                    let power = value.norm_sqr() + 1e-20;
+                    log_sum += power.ln();
+                    sum += power;
+                }
+                let len = bins.len() as Float;
+                self.flatness = if sum > len * 1e-20 {
+                    ((log_sum / len).exp() / (sum / len)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+            }
+
+            *out = Some(self.flatness);
+        }
+
+        Ok(())
+    }
+}
+
+#[track_caller]
+fn assert_stft_lengths(fft_length: usize, hop_length: usize) {
+    assert!(
+        fft_length > 0 && fft_length.is_multiple_of(2),
+        "the FFT length must be even"
+    );
+    assert!(
+        hop_length > 0 && hop_length <= fft_length,
+        "the hop length must be between 1 and the FFT length"
+    );
+}
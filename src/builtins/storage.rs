@@ -53,10 +53,16 @@ impl Processor for AudioBuffer {
     fn process(
         &mut self,
         inputs: ProcessorInputs,
-        outputs: ProcessorOutputs,
+        mut outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
         let buffer = inputs.asset(&self.buffer)?;
-        let mut buffer = buffer.try_lock().unwrap();
+        // the asset is shared with the graph on the editing side, which may be reading it (e.g. to serialize it),
+        // so skip the block rather than wait for it on the audio thread
+        let Some(mut buffer) = buffer.try_lock() else {
+            outputs.output(0).fill_as::<Float>(None);
+            outputs.output(1).fill_as::<i64>(None);
+            return Ok(());
+        };
         let buffer = buffer.as_buffer_mut().ok_or_else(|| {
             ProcessorError::InvalidAsset(self.buffer.clone(), "Buffer".to_string())
         })?;
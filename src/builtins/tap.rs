@@ -0,0 +1,810 @@
+//! Processors that make signals visible to UI threads, such as meters and oscilloscopes.
+//!
+//! Each tap publishes its data into a lock-free triple buffer, which is read through a [`TapReader`].
+//! Readers always see the most recently published data, and never block the audio thread or need the graph to be locked.
+
+use std::sync::{Arc, Mutex};
+
+use smallvec::SmallVec;
+use triple_buffer::{triple_buffer, Input, Output};
+
+use crate::prelude::*;
+
+use super::{
+    dynamics::{amp_to_db, db_to_amp},
+    filters::{dot_product, windowed_sinc, SecondOrderSection},
+};
+
+#[derive(Clone, Debug)]
+struct TapBuffer {
+    // only the processor that is currently running writes to the input, so this lock is never contended
+    input: Arc<Mutex<Input<Vec<Float>>>>,
+    // taken by the first call to `reader`
+    output: Arc<Mutex<Option<Output<Vec<Float>>>>>,
+}
+
+impl TapBuffer {
+    fn new(len: usize) -> Self {
+        let (input, output) = triple_buffer(&vec![0.0; len]);
+        Self {
+            input: Arc::new(Mutex::new(input)),
+            output: Arc::new(Mutex::new(Some(output))),
+        }
+    }
+
+    fn reader(&self) -> Option<TapReader> {
+        let output = self.output.lock().unwrap().take()?;
+        Some(TapReader { output })
+    }
+
+    fn publish(&self, f: impl FnOnce(&mut [Float])) {
+        let Ok(mut input) = self.input.try_lock() else {
+            return;
+        };
+        f(input.input_buffer());
+        input.publish();
+    }
+}
+
+/// Reads the data published by a [`MeterTap`], [`ScopeTap`], [`LoudnessMeter`], or [`TruePeak`], e.g. from a UI thread.
+#[derive(Debug)]
+pub struct TapReader {
+    output: Output<Vec<Float>>,
+}
+
+impl TapReader {
+    /// Returns the most recently published data.
+    pub fn latest(&mut self) -> &[Float] {
+        self.output.read()
+    }
+
+    /// Returns `true` if new data has been published since the last call to [`TapReader::latest`].
+    pub fn updated(&self) -> bool {
+        self.output.updated()
+    }
+}
+
+/// A processor that publishes the level of its input once per block, for drawing meters.
+///
+/// [`TapReader::latest`] returns `[peak, rms]`, the peak and RMS amplitudes of the most recently processed block.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The signal to meter. |
+///
+/// # Outputs
+///
+/// None.
+#[derive(Clone, Debug)]
+pub struct MeterTap {
+    buffer: TapBuffer,
+}
+
+impl MeterTap {
+    /// Creates a new [`MeterTap`] processor.
+    pub fn new() -> Self {
+        Self {
+            buffer: TapBuffer::new(2),
+        }
+    }
+
+    /// Returns the reader for the published levels.
+    ///
+    /// Each tap has a single reader, so this returns `None` if it has already been taken (including by a clone of this tap).
+    pub fn reader(&self) -> Option<TapReader> {
+        self.buffer.reader()
+    }
+}
+
+impl Default for MeterTap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MeterTap {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        _outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let mut peak: Float = 0.0;
+        let mut sum_squares: Float = 0.0;
+        let mut len = 0;
+        for sample in inputs.iter_input_as_floats(0)?.take(inputs.block_size()) {
+            let sample = sample.unwrap_or_default();
+            peak = peak.max(sample.abs());
+            sum_squares += sample * sample;
+            len += 1;
+        }
+
+        let rms = if len > 0 {
+            (sum_squares / len as Float).sqrt()
+        } else {
+            0.0
+        };
+
+        self.buffer.publish(|levels| {
+            levels[0] = peak;
+            levels[1] = rms;
+        });
+
+        Ok(())
+    }
+}
+
+/// A processor that publishes the most recent samples of its input once per block, for drawing oscilloscopes and waveforms.
+///
+/// [`TapReader::latest`] returns the last `length` samples, oldest first.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The signal to capture. |
+///
+/// # Outputs
+///
+/// None.
+#[derive(Clone, Debug)]
+pub struct ScopeTap {
+    buffer: TapBuffer,
+    // the captured samples, as a ring buffer
+    ring: Vec<Float>,
+    write_pos: usize,
+}
+
+impl ScopeTap {
+    /// Creates a new [`ScopeTap`] processor that captures the given number of samples.
+    pub fn new(length: usize) -> Self {
+        Self {
+            buffer: TapBuffer::new(length),
+            ring: vec![0.0; length],
+            write_pos: 0,
+        }
+    }
+
+    /// Returns the number of samples captured.
+    pub fn length(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns the reader for the captured samples.
+    ///
+    /// Each tap has a single reader, so this returns `None` if it has already been taken (including by a clone of this tap).
+    pub fn reader(&self) -> Option<TapReader> {
+        self.buffer.reader()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for ScopeTap {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Float)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        _outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        if self.ring.is_empty() {
+            return Ok(());
+        }
+
+        for sample in inputs.iter_input_as_floats(0)?.take(inputs.block_size()) {
+            self.ring[self.write_pos] = sample.unwrap_or_default();
+            self.write_pos = (self.write_pos + 1) % self.ring.len();
+        }
+
+        let (newest, oldest) = self.ring.split_at(self.write_pos);
+        self.buffer.publish(|samples| {
+            samples[..oldest.len()].copy_from_slice(oldest);
+            samples[oldest.len()..].copy_from_slice(newest);
+        });
+
+        Ok(())
+    }
+}
+
+// the length of the blocks a loudness meter measures, in seconds
+const LOUDNESS_HOP: Float = 0.1;
+// the number of blocks in the momentary (400 ms) and short-term (3 s) windows
+const MOMENTARY_BLOCKS: usize = 4;
+const SHORT_TERM_BLOCKS: usize = 30;
+// the range of loudness the integrated loudness histogram covers, in LUFS, and the width of its bins in LU
+const HISTOGRAM_MIN: Float = -70.0;
+const HISTOGRAM_MAX: Float = 5.0;
+const HISTOGRAM_RESOLUTION: Float = 0.1;
+
+// converts a mean square power to a loudness in LUFS, with silence clamped to about -200 LUFS
+#[inline]
+fn power_to_lufs(power: Float) -> Float {
+    -0.691 + 10.0 * power.max(1e-20).log10()
+}
+
+// the K-weighting filter of ITU-R BS.1770: a high shelf modeling the head, then a highpass
+#[derive(Clone, Debug, Default)]
+struct KWeighting {
+    shelf: SecondOrderSection,
+    highpass: SecondOrderSection,
+}
+
+impl KWeighting {
+    // the coefficients for any sample rate, as derived from the standard's 48 kHz coefficients
+    fn set_sample_rate(&mut self, sample_rate: Float) {
+        let f0: Float = 1681.9744509555319;
+        let gain: Float = 3.99984385397;
+        let q: Float = 0.7071752369554193;
+        let k = (PI * f0 / sample_rate).tan();
+        let vh = Float::powf(10.0, gain / 20.0);
+        let vb = vh.powf(0.499666774155);
+        let a0 = 1.0 + k / q + k * k;
+        self.shelf.set_normalized(
+            [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        );
+
+        let f0: Float = 38.13547087613982;
+        let q: Float = 0.5003270373253953;
+        let k = (PI * f0 / sample_rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        self.highpass.set_normalized(
+            [1.0, -2.0, 1.0],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        );
+    }
+
+    #[inline]
+    fn process(&mut self, x: Float) -> Float {
+        self.highpass.process(self.shelf.process(x))
+    }
+}
+
+/// A loudness meter following ITU-R BS.1770 and EBU R 128, for mastering and normalizing audio.
+///
+/// The channels are K-weighted and summed with equal weights (as for mono or stereo audio), and measured every 100 ms.
+/// The outputs hold the most recent measurements, in LUFS:
+///
+/// - The momentary loudness, over the last 400 ms.
+/// - The short-term loudness, over the last 3 s.
+/// - The integrated loudness, over everything since the meter started (or was reset), with the standard's absolute and relative gates, so silence and quiet passages don't drag it down.
+///
+/// [`TapReader::latest`] returns `[momentary, short_term, integrated]`, so the measurements can also be read from a UI thread.
+/// Before there is enough audio to measure, the measurements are about -200 LUFS.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0..N` | `in0..` | `Float` | The channels to measure. |
+/// | `N` | `reset` | `Bool` | Whether to reset the integrated loudness. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `momentary` | `Float` | The momentary loudness, in LUFS. |
+/// | `1` | `short_term` | `Float` | The short-term loudness, in LUFS. |
+/// | `2` | `integrated` | `Float` | The integrated loudness, in LUFS. |
+#[derive(Clone, Debug)]
+pub struct LoudnessMeter {
+    buffer: TapBuffer,
+    filters: Vec<KWeighting>,
+
+    // the summed power of the block being measured, and how many samples it has so far
+    block_power: Float,
+    block_len: usize,
+    hop_len: usize,
+    // the powers of the most recent blocks, as a ring buffer
+    blocks: [Float; SHORT_TERM_BLOCKS],
+    num_blocks: usize,
+    block_head: usize,
+
+    // a histogram of the loudness of every momentary window above the absolute gate, for the integrated loudness,
+    // with the summed power and number of windows in each bin
+    histogram: Vec<(Float, u64)>,
+    gated_power: Float,
+    gated_count: u64,
+
+    momentary: Float,
+    short_term: Float,
+    integrated: Float,
+}
+
+impl LoudnessMeter {
+    /// Creates a new [`LoudnessMeter`] processor that measures the given number of channels.
+    pub fn new(channels: usize) -> Self {
+        let bins = ((HISTOGRAM_MAX - HISTOGRAM_MIN) / HISTOGRAM_RESOLUTION).round() as usize;
+        let silence = power_to_lufs(0.0);
+        Self {
+            buffer: TapBuffer::new(3),
+            filters: vec![KWeighting::default(); channels],
+            block_power: 0.0,
+            block_len: 0,
+            hop_len: 0,
+            blocks: [0.0; SHORT_TERM_BLOCKS],
+            num_blocks: 0,
+            block_head: 0,
+            histogram: vec![(0.0, 0); bins],
+            gated_power: 0.0,
+            gated_count: 0,
+            momentary: silence,
+            short_term: silence,
+            integrated: silence,
+        }
+    }
+
+    /// Returns the number of channels measured.
+    pub fn channels(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Returns the reader for the measurements.
+    ///
+    /// Each meter has a single reader, so this returns `None` if it has already been taken (including by a clone of this meter).
+    pub fn reader(&self) -> Option<TapReader> {
+        self.buffer.reader()
+    }
+
+    /// Returns the most recent momentary loudness, in LUFS.
+    pub fn momentary(&self) -> Float {
+        self.momentary
+    }
+
+    /// Returns the most recent short-term loudness, in LUFS.
+    pub fn short_term(&self) -> Float {
+        self.short_term
+    }
+
+    /// Returns the most recent integrated loudness, in LUFS.
+    pub fn integrated(&self) -> Float {
+        self.integrated
+    }
+
+    fn reset_integrated(&mut self) {
+        self.histogram.fill((0.0, 0));
+        self.gated_power = 0.0;
+        self.gated_count = 0;
+        self.integrated = power_to_lufs(0.0);
+    }
+
+    // the mean power of the most recent `count` blocks
+    fn window_power(&self, count: usize) -> Float {
+        let sum: Float = (1..=count)
+            .map(|i| self.blocks[(self.block_head + SHORT_TERM_BLOCKS - i) % SHORT_TERM_BLOCKS])
+            .sum();
+        sum / count as Float
+    }
+
+    fn end_block(&mut self) {
+        self.blocks[self.block_head] = self.block_power / self.hop_len as Float;
+        self.block_head = (self.block_head + 1) % SHORT_TERM_BLOCKS;
+        self.num_blocks = (self.num_blocks + 1).min(SHORT_TERM_BLOCKS);
+        self.block_power = 0.0;
+        self.block_len = 0;
+
+        if self.num_blocks >= MOMENTARY_BLOCKS {
+            let power = self.window_power(MOMENTARY_BLOCKS);
+            self.momentary = power_to_lufs(power);
+
+            // each momentary window is a gating block of the integrated loudness
+            if self.momentary > HISTOGRAM_MIN {
+                let bin = ((self.momentary - HISTOGRAM_MIN) / HISTOGRAM_RESOLUTION) as usize;
+                let bin = bin.min(self.histogram.len() - 1);
+                self.histogram[bin].0 += power;
+                self.histogram[bin].1 += 1;
+                self.gated_power += power;
+                self.gated_count += 1;
+                self.integrated = self.gated_loudness();
+            }
+        }
+        if self.num_blocks >= SHORT_TERM_BLOCKS {
+            self.short_term = power_to_lufs(self.window_power(SHORT_TERM_BLOCKS));
+        }
+
+        let (momentary, short_term, integrated) =
+            (self.momentary, self.short_term, self.integrated);
+        self.buffer.publish(|measurements| {
+            measurements[0] = momentary;
+            measurements[1] = short_term;
+            measurements[2] = integrated;
+        });
+    }
+
+    // measures one sample of each channel
+    pub(crate) fn measure(&mut self, frame: impl IntoIterator<Item = Float>) {
+        for (x, filter) in frame.into_iter().zip(&mut self.filters) {
+            let weighted = filter.process(x);
+            self.block_power += weighted * weighted;
+        }
+
+        self.block_len += 1;
+        if self.block_len >= self.hop_len {
+            self.end_block();
+        }
+    }
+
+    // the mean power of the gating blocks no more than 10 LU below the mean of all blocks above the absolute gate
+    fn gated_loudness(&self) -> Float {
+        let relative_gate = power_to_lufs(self.gated_power / self.gated_count as Float) - 10.0;
+        let first_bin = ((relative_gate - HISTOGRAM_MIN) / HISTOGRAM_RESOLUTION).max(0.0) as usize;
+
+        let (power, count) = self.histogram[first_bin.min(self.histogram.len())..]
+            .iter()
+            .fold((0.0, 0), |(power, count), &(bin_power, bin_count)| {
+                (power + bin_power, count + bin_count)
+            });
+
+        if count == 0 {
+            power_to_lufs(0.0)
+        } else {
+            power_to_lufs(power / count as Float)
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for LoudnessMeter {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        let mut spec = (0..self.channels())
+            .map(|i| SignalSpec::new(format!("in{}", i), SignalType::Float))
+            .collect::<Vec<_>>();
+        spec.push(SignalSpec::new("reset", SignalType::Bool));
+        spec
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("momentary", SignalType::Float),
+            SignalSpec::new("short_term", SignalType::Float),
+            SignalSpec::new("integrated", SignalType::Float),
+        ]
+    }
+
+    fn is_tap(&self) -> bool {
+        true
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        for filter in &mut self.filters {
+            filter.set_sample_rate(sample_rate);
+        }
+        self.hop_len = ((LOUDNESS_HOP * sample_rate).round() as usize).max(1);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let channels = self.channels();
+        let mut in_signals = (0..channels)
+            .map(|i| inputs.iter_input_as_floats(i))
+            .collect::<Result<SmallVec<[_; 8]>, _>>()?;
+        let mut reset = inputs.iter_input_as_bools(channels)?;
+
+        for sample_index in 0..inputs.block_size() {
+            if let Some(Some(true)) = reset.next() {
+                self.reset_integrated();
+            }
+
+            self.measure(
+                in_signals
+                    .iter_mut()
+                    .map(|in_signal| in_signal.next().flatten().unwrap_or_default()),
+            );
+
+            outputs
+                .output(0)
+                .set_as::<Float>(sample_index, self.momentary);
+            outputs
+                .output(1)
+                .set_as::<Float>(sample_index, self.short_term);
+            outputs
+                .output(2)
+                .set_as::<Float>(sample_index, self.integrated);
+        }
+
+        Ok(())
+    }
+}
+
+// the oversampling factor of a true peak meter, and the number of input samples each interpolated sample is computed from
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+pub(crate) const TRUE_PEAK_TAPS: usize = 24;
+
+/// A true peak meter following ITU-R BS.1770, which also counts clipped samples.
+///
+/// The signal is oversampled 4x to find the peaks between samples, which can be several dB higher than the sample peaks and clip when the signal is converted to analog or encoded lossily.
+/// A sample is counted as clipped when any of its oversampled values is above the ceiling, which is 0 dBTP by default.
+///
+/// The outputs hold the highest true peak and the number of clipped samples since the meter started (or was reset).
+/// [`TapReader::latest`] returns `[peak, clips]` once per block, so they can also be read from a UI thread.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The signal to meter. |
+/// | `1` | `reset` | `Bool` | Whether to reset the peak and the clip count. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `peak` | `Float` | The highest true peak, in dBTP. |
+/// | `1` | `clips` | `Int` | The number of clipped samples. |
+#[derive(Clone, Debug)]
+pub struct TruePeak {
+    buffer: TapBuffer,
+    // the interpolation kernel for each oversampled phase, in the order of the history
+    phases: Vec<Float>,
+    // the history is stored twice, so the most recent samples are always contiguous
+    history: [Float; 2 * TRUE_PEAK_TAPS],
+    head: usize,
+
+    peak: Float,
+    clips: i64,
+
+    /// The level above which samples are counted as clipped, in dBTP.
+    pub ceiling: Float,
+}
+
+impl TruePeak {
+    /// Creates a new [`TruePeak`] processor with a ceiling of 0 dBTP.
+    pub fn new() -> Self {
+        // a lowpass at the original Nyquist frequency, at the oversampled rate
+        let mut kernel = windowed_sinc(
+            0.5 / TRUE_PEAK_OVERSAMPLING as Float,
+            TRUE_PEAK_OVERSAMPLING * TRUE_PEAK_TAPS - 1,
+        );
+        kernel.resize(TRUE_PEAK_OVERSAMPLING * TRUE_PEAK_TAPS, 0.0);
+
+        // zero-stuffing divides the signal's amplitude by the oversampling factor, so make it up in the kernel
+        let mut phases = Vec::with_capacity(kernel.len());
+        for phase in 0..TRUE_PEAK_OVERSAMPLING {
+            phases.extend((0..TRUE_PEAK_TAPS).rev().map(|tap| {
+                TRUE_PEAK_OVERSAMPLING as Float * kernel[phase + tap * TRUE_PEAK_OVERSAMPLING]
+            }));
+        }
+
+        Self {
+            buffer: TapBuffer::new(2),
+            phases,
+            history: [0.0; 2 * TRUE_PEAK_TAPS],
+            head: 0,
+            peak: 0.0,
+            clips: 0,
+            ceiling: 0.0,
+        }
+    }
+
+    /// Sets the level above which samples are counted as clipped, in dBTP.
+    pub fn with_ceiling(mut self, ceiling: Float) -> Self {
+        self.ceiling = ceiling;
+        self
+    }
+
+    /// Returns the reader for the peak and clip count.
+    ///
+    /// Each meter has a single reader, so this returns `None` if it has already been taken (including by a clone of this meter).
+    pub fn reader(&self) -> Option<TapReader> {
+        self.buffer.reader()
+    }
+
+    /// Returns the highest true peak since the meter started (or was reset), in dBTP.
+    pub fn peak(&self) -> Float {
+        amp_to_db(self.peak)
+    }
+
+    /// Returns the number of clipped samples since the meter started (or was reset).
+    pub fn clips(&self) -> i64 {
+        self.clips
+    }
+
+    // the highest absolute value of the oversampled signal over the most recent sample
+    #[inline]
+    pub(crate) fn true_peak(&mut self, x: Float) -> Float {
+        self.head = (self.head + 1) % TRUE_PEAK_TAPS;
+        self.history[self.head] = x;
+        self.history[self.head + TRUE_PEAK_TAPS] = x;
+
+        let window = &self.history[self.head + 1..self.head + 1 + TRUE_PEAK_TAPS];
+        self.phases
+            .chunks_exact(TRUE_PEAK_TAPS)
+            .map(|phase| dot_product(window, phase).abs())
+            .fold(0.0, Float::max)
+    }
+}
+
+impl Default for TruePeak {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for TruePeak {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("peak", SignalType::Float),
+            SignalSpec::new("clips", SignalType::Int),
+        ]
+    }
+
+    fn is_tap(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let ceiling = db_to_amp(self.ceiling);
+
+        for (in_signal, reset, peak_out, clips_out) in
+            iter_proc_io_as!(inputs as [Float, bool], outputs as [Float, i64])
+        {
+            if let Some(true) = reset {
+                self.peak = 0.0;
+                self.clips = 0;
+            }
+
+            let peak = self.true_peak(in_signal.unwrap_or_default());
+            self.peak = self.peak.max(peak);
+            if peak > ceiling {
+                self.clips += 1;
+            }
+
+            *peak_out = Some(amp_to_db(self.peak));
+            *clips_out = Some(self.clips);
+        }
+
+        let (peak, clips) = (amp_to_db(self.peak), self.clips);
+        self.buffer.publish(|levels| {
+            levels[0] = peak;
+            levels[1] = clips as Float;
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    // the buffers and measurements aren't serialized, so deserialized taps have a fresh reader
+
+    #[derive(Serialize, Deserialize)]
+    struct MeterTapSerde;
+
+    impl Serialize for MeterTap {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            MeterTapSerde.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for MeterTap {
+        fn deserialize<D>(deserializer: D) -> Result<MeterTap, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            MeterTapSerde::deserialize(deserializer)?;
+            Ok(MeterTap::new())
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct LoudnessMeterSerde {
+        channels: usize,
+    }
+
+    impl Serialize for LoudnessMeter {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            LoudnessMeterSerde {
+                channels: self.channels(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for LoudnessMeter {
+        fn deserialize<D>(deserializer: D) -> Result<LoudnessMeter, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let LoudnessMeterSerde { channels } = LoudnessMeterSerde::deserialize(deserializer)?;
+            Ok(LoudnessMeter::new(channels))
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct TruePeakSerde {
+        ceiling: Float,
+    }
+
+    impl Serialize for TruePeak {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            TruePeakSerde {
+                ceiling: self.ceiling,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TruePeak {
+        fn deserialize<D>(deserializer: D) -> Result<TruePeak, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let TruePeakSerde { ceiling } = TruePeakSerde::deserialize(deserializer)?;
+            Ok(TruePeak::new().with_ceiling(ceiling))
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct ScopeTapSerde {
+        length: usize,
+    }
+
+    impl Serialize for ScopeTap {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            ScopeTapSerde {
+                length: self.length(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ScopeTap {
+        fn deserialize<D>(deserializer: D) -> Result<ScopeTap, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let ScopeTapSerde { length } = ScopeTapSerde::deserialize(deserializer)?;
+            Ok(ScopeTap::new(length))
+        }
+    }
+}
@@ -2,17 +2,23 @@
 
 use raug_macros::iter_proc_io_as;
 
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    processor::ProcessMode,
+    signal::{PI, TAU},
+};
 
-use super::lerp;
+use super::{iter_list_floats, lerp, oscillators::NoiseRng, spatial::pan_gains};
 
 /// A processor that generates a single-sample pulse at regular intervals.
 ///
+/// A metro created with [`Metro::synced`] measures its period in beats of the graph's [`Transport`] instead of seconds, pulsing on every multiple of the period while the transport is playing.
+///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `period` | `Float` | The period of the pulse in seconds. |
+/// | `0` | `period` | `Float` | The period of the pulse in seconds (or beats, if synced). |
 /// | `1` | `reset` | `Bool` | Whether to reset the pulse generator. |
 ///
 /// # Outputs
@@ -27,16 +33,51 @@ pub struct Metro {
     last_time: u64,
     next_time: u64,
     time: u64,
+    // whether the period is measured in beats of the transport
+    synced: bool,
+    // the multiple of the period the last pulse was generated for, when synced
+    last_tick: Option<i64>,
 }
 
 impl Metro {
-    /// Creates a new `Metro` processor with the given period.
+    /// Creates a new `Metro` processor with the given period in seconds.
     pub fn new(period: Float) -> Self {
         Self {
             period,
             last_time: 0,
             next_time: 0,
             time: 0,
+            synced: false,
+            last_tick: None,
+        }
+    }
+
+    /// Creates a new `Metro` processor that pulses every given number of beats of the graph's [`Transport`].
+    pub fn synced(beats: Float) -> Self {
+        Self {
+            synced: true,
+            ..Self::new(beats)
+        }
+    }
+
+    fn next_tick(
+        &mut self,
+        transport: &TransportState,
+        sample_index: usize,
+        sample_rate: Float,
+    ) -> bool {
+        if !transport.playing || self.period <= 0.0 {
+            return false;
+        }
+
+        let beat = transport.beat_at(sample_index, sample_rate);
+        let tick = (beat / self.period).floor() as i64;
+
+        if self.last_tick == Some(tick) {
+            false
+        } else {
+            self.last_tick = Some(tick);
+            true
         }
     }
 
@@ -79,19 +120,33 @@ impl Processor for Metro {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (period, reset, out) in iter_proc_io_as!(
+        let offset = match inputs.mode {
+            ProcessMode::Block => 0,
+            ProcessMode::Sample(sample_index) => sample_index,
+        };
+
+        for (i, (period, reset, out)) in iter_proc_io_as!(
             inputs as [Float, bool],
             outputs as [bool]
-        ) {
+        )
+        .enumerate()
+        {
             if reset.unwrap_or(false) {
                 self.time = 0;
                 self.last_time = 0;
                 self.next_time = 0;
+                self.last_tick = None;
             }
 
             self.period = period.unwrap_or(self.period);
 
-            if self.next_sample(inputs.sample_rate()) {
+            let pulse = if self.synced {
+                self.next_tick(inputs.transport(), offset + i, inputs.sample_rate())
+            } else {
+                self.next_sample(inputs.sample_rate())
+            };
+
+            if pulse {
                 *out = Some(true);
             } else {
                 *out = None;
@@ -102,9 +157,71 @@ impl Processor for Metro {
     }
 }
 
+/// A processor that outputs the state of the graph's [`Transport`].
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `beat` | `Float` | The musical position in beats. |
+/// | `1` | `bar` | `Float` | The current bar, starting from `0`. |
+/// | `2` | `bpm` | `Float` | The tempo in beats per minute. |
+/// | `3` | `playing` | `Bool` | Whether the transport is playing. |
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransportClock;
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for TransportClock {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("beat", SignalType::Float),
+            SignalSpec::new("bar", SignalType::Float),
+            SignalSpec::new("bpm", SignalType::Float),
+            SignalSpec::new("playing", SignalType::Bool),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        mut outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let transport = inputs.transport();
+        let offset = match inputs.mode {
+            ProcessMode::Block => 0,
+            ProcessMode::Sample(sample_index) => sample_index,
+        };
+
+        for (i, beat) in outputs.iter_output_mut_as::<Float>(0)?.enumerate() {
+            *beat = Some(transport.beat_at(offset + i, inputs.sample_rate()));
+        }
+
+        for (i, bar) in outputs.iter_output_mut_as::<Float>(1)?.enumerate() {
+            let beat = transport.beat_at(offset + i, inputs.sample_rate());
+            *bar = Some(transport.bar_of(beat));
+        }
+
+        for bpm in outputs.iter_output_mut_as::<Float>(2)? {
+            *bpm = Some(transport.bpm);
+        }
+
+        for playing in outputs.iter_output_mut_as::<bool>(3)? {
+            *playing = Some(transport.playing);
+        }
+
+        Ok(())
+    }
+}
+
 /// A processor that delays a signal by one sample.
 ///
 /// Note that feedback loops in a [`Graph`] implicitly introduce a delay of one sample, so this processor is not usually required to be used manually.
+/// To delay a feedback loop by a whole block instead, use [`Feedback`].
 ///
 /// # Inputs
 ///
@@ -145,58 +262,1083 @@ impl Processor for UnitDelay {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, out) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
-            *out = self.value;
-            self.value = *in_signal;
+        for (in_signal, out) in iter_proc_io_as!(inputs as [Float], outputs as [Float]) {
+            *out = self.value;
+            self.value = *in_signal;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that closes a feedback loop with a delay of one block.
+///
+/// Edges into a `Feedback` node are ignored when scheduling the graph, so the node runs before the node feeding it and outputs what that node produced during the previous block.
+/// Loops closed this way are processed block by block like the rest of the graph, instead of one sample at a time as implicit feedback loops are.
+/// The delay is as long as the block size, so it varies with the runtime's configuration.
+///
+/// See [`GraphBuilder::feedback`](crate::builder::graph_builder::GraphBuilder::feedback).
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Any` | The signal to feed back. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Any` | The input signal from the previous block. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Feedback {
+    signal_type: SignalType,
+}
+
+impl Feedback {
+    /// Creates a new `Feedback` processor for the given signal type.
+    pub fn new(signal_type: SignalType) -> Self {
+        Self { signal_type }
+    }
+}
+
+impl Default for Feedback {
+    fn default() -> Self {
+        Self::new(SignalType::Float)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Feedback {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", self.signal_type)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", self.signal_type)]
+    }
+
+    fn reads_previous_block(&self) -> bool {
+        true
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        // the input buffer still holds the source's output from the previous block
+        for (in_signal, mut out_signal) in iter_proc_io_as!(inputs as [Any], outputs as [Any]) {
+            if let Some(in_signal) = in_signal {
+                out_signal.clone_from_ref(in_signal);
+            } else {
+                out_signal.set_none();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that delays a signal by a number of samples.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `delay` | `Int` | The delay in samples. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The delayed signal. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SampleDelay {
+    ring_buffer: Vec<Float>,
+    head: usize,
+}
+
+impl SampleDelay {
+    /// Creates a new `SampleDelay` processor with the given maximum delay.
+    pub fn new(max_delay: usize) -> Self {
+        let ring_buffer = vec![0.0; max_delay];
+        Self {
+            ring_buffer,
+            head: 0,
+        }
+    }
+
+    #[inline]
+    fn index_modulo(&self, delay: usize) -> usize {
+        (self.head + self.ring_buffer.len() - delay) % self.ring_buffer.len()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SampleDelay {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("delay", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, delay, out) in iter_proc_io_as!(
+            inputs as [Float, i64],
+            outputs as [Float]
+        ) {
+            let in_signal = in_signal.unwrap_or_default();
+
+            let delay = delay.unwrap_or_default() as usize;
+
+            self.ring_buffer[self.head] = in_signal;
+
+            let index = self.index_modulo(delay);
+            *out = Some(self.ring_buffer[index]);
+
+            self.head = (self.head + 1) % self.ring_buffer.len();
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that delays a signal by a number of samples with linear interpolation.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `delay` | `Float` | The delay in samples. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The delayed signal. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FractDelay {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ring_buffer: Vec<Float>,
+    head: usize,
+}
+
+impl FractDelay {
+    /// Creates a new `FractDelay` processor with the given maximum delay.
+    pub fn new() -> Self {
+        Self {
+            ring_buffer: vec![0.0; 2],
+            head: 0,
+        }
+    }
+
+    #[inline]
+    fn index_modulo(&self, delay: Float) -> (usize, Float) {
+        let delay_floor = delay.floor() as usize;
+        let delay_frac = delay - delay_floor as Float;
+        let index = (self.head + self.ring_buffer.len() - delay_floor) % self.ring_buffer.len();
+        (index, delay_frac)
+    }
+}
+
+impl Default for FractDelay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for FractDelay {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("delay", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.ring_buffer.resize(sample_rate as usize * 2, 0.0);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, delay, out) in iter_proc_io_as!(
+            inputs as [Float, Float],
+            outputs as [Float]
+        ) {
+            let delay = delay.unwrap_or_default();
+
+            self.ring_buffer[self.head] = in_signal.unwrap_or_default();
+
+            let (index, delay_frac) = self.index_modulo(delay);
+
+            let delayed = self.ring_buffer[index];
+
+            let next_index = (index + 1) % self.ring_buffer.len();
+            let next = self.ring_buffer[next_index];
+
+            *out = Some(lerp(delayed, next, delay_frac));
+
+            self.head = (self.head + 1) % self.ring_buffer.len();
+        }
+
+        Ok(())
+    }
+}
+
+// a ring buffer that reads delayed samples with linear interpolation
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct DelayLine {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    buffer: Vec<Float>,
+    head: usize,
+}
+
+impl DelayLine {
+    // makes room for delays of up to `max_delay` seconds, keeping the current contents
+    pub(crate) fn allocate(&mut self, max_delay: Float, sample_rate: Float) {
+        let len = (max_delay * sample_rate).ceil() as usize + 3;
+        self.buffer.resize(len, 0.0);
+        self.head %= len;
+    }
+
+    // reads the sample written the given (fractional) number of samples ago, at least 1
+    #[inline]
+    pub(crate) fn read(&self, delay: Float) -> Float {
+        let len = self.buffer.len();
+        let delay = delay.clamp(1.0, (len - 2) as Float);
+        let delay_floor = delay.floor();
+        let frac = delay - delay_floor;
+        let index = (self.head + len - delay_floor as usize) % len;
+        let prev_index = (index + len - 1) % len;
+        lerp(self.buffer[index], self.buffer[prev_index], frac)
+    }
+
+    #[inline]
+    pub(crate) fn write(&mut self, value: Float) {
+        self.buffer[self.head] = value;
+        self.head = (self.head + 1) % self.buffer.len();
+    }
+}
+
+/// The rhythmic feel of a note value, which scales its length.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoteFeel {
+    /// The note's written length.
+    #[default]
+    Straight,
+    /// One and a half times the note's written length.
+    Dotted,
+    /// Two thirds of the note's written length, so three notes fit in the space of two.
+    Triplet,
+}
+
+impl NoteFeel {
+    /// Returns the feel with the given index, as used by the `feel` input of a [`SyncDelay`] (`0` is straight, `1` is dotted, and `2` is triplet).
+    pub fn from_index(index: i64) -> Option<Self> {
+        match index {
+            0 => Some(Self::Straight),
+            1 => Some(Self::Dotted),
+            2 => Some(Self::Triplet),
+            _ => None,
+        }
+    }
+
+    /// Returns the amount the feel scales a note's length by.
+    pub fn multiplier(self) -> Float {
+        match self {
+            Self::Straight => 1.0,
+            Self::Dotted => 1.5,
+            Self::Triplet => 2.0 / 3.0,
+        }
+    }
+}
+
+// how long the delay takes to move to a new delay time, in seconds
+const SYNC_DELAY_CROSSFADE: Float = 0.05;
+
+/// A processor that delays a signal by a musical note value, following the tempo of the graph's [`Transport`].
+///
+/// The note value is given as a fraction of a whole note, so `0.25` is a quarter note (one beat), and `0.125` with a [`NoteFeel::Dotted`] feel is a dotted eighth note.
+/// When the tempo or note value changes, the delay crossfades to the new delay time instead of sweeping to it, so there are no pitch artifacts.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `note` | `Float` | The note value of the delay, as a fraction of a whole note. |
+/// | `2` | `feel` | `Int` | The feel of the note value (`0` for straight, `1` for dotted, `2` for triplet). |
+/// | `3` | `feedback` | `Float` | The amount of the delayed signal fed back into the delay, between `-1.0` and `1.0`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The delayed signal. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncDelay {
+    max_delay: Float,
+
+    line: DelayLine,
+    // the sample rate the delay times were computed at
+    sample_rate: Option<Float>,
+    // the delay time being read, in samples, or `None` until the first sample is processed
+    current_delay: Option<Float>,
+    // the delay time being crossfaded to, and how far through the crossfade the delay is
+    next_delay: Option<Float>,
+    fade: Float,
+
+    /// The note value of the delay, as a fraction of a whole note.
+    pub note: Float,
+
+    /// The feel of the note value.
+    pub feel: NoteFeel,
+
+    /// The amount of the delayed signal fed back into the delay.
+    pub feedback: Float,
+}
+
+impl SyncDelay {
+    /// Creates a new `SyncDelay` processor with the given note value and feel, which can delay signals by up to `max_delay` seconds.
+    pub fn new(note: Float, feel: NoteFeel, max_delay: Float) -> Self {
+        Self {
+            max_delay,
+            line: DelayLine::default(),
+            sample_rate: None,
+            current_delay: None,
+            next_delay: None,
+            fade: 0.0,
+            note,
+            feel,
+            feedback: 0.0,
+        }
+    }
+
+    /// Sets the amount of the delayed signal fed back into the delay.
+    pub fn with_feedback(mut self, feedback: Float) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Returns the maximum delay time in seconds.
+    pub fn max_delay(&self) -> Float {
+        self.max_delay
+    }
+
+    /// Returns the delay time in seconds at the given tempo.
+    pub fn delay_time(&self, bpm: Float) -> Float {
+        if bpm <= 0.0 {
+            return self.max_delay;
+        }
+        // a whole note lasts four beats
+        let beats = self.note * 4.0 * self.feel.multiplier();
+        (beats * 60.0 / bpm).clamp(0.0, self.max_delay)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for SyncDelay {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("note", SignalType::Float),
+            SignalSpec::new("feel", SignalType::Int),
+            SignalSpec::new("feedback", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.line.allocate(self.max_delay, sample_rate);
+    }
+
+    fn resize_buffers(&mut self, sample_rate: Float, _block_size: usize) {
+        if self.sample_rate != Some(sample_rate) {
+            // delay times in samples are meaningless at the new sample rate, so jump straight to the new one
+            self.sample_rate = Some(sample_rate);
+            self.current_delay = None;
+            self.next_delay = None;
+        }
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let bpm = inputs.transport().bpm;
+        let fade_step = 1.0 / (SYNC_DELAY_CROSSFADE * sample_rate);
+
+        for (in_signal, note, feel, feedback, out) in iter_proc_io_as!(
+            inputs as [Float, Float, i64, Float],
+            outputs as [Float]
+        ) {
+            self.note = note.unwrap_or(self.note);
+            if let Some(feel) = feel.and_then(NoteFeel::from_index) {
+                self.feel = feel;
+            }
+            if let Some(feedback) = feedback {
+                self.feedback = feedback.clamp(-1.0, 1.0);
+            }
+
+            let target = self.delay_time(bpm) * sample_rate;
+            let current_delay = *self.current_delay.get_or_insert(target);
+            if self.next_delay.is_none() && target != current_delay {
+                // changes that arrive mid-crossfade are picked up once it finishes
+                self.next_delay = Some(target);
+                self.fade = 0.0;
+            }
+
+            let mut delayed = self.line.read(current_delay);
+            if let Some(next_delay) = self.next_delay {
+                delayed = lerp(delayed, self.line.read(next_delay), self.fade);
+                self.fade += fade_step;
+                if self.fade >= 1.0 {
+                    self.current_delay = Some(next_delay);
+                    self.next_delay = None;
+                }
+            }
+
+            self.line
+                .write(in_signal.unwrap_or_default() + self.feedback * delayed);
+
+            *out = Some(delayed);
+        }
+
+        Ok(())
+    }
+}
+
+/// A stereo delay whose echoes bounce between the left and right channels.
+///
+/// The input channels are summed into the left delay line, which feeds the right delay line, which feeds back into the left.
+///
+/// A delay made with [`PingPongDelay::framed`] takes and outputs a single stereo [`Frame`] instead of separate channels.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left input signal. |
+/// | `1` | `right` | `Float` | The right input signal. |
+/// | `2` | `delay` | `Float` | The time between echoes in seconds. |
+/// | `3` | `feedback` | `Float` | The gain of each successive echo, between `-1.0` and `1.0`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left delayed signal. |
+/// | `1` | `right` | `Float` | The right delayed signal. |
+///
+/// If framed, the inputs and outputs are instead:
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Frame` | The stereo input signal. |
+/// | `1` | `delay` | `Float` | The time between echoes in seconds. |
+/// | `2` | `feedback` | `Float` | The gain of each successive echo, between `-1.0` and `1.0`. |
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Frame` | The stereo delayed signal. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PingPongDelay {
+    max_delay: Float,
+    #[cfg_attr(feature = "serde", serde(default))]
+    framed: bool,
+
+    left: DelayLine,
+    right: DelayLine,
+
+    /// The time between echoes in seconds.
+    pub delay: Float,
+
+    /// The gain of each successive echo.
+    pub feedback: Float,
+}
+
+impl PingPongDelay {
+    /// Creates a new `PingPongDelay` processor, which can delay signals by up to `max_delay` seconds.
+    pub fn new(max_delay: Float) -> Self {
+        Self {
+            max_delay,
+            framed: false,
+            left: DelayLine::default(),
+            right: DelayLine::default(),
+            delay: max_delay,
+            feedback: 0.5,
+        }
+    }
+
+    /// Makes the delay take and output a single stereo [`Frame`] instead of separate left and right channels.
+    pub fn framed(mut self) -> Self {
+        self.framed = true;
+        self
+    }
+
+    /// Returns the maximum delay time in seconds.
+    pub fn max_delay(&self) -> Float {
+        self.max_delay
+    }
+
+    /// Returns `true` if the delay takes and outputs stereo [`Frame`]s.
+    pub fn is_framed(&self) -> bool {
+        self.framed
+    }
+
+    // updates the settings from the given inputs, writes the summed input and returns the delayed channels
+    #[inline]
+    fn process_sample(
+        &mut self,
+        input: Float,
+        delay: Option<Float>,
+        feedback: Option<Float>,
+        sample_rate: Float,
+    ) -> (Float, Float) {
+        if let Some(delay) = delay {
+            self.delay = delay.clamp(0.0, self.max_delay);
+        }
+        if let Some(feedback) = feedback {
+            self.feedback = feedback.clamp(-1.0, 1.0);
+        }
+
+        let delay = self.delay * sample_rate;
+        let delayed_left = self.left.read(delay);
+        let delayed_right = self.right.read(delay);
+
+        self.left.write(input + self.feedback * delayed_right);
+        self.right.write(self.feedback * delayed_left);
+
+        (delayed_left, delayed_right)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for PingPongDelay {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        if self.framed {
+            return vec![
+                SignalSpec::new("in", SignalType::Frame),
+                SignalSpec::new("delay", SignalType::Float),
+                SignalSpec::new("feedback", SignalType::Float),
+            ];
+        }
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+            SignalSpec::new("delay", SignalType::Float),
+            SignalSpec::new("feedback", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        if self.framed {
+            return vec![SignalSpec::new("out", SignalType::Frame)];
+        }
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.left.allocate(self.max_delay, sample_rate);
+        self.right.allocate(self.max_delay, sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        if self.framed {
+            for (in_frame, delay, feedback, out) in iter_proc_io_as!(
+                inputs as [Frame, Float, Float],
+                outputs as [Frame]
+            ) {
+                let input = in_frame
+                    .as_ref()
+                    .map_or(0.0, |frame| frame.left() + frame.right());
+                let (left, right) = self.process_sample(input, *delay, *feedback, sample_rate);
+                *out = Some(Frame::stereo(left, right));
+            }
+
+            return Ok(());
+        }
+
+        for (in_left, in_right, delay, feedback, out_left, out_right) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float],
+            outputs as [Float, Float]
+        ) {
+            let input = in_left.unwrap_or_default() + in_right.unwrap_or_default();
+            let (left, right) = self.process_sample(input, *delay, *feedback, sample_rate);
+            *out_left = Some(left);
+            *out_right = Some(right);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct DelayTap {
+    time: Float,
+    level: Float,
+    pan: Float,
+}
+
+impl Default for DelayTap {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            level: 1.0,
+            pan: 0.0,
+        }
+    }
+}
+
+/// A delay with multiple taps, each with its own time, level, and stereo position.
+///
+/// The times, levels, and pans of the taps are set from lists of floats, where the first element sets the first tap, and so on.
+/// The number of times sets how many taps are active, up to the maximum the delay was created with.
+/// Taps whose level or pan isn't given keep their previous value (initially a level of `1.0`, panned to the center).
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `times` | `List` | The delay times of the taps in seconds. |
+/// | `2` | `levels` | `List` | The gains of the taps. |
+/// | `3` | `pans` | `List` | The stereo positions of the taps, from `-1.0` (left) to `1.0` (right). |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `left` | `Float` | The left sum of the taps. |
+/// | `1` | `right` | `Float` | The right sum of the taps. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiTapDelay {
+    max_delay: Float,
+    line: DelayLine,
+    taps: Vec<DelayTap>,
+    num_active: usize,
+}
+
+impl MultiTapDelay {
+    /// Creates a new `MultiTapDelay` processor with up to the given number of taps, all initially inactive, which can delay signals by up to `max_delay` seconds.
+    pub fn new(max_delay: Float, max_taps: usize) -> Self {
+        Self {
+            max_delay,
+            line: DelayLine::default(),
+            taps: vec![DelayTap::default(); max_taps],
+            num_active: 0,
+        }
+    }
+
+    /// Sets the delay times of the taps in seconds, activating one tap for each time.
+    pub fn with_times(mut self, times: &[Float]) -> Self {
+        self.set_times(times.iter().copied());
+        self
+    }
+
+    /// Sets the gains of the taps.
+    pub fn with_levels(mut self, levels: &[Float]) -> Self {
+        self.set_levels(levels.iter().copied());
+        self
+    }
+
+    /// Sets the stereo positions of the taps.
+    pub fn with_pans(mut self, pans: &[Float]) -> Self {
+        self.set_pans(pans.iter().copied());
+        self
+    }
+
+    /// Returns the maximum delay time in seconds.
+    pub fn max_delay(&self) -> Float {
+        self.max_delay
+    }
+
+    /// Returns the maximum number of taps.
+    pub fn max_taps(&self) -> usize {
+        self.taps.len()
+    }
+
+    /// Returns the number of active taps.
+    pub fn num_active(&self) -> usize {
+        self.num_active
+    }
+
+    fn set_times(&mut self, times: impl Iterator<Item = Float>) {
+        let mut num_active = 0;
+        for (tap, time) in self.taps.iter_mut().zip(times) {
+            tap.time = time.clamp(0.0, self.max_delay);
+            num_active += 1;
+        }
+        self.num_active = num_active;
+    }
+
+    fn set_levels(&mut self, levels: impl Iterator<Item = Float>) {
+        for (tap, level) in self.taps.iter_mut().zip(levels) {
+            tap.level = level;
+        }
+    }
+
+    fn set_pans(&mut self, pans: impl Iterator<Item = Float>) {
+        for (tap, pan) in self.taps.iter_mut().zip(pans) {
+            tap.pan = pan.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MultiTapDelay {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("times", SignalType::List),
+            SignalSpec::new("levels", SignalType::List),
+            SignalSpec::new("pans", SignalType::List),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("left", SignalType::Float),
+            SignalSpec::new("right", SignalType::Float),
+        ]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.line.allocate(self.max_delay, sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, times, levels, pans, out_left, out_right) in iter_proc_io_as!(
+            inputs as [Float, List, List, List],
+            outputs as [Float, Float]
+        ) {
+            if let Some(times) = times {
+                self.set_times(iter_list_floats(times));
+            }
+            if let Some(levels) = levels {
+                self.set_levels(iter_list_floats(levels));
+            }
+            if let Some(pans) = pans {
+                self.set_pans(iter_list_floats(pans));
+            }
+
+            self.line.write(in_signal.unwrap_or_default());
+
+            // the input was just written, so a tap with no delay reads it back
+            let mut left = 0.0;
+            let mut right = 0.0;
+            for tap in &self.taps[..self.num_active] {
+                let delayed = self.line.read(tap.time * inputs.sample_rate() + 1.0);
+                let (left_gain, right_gain) = pan_gains(tap.pan);
+                left += delayed * tap.level * left_gain;
+                right += delayed * tap.level * right_gain;
+            }
+
+            *out_left = Some(left);
+            *out_right = Some(right);
+        }
+
+        Ok(())
+    }
+}
+
+// the time constant of a tape delay's changes in delay time, in seconds
+const TAPE_DELAY_SLEW: Float = 0.2;
+// the rates (Hz) and maximum depths (seconds) of a tape delay's wow and flutter
+const TAPE_WOW_RATE: Float = 0.6;
+const TAPE_WOW_DEPTH: Float = 0.004;
+const TAPE_FLUTTER_RATE: Float = 7.3;
+const TAPE_FLUTTER_DEPTH: Float = 0.0004;
+
+/// A delay modeled after a tape echo, with wow and flutter, a tone control, and soft saturation in the feedback path.
+///
+/// Changes to the delay time glide smoothly, bending the pitch of the echoes like a tape machine changing speed, instead of clicking.
+/// The echoes are soft clipped, so feedback above `1.0` builds up into saturation instead of growing forever.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `delay` | `Float` | The delay time in seconds. |
+/// | `2` | `feedback` | `Float` | The amount of the echoes fed back into the delay, between `0.0` and `1.5`. |
+/// | `3` | `wow` | `Float` | The depth of slow pitch wobble, between `0.0` and `1.0`. |
+/// | `4` | `flutter` | `Float` | The depth of fast pitch wobble, between `0.0` and `1.0`. |
+/// | `5` | `tone` | `Float` | The brightness of the echoes, from `0.0` (dark) to `1.0` (bright). |
+/// | `6` | `drive` | `Float` | How hard the echoes are saturated. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The delayed signal. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TapeDelay {
+    max_delay: Float,
+    line: DelayLine,
+    // the delay time being read, gliding towards `delay` (`None` until the first sample)
+    current_delay: Option<Float>,
+    wow_phase: Float,
+    flutter_phase: Float,
+    // state of the tone filter
+    toned: Float,
+
+    /// The delay time in seconds.
+    pub delay: Float,
+
+    /// The amount of the echoes fed back into the delay.
+    pub feedback: Float,
+
+    /// The depth of slow pitch wobble.
+    pub wow: Float,
+
+    /// The depth of fast pitch wobble.
+    pub flutter: Float,
+
+    /// The brightness of the echoes.
+    pub tone: Float,
+
+    /// How hard the echoes are saturated.
+    pub drive: Float,
+}
+
+impl TapeDelay {
+    /// Creates a new `TapeDelay` processor, which can delay signals by up to `max_delay` seconds.
+    pub fn new(max_delay: Float) -> Self {
+        Self {
+            max_delay,
+            line: DelayLine::default(),
+            current_delay: None,
+            wow_phase: 0.0,
+            flutter_phase: 0.0,
+            toned: 0.0,
+            delay: max_delay,
+            feedback: 0.5,
+            wow: 0.2,
+            flutter: 0.2,
+            tone: 0.6,
+            drive: 1.0,
+        }
+    }
+
+    /// Returns the maximum delay time in seconds.
+    pub fn max_delay(&self) -> Float {
+        self.max_delay
+    }
+
+    // soft clips the signal, passing quiet signals at unity gain and limiting loud ones to `1.0 / drive`
+    #[inline]
+    fn saturate(&self, x: Float) -> Float {
+        if self.drive <= 0.0 {
+            x
+        } else {
+            (x * self.drive).tanh() / self.drive
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for TapeDelay {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("delay", SignalType::Float),
+            SignalSpec::new("feedback", SignalType::Float),
+            SignalSpec::new("wow", SignalType::Float),
+            SignalSpec::new("flutter", SignalType::Float),
+            SignalSpec::new("tone", SignalType::Float),
+            SignalSpec::new("drive", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        // leave room for the modulation on top of the longest delay
+        self.line.allocate(
+            self.max_delay + TAPE_WOW_DEPTH + TAPE_FLUTTER_DEPTH,
+            sample_rate,
+        );
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+        let slew = 1.0 - (-1.0 / (TAPE_DELAY_SLEW * sample_rate)).exp();
+
+        for (in_signal, delay, feedback, wow, flutter, tone, drive, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, Float, Float, Float, Float],
+            outputs as [Float]
+        ) {
+            if let Some(delay) = delay {
+                self.delay = delay.clamp(0.0, self.max_delay);
+            }
+            if let Some(feedback) = feedback {
+                self.feedback = feedback.clamp(0.0, 1.5);
+            }
+            if let Some(wow) = wow {
+                self.wow = wow.clamp(0.0, 1.0);
+            }
+            if let Some(flutter) = flutter {
+                self.flutter = flutter.clamp(0.0, 1.0);
+            }
+            if let Some(tone) = tone {
+                self.tone = tone.clamp(0.0, 1.0);
+            }
+            if let Some(drive) = drive {
+                self.drive = drive.max(0.0);
+            }
+
+            let current_delay = match self.current_delay {
+                Some(current_delay) => current_delay + (self.delay - current_delay) * slew,
+                None => self.delay,
+            };
+            self.current_delay = Some(current_delay);
+
+            // the modulation only ever lengthens the delay, so it never reads ahead of the write head
+            let wow_offset = self.wow * TAPE_WOW_DEPTH * 0.5 * (1.0 - (TAU * self.wow_phase).cos());
+            let flutter_offset =
+                self.flutter * TAPE_FLUTTER_DEPTH * 0.5 * (1.0 - (TAU * self.flutter_phase).cos());
+            self.wow_phase = (self.wow_phase + TAPE_WOW_RATE / sample_rate).fract();
+            self.flutter_phase = (self.flutter_phase + TAPE_FLUTTER_RATE / sample_rate).fract();
+
+            let delayed = self
+                .line
+                .read((current_delay + wow_offset + flutter_offset) * sample_rate);
+
+            // a 1-pole lowpass from 500 Hz to 16 kHz darkens the echoes as they repeat
+            let cutoff = 500.0 * (32.0 as Float).powf(self.tone);
+            let coeff = 1.0 - (-TAU * cutoff / sample_rate).exp();
+            self.toned += (delayed - self.toned) * coeff;
+
+            let echo = self.saturate(self.toned);
+            self.line
+                .write(self.saturate(in_signal.unwrap_or_default() + self.feedback * echo));
+
+            *out = Some(echo);
         }
 
         Ok(())
     }
 }
 
-/// A processor that delays a signal by a number of samples.
+/// A delay that plays its input backwards, one chunk at a time.
+///
+/// Two overlapping read heads each play the most recent chunk in reverse, with smooth windows that crossfade between them, so the chunk boundaries don't click.
 ///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
 /// | `0` | `in` | `Float` | The input signal. |
-/// | `1` | `delay` | `Int` | The delay in samples. |
+/// | `1` | `size` | `Float` | The length of the chunks in seconds. |
+/// | `2` | `feedback` | `Float` | The amount of the reversed signal fed back into the delay, between `-1.0` and `1.0`. |
 ///
 /// # Outputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `out` | `Float` | The delayed signal. |
+/// | `0` | `out` | `Float` | The reversed signal. |
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct SampleDelay {
-    ring_buffer: Vec<Float>,
-    head: usize,
+pub struct ReverseDelay {
+    max_size: Float,
+    line: DelayLine,
+    // the position of the first read head within its chunk, in samples
+    position: Float,
+
+    /// The length of the chunks in seconds.
+    pub size: Float,
+
+    /// The amount of the reversed signal fed back into the delay.
+    pub feedback: Float,
 }
 
-impl SampleDelay {
-    /// Creates a new `SampleDelay` processor with the given maximum delay.
-    pub fn new(max_delay: usize) -> Self {
-        let ring_buffer = vec![0.0; max_delay];
+impl ReverseDelay {
+    /// Creates a new `ReverseDelay` processor, which can reverse chunks of up to `max_size` seconds.
+    pub fn new(max_size: Float) -> Self {
         Self {
-            ring_buffer,
-            head: 0,
+            max_size,
+            line: DelayLine::default(),
+            position: 0.0,
+            size: max_size,
+            feedback: 0.0,
         }
     }
 
+    /// Returns the maximum length of the chunks in seconds.
+    pub fn max_size(&self) -> Float {
+        self.max_size
+    }
+
     #[inline]
-    fn index_modulo(&self, delay: usize) -> usize {
-        (self.head + self.ring_buffer.len() - delay) % self.ring_buffer.len()
+    fn read_head(&self, position: Float, length: Float) -> Float {
+        // reading twice as far back as the head has moved plays the previous chunk backwards
+        let window = (PI * position / length).sin();
+        self.line.read(2.0 * position + 1.0) * window * window
     }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde)]
-impl Processor for SampleDelay {
+impl Processor for ReverseDelay {
     fn input_spec(&self) -> Vec<SignalSpec> {
         vec![
             SignalSpec::new("in", SignalType::Float),
-            SignalSpec::new("delay", SignalType::Int),
+            SignalSpec::new("size", SignalType::Float),
+            SignalSpec::new("feedback", SignalType::Float),
         ]
     }
 
@@ -204,83 +1346,181 @@ impl Processor for SampleDelay {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
+    fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
+        self.line.allocate(2.0 * self.max_size, sample_rate);
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, delay, out) in iter_proc_io_as!(
-            inputs as [Float, i64],
+        for (in_signal, size, feedback, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float],
             outputs as [Float]
         ) {
-            let in_signal = in_signal.unwrap_or_default();
+            if let Some(size) = size {
+                self.size = size.clamp(0.0, self.max_size);
+            }
+            if let Some(feedback) = feedback {
+                self.feedback = feedback.clamp(-1.0, 1.0);
+            }
 
-            let delay = delay.unwrap_or_default() as usize;
+            let length = (self.size * inputs.sample_rate()).max(2.0);
+            self.position %= length;
+            let other_position = (self.position + length * 0.5) % length;
 
-            self.ring_buffer[self.head] = in_signal;
+            // the squared sine windows of the two heads, half a chunk apart, always sum to 1
+            let reversed =
+                self.read_head(self.position, length) + self.read_head(other_position, length);
 
-            let index = self.index_modulo(delay);
-            *out = Some(self.ring_buffer[index]);
+            self.line
+                .write(in_signal.unwrap_or_default() + self.feedback * reversed);
+            self.position += 1.0;
 
-            self.head = (self.head + 1) % self.ring_buffer.len();
+            *out = Some(reversed);
         }
 
         Ok(())
     }
 }
 
-/// A processor that delays a signal by a number of samples with linear interpolation.
+// how long a glitch delay crossfades between slices, in seconds
+const GLITCH_CROSSFADE: Float = 0.003;
+
+// where a glitch delay reads a slice from
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum GlitchSource {
+    // the input, undelayed
+    Through,
+    // an earlier slice of the input, the given number of samples ago
+    Forward(Float),
+    // an earlier slice of the input, played backwards from the given number of samples ago
+    Reverse(Float),
+}
+
+/// A delay that shuffles slices of its input, for stutters, skips, and reversals.
+///
+/// At the start of every slice, the `chance` input sets the probability that the slice is replaced by a glitch: a repeat of the previous slice, a jump to a random earlier slice, or a random earlier slice played backwards.
+/// Otherwise, the input passes through unchanged. Slices are crossfaded, so the edits don't click.
 ///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
 /// | `0` | `in` | `Float` | The input signal. |
-/// | `1` | `delay` | `Float` | The delay in samples. |
+/// | `1` | `slice` | `Float` | The length of the slices in seconds, which takes effect at the start of the next slice. |
+/// | `2` | `chance` | `Float` | The probability that each slice is glitched (0.0 to 1.0). |
+/// | `3` | `seed` | `Int` | Reseeds the random number generator when it changes, for reproducible glitches. |
 ///
 /// # Outputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
-/// | `0` | `out` | `Float` | The delayed signal. |
+/// | `0` | `out` | `Float` | The glitched signal. |
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct FractDelay {
-    #[cfg_attr(feature = "serde", serde(skip))]
-    ring_buffer: Vec<Float>,
-    head: usize,
+pub struct GlitchDelay {
+    max_size: Float,
+    line: DelayLine,
+    rng: NoiseRng,
+    source: GlitchSource,
+    previous_source: GlitchSource,
+    // the position within the current slice, and the lengths of the current and previous slices, in samples
+    position: Float,
+    length: Float,
+    previous_length: Float,
+
+    /// The length of the slices in seconds.
+    pub slice: Float,
+
+    /// The probability that each slice is glitched.
+    pub chance: Float,
 }
 
-impl FractDelay {
-    /// Creates a new `FractDelay` processor with the given maximum delay.
-    pub fn new() -> Self {
+impl GlitchDelay {
+    /// Creates a new `GlitchDelay` processor with a random seed, which can reach back up to `max_size` seconds for its slices.
+    pub fn new(max_size: Float) -> Self {
+        Self::with_rng(max_size, NoiseRng::from_entropy())
+    }
+
+    /// Creates a new `GlitchDelay` processor with the given seed, which can reach back up to `max_size` seconds for its slices.
+    pub fn with_seed(max_size: Float, seed: u64) -> Self {
+        Self::with_rng(max_size, NoiseRng::new(seed))
+    }
+
+    fn with_rng(max_size: Float, rng: NoiseRng) -> Self {
         Self {
-            ring_buffer: vec![0.0; 2],
-            head: 0,
+            max_size,
+            line: DelayLine::default(),
+            rng,
+            source: GlitchSource::Through,
+            previous_source: GlitchSource::Through,
+            position: 0.0,
+            length: 0.0,
+            previous_length: 0.0,
+            slice: 0.125,
+            chance: 0.5,
         }
     }
 
-    #[inline]
-    fn index_modulo(&self, delay: Float) -> (usize, Float) {
-        let delay_floor = delay.floor() as usize;
-        let delay_frac = delay - delay_floor as Float;
-        let index = (self.head + self.ring_buffer.len() - delay_floor) % self.ring_buffer.len();
-        (index, delay_frac)
+    /// Returns the longest time in seconds the delay can reach back for its slices.
+    pub fn max_size(&self) -> Float {
+        self.max_size
     }
-}
 
-impl Default for FractDelay {
-    fn default() -> Self {
-        Self::new()
+    // picks the source of the next slice
+    fn next_source(&mut self, max_delay: Float) -> GlitchSource {
+        if self.rng.next_unipolar() >= self.chance {
+            return GlitchSource::Through;
+        }
+
+        // leave room to play the whole slice (backwards, and into the next crossfade) without running out of buffer
+        let length = self.length;
+        let latest = length;
+        let earliest = (max_delay - 3.0 * length).max(latest);
+        let random_delay = latest + (earliest - latest) * self.rng.next_unipolar();
+
+        let choice = self.rng.next_unipolar();
+        if choice < 1.0 / 3.0 {
+            // repeat whatever the previous slice played, by reaching one slice further back
+            match self.source {
+                GlitchSource::Through => GlitchSource::Forward(length),
+                GlitchSource::Forward(delay) if delay + length <= earliest => {
+                    GlitchSource::Forward(delay + length)
+                }
+                GlitchSource::Reverse(delay) if delay + length <= earliest => {
+                    GlitchSource::Reverse(delay + length)
+                }
+                _ => GlitchSource::Forward(random_delay),
+            }
+        } else if choice < 2.0 / 3.0 {
+            GlitchSource::Forward(random_delay)
+        } else {
+            GlitchSource::Reverse(random_delay)
+        }
+    }
+
+    #[inline]
+    fn read(&self, source: GlitchSource, position: Float, input: Float) -> Float {
+        match source {
+            GlitchSource::Through => input,
+            GlitchSource::Forward(delay) => self.line.read(delay),
+            // at the start of the slice, this reads the end of the earlier slice
+            GlitchSource::Reverse(delay) => self.line.read(delay + 2.0 * position),
+        }
     }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde)]
-impl Processor for FractDelay {
+impl Processor for GlitchDelay {
     fn input_spec(&self) -> Vec<SignalSpec> {
         vec![
             SignalSpec::new("in", SignalType::Float),
-            SignalSpec::new("delay", SignalType::Float),
+            SignalSpec::new("slice", SignalType::Float),
+            SignalSpec::new("chance", SignalType::Float),
+            SignalSpec::new("seed", SignalType::Int),
         ]
     }
 
@@ -288,8 +1528,12 @@ impl Processor for FractDelay {
         vec![SignalSpec::new("out", SignalType::Float)]
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = NoiseRng::new(seed);
+    }
+
     fn allocate(&mut self, sample_rate: Float, _max_block_size: usize) {
-        self.ring_buffer.resize(sample_rate as usize * 2, 0.0);
+        self.line.allocate(self.max_size, sample_rate);
     }
 
     fn process(
@@ -297,24 +1541,52 @@ impl Processor for FractDelay {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, delay, out) in iter_proc_io_as!(
-            inputs as [Float, Float],
+        let sample_rate = inputs.sample_rate();
+        let crossfade = GLITCH_CROSSFADE * sample_rate;
+        let max_delay = self.max_size * sample_rate;
+
+        for (in_signal, slice, chance, seed, out) in iter_proc_io_as!(
+            inputs as [Float, Float, Float, i64],
             outputs as [Float]
         ) {
-            let delay = delay.unwrap_or_default();
-
-            self.ring_buffer[self.head] = in_signal.unwrap_or_default();
-
-            let (index, delay_frac) = self.index_modulo(delay);
-
-            let delayed = self.ring_buffer[index];
+            if self.rng.update_seed(*seed) {
+                self.position = self.length;
+            }
+            if let Some(slice) = slice {
+                self.slice = slice.max(0.0);
+            }
+            if let Some(chance) = chance {
+                self.chance = chance.clamp(0.0, 1.0);
+            }
 
-            let next_index = (index + 1) % self.ring_buffer.len();
-            let next = self.ring_buffer[next_index];
+            let input = in_signal.unwrap_or_default();
+            self.line.write(input);
+
+            if self.position >= self.length {
+                self.position = 0.0;
+                self.previous_length = self.length;
+                // slices must fit in the buffer a few times over, and be longer than the crossfade
+                self.length = (self.slice * sample_rate)
+                    .min(max_delay / 4.0)
+                    .max(2.0 * crossfade)
+                    .floor();
+                self.previous_source = self.source;
+                self.source = self.next_source(max_delay);
+            }
 
-            *out = Some(lerp(delayed, next, delay_frac));
+            let mut value = self.read(self.source, self.position, input);
+            if self.position < crossfade {
+                // keep playing the previous slice as it fades out
+                let previous = self.read(
+                    self.previous_source,
+                    self.previous_length + self.position,
+                    input,
+                );
+                value = lerp(previous, value, self.position / crossfade);
+            }
+            self.position += 1.0;
 
-            self.head = (self.head + 1) % self.ring_buffer.len();
+            *out = Some(value);
         }
 
         Ok(())
@@ -630,7 +1902,33 @@ impl Processor for AREnv {
     }
 }
 
-/// A linear ADSR (attack-decay-sustain-release) envelope generator.
+/// How an [`ADSREnv`] restarts when it is triggered while it is still sounding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RetriggerMode {
+    /// The attack restarts from `0.0`.
+    #[default]
+    Reset,
+    /// The attack starts from the envelope's current level, like an analog envelope, so there is no click.
+    FromCurrent,
+}
+
+// shapes the progress through an envelope stage with the given curvature, keeping the ends fixed at 0 and 1
+#[inline]
+fn curve(t: Float, curvature: Float) -> Float {
+    if curvature.abs() < 1e-3 {
+        t
+    } else {
+        (1.0 - (-curvature * t).exp()) / (1.0 - (-curvature).exp())
+    }
+}
+
+/// An ADSR (attack-decay-sustain-release) envelope generator.
+///
+/// Each stage is linear by default, or curved with [`ADSREnv::with_curves`].
+/// Positive curvatures move quickly at the start of a stage and slowly towards its end, like the exponential stages of an analog envelope, and negative curvatures do the opposite.
+///
+/// While the `hold` input is `true` (like a sustain pedal), releasing the gate doesn't start the release until the hold is also released.
 ///
 /// # Inputs
 ///
@@ -641,6 +1939,7 @@ impl Processor for AREnv {
 /// | `2` | `decay` | `Float` | The decay time in seconds. |
 /// | `3` | `sustain` | `Float` | The sustain level. |
 /// | `4` | `release` | `Float` | The release time in seconds. |
+/// | `5` | `hold` | `Bool` | Whether to hold the envelope at its sustain level after the gate is released. |
 ///
 /// # Outputs
 ///
@@ -656,7 +1955,21 @@ pub struct ADSREnv {
     sustain: Float,
     release: Float,
     value: Float,
-    state: ADSRState,
+    // the current stage, or `None` once the release has finished
+    #[cfg_attr(feature = "serde", serde(default))]
+    stage: Option<ADSRState>,
+    // the level the current stage started from, and the progress through it (0.0 to 1.0)
+    #[cfg_attr(feature = "serde", serde(default))]
+    stage_start: Float,
+    #[cfg_attr(feature = "serde", serde(default))]
+    stage_progress: Float,
+    // whether the gate was released while the envelope was held
+    #[cfg_attr(feature = "serde", serde(default))]
+    release_pending: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    curves: [Float; 3],
+    #[cfg_attr(feature = "serde", serde(default))]
+    retrigger: RetriggerMode,
 }
 
 impl ADSREnv {
@@ -669,8 +1982,56 @@ impl ADSREnv {
             sustain,
             release,
             value: 0.0,
-            state: ADSRState::Sustain,
+            stage: None,
+            stage_start: 0.0,
+            stage_progress: 0.0,
+            release_pending: false,
+            curves: [0.0; 3],
+            retrigger: RetriggerMode::Reset,
+        }
+    }
+
+    /// Sets the curvatures of the attack, decay, and release stages, where `0.0` is linear.
+    ///
+    /// Curvatures around `4.0` to `6.0` sound like an analog envelope.
+    pub fn with_curves(mut self, attack: Float, decay: Float, release: Float) -> Self {
+        self.curves = [attack, decay, release];
+        self
+    }
+
+    /// Sets how the envelope restarts when it is triggered while it is still sounding.
+    pub fn with_retrigger(mut self, retrigger: RetriggerMode) -> Self {
+        self.retrigger = retrigger;
+        self
+    }
+
+    /// Returns the current stage of the envelope, or `None` if it has finished releasing.
+    pub fn state(&self) -> Option<ADSRState> {
+        self.stage
+    }
+
+    fn enter(&mut self, state: ADSRState) {
+        self.stage = Some(state);
+        self.stage_start = self.value;
+        self.stage_progress = 0.0;
+    }
+
+    // advances through the current stage, returning whether it has finished
+    fn advance(
+        &mut self,
+        time: Float,
+        target: Float,
+        curvature: Float,
+        sample_rate: Float,
+    ) -> bool {
+        if time <= 0.0 {
+            self.stage_progress = 1.0;
+        } else {
+            self.stage_progress = (self.stage_progress + 1.0 / (time * sample_rate)).min(1.0);
         }
+        self.value =
+            self.stage_start + (target - self.stage_start) * curve(self.stage_progress, curvature);
+        self.stage_progress >= 1.0
     }
 }
 
@@ -689,6 +2050,7 @@ impl Processor for ADSREnv {
             SignalSpec::new("decay", SignalType::Float),
             SignalSpec::new("sustain", SignalType::Float),
             SignalSpec::new("release", SignalType::Float),
+            SignalSpec::new("hold", SignalType::Bool),
         ]
     }
 
@@ -701,8 +2063,11 @@ impl Processor for ADSREnv {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (trig, attack, decay, sustain, release, out) in iter_proc_io_as!(
-            inputs as [bool, Float, Float, Float, Float],
+        let sample_rate = inputs.sample_rate();
+        let [attack_curve, decay_curve, release_curve] = self.curves;
+
+        for (trig, attack, decay, sustain, release, hold, out) in iter_proc_io_as!(
+            inputs as [bool, Float, Float, Float, Float, bool],
             outputs as [Float]
         ) {
             self.attack = attack.unwrap_or(self.attack);
@@ -710,37 +2075,303 @@ impl Processor for ADSREnv {
             self.sustain = sustain.unwrap_or(self.sustain);
             self.release = release.unwrap_or(self.release);
             let trig = trig.unwrap_or(false);
+            let hold = hold.unwrap_or(false);
 
             if trig && !self.last_trig {
-                self.value = 0.0;
-                self.state = ADSRState::Attack;
+                if self.retrigger == RetriggerMode::Reset {
+                    self.value = 0.0;
+                }
+                self.release_pending = false;
+                self.enter(ADSRState::Attack);
             } else if !trig && self.last_trig {
-                self.state = ADSRState::Release;
+                self.release_pending = true;
+            }
+            if self.release_pending && !hold {
+                self.release_pending = false;
+                if self.stage.is_some() {
+                    self.enter(ADSRState::Release);
+                }
             }
 
-            let slope = match self.state {
-                ADSRState::Attack => 1.0 / (self.attack * inputs.sample_rate()),
-                ADSRState::Decay => -(1.0 - self.sustain) / (self.decay * inputs.sample_rate()),
-                ADSRState::Sustain => 0.0,
-                ADSRState::Release => -self.sustain / (self.release * inputs.sample_rate()),
-            };
+            match self.stage {
+                Some(ADSRState::Attack) => {
+                    // an attack from a higher level is shorter, so it rises at the same rate
+                    let time = self.attack * (1.0 - self.stage_start).max(0.0);
+                    if self.advance(time, 1.0, attack_curve, sample_rate) {
+                        self.enter(ADSRState::Decay);
+                    }
+                }
+                Some(ADSRState::Decay) => {
+                    if self.advance(self.decay, self.sustain, decay_curve, sample_rate) {
+                        self.enter(ADSRState::Sustain);
+                    }
+                }
+                Some(ADSRState::Sustain) => self.value = self.sustain,
+                Some(ADSRState::Release) => {
+                    if self.advance(self.release, 0.0, release_curve, sample_rate) {
+                        self.stage = None;
+                    }
+                }
+                None => self.value = 0.0,
+            }
 
-            self.value += slope;
+            self.last_trig = trig;
 
-            if self.state == ADSRState::Attack && self.value >= 1.0 {
-                self.value = 1.0;
-                self.state = ADSRState::Decay;
-            } else if self.state == ADSRState::Decay && self.value <= self.sustain {
-                self.value = self.sustain;
-                self.state = ADSRState::Sustain;
-            } else if self.state == ADSRState::Release && self.value <= 0.0 {
-                self.value = 0.0;
-                self.state = ADSRState::Sustain;
+            *out = Some(self.value);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct EnvStage {
+    time: Float,
+    level: Float,
+    curve: Float,
+}
+
+/// A looping multi-stage envelope generator (MSEG), for modulation shapes that don't fit an [`ADSREnv`].
+///
+/// Each stage moves from the level the previous stage ended on to its own level, over its own time and with its own curvature (see [`ADSREnv::with_curves`]).
+/// The number of stages is the length of the `levels` list; missing times and curvatures are `0.0`.
+///
+/// When the gate rises, the envelope starts at the first stage.
+/// If a loop is set, the stages from `loop_start` to `loop_end` (inclusive) repeat while the gate is held, and releasing the gate jumps to the stage after `loop_end`.
+/// Without a loop, the stages play through regardless of the gate.
+/// After the last stage, the envelope holds the last stage's level.
+///
+/// Each stage lasts at least one sample.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `gate` | `Bool` | The gate signal. |
+/// | `1` | `times` | `List` | The time of each stage in seconds. |
+/// | `2` | `levels` | `List` | The level each stage ends on. |
+/// | `3` | `curves` | `List` | The curvature of each stage, where `0.0` is linear. |
+/// | `4` | `loop_start` | `Int` | The first stage of the loop, or a negative number to disable looping. |
+/// | `5` | `loop_end` | `Int` | The last stage of the loop, or a negative number to disable looping. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The envelope signal. |
+/// | `1` | `stage` | `Int` | The index of the current stage, or `None` if the envelope isn't running. |
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiStageEnv {
+    stages: Vec<EnvStage>,
+    num_active: usize,
+    loop_start: Option<usize>,
+    loop_end: Option<usize>,
+    last_trig: bool,
+    value: Float,
+    // the current stage, or `None` before the first trigger and after the last stage
+    stage: Option<usize>,
+    // the level the current stage started from, and the progress through it (0.0 to 1.0)
+    stage_start: Float,
+    stage_progress: Float,
+    retrigger: RetriggerMode,
+}
+
+impl MultiStageEnv {
+    /// Creates a new [`MultiStageEnv`] processor with room for up to the given number of stages, initially with no stages.
+    pub fn new(max_stages: usize) -> Self {
+        Self {
+            stages: vec![EnvStage::default(); max_stages],
+            num_active: 0,
+            loop_start: None,
+            loop_end: None,
+            last_trig: false,
+            value: 0.0,
+            stage: None,
+            stage_start: 0.0,
+            stage_progress: 0.0,
+            retrigger: RetriggerMode::Reset,
+        }
+    }
+
+    /// Sets the levels of the stages, activating one stage for each level.
+    pub fn with_levels(mut self, levels: &[Float]) -> Self {
+        self.set_levels(levels.iter().copied());
+        self
+    }
+
+    /// Sets the times of the stages, in seconds.
+    pub fn with_times(mut self, times: &[Float]) -> Self {
+        self.set_times(times.iter().copied());
+        self
+    }
+
+    /// Sets the curvatures of the stages, where `0.0` is linear.
+    pub fn with_curves(mut self, curves: &[Float]) -> Self {
+        self.set_curves(curves.iter().copied());
+        self
+    }
+
+    /// Sets the first and last stages (inclusive) to repeat while the gate is held.
+    pub fn with_loop(mut self, start: usize, end: usize) -> Self {
+        self.loop_start = Some(start);
+        self.loop_end = Some(end);
+        self
+    }
+
+    /// Sets how the envelope restarts when it is triggered while it is still running.
+    pub fn with_retrigger(mut self, retrigger: RetriggerMode) -> Self {
+        self.retrigger = retrigger;
+        self
+    }
+
+    /// Returns the number of active stages.
+    pub fn num_stages(&self) -> usize {
+        self.num_active
+    }
+
+    /// Returns the index of the current stage, or `None` if the envelope isn't running.
+    pub fn stage(&self) -> Option<usize> {
+        self.stage
+    }
+
+    fn set_levels(&mut self, levels: impl Iterator<Item = Float>) {
+        let mut num_active = 0;
+        for (stage, level) in self.stages.iter_mut().zip(levels) {
+            stage.level = level;
+            num_active += 1;
+        }
+        self.num_active = num_active;
+    }
+
+    fn set_times(&mut self, times: impl Iterator<Item = Float>) {
+        let mut times = times.fuse();
+        for stage in &mut self.stages {
+            stage.time = times.next().unwrap_or(0.0);
+        }
+    }
+
+    fn set_curves(&mut self, curves: impl Iterator<Item = Float>) {
+        let mut curves = curves.fuse();
+        for stage in &mut self.stages {
+            stage.curve = curves.next().unwrap_or(0.0);
+        }
+    }
+
+    // the loop points, if they make a valid loop over the active stages
+    fn loop_points(&self) -> Option<(usize, usize)> {
+        let (start, end) = (self.loop_start?, self.loop_end?);
+        (start <= end && end < self.num_active).then_some((start, end))
+    }
+
+    fn enter(&mut self, stage: usize) {
+        self.stage = (stage < self.num_active).then_some(stage);
+        self.stage_start = self.value;
+        self.stage_progress = 0.0;
+    }
+}
+
+impl Default for MultiStageEnv {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for MultiStageEnv {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("gate", SignalType::Bool),
+            SignalSpec::new("times", SignalType::List),
+            SignalSpec::new("levels", SignalType::List),
+            SignalSpec::new("curves", SignalType::List),
+            SignalSpec::new("loop_start", SignalType::Int),
+            SignalSpec::new("loop_end", SignalType::Int),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("out", SignalType::Float),
+            SignalSpec::new("stage", SignalType::Int),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        for (trig, times, levels, curves, loop_start, loop_end, out, out_stage) in iter_proc_io_as!(
+            inputs as [bool, List, List, List, i64, i64],
+            outputs as [Float, i64]
+        ) {
+            if let Some(levels) = levels {
+                self.set_levels(iter_list_floats(levels));
+            }
+            if let Some(times) = times {
+                self.set_times(iter_list_floats(times));
+            }
+            if let Some(curves) = curves {
+                self.set_curves(iter_list_floats(curves));
+            }
+            if let Some(loop_start) = loop_start {
+                self.loop_start = usize::try_from(*loop_start).ok();
+            }
+            if let Some(loop_end) = loop_end {
+                self.loop_end = usize::try_from(*loop_end).ok();
+            }
+            let trig = trig.unwrap_or(false);
+            let looping = self.loop_points();
+
+            if trig && !self.last_trig {
+                if self.retrigger == RetriggerMode::Reset {
+                    self.value = 0.0;
+                }
+                self.enter(0);
+            } else if !trig && self.last_trig {
+                if let (Some(stage), Some((_, end))) = (self.stage, looping) {
+                    if stage <= end {
+                        self.enter(end + 1);
+                    }
+                }
+            }
+
+            if let Some(index) = self.stage {
+                if index >= self.num_active {
+                    // the stages were shortened while running
+                    self.stage = None;
+                } else {
+                    let EnvStage {
+                        time,
+                        level,
+                        curve: curvature,
+                    } = self.stages[index];
+                    if time <= 0.0 {
+                        self.stage_progress = 1.0;
+                    } else {
+                        self.stage_progress =
+                            (self.stage_progress + 1.0 / (time * sample_rate)).min(1.0);
+                    }
+                    self.value = self.stage_start
+                        + (level - self.stage_start) * curve(self.stage_progress, curvature);
+
+                    if self.stage_progress >= 1.0 {
+                        match looping {
+                            Some((start, end)) if trig && index == end => self.enter(start),
+                            _ => self.enter(index + 1),
+                        }
+                    }
+                }
             }
 
             self.last_trig = trig;
 
             *out = Some(self.value);
+            *out_stage = self.stage.map(|stage| stage as i64);
         }
 
         Ok(())
@@ -1,11 +1,14 @@
 //! Utility processors.
 
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
 
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, Sender, TrySendError};
 use raug_macros::iter_proc_io_as;
 
-use crate::prelude::*;
+use crate::{prelude::*, signal::AnySignalMut};
 
 use super::lerp;
 
@@ -81,11 +84,20 @@ impl Processor for Passthrough {
         vec![SignalSpec::new("out", self.signal_type)]
     }
 
+    fn can_process_in_place(&self) -> bool {
+        true
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
+        // the input is already in the output
+        if inputs.in_place {
+            return Ok(());
+        }
+
         for (in_signal, mut out_signal) in iter_proc_io_as!(inputs as [Any], outputs as [Any]) {
             if let Some(in_signal) = in_signal {
                 out_signal.clone_from_ref(in_signal);
@@ -99,6 +111,9 @@ impl Processor for Passthrough {
 
 /// A processor that casts its input to a different signal type.
 ///
+/// See [`AnySignal::cast`] for the supported conversions.
+/// Strings that don't parse as the target type are output as `None`.
+///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
@@ -134,6 +149,10 @@ impl Processor for Cast {
         vec![SignalSpec::new("out", self.to)]
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn process(
         &mut self,
         inputs: ProcessorInputs,
@@ -145,13 +164,17 @@ impl Processor for Cast {
                 continue;
             };
             let in_signal = in_signal.to_owned();
-            let Some(cast) = in_signal.cast(self.to) else {
-                return Err(ProcessorError::InvalidCast(
-                    in_signal.signal_type(),
-                    self.to,
-                ));
-            };
-            out_signal.clone_from_ref(cast.as_ref());
+            match in_signal.cast(self.to) {
+                Some(cast) => out_signal.clone_from_ref(cast.as_ref()),
+                // the types can be cast, so only the value failed to parse
+                None if self.from.can_cast_to(&self.to) => out_signal.set_none(),
+                None => {
+                    return Err(ProcessorError::InvalidCast(
+                        in_signal.signal_type(),
+                        self.to,
+                    ))
+                }
+            }
         }
 
         Ok(())
@@ -351,6 +374,26 @@ impl GraphBuilder {
     }
 }
 
+// returns the target of a `Smooth` if neither it nor the factor change during the block
+fn settled_target(
+    target: Option<&[Option<Float>]>,
+    factor: Option<&[Option<Float>]>,
+    len: usize,
+) -> Option<Option<Float>> {
+    fn constant(signal: &[Option<Float>], len: usize) -> Option<Option<Float>> {
+        let first = *signal.first()?;
+        (signal.len() >= len && signal[..len].iter().all(|value| *value == first)).then_some(first)
+    }
+
+    if let Some(factor) = factor {
+        constant(factor, len)?;
+    }
+    match target {
+        Some(target) => constant(target, len),
+        None => Some(None),
+    }
+}
+
 /// A processor that smooths a signal to a target value using a smoothing factor.
 ///
 /// The output signal will converge to the target value with a speed determined by the smoothing factor.
@@ -390,23 +433,40 @@ impl Processor for Smooth {
     fn process(
         &mut self,
         inputs: ProcessorInputs,
-        outputs: ProcessorOutputs,
+        mut outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (target, factor, out) in iter_proc_io_as!(
-            inputs as [Float, Float],
-            outputs as [Float]
-        ) {
-            self.factor = factor.unwrap_or(self.factor).clamp(0.0, 1.0);
+        let target = inputs.input_as_slice::<Float>(0)?;
+        let factor = inputs.input_as_slice::<Float>(1)?;
+        let out = outputs.output_as_slice_mut::<Float>(0)?;
 
-            let Some(target) = target else {
-                *out = Some(self.current);
-                continue;
-            };
+        let mut current_factor = self.factor.clamp(0.0, 1.0);
 
-            self.current = lerp(self.current, *target, self.factor);
+        // each sample depends on the last, so the loop can't be vectorized, but a settled smoother can skip it entirely
+        if let Some(value) = settled_target(target, factor, out.len()) {
+            if let Some(factor) = factor.and_then(|factor| factor[0]) {
+                current_factor = factor.clamp(0.0, 1.0);
+            }
+            let settled = match value {
+                Some(target) => lerp(self.current, target, current_factor) == self.current,
+                None => true,
+            };
+            if settled {
+                out.fill(Some(self.current));
+                self.factor = current_factor;
+                return Ok(());
+            }
+        }
 
+        for (i, out) in out.iter_mut().enumerate() {
+            if let Some(factor) = factor.and_then(|factor| factor.get(i).copied().flatten()) {
+                current_factor = factor.clamp(0.0, 1.0);
+            }
+            if let Some(target) = target.and_then(|target| target.get(i).copied().flatten()) {
+                self.current = lerp(self.current, target, current_factor);
+            }
             *out = Some(self.current);
         }
+        self.factor = current_factor;
 
         Ok(())
     }
@@ -470,12 +530,8 @@ impl Processor for Changed {
             self.threshold = threshold.unwrap_or(self.threshold);
 
             match (self.last, in_signal) {
-                (Some(last), Some(in_signal)) => {
-                    if (last - in_signal).abs() > self.threshold {
-                        *out_signal = Some(true);
-                    } else {
-                        *out_signal = None;
-                    }
+                (Some(last), Some(in_signal)) if (last - in_signal).abs() > self.threshold => {
+                    *out_signal = Some(true);
                 }
                 (None, Some(_)) if self.include_none => {
                     *out_signal = Some(true);
@@ -659,8 +715,166 @@ impl Default for ParamChannel {
     }
 }
 
+/// The shape of the transition to a scheduled [`Param`] value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RampShape {
+    /// Jump to the value at the scheduled time.
+    Step,
+    /// Linearly ramp to the value, reaching it at the scheduled time.
+    Linear,
+    /// Exponentially ramp to the value, reaching it at the scheduled time.
+    ///
+    /// Falls back to a linear ramp if the start and end values don't have the same sign, or either is zero.
+    Exponential,
+}
+
+// the number of scheduled events a parameter holds at a time
+const MAX_SCHEDULED_EVENTS: usize = 64;
+
+#[derive(Clone, Copy, Debug)]
+struct AutomationEvent {
+    time: u64,
+    value: Float,
+    shape: RampShape,
+}
+
+#[derive(Debug)]
+struct ParamAutomation {
+    // the event queues of this parameter and all of its clones, so every clone receives every event
+    senders: Arc<Mutex<Vec<Sender<AutomationEvent>>>>,
+    tx: Sender<AutomationEvent>,
+    rx: Receiver<AutomationEvent>,
+
+    // the number of samples this instance has processed
+    time: u64,
+    // the largest number of samples any clone has processed, for `Param::time`
+    clock: Arc<AtomicU64>,
+
+    // pending events, sorted by time
+    events: Vec<AutomationEvent>,
+    // the time and value the next ramp starts from
+    ramp_start: Option<(u64, Float)>,
+    // the current automated value
+    value: Option<Float>,
+}
+
+impl Default for ParamAutomation {
+    fn default() -> Self {
+        let (tx, rx) = crossbeam_channel::bounded(MAX_SCHEDULED_EVENTS);
+        Self {
+            senders: Arc::new(Mutex::new(vec![tx.clone()])),
+            tx,
+            rx,
+            time: 0,
+            clock: Arc::new(AtomicU64::new(0)),
+            events: Vec::with_capacity(MAX_SCHEDULED_EVENTS),
+            ramp_start: None,
+            value: None,
+        }
+    }
+}
+
+impl Clone for ParamAutomation {
+    fn clone(&self) -> Self {
+        let (tx, rx) = crossbeam_channel::bounded(MAX_SCHEDULED_EVENTS);
+        self.senders.lock().unwrap().push(tx.clone());
+
+        // events that haven't been received yet are queued for both instances
+        let queued: Vec<_> = self.rx.try_iter().collect();
+        for event in queued {
+            self.tx.try_send(event).ok();
+            tx.try_send(event).ok();
+        }
+
+        let mut events = Vec::with_capacity(MAX_SCHEDULED_EVENTS);
+        events.extend_from_slice(&self.events);
+
+        Self {
+            senders: self.senders.clone(),
+            tx,
+            rx,
+            time: self.time,
+            clock: self.clock.clone(),
+            events,
+            ramp_start: self.ramp_start,
+            value: self.value,
+        }
+    }
+}
+
+impl ParamAutomation {
+    fn schedule(&self, time: u64, value: Float, shape: RampShape) {
+        let event = AutomationEvent { time, value, shape };
+        // queues that are full drop the event, and queues of dropped clones are forgotten
+        self.senders
+            .lock()
+            .unwrap()
+            .retain(|tx| !matches!(tx.try_send(event), Err(TrySendError::Disconnected(_))));
+    }
+
+    fn receive_events(&mut self) {
+        // events past the limit stay queued until earlier ones have passed
+        while self.events.len() < MAX_SCHEDULED_EVENTS {
+            let Ok(event) = self.rx.try_recv() else {
+                break;
+            };
+            let index = self
+                .events
+                .partition_point(|pending| pending.time <= event.time);
+            self.events.insert(index, event);
+        }
+    }
+
+    fn advance(&mut self, num_samples: u64) {
+        self.time += num_samples;
+        self.clock.fetch_max(self.time, Ordering::Relaxed);
+    }
+
+    // sets the value immediately, e.g. when the parameter is sent a new value
+    fn set_value(&mut self, time: u64, value: Float) {
+        self.value = Some(value);
+        self.ramp_start = Some((time, value));
+    }
+
+    fn value_at(&mut self, time: u64) -> Option<Float> {
+        while let Some(event) = self.events.first().copied() {
+            if event.time > time {
+                break;
+            }
+            self.events.remove(0);
+            self.set_value(event.time, event.value);
+        }
+
+        let Some(next) = self.events.first() else {
+            return self.value;
+        };
+
+        let Some((start_time, start_value)) = self.ramp_start else {
+            return self.value;
+        };
+
+        let t = (time - start_time) as Float / (next.time - start_time) as Float;
+
+        let value = match next.shape {
+            RampShape::Step => start_value,
+            RampShape::Exponential if start_value * next.value > 0.0 => {
+                start_value * (next.value / start_value).powf(t)
+            }
+            RampShape::Linear | RampShape::Exponential => lerp(start_value, next.value, t),
+        };
+
+        self.value = Some(value);
+        self.value
+    }
+}
+
 /// A processor that can be used to control a parameter from outside the graph.
 ///
+/// [`Float`] parameters can also be automated with sample accuracy by scheduling values at specific times with [`Param::set_at`], [`Param::linear_ramp_to`], and [`Param::exponential_ramp_to`].
+/// Times are measured in samples since the parameter started processing (see [`Param::time`]).
+/// Every clone of the parameter (e.g. in each voice of a [`PolyVoice`]) receives the scheduled events and keeps its own time.
+/// Up to 64 events can be pending at a time; events scheduled while a parameter already has that many pending are dropped.
+///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
@@ -679,6 +893,8 @@ pub struct Param {
     signal_type: SignalType,
     minimum: Option<Float>,
     maximum: Option<Float>,
+    // boxed so that enums holding a `Param` don't grow with the automation state
+    automation: Box<ParamAutomation>,
 }
 
 impl Param {
@@ -690,6 +906,7 @@ impl Param {
             signal_type: S::signal_type(),
             minimum: None,
             maximum: None,
+            automation: Box::default(),
         };
         if let Some(initial_value) = initial_value.into() {
             this.send(initial_value);
@@ -710,6 +927,7 @@ impl Param {
             signal_type: SignalType::Float,
             minimum: minimum.into(),
             maximum: maximum.into(),
+            automation: Box::default(),
         };
         if let Some(initial_value) = initial_value.into() {
             this.send(initial_value);
@@ -744,7 +962,10 @@ impl Param {
 
     /// Sends a value to the parameter.
     pub fn send(&self, message: impl Signal) {
-        let message = message.into_any_signal();
+        self.send_any(message.into_any_signal());
+    }
+
+    pub(crate) fn send_any(&self, message: AnySignal) {
         match (message, self.minimum, self.maximum) {
             (AnySignal::Float(Some(value)), Some(min), Some(max)) => {
                 self.tx()
@@ -778,6 +999,42 @@ impl Param {
         }
     }
 
+    /// Returns the current time of the parameter, in samples since it started processing.
+    ///
+    /// If the parameter has been cloned, this is the time of the clone that has processed the most samples.
+    pub fn time(&self) -> u64 {
+        self.automation.clock.load(Ordering::Relaxed)
+    }
+
+    /// Schedules the parameter to jump to the given value at the given time (in samples).
+    ///
+    /// Only applies to [`Float`] parameters.
+    pub fn set_at(&self, time: u64, value: Float) {
+        self.automation
+            .schedule(time, self.clamp(value), RampShape::Step);
+    }
+
+    /// Schedules the parameter to ramp linearly from its previous value, reaching the given value at the given time (in samples).
+    ///
+    /// Only applies to [`Float`] parameters.
+    pub fn linear_ramp_to(&self, time: u64, value: Float) {
+        self.automation
+            .schedule(time, self.clamp(value), RampShape::Linear);
+    }
+
+    /// Schedules the parameter to ramp exponentially from its previous value, reaching the given value at the given time (in samples).
+    ///
+    /// Only applies to [`Float`] parameters.
+    pub fn exponential_ramp_to(&self, time: u64, value: Float) {
+        self.automation
+            .schedule(time, self.clamp(value), RampShape::Exponential);
+    }
+
+    fn clamp(&self, value: Float) -> Float {
+        let value = self.minimum.map_or(value, |min| value.max(min));
+        self.maximum.map_or(value, |max| value.min(max))
+    }
+
     /// Returns the last received value of the parameter.
     pub fn last(&self) -> Option<AnySignal> {
         let last = self.rx().last();
@@ -812,12 +1069,41 @@ impl Processor for Param {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
+        self.automation.receive_events();
+
+        let start_time = self.automation.time;
+        let mut num_samples = 0;
+
         for (set, mut get) in iter_proc_io_as!(inputs as [Any], outputs as [Any]) {
+            let time = start_time + num_samples;
+            num_samples += 1;
+
             if let Some(set) = set {
                 self.tx().send(set.to_owned());
             }
 
-            if let Some(msg) = self.rx_mut().recv() {
+            let msg = self.rx_mut().recv();
+
+            match &msg {
+                Some(AnySignal::Float(Some(value))) => self.automation.set_value(time, *value),
+                // a clone that didn't receive the last value itself ramps from it all the same
+                None if self.automation.ramp_start.is_none() => {
+                    if let Some(AnySignal::Float(Some(value))) = self.rx().last() {
+                        self.automation.set_value(time, value);
+                    }
+                }
+                _ => {}
+            }
+
+            // scheduled values take precedence over the last received value
+            if let AnySignalMut::Float(get) = &mut get {
+                if let Some(value) = self.automation.value_at(time) {
+                    **get = Some(value);
+                    continue;
+                }
+            }
+
+            if let Some(msg) = msg {
                 get.clone_from_ref(msg.as_ref());
             } else if let Some(last) = self.rx().last() {
                 get.clone_from_ref(last.as_ref());
@@ -826,6 +1112,8 @@ impl Processor for Param {
             }
         }
 
+        self.automation.advance(num_samples);
+
         Ok(())
     }
 }
@@ -876,6 +1164,7 @@ impl<'de> serde::Deserialize<'de> for Param {
             signal_type: de.signal_type,
             minimum: de.minimum,
             maximum: de.maximum,
+            automation: Box::default(),
         };
         if let Some(initial_value) = de.initial_value {
             param.tx().send(initial_value);
@@ -946,12 +1235,15 @@ impl Processor for Counter {
 
 /// A processor that captures the value of a signal when triggered and contuously outputs it.
 ///
+/// With a slew time (see [`SampleAndHold::with_slew`]), the output glides linearly from the previous held value to the new one instead of jumping.
+///
 /// # Inputs
 ///
 /// | Index | Name | Type | Description |
 /// | --- | --- | --- | --- |
 /// | `0` | `in` | `Float` | The input signal. |
 /// | `1` | `trig` | `Bool` | The trigger signal. |
+/// | `2` | `slew` | `Float` | The time to glide to a new held value, in seconds. |
 ///
 /// # Outputs
 ///
@@ -962,6 +1254,21 @@ impl Processor for Counter {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SampleAndHold {
     last: Option<Float>,
+    // the output while gliding towards `last`, and how far it moves each sample
+    #[cfg_attr(feature = "serde", serde(default))]
+    value: Option<Float>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    step: Float,
+    #[cfg_attr(feature = "serde", serde(default))]
+    slew: Float,
+}
+
+impl SampleAndHold {
+    /// Sets the time to glide to a new held value, in seconds.
+    pub fn with_slew(mut self, slew: Float) -> Self {
+        self.slew = slew;
+        self
+    }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde)]
@@ -970,6 +1277,7 @@ impl Processor for SampleAndHold {
         vec![
             SignalSpec::new("in", SignalType::Float),
             SignalSpec::new("trig", SignalType::Bool),
+            SignalSpec::new("slew", SignalType::Float),
         ]
     }
 
@@ -982,12 +1290,89 @@ impl Processor for SampleAndHold {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        for (in_signal, trig, out_signal) in iter_proc_io_as!(
-            inputs as [Float, bool],
+        let sample_rate = inputs.sample_rate();
+
+        for (in_signal, trig, slew, out_signal) in iter_proc_io_as!(
+            inputs as [Float, bool, Float],
             outputs as [Float]
         ) {
+            self.slew = slew.unwrap_or(self.slew);
+
             if let Some(true) = trig {
                 self.last = *in_signal;
+                let samples = self.slew * sample_rate;
+                match (self.value, self.last) {
+                    (Some(value), Some(last)) if samples > 1.0 => {
+                        self.step = (last - value) / samples;
+                    }
+                    // there's nothing to glide from or to
+                    _ => self.value = self.last,
+                }
+            }
+
+            if let (Some(value), Some(last)) = (self.value, self.last) {
+                let remaining = last - value;
+                if remaining.abs() <= self.step.abs() {
+                    self.value = Some(last);
+                } else {
+                    self.value = Some(value + self.step);
+                }
+            }
+
+            *out_signal = self.value;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that follows its input while the gate is high, and holds the last value while it is low.
+///
+/// `None` samples in the input are skipped, so the last value is held through them even while the gate is high.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Float` | The input signal. |
+/// | `1` | `gate` | `Bool` | Whether to track the input. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Float` | The output signal. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrackAndHold {
+    last: Option<Float>,
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for TrackAndHold {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Float),
+            SignalSpec::new("gate", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Float)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, gate, out_signal) in iter_proc_io_as!(
+            inputs as [Float, bool],
+            outputs as [Float]
+        ) {
+            // gaps in the input keep the held value
+            if let (Some(true), Some(value)) = (gate, in_signal) {
+                self.last = Some(*value);
             }
 
             *out_signal = self.last;
@@ -997,6 +1382,364 @@ impl Processor for SampleAndHold {
     }
 }
 
+/// A processor that captures the value of a boolean signal when triggered and continuously outputs it.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Bool` | The input signal. |
+/// | `1` | `trig` | `Bool` | The trigger signal. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Bool` | The latched value. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Latch {
+    state: bool,
+}
+
+impl Latch {
+    /// Creates a new `Latch` processor with the given initial state.
+    pub fn new(state: bool) -> Self {
+        Self { state }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Latch {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Bool),
+            SignalSpec::new("trig", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Bool)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, trig, out_signal) in iter_proc_io_as!(
+            inputs as [bool, bool],
+            outputs as [bool]
+        ) {
+            if let Some(true) = trig {
+                self.state = in_signal.unwrap_or(false);
+            }
+
+            *out_signal = Some(self.state);
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that outputs triggers on the rising and falling edges of a gate signal.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Bool` | The gate signal. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `rising` | `Bool` | A trigger when the gate goes from `false` to `true`. |
+/// | `1` | `falling` | `Bool` | A trigger when the gate goes from `true` to `false`. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeDetect {
+    last: bool,
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for EdgeDetect {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("in", SignalType::Bool)]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("rising", SignalType::Bool),
+            SignalSpec::new("falling", SignalType::Bool),
+        ]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, rising, falling) in iter_proc_io_as!(
+            inputs as [bool],
+            outputs as [bool, bool]
+        ) {
+            let gate = in_signal.unwrap_or(false);
+
+            *rising = (gate && !self.last).then_some(true);
+            *falling = (!gate && self.last).then_some(true);
+
+            self.last = gate;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that stretches short pulses into gates of a minimum length.
+///
+/// The output goes high on a rising edge of the input, and stays high for at least the given length, or for as long as the input is held, whichever is longer.
+/// A rising edge while the output is high restarts the length.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Bool` | The input trigger or gate. |
+/// | `1` | `length` | `Float` | The minimum gate length in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Bool` | The stretched gate. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PulseStretch {
+    last: bool,
+    // the number of samples left before the minimum length is reached
+    remaining: usize,
+
+    /// The minimum gate length in seconds.
+    pub length: Float,
+}
+
+impl PulseStretch {
+    /// Creates a new `PulseStretch` processor with the given minimum gate length in seconds.
+    pub fn new(length: Float) -> Self {
+        Self {
+            last: false,
+            remaining: 0,
+            length,
+        }
+    }
+}
+
+impl Default for PulseStretch {
+    fn default() -> Self {
+        Self::new(0.01)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for PulseStretch {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Bool),
+            SignalSpec::new("length", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Bool)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        for (in_signal, length, out) in iter_proc_io_as!(
+            inputs as [bool, Float],
+            outputs as [bool]
+        ) {
+            self.length = length.unwrap_or(self.length);
+            let gate = in_signal.unwrap_or(false);
+
+            if gate && !self.last {
+                self.remaining = (self.length.max(0.0) * sample_rate).round() as usize;
+            }
+
+            *out = Some(gate || self.remaining > 0);
+
+            self.remaining = self.remaining.saturating_sub(1);
+            self.last = gate;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that flips its output on every rising edge of its input (a toggle flip-flop).
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `trig` | `Bool` | The trigger signal. |
+/// | `1` | `reset` | `Bool` | Sets the output back to `false`. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Bool` | The current state. |
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToggleFF {
+    last: bool,
+    state: bool,
+}
+
+impl ToggleFF {
+    /// Creates a new `ToggleFF` processor with the given initial state.
+    pub fn new(state: bool) -> Self {
+        Self { last: false, state }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> bool {
+        self.state
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for ToggleFF {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("trig", SignalType::Bool),
+            SignalSpec::new("reset", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Bool)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (trig, reset, out) in iter_proc_io_as!(
+            inputs as [bool, bool],
+            outputs as [bool]
+        ) {
+            let trig = trig.unwrap_or(false);
+
+            if let Some(true) = reset {
+                self.state = false;
+            } else if trig && !self.last {
+                self.state = !self.state;
+            }
+
+            *out = Some(self.state);
+
+            self.last = trig;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processor that ignores changes of a gate signal until it has been stable for a given time, like a debounced switch.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Bool` | The gate signal. |
+/// | `1` | `time` | `Float` | How long the gate must stay in its new state before the output follows, in seconds. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Bool` | The debounced gate. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Debounce {
+    state: bool,
+    // the number of samples the input has differed from the output for
+    elapsed: usize,
+
+    /// How long the gate must be stable for, in seconds.
+    pub time: Float,
+}
+
+impl Debounce {
+    /// Creates a new `Debounce` processor with the given stable time in seconds.
+    pub fn new(time: Float) -> Self {
+        Self {
+            state: false,
+            elapsed: 0,
+            time,
+        }
+    }
+}
+
+impl Default for Debounce {
+    fn default() -> Self {
+        Self::new(0.01)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Debounce {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", SignalType::Bool),
+            SignalSpec::new("time", SignalType::Float),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", SignalType::Bool)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        let sample_rate = inputs.sample_rate();
+
+        for (in_signal, time, out) in iter_proc_io_as!(
+            inputs as [bool, Float],
+            outputs as [bool]
+        ) {
+            self.time = time.unwrap_or(self.time);
+            let gate = in_signal.unwrap_or(false);
+
+            if gate == self.state {
+                self.elapsed = 0;
+            } else {
+                self.elapsed += 1;
+                if self.elapsed as Float >= self.time * sample_rate {
+                    self.state = gate;
+                    self.elapsed = 0;
+                }
+            }
+
+            *out = Some(self.state);
+        }
+
+        Ok(())
+    }
+}
+
 /// A processor that panics with a message if the input signal is NaN or infinite.
 ///
 /// # Inputs
@@ -1327,3 +2070,72 @@ impl Processor for OrElse {
         Ok(())
     }
 }
+
+/// A processor that passes its input through while `pass` is `true`, and outputs `None` otherwise.
+///
+/// This turns a continuous signal into a sparse one, e.g. to only forward values while a gate is open.
+/// `pass` holds its last value while it is `None`, and starts out `false`.
+///
+/// # Inputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `in` | `Any` | The input signal. |
+/// | `1` | `pass` | `Bool` | Whether to pass the input through. |
+///
+/// # Outputs
+///
+/// | Index | Name | Type | Description |
+/// | --- | --- | --- | --- |
+/// | `0` | `out` | `Any` | The input signal while `pass` is `true`, otherwise `None`. |
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Filter {
+    signal_type: SignalType,
+    pass: bool,
+}
+
+impl Filter {
+    /// Create a new `Filter` processor.
+    pub fn new(signal_type: SignalType) -> Self {
+        Self {
+            signal_type,
+            pass: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Processor for Filter {
+    fn input_spec(&self) -> Vec<SignalSpec> {
+        vec![
+            SignalSpec::new("in", self.signal_type),
+            SignalSpec::new("pass", SignalType::Bool),
+        ]
+    }
+
+    fn output_spec(&self) -> Vec<SignalSpec> {
+        vec![SignalSpec::new("out", self.signal_type)]
+    }
+
+    fn process(
+        &mut self,
+        inputs: ProcessorInputs,
+        outputs: ProcessorOutputs,
+    ) -> Result<(), ProcessorError> {
+        for (in_signal, pass, mut out_signal) in
+            iter_proc_io_as!(inputs as [Any, bool], outputs as [Any])
+        {
+            if let Some(pass) = pass {
+                self.pass = *pass;
+            }
+
+            match in_signal {
+                Some(in_signal) if self.pass => out_signal.clone_from_ref(in_signal),
+                _ => out_signal.set_none(),
+            }
+        }
+
+        Ok(())
+    }
+}
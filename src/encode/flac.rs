@@ -0,0 +1,604 @@
+//! A FLAC encoder.
+//!
+//! The encoder uses FLAC's fixed linear predictors with partitioned Rice coding of the residual, falling back to constant or verbatim subframes where they are smaller.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::signal::Float;
+
+/// Options for encoding FLAC streams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlacSettings {
+    /// The bit depth of the encoded samples. Must be 8, 16, or 24.
+    pub bits_per_sample: u32,
+    /// The compression level, from 0 (fastest) to 8 (smallest files).
+    ///
+    /// Higher levels search more prediction and partitioning options per block, which makes files smaller at the cost of encoding time.
+    /// All levels are lossless at the chosen bit depth.
+    pub compression_level: u32,
+}
+
+impl Default for FlacSettings {
+    fn default() -> Self {
+        Self {
+            bits_per_sample: 16,
+            compression_level: 5,
+        }
+    }
+}
+
+impl FlacSettings {
+    /// Creates new [`FlacSettings`] with the given bit depth and compression level.
+    pub fn new(bits_per_sample: u32, compression_level: u32) -> Self {
+        Self {
+            bits_per_sample,
+            compression_level,
+        }
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        if !matches!(self.bits_per_sample, 8 | 16 | 24) {
+            return Err(invalid_input(format!(
+                "unsupported FLAC bit depth: {}",
+                self.bits_per_sample
+            )));
+        }
+        if self.compression_level > 8 {
+            return Err(invalid_input(format!(
+                "FLAC compression level must be between 0 and 8, got {}",
+                self.compression_level
+            )));
+        }
+        Ok(())
+    }
+
+    fn block_size(&self) -> usize {
+        if self.compression_level < 3 {
+            1152
+        } else {
+            4096
+        }
+    }
+
+    fn max_fixed_order(&self) -> usize {
+        match self.compression_level {
+            0 => 2,
+            1 | 2 => 3,
+            _ => 4,
+        }
+    }
+
+    fn max_partition_order(&self) -> u32 {
+        [3, 3, 3, 4, 4, 5, 6, 6, 6][self.compression_level as usize]
+    }
+}
+
+fn invalid_input(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+/// Writes bits MSB-first into a byte buffer.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, num_bits: u32) {
+        debug_assert!(num_bits <= 32);
+        if num_bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << num_bits) | (value & ((1 << num_bits) - 1));
+        self.bits += num_bits;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+        self.acc &= (1 << self.bits) - 1;
+    }
+
+    fn write_signed(&mut self, value: i64, num_bits: u32) {
+        self.write(value as u64, num_bits);
+    }
+
+    fn write_unary(&mut self, mut zeros: u64) {
+        while zeros >= 32 {
+            self.write(0, 32);
+            zeros -= 32;
+        }
+        self.write(1, zeros as u32 + 1);
+    }
+
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in bytes {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in bytes {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+// the frame number, coded like an extended UTF-8 code point
+fn write_utf8(writer: &mut BitWriter, value: u64) {
+    let num_bytes = match value {
+        ..0x80 => {
+            writer.write(value, 8);
+            return;
+        }
+        0x80..0x800 => 2,
+        0x800..0x10000 => 3,
+        0x10000..0x200000 => 4,
+        0x200000..0x4000000 => 5,
+        0x4000000..0x80000000 => 6,
+        _ => 7,
+    };
+    let lead = (0xff00 >> num_bytes) & 0xff;
+    writer.write(lead | (value >> (6 * (num_bytes - 1))), 8);
+    for i in (0..num_bytes - 1).rev() {
+        writer.write(0x80 | ((value >> (6 * i)) & 0x3f), 8);
+    }
+}
+
+// the Rice parameters and estimated size of a residual, for one partition order
+struct RicePartitioning {
+    order: u32,
+    params: Vec<u32>,
+    bits: u64,
+}
+
+fn zigzag(residual: i64) -> u64 {
+    ((residual << 1) ^ (residual >> 63)) as u64
+}
+
+fn best_rice_param(num_samples: u64, sum: u64) -> (u32, u64) {
+    let mut best = (0, u64::MAX);
+    for param in 0..=30 {
+        let bits = num_samples * (param as u64 + 1) + (sum >> param);
+        if bits < best.1 {
+            best = (param, bits);
+        }
+    }
+    best
+}
+
+// chooses the partition order and Rice parameters that minimize the size of the residual of a subframe with the given predictor order
+fn partition_residual(
+    residual: &[u64],
+    block_size: usize,
+    predictor_order: usize,
+    max_partition_order: u32,
+) -> RicePartitioning {
+    let mut max_order = 0;
+    while max_order < max_partition_order
+        && block_size.is_multiple_of(1 << (max_order + 1))
+        && (block_size >> (max_order + 1)) > predictor_order
+    {
+        max_order += 1;
+    }
+
+    // the sums of each partition at the finest partition order
+    let num_partitions = 1 << max_order;
+    let partition_len = block_size >> max_order;
+    let mut sums = vec![0u64; num_partitions];
+    let mut counts = vec![partition_len as u64; num_partitions];
+    counts[0] -= predictor_order as u64;
+    for (i, &value) in residual.iter().enumerate() {
+        sums[(i + predictor_order) / partition_len] += value;
+    }
+
+    let mut best: Option<RicePartitioning> = None;
+    let mut order = max_order;
+    loop {
+        let mut params = Vec::with_capacity(sums.len());
+        let mut bits = 0;
+        for (&sum, &count) in sums.iter().zip(&counts) {
+            let (param, param_bits) = best_rice_param(count, sum);
+            params.push(param);
+            bits += param_bits;
+        }
+        let param_len = if params.iter().any(|&p| p > 14) { 5 } else { 4 };
+        bits += 6 + param_len * params.len() as u64;
+
+        if best.as_ref().is_none_or(|best| bits < best.bits) {
+            best = Some(RicePartitioning {
+                order,
+                params,
+                bits,
+            });
+        }
+
+        if order == 0 {
+            break;
+        }
+        order -= 1;
+        sums = sums.chunks(2).map(|pair| pair[0] + pair[1]).collect();
+        counts = counts.chunks(2).map(|pair| pair[0] + pair[1]).collect();
+    }
+
+    best.unwrap()
+}
+
+/// Encodes blocks of integer samples into FLAC frames.
+pub(crate) struct FrameEncoder {
+    settings: FlacSettings,
+    num_channels: usize,
+    frame_number: u64,
+}
+
+impl FrameEncoder {
+    pub(crate) fn new(settings: FlacSettings, num_channels: usize) -> Self {
+        Self {
+            settings,
+            num_channels,
+            frame_number: 0,
+        }
+    }
+
+    /// Encodes one frame containing a block of samples for each channel.
+    pub(crate) fn encode(&mut self, channels: &[Vec<i64>]) -> Vec<u8> {
+        debug_assert_eq!(channels.len(), self.num_channels);
+        let block_size = channels[0].len();
+        let bits_per_sample = self.settings.bits_per_sample;
+
+        let mut writer = BitWriter::default();
+
+        // frame header: sync code, fixed block size strategy
+        writer.write(0b11111111111110, 14);
+        writer.write(0, 1);
+        writer.write(0, 1);
+        // block size stored as 16 bits at the end of the header, sample rate taken from the stream info
+        writer.write(0b0111, 4);
+        writer.write(0b0000, 4);
+        // independent channels
+        writer.write(self.num_channels as u64 - 1, 4);
+        let sample_size_code = match bits_per_sample {
+            8 => 0b001,
+            16 => 0b100,
+            _ => 0b110,
+        };
+        writer.write(sample_size_code, 3);
+        writer.write(0, 1);
+        write_utf8(&mut writer, self.frame_number);
+        writer.write(block_size as u64 - 1, 16);
+        let crc = crc8(&writer.bytes);
+        writer.write(crc as u64, 8);
+
+        for samples in channels {
+            self.encode_subframe(&mut writer, samples);
+        }
+
+        writer.align();
+        let crc = crc16(&writer.bytes);
+        writer.write(crc as u64, 16);
+
+        self.frame_number += 1;
+        writer.bytes
+    }
+
+    fn encode_subframe(&self, writer: &mut BitWriter, samples: &[i64]) {
+        let bits_per_sample = self.settings.bits_per_sample;
+        let block_size = samples.len();
+
+        if samples.iter().all(|&s| s == samples[0]) {
+            // constant subframe
+            writer.write(0b00000000, 8);
+            writer.write_signed(samples[0], bits_per_sample);
+            return;
+        }
+
+        let verbatim_bits = block_size as u64 * bits_per_sample as u64;
+
+        // try each fixed predictor, each of which is the difference of the previous one
+        let mut best: Option<(usize, Vec<u64>, RicePartitioning)> = None;
+        let mut residual = samples.to_vec();
+        let max_order = self.settings.max_fixed_order().min(block_size - 1);
+        for order in 0..=max_order {
+            if order > 0 {
+                residual = residual.windows(2).map(|w| w[1] - w[0]).collect();
+            }
+            let coded: Vec<u64> = residual.iter().map(|&r| zigzag(r)).collect();
+            let partitioning = partition_residual(
+                &coded,
+                block_size,
+                order,
+                self.settings.max_partition_order(),
+            );
+            let bits = order as u64 * bits_per_sample as u64 + partitioning.bits;
+            let best_bits = best
+                .as_ref()
+                .map(|(order, _, p)| *order as u64 * bits_per_sample as u64 + p.bits);
+            if best_bits.is_none_or(|best_bits| bits < best_bits) {
+                best = Some((order, coded, partitioning));
+            }
+        }
+
+        let (order, residual, partitioning) = best.unwrap();
+        if order as u64 * bits_per_sample as u64 + partitioning.bits >= verbatim_bits {
+            // verbatim subframe
+            writer.write(0b00000010, 8);
+            for &sample in samples {
+                writer.write_signed(sample, bits_per_sample);
+            }
+            return;
+        }
+
+        // fixed subframe
+        writer.write((0b001000 | order as u64) << 1, 8);
+        for &sample in &samples[..order] {
+            writer.write_signed(sample, bits_per_sample);
+        }
+
+        let wide_params = partitioning.params.iter().any(|&p| p > 14);
+        let param_len = if wide_params { 5 } else { 4 };
+        writer.write(wide_params as u64, 2);
+        writer.write(partitioning.order as u64, 4);
+
+        let partition_len = block_size >> partitioning.order;
+        let mut start = 0;
+        for (i, &param) in partitioning.params.iter().enumerate() {
+            let end = (i + 1) * partition_len - order;
+            writer.write(param as u64, param_len);
+            for &value in &residual[start..end] {
+                writer.write_unary(value >> param);
+                writer.write(value, param);
+            }
+            start = end;
+        }
+    }
+}
+
+/// Buffers interleaved samples into blocks and encodes them into FLAC frames, keeping track of the stream information.
+pub(crate) struct FlacStream {
+    settings: FlacSettings,
+    sample_rate: u32,
+    encoder: FrameEncoder,
+    buffers: Vec<Vec<i64>>,
+    next_channel: usize,
+    total_samples: u64,
+    min_frame_size: u32,
+    max_frame_size: u32,
+}
+
+impl FlacStream {
+    pub(crate) fn new(
+        num_channels: usize,
+        sample_rate: u32,
+        settings: FlacSettings,
+    ) -> io::Result<Self> {
+        settings.validate()?;
+        if !(1..=8).contains(&num_channels) {
+            return Err(invalid_input(format!(
+                "FLAC supports 1 to 8 channels, got {num_channels}"
+            )));
+        }
+        if sample_rate == 0 || sample_rate >= 1 << 20 {
+            return Err(invalid_input(format!(
+                "unsupported FLAC sample rate: {sample_rate}"
+            )));
+        }
+
+        let block_size = settings.block_size();
+        Ok(Self {
+            settings,
+            sample_rate,
+            encoder: FrameEncoder::new(settings, num_channels),
+            buffers: vec![Vec::with_capacity(block_size); num_channels],
+            next_channel: 0,
+            total_samples: 0,
+            min_frame_size: u32::MAX,
+            max_frame_size: 0,
+        })
+    }
+
+    pub(crate) fn num_channels(&self) -> usize {
+        self.buffers.len()
+    }
+
+    pub(crate) fn total_samples(&self) -> u64 {
+        self.total_samples
+    }
+
+    /// Adds the next interleaved sample, returning an encoded frame if a block was completed.
+    pub(crate) fn push(&mut self, sample: Float) -> Option<Vec<u8>> {
+        let scale = (1i64 << (self.settings.bits_per_sample - 1)) as Float;
+        let quantized = (sample.clamp(-1.0, 1.0) * scale).round() as i64;
+        let quantized = quantized.clamp(-(scale as i64), scale as i64 - 1);
+
+        self.buffers[self.next_channel].push(quantized);
+        self.next_channel = (self.next_channel + 1) % self.num_channels();
+
+        if self.next_channel == 0 && self.buffers[0].len() == self.settings.block_size() {
+            Some(self.encode_block())
+        } else {
+            None
+        }
+    }
+
+    /// Encodes any buffered samples into a final, shorter frame.
+    ///
+    /// Channels that are missing their last sample are padded with silence.
+    pub(crate) fn flush(&mut self) -> Option<Vec<u8>> {
+        if self.buffers[0].is_empty() {
+            return None;
+        }
+        let len = self.buffers[0].len();
+        for buffer in &mut self.buffers {
+            buffer.resize(len, 0);
+        }
+        self.next_channel = 0;
+        Some(self.encode_block())
+    }
+
+    fn encode_block(&mut self) -> Vec<u8> {
+        let frame = self.encoder.encode(&self.buffers);
+        self.total_samples += self.buffers[0].len() as u64;
+        self.min_frame_size = self.min_frame_size.min(frame.len() as u32);
+        self.max_frame_size = self.max_frame_size.max(frame.len() as u32);
+        for buffer in &mut self.buffers {
+            buffer.clear();
+        }
+        frame
+    }
+
+    /// Returns the STREAMINFO metadata block (without its header) describing the frames encoded so far.
+    ///
+    /// Frame sizes and the total number of samples are left as "unknown" if `complete` is false.
+    pub(crate) fn stream_info(&self, complete: bool) -> Vec<u8> {
+        let block_size = self.settings.block_size() as u64;
+        let mut writer = BitWriter::default();
+        writer.write(block_size, 16);
+        writer.write(block_size, 16);
+        if complete && self.max_frame_size > 0 {
+            writer.write(self.min_frame_size as u64, 24);
+            writer.write(self.max_frame_size as u64, 24);
+        } else {
+            writer.write(0, 24);
+            writer.write(0, 24);
+        }
+        writer.write(self.sample_rate as u64, 20);
+        writer.write(self.num_channels() as u64 - 1, 3);
+        writer.write(self.settings.bits_per_sample as u64 - 1, 5);
+        let total_samples = if complete { self.total_samples } else { 0 };
+        writer.write(total_samples >> 32, 4);
+        writer.write(total_samples & 0xffff_ffff, 32);
+        // the MD5 signature of the audio is left unset (all zeros), which decoders take to mean it is unknown
+        writer.bytes.extend_from_slice(&[0; 16]);
+        writer.bytes
+    }
+}
+
+/// Writes a metadata block header for a block of the given type and length.
+pub(crate) fn metadata_block_header(is_last: bool, block_type: u8, len: usize) -> [u8; 4] {
+    let len = len as u32;
+    [
+        ((is_last as u8) << 7) | block_type,
+        (len >> 16) as u8,
+        (len >> 8) as u8,
+        len as u8,
+    ]
+}
+
+/// Writes audio to a FLAC file.
+///
+/// Samples are written interleaved, one at a time, like [`hound::WavWriter`].
+/// The stream information at the start of the file is completed when the writer is finalized.
+/// The MD5 signature of the audio in the stream information is left as all zeros, which the FLAC format defines as "unknown", so decoders skip verifying it.
+/// The writer is finalized automatically when it is dropped, but any errors that occur are then ignored, so [`FlacFileOut::finalize`] should be preferred.
+pub struct FlacFileOut {
+    writer: BufWriter<File>,
+    stream: FlacStream,
+    finalized: bool,
+}
+
+impl FlacFileOut {
+    /// Creates a new FLAC file at the given path and writes its header.
+    pub fn create(
+        path: impl AsRef<Path>,
+        num_channels: usize,
+        sample_rate: u32,
+        settings: FlacSettings,
+    ) -> io::Result<Self> {
+        let stream = FlacStream::new(num_channels, sample_rate, settings)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        let stream_info = stream.stream_info(false);
+        writer.write_all(b"fLaC")?;
+        writer.write_all(&metadata_block_header(true, 0, stream_info.len()))?;
+        writer.write_all(&stream_info)?;
+
+        Ok(Self {
+            writer,
+            stream,
+            finalized: false,
+        })
+    }
+
+    /// Returns the number of channels in the file.
+    pub fn num_channels(&self) -> usize {
+        self.stream.num_channels()
+    }
+
+    /// Returns the number of samples per channel that have been encoded so far.
+    pub fn len(&self) -> u64 {
+        self.stream.total_samples()
+    }
+
+    /// Returns `true` if no samples have been encoded yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the next sample. Samples of different channels are interleaved.
+    ///
+    /// Samples are clamped to the range `[-1.0, 1.0]` before being quantized to the file's bit depth.
+    pub fn write_sample(&mut self, sample: Float) -> io::Result<()> {
+        if let Some(frame) = self.stream.push(sample) {
+            self.writer.write_all(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes any remaining samples, updates the stream information, and flushes the file.
+    pub fn finalize(mut self) -> io::Result<()> {
+        self.finalize_inner()
+    }
+
+    fn finalize_inner(&mut self) -> io::Result<()> {
+        if self.finalized {
+            return Ok(());
+        }
+        self.finalized = true;
+
+        if let Some(frame) = self.stream.flush() {
+            self.writer.write_all(&frame)?;
+        }
+
+        // rewrite the stream info now that the frame sizes and length are known
+        self.writer.seek(SeekFrom::Start(8))?;
+        self.writer.write_all(&self.stream.stream_info(true))?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()
+    }
+}
+
+impl Drop for FlacFileOut {
+    fn drop(&mut self) {
+        let _ = self.finalize_inner();
+    }
+}
@@ -0,0 +1,13 @@
+//! Writers for compressed audio file formats, and normalization of offline renders.
+//!
+//! The writers complement the 32-bit float WAV files written by [`Runtime::run_offline_to_file`](crate::runtime::Runtime::run_offline_to_file), which quickly grow very large for long captures.
+
+pub mod flac;
+pub mod normalize;
+#[cfg(feature = "ogg")]
+pub mod ogg;
+
+#[cfg(feature = "ogg")]
+pub use self::ogg::{OggFileOut, OggFlacFileOut, VorbisSettings};
+pub use flac::{FlacFileOut, FlacSettings};
+pub use normalize::Normalization;
@@ -0,0 +1,96 @@
+//! Normalization of offline renders.
+
+use crate::{
+    builtins::{
+        dynamics::{amp_to_db, db_to_amp},
+        tap::TRUE_PEAK_TAPS,
+        LoudnessMeter, TruePeak,
+    },
+    processor::Processor,
+    signal::Float,
+};
+
+/// How to normalize the level of an offline render, so it doesn't come out clipped or too quiet.
+///
+/// Silent renders are never amplified.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Normalization {
+    /// Scale the render so its highest sample peak is at the given level, in dBFS.
+    Peak(Float),
+    /// Scale the render so its integrated loudness (see [`LoudnessMeter`]) is at the given level, in LUFS.
+    ///
+    /// The gain is reduced if needed so the render's true peak (see [`TruePeak`]) stays at or below the given ceiling, in dBTP.
+    Loudness {
+        /// The target integrated loudness, in LUFS.
+        target: Float,
+        /// The highest allowed true peak, in dBTP.
+        ceiling: Float,
+    },
+}
+
+impl Normalization {
+    /// Returns the linear gain that normalizes the given channels.
+    pub fn gain(&self, channels: &[Box<[Float]>], sample_rate: Float) -> Float {
+        match *self {
+            Normalization::Peak(target) => {
+                let peak = channels
+                    .iter()
+                    .flat_map(|channel| channel.iter())
+                    .fold(0.0, |peak: Float, x| peak.max(x.abs()));
+                if peak == 0.0 {
+                    return 1.0;
+                }
+                db_to_amp(target) / peak
+            }
+            Normalization::Loudness { target, ceiling } => {
+                let Some(loudness) = integrated_loudness(channels, sample_rate) else {
+                    return 1.0;
+                };
+                let gain = db_to_amp(target - loudness);
+                // a render loud enough to measure always has a peak
+                gain.min(db_to_amp(ceiling) / true_peak(channels))
+            }
+        }
+    }
+
+    /// Scales the given channels in place, returning the linear gain that was applied.
+    pub fn apply(&self, channels: &mut [Box<[Float]>], sample_rate: Float) -> Float {
+        let gain = self.gain(channels, sample_rate);
+        for x in channels.iter_mut().flat_map(|channel| channel.iter_mut()) {
+            *x *= gain;
+        }
+        log::debug!("Normalized render by {:.2} dB", amp_to_db(gain));
+        gain
+    }
+}
+
+// the integrated loudness of the channels in LUFS, or `None` if no part of them is above the absolute gate
+fn integrated_loudness(channels: &[Box<[Float]>], sample_rate: Float) -> Option<Float> {
+    let mut meter = LoudnessMeter::new(channels.len());
+    meter.resize_buffers(sample_rate, 0);
+    let len = channels.iter().map(|channel| channel.len()).min()?;
+    for i in 0..len {
+        meter.measure(channels.iter().map(|channel| channel[i]));
+    }
+    let loudness = meter.integrated();
+    // the meter reports about -200 LUFS until a block passes the absolute gate at -70 LUFS
+    (loudness > -100.0).then_some(loudness)
+}
+
+// the highest true peak of the channels, as a linear amplitude
+fn true_peak(channels: &[Box<[Float]>]) -> Float {
+    let mut peak: Float = 0.0;
+    for channel in channels {
+        let mut meter = TruePeak::new();
+        // pad with silence, so the interpolation reaches the end of the channel
+        let samples = channel
+            .iter()
+            .copied()
+            .chain(std::iter::repeat_n(0.0, TRUE_PEAK_TAPS));
+        for x in samples {
+            peak = peak.max(meter.true_peak(x));
+        }
+    }
+    peak
+}
@@ -0,0 +1,343 @@
+//! Encoders for Vorbis and FLAC streams in an Ogg container.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    num::{NonZeroU32, NonZeroU8},
+    path::Path,
+};
+
+use ::ogg::{PacketWriteEndInfo, PacketWriter};
+use vorbis_rs::{VorbisBitrateManagementStrategy, VorbisEncoder, VorbisEncoderBuilder};
+
+use crate::signal::Float;
+
+use super::flac::{metadata_block_header, FlacSettings, FlacStream};
+
+const VENDOR: &str = concat!("raug ", env!("CARGO_PKG_VERSION"));
+
+// the number of samples per channel handed to the Vorbis encoder at once, as recommended by libvorbis
+const VORBIS_BLOCK_SIZE: usize = 1024;
+
+/// Options for encoding Vorbis streams.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VorbisSettings {
+    /// The quality of the encoded audio, from `-0.1` (smallest files) to `1.0` (best quality).
+    ///
+    /// The bitrate varies with the audio to keep the quality constant. This is the `-q` option of `oggenc`, divided by 10.
+    pub quality: f32,
+}
+
+impl Default for VorbisSettings {
+    fn default() -> Self {
+        Self { quality: 0.5 }
+    }
+}
+
+impl VorbisSettings {
+    /// Creates new [`VorbisSettings`] with the given quality.
+    pub fn new(quality: f32) -> Self {
+        Self { quality }
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        if !(-0.1..=1.0).contains(&self.quality) {
+            return Err(invalid_input(format!(
+                "Vorbis quality must be between -0.1 and 1.0, got {}",
+                self.quality
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn vorbis_error(err: vorbis_rs::VorbisError) -> io::Error {
+    io::Error::other(err)
+}
+
+/// Writes audio to an Ogg Vorbis file.
+///
+/// Vorbis is a lossy format, so files are much smaller than FLAC at the cost of some fidelity, which is set by the [`VorbisSettings`].
+/// For lossless audio in an Ogg container, use an [`OggFlacFileOut`] instead.
+///
+/// Samples are written interleaved, one at a time, like [`hound::WavWriter`].
+/// The writer is finalized automatically when it is dropped, but any errors that occur are then ignored, so [`OggFileOut::finalize`] should be preferred.
+pub struct OggFileOut {
+    // taken when the writer is finalized
+    encoder: Option<VorbisEncoder<BufWriter<File>>>,
+    // the samples of each channel waiting to be encoded
+    buffers: Vec<Vec<f32>>,
+    next_channel: usize,
+    len: u64,
+}
+
+impl OggFileOut {
+    /// Creates a new Ogg Vorbis file at the given path and writes its headers.
+    pub fn create(
+        path: impl AsRef<Path>,
+        num_channels: usize,
+        sample_rate: u32,
+        settings: VorbisSettings,
+    ) -> io::Result<Self> {
+        settings.validate()?;
+        let channels = u8::try_from(num_channels)
+            .ok()
+            .and_then(NonZeroU8::new)
+            .ok_or_else(|| {
+                invalid_input(format!(
+                    "Vorbis streams must have between 1 and 255 channels, got {num_channels}"
+                ))
+            })?;
+        let sample_rate = NonZeroU32::new(sample_rate)
+            .ok_or_else(|| invalid_input("sample rate must not be zero".to_string()))?;
+
+        let writer = BufWriter::new(File::create(path)?);
+        let mut builder =
+            VorbisEncoderBuilder::new_with_serial(sample_rate, channels, writer, rand::random());
+        builder.bitrate_management_strategy(VorbisBitrateManagementStrategy::QualityVbr {
+            target_quality: settings.quality,
+        });
+        builder
+            .comment_tag("ENCODER", VENDOR)
+            .map_err(vorbis_error)?;
+        let encoder = builder.build().map_err(vorbis_error)?;
+
+        Ok(Self {
+            encoder: Some(encoder),
+            buffers: vec![Vec::with_capacity(VORBIS_BLOCK_SIZE); num_channels],
+            next_channel: 0,
+            len: 0,
+        })
+    }
+
+    /// Returns the number of channels in the file.
+    pub fn num_channels(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns the number of samples per channel that have been written so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no samples have been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the next sample. Samples of different channels are interleaved.
+    ///
+    /// Samples are clamped to the range `[-1.0, 1.0]`.
+    pub fn write_sample(&mut self, sample: Float) -> io::Result<()> {
+        self.buffers[self.next_channel].push(sample.clamp(-1.0, 1.0) as f32);
+        self.next_channel = (self.next_channel + 1) % self.num_channels();
+
+        if self.next_channel == 0 {
+            self.len += 1;
+            if self.buffers[0].len() == VORBIS_BLOCK_SIZE {
+                self.encode_block()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes any remaining samples, marks the end of the stream, and flushes the file.
+    ///
+    /// Channels that are missing their last sample are padded with silence.
+    pub fn finalize(mut self) -> io::Result<()> {
+        self.finalize_inner()
+    }
+
+    fn encode_block(&mut self) -> io::Result<()> {
+        if let Some(encoder) = &mut self.encoder {
+            encoder
+                .encode_audio_block(&self.buffers)
+                .map_err(vorbis_error)?;
+        }
+        for buffer in &mut self.buffers {
+            buffer.clear();
+        }
+        Ok(())
+    }
+
+    fn finalize_inner(&mut self) -> io::Result<()> {
+        let Some(mut encoder) = self.encoder.take() else {
+            return Ok(());
+        };
+
+        let len = self.buffers[0].len();
+        if len > 0 {
+            for buffer in &mut self.buffers {
+                buffer.resize(len, 0.0);
+            }
+            encoder
+                .encode_audio_block(&self.buffers)
+                .map_err(vorbis_error)?;
+        }
+
+        encoder.finish().map_err(vorbis_error)?.flush()
+    }
+}
+
+impl Drop for OggFileOut {
+    fn drop(&mut self) {
+        let _ = self.finalize_inner();
+    }
+}
+
+/// Writes audio to an Ogg FLAC file.
+///
+/// The audio is stored losslessly as FLAC frames inside the Ogg container (commonly given the `.oga` extension), using the same [`FlacSettings`] as [`FlacFileOut`](super::FlacFileOut).
+/// Unlike a native FLAC file, the length of the stream is only recorded in the Ogg pages, so the file can be written without seeking.
+/// As in a native FLAC file, the MD5 signature of the audio is left as all zeros ("unknown").
+///
+/// Samples are written interleaved, one at a time, like [`hound::WavWriter`].
+/// The writer is finalized automatically when it is dropped, but any errors that occur are then ignored, so [`OggFlacFileOut::finalize`] should be preferred.
+pub struct OggFlacFileOut {
+    writer: PacketWriter<BufWriter<File>>,
+    serial: u32,
+    stream: FlacStream,
+    // the last encoded frame, held back so the end of the stream can be marked on it
+    pending: Option<Vec<u8>>,
+    pending_end: u64,
+    finalized: bool,
+}
+
+impl OggFlacFileOut {
+    /// Creates a new Ogg FLAC file at the given path and writes its headers.
+    pub fn create(
+        path: impl AsRef<Path>,
+        num_channels: usize,
+        sample_rate: u32,
+        settings: FlacSettings,
+    ) -> io::Result<Self> {
+        let stream = FlacStream::new(num_channels, sample_rate, settings)?;
+        let mut writer = PacketWriter::new(BufWriter::new(File::create(path)?));
+        let serial = rand::random();
+
+        // the first page holds the mapping header and the stream info
+        let stream_info = stream.stream_info(false);
+        let mut header = vec![0x7f];
+        header.extend_from_slice(b"FLAC");
+        // mapping version 1.0, followed by one more header packet
+        header.extend_from_slice(&[1, 0]);
+        header.extend_from_slice(&1u16.to_be_bytes());
+        header.extend_from_slice(b"fLaC");
+        header.extend_from_slice(&metadata_block_header(false, 0, stream_info.len()));
+        header.extend_from_slice(&stream_info);
+        writer.write_packet(
+            header.into_boxed_slice(),
+            serial,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+
+        // the mapping requires a comment block before the audio
+        let mut comment = vec![];
+        comment.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+        comment.extend_from_slice(VENDOR.as_bytes());
+        comment.extend_from_slice(&0u32.to_le_bytes());
+        let mut packet = metadata_block_header(true, 4, comment.len()).to_vec();
+        packet.extend_from_slice(&comment);
+        writer.write_packet(
+            packet.into_boxed_slice(),
+            serial,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+
+        Ok(Self {
+            writer,
+            serial,
+            stream,
+            pending: None,
+            pending_end: 0,
+            finalized: false,
+        })
+    }
+
+    /// Returns the number of channels in the file.
+    pub fn num_channels(&self) -> usize {
+        self.stream.num_channels()
+    }
+
+    /// Returns the number of samples per channel that have been encoded so far.
+    pub fn len(&self) -> u64 {
+        self.stream.total_samples()
+    }
+
+    /// Returns `true` if no samples have been encoded yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the next sample. Samples of different channels are interleaved.
+    ///
+    /// Samples are clamped to the range `[-1.0, 1.0]` before being quantized to the file's bit depth.
+    pub fn write_sample(&mut self, sample: Float) -> io::Result<()> {
+        if let Some(frame) = self.stream.push(sample) {
+            self.write_frame(frame, PacketWriteEndInfo::NormalPacket)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes any remaining samples, marks the end of the stream, and flushes the file.
+    pub fn finalize(mut self) -> io::Result<()> {
+        self.finalize_inner()
+    }
+
+    fn write_frame(&mut self, frame: Vec<u8>, end: PacketWriteEndInfo) -> io::Result<()> {
+        if let Some(pending) = self.pending.take() {
+            self.writer.write_packet(
+                pending.into_boxed_slice(),
+                self.serial,
+                PacketWriteEndInfo::NormalPacket,
+                self.pending_end,
+            )?;
+        }
+
+        if end == PacketWriteEndInfo::EndStream {
+            self.writer.write_packet(
+                frame.into_boxed_slice(),
+                self.serial,
+                end,
+                self.stream.total_samples(),
+            )?;
+        } else {
+            self.pending = Some(frame);
+            self.pending_end = self.stream.total_samples();
+        }
+        Ok(())
+    }
+
+    fn finalize_inner(&mut self) -> io::Result<()> {
+        if self.finalized {
+            return Ok(());
+        }
+        self.finalized = true;
+
+        if let Some(frame) = self.stream.flush() {
+            self.write_frame(frame, PacketWriteEndInfo::EndStream)?;
+        } else if let Some(pending) = self.pending.take() {
+            self.writer.write_packet(
+                pending.into_boxed_slice(),
+                self.serial,
+                PacketWriteEndInfo::EndStream,
+                self.pending_end,
+            )?;
+        }
+
+        self.writer.inner_mut().flush()
+    }
+}
+
+impl Drop for OggFlacFileOut {
+    fn drop(&mut self) {
+        let _ = self.finalize_inner();
+    }
+}
@@ -19,6 +19,7 @@ use crate::{fft::FftError, prelude::*};
 /// | `0` | `out` | `ComplexBuf` | The output signal. |
 #[derive(Clone)]
 pub struct Rfft {
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
     padded_length: usize,
     plan: Arc<dyn realfft::RealToComplex<Float>>,
     scratch: ComplexBuf,
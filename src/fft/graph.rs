@@ -425,7 +425,7 @@ impl FftGraph {
             }
         }
 
-        let inputs: smallvec::SmallVec<[_; 4]> = inputs.into_iter().map(|(_, v)| v).collect();
+        let inputs: smallvec::SmallVec<[_; 4]> = inputs.into_values().collect();
 
         self.digraph[node_id].process(self.fft_length, &inputs, &mut outputs)?;
 
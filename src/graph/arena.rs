@@ -0,0 +1,135 @@
+//! Contains the `BufferArena` struct, which stores the [`Float`] output buffers of a graph's nodes in a single allocation.
+
+use std::{ptr::NonNull, sync::Arc};
+
+use crate::signal::Float;
+
+// the number of samples in a cache line
+const LINE_SAMPLES: usize = 64 / std::mem::size_of::<Option<Float>>();
+
+// a cache line worth of samples, so every slice of the arena starts on a cache line boundary
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+struct Line([Option<Float>; LINE_SAMPLES]);
+
+// the memory of an arena, which stays allocated for as long as any buffer uses a slice of it
+pub(crate) struct ArenaMemory {
+    lines: NonNull<[Line]>,
+}
+
+// SAFETY: the memory is only accessed through the slices handed out by the arena, each of which is used by a single buffer
+unsafe impl Send for ArenaMemory {}
+// SAFETY: shared references to the memory don't give access to its contents; only the buffers using its slices do
+unsafe impl Sync for ArenaMemory {}
+
+impl Drop for ArenaMemory {
+    fn drop(&mut self) {
+        // SAFETY: the lines were leaked from a box in `BufferArena::new`, and every buffer using them holds a reference to this memory, so none are left
+        drop(unsafe { Box::from_raw(self.lines.as_ptr()) });
+    }
+}
+
+// a single allocation holding a number of equally sized, cache-line aligned slices of `Float` signals
+//
+// the runtime binds the float output buffers of its nodes to slices of an arena in processing order,
+// so the buffers of nodes processed one after another sit next to each other in memory,
+// and growing the buffers past the arena's capacity replaces the whole arena in one allocation
+pub(crate) struct BufferArena {
+    memory: Arc<ArenaMemory>,
+    // the length of each slice in samples, a multiple of `LINE_SAMPLES`
+    stride: usize,
+    count: usize,
+}
+
+impl BufferArena {
+    // allocates an arena with `count` slices of at least `capacity` signals each, all `None`
+    pub(crate) fn new(count: usize, capacity: usize) -> Self {
+        let stride = capacity.div_ceil(LINE_SAMPLES).max(1) * LINE_SAMPLES;
+        let lines = vec![Line([None; LINE_SAMPLES]); count * stride / LINE_SAMPLES];
+        let lines = NonNull::from(Box::leak(lines.into_boxed_slice()));
+        Self {
+            memory: Arc::new(ArenaMemory { lines }),
+            stride,
+            count,
+        }
+    }
+
+    // the number of signals in each slice
+    pub(crate) fn capacity(&self) -> usize {
+        self.stride
+    }
+
+    // returns a pointer to the first of the `capacity()` signals in the given slice, along with the memory it belongs to
+    pub(crate) fn slice(&self, index: usize) -> (NonNull<Option<Float>>, Arc<ArenaMemory>) {
+        assert!(index < self.count, "arena slice index out of bounds");
+        let base = self.memory.lines.cast::<Option<Float>>();
+        // SAFETY: the lines are contiguous, so the arena holds `count * stride` signals
+        let ptr = unsafe { base.add(index * self.stride) };
+        (ptr, self.memory.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::{AnySignal, Buffer, SignalBuffer};
+
+    fn bound_buffers(arena: &BufferArena, count: usize) -> Vec<SignalBuffer> {
+        (0..count)
+            .map(|index| {
+                let mut buffer = Buffer::zeros(arena.capacity());
+                // SAFETY: each buffer gets its own slice of the arena
+                unsafe { buffer.bind_to_arena(arena, index) };
+                SignalBuffer::Float(buffer)
+            })
+            .collect()
+    }
+
+    fn samples(buffer: &SignalBuffer) -> &[Option<Float>] {
+        buffer.as_type::<Float>().unwrap()
+    }
+
+    #[test]
+    fn slices_do_not_alias() {
+        let arena = BufferArena::new(3, 10);
+        let mut buffers = bound_buffers(&arena, 3);
+
+        for (index, buffer) in buffers.iter_mut().enumerate() {
+            buffer.fill(AnySignal::Float(Some(index as Float)));
+        }
+        for (index, buffer) in buffers.iter().enumerate() {
+            assert!(samples(buffer)
+                .iter()
+                .all(|&sample| sample == Some(index as Float)));
+        }
+
+        // shrinking and growing back within the slice keeps the buffers apart
+        buffers[1].resize(4, AnySignal::Float(None));
+        buffers[1].resize(arena.capacity(), AnySignal::Float(Some(-1.0)));
+        assert!(samples(&buffers[0])
+            .iter()
+            .all(|&sample| sample == Some(0.0)));
+        assert!(samples(&buffers[2])
+            .iter()
+            .all(|&sample| sample == Some(2.0)));
+        assert_eq!(samples(&buffers[1])[..4], [Some(1.0); 4]);
+        assert!(samples(&buffers[1])[4..]
+            .iter()
+            .all(|&sample| sample == Some(-1.0)));
+
+        // clones own their samples
+        let mut clone = buffers[0].clone();
+        clone.fill(AnySignal::Float(Some(5.0)));
+        assert!(samples(&buffers[0])
+            .iter()
+            .all(|&sample| sample == Some(0.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity of its arena slice")]
+    fn growing_past_the_slice_panics() {
+        let arena = BufferArena::new(1, 10);
+        let mut buffers = bound_buffers(&arena, 1);
+        buffers[0].resize(arena.capacity() + 1, AnySignal::Float(None));
+    }
+}
@@ -51,7 +51,7 @@ impl Assets {
         }
     }
 
-    pub fn get(&self, name: &str) -> Option<AssetRef> {
+    pub fn get(&self, name: &str) -> Option<AssetRef<'_>> {
         self.assets.get(name).map(AssetRef)
     }
 
@@ -5,19 +5,25 @@ use edge::Edge;
 use node::ProcessorNode;
 use petgraph::{
     prelude::{Direction, EdgeRef, StableDiGraph},
-    visit::DfsPostOrder,
+    stable_graph::EdgeReference,
+    visit::{DfsPostOrder, EdgeFiltered},
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
-    prelude::{Null, Param, Passthrough},
-    processor::{Processor, ProcessorError},
-    signal::{Float, MidiMessage, SignalType},
+    prelude::{Constant, Null, Param, Passthrough, TapReader, TruePeak},
+    processor::{ProcessMode, Processor, ProcessorError, ProcessorInputs, ProcessorOutputs},
+    signal::{AnySignal, Float, MidiMessage, SignalBuffer, SignalType},
+    transport::Transport,
 };
 
+pub(crate) mod arena;
 pub mod asset;
 pub mod edge;
 pub mod node;
+#[cfg(feature = "profiling")]
+pub mod profile;
+pub(crate) mod schedule;
 
 /// The type of graph indices.
 pub type GraphIx = u32;
@@ -65,9 +71,76 @@ pub enum GraphConstructionError {
         signal_type: String,
     },
 
+    /// The graph has no node with the given index.
+    #[error("Node not found: {0:?}")]
+    NodeNotFound(NodeIndex),
+
+    /// Attempted to use an input that the node doesn't have.
+    #[error("Node `{node}` has no input `{input}`")]
+    InputNotFound {
+        /// The name of the node.
+        node: String,
+        /// The index or name of the input.
+        input: String,
+    },
+
+    /// Attempted to use an output that the node doesn't have.
+    #[error("Node `{node}` has no output `{output}`")]
+    OutputNotFound {
+        /// The name of the node.
+        node: String,
+        /// The index or name of the output.
+        output: String,
+    },
+
+    /// Attempted to connect an output to an input of an incompatible signal type.
+    #[error("Cannot connect output `{source_output}` ({source_type:?}) of node `{source_node}` to input `{target_input}` ({target_type:?}) of node `{target_node}`")]
+    IncompatibleSignalTypes {
+        /// The name of the source node.
+        source_node: String,
+        /// The name of the source node's output.
+        source_output: String,
+        /// The signal type of the source node's output.
+        source_type: SignalType,
+        /// The name of the target node.
+        target_node: String,
+        /// The name of the target node's input.
+        target_input: String,
+        /// The signal type of the target node's input.
+        target_type: SignalType,
+    },
+
     /// Filesystem error.
     #[error("Filesystem error: {0}")]
     FilesystemError(#[from] std::io::Error),
+
+    /// An error occurred while serializing or deserializing a graph.
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    /// The file format could not be determined from the file extension.
+    #[error("Unknown graph file format: {0}")]
+    UnknownFileFormat(String),
+}
+
+// derives a distinct seed for the given index (e.g. of a node) from the given seed
+#[inline]
+pub(crate) fn derive_seed(seed: u64, index: u64) -> u64 {
+    seed ^ index.wrapping_add(1).wrapping_mul(0x9E3779B97F4A7C15)
+}
+
+// whether two constants output the same value, comparing floats bit for bit so `0.0` and `-0.0` stay apart
+fn same_constant_value(a: &AnySignal, b: &AnySignal) -> bool {
+    match (a, b) {
+        (AnySignal::Float(Some(a)), AnySignal::Float(Some(b))) => a.to_bits() == b.to_bits(),
+        _ => a == b,
+    }
+}
+
+// whether the processing order has to respect the given edge, which is not the case for edges into processors that read their inputs from the previous block
+#[inline]
+pub(crate) fn is_scheduled_edge(digraph: &DiGraph, edge: EdgeReference<'_, Edge, GraphIx>) -> bool {
+    !digraph[edge.target()].processor().reads_previous_block()
 }
 
 /// A result type for graph run operations.
@@ -76,6 +149,42 @@ pub type GraphRunResult<T> = Result<T, GraphRunError>;
 /// A result type for graph construction operations.
 pub type GraphConstructionResult<T> = Result<T, GraphConstructionError>;
 
+// checks that the given output of one node can be connected to the given input of another, returning the names of the output and input
+fn check_connection(
+    source_node: &ProcessorNode,
+    source_output: u32,
+    target_node: &ProcessorNode,
+    target_input: u32,
+) -> GraphConstructionResult<(String, String)> {
+    let Some(source_spec) = source_node.output_spec().get(source_output as usize) else {
+        return Err(GraphConstructionError::OutputNotFound {
+            node: source_node.name().to_string(),
+            output: source_output.to_string(),
+        });
+    };
+    let Some(target_spec) = target_node.input_spec().get(target_input as usize) else {
+        return Err(GraphConstructionError::InputNotFound {
+            node: target_node.name().to_string(),
+            input: target_input.to_string(),
+        });
+    };
+    if !source_spec
+        .signal_type
+        .is_compatible_with(&target_spec.signal_type)
+    {
+        return Err(GraphConstructionError::IncompatibleSignalTypes {
+            source_node: source_node.name().to_string(),
+            source_output: source_spec.name.clone(),
+            source_type: source_spec.signal_type,
+            target_node: target_node.name().to_string(),
+            target_input: target_spec.name.clone(),
+            target_type: target_spec.signal_type,
+        });
+    }
+
+    Ok((source_spec.name.clone(), target_spec.name.clone()))
+}
+
 /// A directed graph of [`Processor`]s connected by [`Edge`]s.
 #[derive(Default, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -95,13 +204,29 @@ pub struct Graph {
     input_nodes: Vec<NodeIndex>,
     output_nodes: Vec<NodeIndex>,
 
+    // names of the audio inputs/outputs, in the same order as the nodes above
+    #[cfg_attr(feature = "serde", serde(default))]
+    input_names: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    output_names: Vec<String>,
+
     // cached visitor state for graph traversal
     #[cfg_attr(feature = "serde", serde(skip))]
     visitor: DfsPostOrder<NodeIndex, FxHashSet<NodeIndex>>,
     visit_path: Vec<NodeIndex>,
 
-    // cached strongly connected components (feedback loops)
-    sccs: Vec<Vec<NodeIndex>>,
+    // cached processing order and input tables, rebuilt when the structure of the graph changes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) schedule: schedule::Schedule,
+
+    // the musical clock shared with the runtime
+    #[cfg_attr(feature = "serde", serde(skip))]
+    transport: Transport,
+
+    // cached schedule for processing independent nodes concurrently
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) parallel_schedule: schedule::ParallelSchedule,
 }
 
 impl Graph {
@@ -128,24 +253,54 @@ impl Graph {
         &self.assets
     }
 
+    /// Returns the graph's [`Transport`].
+    ///
+    /// Clones of the graph share the same transport.
+    #[inline]
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
     /// Adds an asset to the graph.
     pub fn add_asset(&mut self, name: impl Into<String>, asset: Asset) {
         self.assets.insert(name.into(), asset);
     }
 
     /// Adds an audio input node to the graph.
+    ///
+    /// The input is named after its index.
     pub fn add_audio_input(&mut self) -> NodeIndex {
+        let name = self.input_nodes.len().to_string();
+        self.add_named_audio_input(name)
+    }
+
+    /// Adds an audio input node with the given name to the graph.
+    ///
+    /// The name is used for the corresponding input when the graph is wrapped in a [`SubGraph`](crate::builtins::SubGraph).
+    pub fn add_named_audio_input(&mut self, name: impl Into<String>) -> NodeIndex {
         let idx = self.digraph.add_node(ProcessorNode::new(Null));
         self.input_nodes.push(idx);
+        self.input_names.push(name.into());
         idx
     }
 
     /// Adds an audio output node to the graph.
+    ///
+    /// The output is named after its index.
     pub fn add_audio_output(&mut self) -> NodeIndex {
+        let name = self.output_nodes.len().to_string();
+        self.add_named_audio_output(name)
+    }
+
+    /// Adds an audio output node with the given name to the graph.
+    ///
+    /// The name is used for the corresponding output when the graph is wrapped in a [`SubGraph`](crate::builtins::SubGraph).
+    pub fn add_named_audio_output(&mut self, name: impl Into<String>) -> NodeIndex {
         let idx = self
             .digraph
             .add_node(ProcessorNode::new(Passthrough::new(SignalType::Float)));
         self.output_nodes.push(idx);
+        self.output_names.push(name.into());
         idx
     }
 
@@ -170,11 +325,28 @@ impl Graph {
         index
     }
 
+    /// Attaches a [`TruePeak`] meter to each audio output of the graph, for monitoring the levels and clipping of everything the graph outputs.
+    ///
+    /// Returns the meters' readers, in the same order as the audio outputs.
+    pub fn monitor_outputs(&mut self) -> Vec<TapReader> {
+        let mut readers = Vec::with_capacity(self.output_nodes.len());
+        for i in 0..self.output_nodes.len() {
+            let meter = TruePeak::new();
+            // the reader of a new meter hasn't been taken yet
+            readers.push(meter.reader().unwrap());
+            let meter = self.add_processor(meter);
+            self.connect(self.output_nodes[i], 0, meter, 0).unwrap();
+        }
+        readers
+    }
+
     /// Connects two nodes in the graph.
     ///
     /// If the edge already exists, this function does nothing.
     ///
     /// If the target node already has an incoming edge at the target input, the existing edge is removed.
+    ///
+    /// Returns an error if either node isn't in the graph, if either node doesn't have the given output or input, or if their signal types are incompatible.
     pub fn connect(
         &mut self,
         source: NodeIndex,
@@ -182,6 +354,17 @@ impl Graph {
         target: NodeIndex,
         target_input: u32,
     ) -> Result<(), GraphConstructionError> {
+        let source_node = self
+            .digraph
+            .node_weight(source)
+            .ok_or(GraphConstructionError::NodeNotFound(source))?;
+        let target_node = self
+            .digraph
+            .node_weight(target)
+            .ok_or(GraphConstructionError::NodeNotFound(target))?;
+        let (source_output_name, target_input_name) =
+            check_connection(source_node, source_output, target_node, target_input)?;
+
         // check if there's already a connection to the target input
         if let Some(edge) = self
             .digraph
@@ -192,14 +375,6 @@ impl Graph {
             self.digraph.remove_edge(edge.id()).unwrap();
         }
 
-        let source_output_name = self.digraph[source].output_spec()[source_output as usize]
-            .name
-            .clone();
-
-        let target_input_name = self.digraph[target].input_spec()[target_input as usize]
-            .name
-            .clone();
-
         self.digraph.add_edge(
             source,
             target,
@@ -218,6 +393,45 @@ impl Graph {
         Ok(())
     }
 
+    // adds a node and connects the given output to its first input, without adding the node if they can't be connected
+    pub(crate) fn add_connected(
+        &mut self,
+        source: NodeIndex,
+        source_output: u32,
+        node: ProcessorNode,
+    ) -> Result<NodeIndex, GraphConstructionError> {
+        let source_node = self
+            .digraph
+            .node_weight(source)
+            .ok_or(GraphConstructionError::NodeNotFound(source))?;
+        check_connection(source_node, source_output, &node, 0)?;
+
+        let index = self.digraph.add_node(node);
+        self.connect(source, source_output, index, 0)?;
+        Ok(index)
+    }
+
+    // adds the nodes in order, connecting the first output of each one to the first input of the next, and returns the last one
+    // none of the nodes are added if any of them can't be connected
+    pub(crate) fn add_chain(
+        &mut self,
+        nodes: Vec<ProcessorNode>,
+    ) -> Result<Option<NodeIndex>, GraphConstructionError> {
+        for pair in nodes.windows(2) {
+            check_connection(&pair[0], 0, &pair[1], 0)?;
+        }
+
+        let mut last = None;
+        for node in nodes {
+            let index = self.digraph.add_node(node);
+            if let Some(last) = last {
+                self.connect(last, 0, index, 0)?;
+            }
+            last = Some(index);
+        }
+        Ok(last)
+    }
+
     /// Disconnects two nodes in the graph at the specified input and output indices.
     ///
     /// Does nothing if the edge does not exist.
@@ -279,6 +493,294 @@ impl Graph {
         self.disconnect_all_outputs(node);
     }
 
+    /// Removes every node whose signals can't reach an audio output or a tap (see [`Processor::is_tap`]), such as nodes left behind after being disconnected.
+    ///
+    /// Audio inputs, parameters and MIDI inputs are always kept. Returns the removed nodes along with the indices they had.
+    /// The indices of the remaining nodes are unchanged.
+    pub fn garbage_collect(&mut self) -> Vec<(NodeIndex, ProcessorNode)> {
+        let mut reachable = FxHashSet::default();
+        let mut stack = self
+            .digraph
+            .node_indices()
+            .filter(|&node| {
+                let processor = self.digraph[node].processor();
+                self.output_nodes.contains(&node)
+                    || processor.num_outputs() == 0
+                    || processor.is_tap()
+            })
+            .collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if reachable.insert(node) {
+                stack.extend(self.digraph.neighbors_directed(node, Direction::Incoming));
+            }
+        }
+
+        let garbage = self
+            .digraph
+            .node_indices()
+            .filter(|node| {
+                !reachable.contains(node)
+                    && !self.input_nodes.contains(node)
+                    && !self.params.values().any(|param| param == node)
+                    && !self.midi_params.contains(node)
+            })
+            .collect::<Vec<_>>();
+        if garbage.is_empty() {
+            return vec![];
+        }
+
+        let removed = garbage
+            .into_iter()
+            .map(|node| (node, self.digraph.remove_node(node).unwrap()))
+            .collect::<Vec<_>>();
+        log::debug!("Garbage collected {} nodes", removed.len());
+
+        self.reset_visitor();
+        self.detect_sccs();
+
+        removed
+    }
+
+    /// Simplifies the graph without changing what it outputs.
+    ///
+    /// - [`Passthrough`] nodes are removed, and the nodes they fed are connected to their source instead. Audio outputs are kept.
+    /// - Pure processors (see [`Processor::is_pure`]) whose inputs are all connected to [`Constant`]s are evaluated once and replaced with constants, so chains of constant math collapse into a single constant.
+    /// - Constants that output the same value are merged into one.
+    ///
+    /// Returns the removed nodes along with the indices they had. The indices of the remaining nodes are unchanged,
+    /// and the constants added by folding never take the index of a removed node.
+    pub fn optimize(&mut self) -> Vec<(NodeIndex, ProcessorNode)> {
+        let mut removed = vec![];
+        // nodes to remove once folding has added all of its constants, so the constants can't reuse their indices
+        let mut detached = vec![];
+
+        let passthroughs = self
+            .digraph
+            .node_indices()
+            .filter(|node| {
+                self.digraph[*node]
+                    .processor()
+                    .downcast_ref::<Passthrough>()
+                    .is_some()
+                    && !self.output_nodes.contains(node)
+            })
+            .collect::<Vec<_>>();
+        for node in passthroughs {
+            let Some(input) = self
+                .digraph
+                .edges_directed(node, Direction::Incoming)
+                .next()
+                .map(|edge| (edge.source(), edge.weight().clone()))
+            else {
+                continue;
+            };
+            let (source, input) = input;
+            if source == node {
+                continue;
+            }
+            let outgoing = self
+                .digraph
+                .edges_directed(node, Direction::Outgoing)
+                .map(|edge| (edge.target(), edge.weight().clone()))
+                .collect::<Vec<_>>();
+            if outgoing.iter().any(|(target, _)| *target == node) {
+                continue;
+            }
+            for (target, edge) in outgoing {
+                self.digraph.add_edge(
+                    source,
+                    target,
+                    Edge {
+                        source_output: input.source_output,
+                        source_output_name: input.source_output_name.clone(),
+                        ..edge
+                    },
+                );
+            }
+            self.detach(node);
+            detached.push(node);
+        }
+
+        // fold constants in processing order, so the folded outputs of a node are seen by the nodes it feeds
+        self.detect_sccs();
+        let mut orphans = FxHashSet::default();
+        for node in self.schedule.nodes.clone() {
+            let Some(values) = self.evaluate_constant(node) else {
+                continue;
+            };
+            orphans.extend(self.digraph.neighbors_directed(node, Direction::Incoming));
+
+            let outgoing = self
+                .digraph
+                .edges_directed(node, Direction::Outgoing)
+                .map(|edge| (edge.target(), edge.weight().clone()))
+                .collect::<Vec<_>>();
+            let mut constants = vec![None; values.len()];
+            for (target, edge) in outgoing {
+                let output = edge.source_output as usize;
+                let constant = *constants[output].get_or_insert_with(|| {
+                    self.digraph.add_node(ProcessorNode::new(Constant::new_any(
+                        values[output].clone(),
+                    )))
+                });
+                self.digraph.add_edge(
+                    constant,
+                    target,
+                    Edge {
+                        source_output: 0,
+                        source_output_name: Some("out".to_string()),
+                        ..edge
+                    },
+                );
+            }
+            self.detach(node);
+            detached.push(node);
+        }
+
+        for node in detached {
+            removed.push((node, self.digraph.remove_node(node).unwrap()));
+        }
+
+        let mut kept: Vec<(NodeIndex, AnySignal)> = vec![];
+        let constants = self.digraph.node_indices().collect::<Vec<_>>();
+        for node in constants {
+            let Some(constant) = self.digraph[node].processor().downcast_ref::<Constant>() else {
+                continue;
+            };
+            let value = constant.value().clone();
+            let Some(&(original, _)) = kept
+                .iter()
+                .find(|(_, kept_value)| same_constant_value(kept_value, &value))
+            else {
+                kept.push((node, value));
+                continue;
+            };
+            let outgoing = self
+                .digraph
+                .edges_directed(node, Direction::Outgoing)
+                .map(|edge| (edge.target(), edge.weight().clone()))
+                .collect::<Vec<_>>();
+            for (target, edge) in outgoing {
+                self.digraph.add_edge(original, target, edge);
+            }
+            orphans.remove(&node);
+            removed.push((node, self.digraph.remove_node(node).unwrap()));
+        }
+
+        // the constants that only fed folded nodes are no longer needed
+        for node in orphans {
+            if self.digraph.contains_node(node)
+                && self.digraph[node]
+                    .processor()
+                    .downcast_ref::<Constant>()
+                    .is_some()
+                && self
+                    .digraph
+                    .edges_directed(node, Direction::Outgoing)
+                    .next()
+                    .is_none()
+            {
+                removed.push((node, self.digraph.remove_node(node).unwrap()));
+            }
+        }
+
+        if !removed.is_empty() {
+            log::debug!("Optimized away {} nodes", removed.len());
+        }
+
+        self.reset_visitor();
+        self.detect_sccs();
+
+        removed
+    }
+
+    // removes every edge into or out of the given node, leaving the node itself in place
+    fn detach(&mut self, node: NodeIndex) {
+        let edges = self
+            .digraph
+            .edges_directed(node, Direction::Incoming)
+            .chain(self.digraph.edges_directed(node, Direction::Outgoing))
+            .map(|edge| edge.id())
+            .collect::<Vec<_>>();
+        for edge in edges {
+            // a self-loop shows up as both an incoming and an outgoing edge
+            self.digraph.remove_edge(edge);
+        }
+    }
+
+    // evaluates the given node if it is pure and all of its inputs are connected to constants with a value, returning its outputs
+    fn evaluate_constant(&self, node: NodeIndex) -> Option<Vec<AnySignal>> {
+        let processor = &self.digraph[node];
+        if !processor.processor().is_pure() || processor.num_inputs() == 0 {
+            return None;
+        }
+
+        let input_spec = processor.input_spec().to_vec();
+        let output_spec = processor.output_spec().to_vec();
+        let mut inputs = input_spec
+            .iter()
+            .map(|spec| (false, SignalBuffer::new_of_type(&spec.signal_type, 1)))
+            .collect::<Vec<_>>();
+        for edge in self.digraph.edges_directed(node, Direction::Incoming) {
+            let constant = self.digraph[edge.source()]
+                .processor()
+                .downcast_ref::<Constant>()?;
+            if constant.value().is_none() {
+                return None;
+            }
+            let (connected, buffer) = &mut inputs[edge.weight().target_input as usize];
+            *connected = true;
+            buffer.fill(constant.value().clone());
+        }
+        if inputs.iter().any(|(connected, _)| !connected) {
+            return None;
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|(_, buffer)| Some(buffer))
+            .collect::<Vec<_>>();
+        let mut outputs = output_spec
+            .iter()
+            .map(|spec| SignalBuffer::new_of_type(&spec.signal_type, 1))
+            .collect::<Vec<_>>();
+        let transport = self.transport.state();
+
+        // pure processors don't depend on the sample rate, so any will do
+        let mut processor = processor.clone();
+        processor
+            .process(
+                ProcessorInputs::new(
+                    &input_spec,
+                    &inputs,
+                    &self.assets,
+                    ProcessMode::Block,
+                    48_000.0,
+                    1,
+                    &transport,
+                ),
+                ProcessorOutputs::new(&output_spec, &mut outputs, ProcessMode::Block),
+            )
+            .ok()?;
+
+        outputs
+            .iter()
+            .map(|buffer| buffer.get(0).map(|value| value.to_owned()))
+            .collect()
+    }
+
+    /// Seeds the random number generators of every node in the graph (see [`Processor::set_seed`]), so renders of the graph are reproducible.
+    ///
+    /// Each node gets its own seed, derived from the given seed and the node's index, so identical processors don't produce identical output.
+    pub fn set_seed(&mut self, seed: u64) {
+        let nodes = self.digraph.node_indices().collect::<Vec<_>>();
+        for node in nodes {
+            self.digraph[node]
+                .processor_mut()
+                .set_seed(derive_seed(seed, node.index() as u64));
+        }
+    }
+
     /// Returns the number of audio inputs in the graph.
     #[inline]
     pub fn num_audio_inputs(&self) -> usize {
@@ -291,12 +793,75 @@ impl Graph {
         self.output_nodes.len()
     }
 
+    /// Returns the name of the audio input at the given index.
+    ///
+    /// Inputs without a name (such as those of graphs saved before inputs could be named) are named after their index.
+    pub fn audio_input_name(&self, index: usize) -> Option<String> {
+        if index >= self.input_nodes.len() {
+            return None;
+        }
+        Some(
+            self.input_names
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| index.to_string()),
+        )
+    }
+
+    /// Returns the name of the audio output at the given index.
+    ///
+    /// Outputs without a name (such as those of graphs saved before outputs could be named) are named after their index.
+    pub fn audio_output_name(&self, index: usize) -> Option<String> {
+        if index >= self.output_nodes.len() {
+            return None;
+        }
+        Some(
+            self.output_names
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| index.to_string()),
+        )
+    }
+
     /// Returns the name of the given node's processor.
     #[inline]
     pub fn node_name(&self, node: NodeIndex) -> &str {
         self.digraph[node].name()
     }
 
+    /// Returns the value of the given node's metadata entry with the given key, if it exists.
+    ///
+    /// Metadata is never used by the graph itself. It's meant for tools like patch editors, e.g. to store a node's position or color, and is kept when the graph is serialized.
+    #[inline]
+    pub fn node_metadata(&self, node: NodeIndex, key: &str) -> Option<&str> {
+        self.digraph[node].metadata().get(key).map(String::as_str)
+    }
+
+    /// Sets the given node's metadata entry with the given key, returning its previous value.
+    pub fn set_node_metadata(
+        &mut self,
+        node: NodeIndex,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.digraph[node]
+            .metadata_mut()
+            .insert(key.into(), value.into())
+    }
+
+    /// Removes the given node's metadata entry with the given key, returning its value.
+    pub fn remove_node_metadata(&mut self, node: NodeIndex, key: &str) -> Option<String> {
+        self.digraph[node].metadata_mut().remove(key)
+    }
+
+    /// Returns an iterator over the given node's metadata entries, sorted by key.
+    pub fn node_metadata_iter(&self, node: NodeIndex) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.digraph[node]
+            .metadata()
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
     /// Returns the number of parameters in the graph.
     #[inline]
     pub fn num_params(&self) -> usize {
@@ -316,6 +881,19 @@ impl Graph {
             .map(|idx| (*self.digraph[idx].processor()).downcast_ref().unwrap())
     }
 
+    /// Returns an iterator over the names and processors of the parameters in the graph, including MIDI inputs.
+    #[inline]
+    pub fn param_iter(&self) -> impl Iterator<Item = (&str, &Param)> + '_ {
+        self.params.iter().map(|(name, idx)| {
+            (
+                name.as_str(),
+                (*self.digraph[*idx].processor())
+                    .downcast_ref::<Param>()
+                    .unwrap(),
+            )
+        })
+    }
+
     /// Returns the index of the MIDI input with the specified name.
     #[inline]
     pub fn midi_input_index(&self, name: &str) -> Option<NodeIndex> {
@@ -354,15 +932,21 @@ impl Graph {
         &self.output_nodes
     }
 
-    #[inline]
-    pub(crate) fn sccs(&self) -> &[Vec<NodeIndex>] {
-        &self.sccs
-    }
-
     #[inline]
     pub(crate) fn detect_sccs(&mut self) {
-        self.sccs = petgraph::algo::kosaraju_scc(&self.digraph);
-        self.sccs.reverse();
+        let mut sccs =
+            petgraph::algo::kosaraju_scc(&EdgeFiltered::from_fn(&self.digraph, |edge| {
+                is_scheduled_edge(&self.digraph, edge)
+            }));
+        sccs.reverse();
+
+        self.schedule =
+            schedule::Schedule::new(&self.digraph, &sccs, &self.input_nodes, &self.output_nodes);
+
+        #[cfg(feature = "parallel")]
+        {
+            self.parallel_schedule = schedule::ParallelSchedule::new(&self.digraph, &self.schedule);
+        }
     }
 
     #[inline]
@@ -375,10 +959,18 @@ impl Graph {
         self.visitor.stack.clear();
         self.visitor.finished.clear();
 
-        for node in self.digraph.externals(Direction::Incoming) {
-            self.visitor.stack.push(node);
+        let digraph =
+            EdgeFiltered::from_fn(&self.digraph, |edge| is_scheduled_edge(&self.digraph, edge));
+        for node in self.digraph.node_indices() {
+            if !self
+                .digraph
+                .edges_directed(node, Direction::Incoming)
+                .any(|edge| is_scheduled_edge(&self.digraph, edge))
+            {
+                self.visitor.stack.push(node);
+            }
         }
-        while let Some(node) = self.visitor.next(&self.digraph) {
+        while let Some(node) = self.visitor.next(&digraph) {
             self.visit_path.push(node);
         }
         self.visit_path.reverse();
@@ -400,6 +992,11 @@ impl Graph {
 
     /// Calls [`Processor::allocate()`] on each node in the graph.
     pub fn allocate(&mut self, sample_rate: Float, max_block_size: usize) {
+        // graphs saved by older versions don't include a schedule
+        if !self.schedule.is_complete(&self.digraph) {
+            self.detect_sccs();
+        }
+
         self.visit(|graph, node| -> Result<(), ()> {
             graph.digraph[node].allocate(sample_rate, max_block_size);
             Ok(())
@@ -416,8 +1013,129 @@ impl Graph {
         .unwrap();
     }
 
+    /// Serializes the graph to a JSON string.
+    ///
+    /// Processors are serialized by their type name and fields, so every processor in the graph must be registered with [`typetag`] (all built-in processors are).
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> GraphConstructionResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| GraphConstructionError::SerializationError(e.to_string()))
+    }
+
+    /// Deserializes a graph from a JSON string.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> GraphConstructionResult<Self> {
+        let mut graph: Self = serde_json::from_str(json)
+            .map_err(|e| GraphConstructionError::SerializationError(e.to_string()))?;
+        graph.reset_visitor();
+        graph.detect_sccs();
+        Ok(graph)
+    }
+
+    /// Serializes the graph to a RON string.
+    #[cfg(feature = "serde")]
+    pub fn to_ron(&self) -> GraphConstructionResult<String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| GraphConstructionError::SerializationError(e.to_string()))
+    }
+
+    /// Deserializes a graph from a RON string.
+    #[cfg(feature = "serde")]
+    pub fn from_ron(ron: &str) -> GraphConstructionResult<Self> {
+        let mut graph: Self = ron::from_str(ron)
+            .map_err(|e| GraphConstructionError::SerializationError(e.to_string()))?;
+        graph.reset_visitor();
+        graph.detect_sccs();
+        Ok(graph)
+    }
+
+    /// Saves the graph to the given file.
+    ///
+    /// The format is chosen based on the file extension (`.json` or `.ron`).
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> GraphConstructionResult<()> {
+        let path = path.as_ref();
+        let contents = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => self.to_json()?,
+            Some("ron") => self.to_ron()?,
+            _ => {
+                return Err(GraphConstructionError::UnknownFileFormat(
+                    path.display().to_string(),
+                ))
+            }
+        };
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Loads a graph from the given file.
+    ///
+    /// The format is chosen based on the file extension (`.json` or `.ron`).
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> GraphConstructionResult<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            Some("ron") => Self::from_ron(&contents),
+            _ => Err(GraphConstructionError::UnknownFileFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
     /// Writes a DOT representation of the graph to the provided writer, suitable for rendering with Graphviz.
+    ///
+    /// With the `profiling` feature, each node is labeled with its average and worst processing time.
     pub fn write_dot<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        write!(writer, "{:?}", petgraph::dot::Dot::new(&self.digraph))
+        #[cfg(feature = "profiling")]
+        {
+            use petgraph::dot::{Config, Dot};
+
+            let node_label = |_, (_, node): (NodeIndex, &ProcessorNode)| {
+                let profile = node.profile();
+                format!(
+                    "label = \"{}\\navg {:.1} us, worst {:.1} us\"",
+                    node.name(),
+                    profile.average.as_secs_f64() * 1e6,
+                    profile.worst.as_secs_f64() * 1e6,
+                )
+            };
+            let dot = Dot::with_attr_getters(
+                &self.digraph,
+                &[Config::NodeNoLabel],
+                &|_, _| String::new(),
+                &node_label,
+            );
+            write!(writer, "{:?}", dot)
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            write!(writer, "{:?}", petgraph::dot::Dot::new(&self.digraph))
+        }
+    }
+
+    /// Returns how long each node has been taking to process, slowest first.
+    #[cfg(feature = "profiling")]
+    pub fn profile_report(&self) -> profile::ProfileReport {
+        let mut entries = self
+            .digraph
+            .node_indices()
+            .map(|node| profile::ProfileEntry {
+                node,
+                name: self.digraph[node].name().to_string(),
+                profile: *self.digraph[node].profile(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.profile.average));
+        profile::ProfileReport { entries }
+    }
+
+    // folds the time each node spent processing the last block into its profile
+    #[cfg(feature = "profiling")]
+    pub(crate) fn finish_profile_block(&mut self) {
+        for node in self.digraph.node_weights_mut() {
+            node.profile_mut().finish_block();
+        }
     }
 }
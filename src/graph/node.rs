@@ -1,12 +1,22 @@
 //! Contains the [`ProcessorNode`] struct, which represents a node in the audio graph that processes signals.
 
-use std::fmt::Debug;
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use crate::{
     prelude::{Processor, ProcessorError, ProcessorInputs, ProcessorOutputs, SignalSpec},
     signal::Float,
 };
 
+// a new identifier for a node, distinct from every other node's
+fn next_instance() -> u64 {
+    static NEXT_INSTANCE: AtomicU64 = AtomicU64::new(0);
+    NEXT_INSTANCE.fetch_add(1, Ordering::Relaxed)
+}
+
 /// A node in the audio graph that processes signals.
 #[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -14,6 +24,19 @@ pub struct ProcessorNode {
     processor: Box<dyn Processor>,
     input_spec: Vec<SignalSpec>,
     output_spec: Vec<SignalSpec>,
+
+    // arbitrary key/value pairs for tools, such as a node's position in an editor
+    #[cfg_attr(feature = "serde", serde(default))]
+    metadata: BTreeMap<String, String>,
+
+    // shared by clones of the node until its processor is changed, so a running graph can tell which of its nodes an edit left alone
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_instance"))]
+    instance: u64,
+
+    // how long the node has been taking to process
+    #[cfg(feature = "profiling")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    profile: super::profile::NodeProfile,
 }
 
 impl Debug for ProcessorNode {
@@ -36,6 +59,10 @@ impl ProcessorNode {
             processor,
             input_spec,
             output_spec,
+            metadata: BTreeMap::new(),
+            instance: next_instance(),
+            #[cfg(feature = "profiling")]
+            profile: Default::default(),
         }
     }
 
@@ -78,9 +105,49 @@ impl ProcessorNode {
     /// Returns a mutable reference to the processor.
     #[inline]
     pub fn processor_mut(&mut self) -> &mut dyn Processor {
+        // the processor may be changed, so it no longer matches its clones
+        self.instance = next_instance();
         &mut *self.processor
     }
 
+    // identifies the node and its clones, until the processor is changed or replaced
+    #[inline]
+    pub(crate) fn instance(&self) -> u64 {
+        self.instance
+    }
+
+    // swaps the processors (and with them, their state) of two clones of the same node
+    #[inline]
+    pub(crate) fn swap_processor(&mut self, other: &mut ProcessorNode) {
+        debug_assert_eq!(self.instance, other.instance);
+        std::mem::swap(&mut self.processor, &mut other.processor);
+    }
+
+    /// Returns the node's metadata.
+    #[inline]
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the node's metadata.
+    #[inline]
+    pub fn metadata_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.metadata
+    }
+
+    /// Returns how long the node has been taking to process.
+    #[cfg(feature = "profiling")]
+    #[inline]
+    pub fn profile(&self) -> &super::profile::NodeProfile {
+        &self.profile
+    }
+
+    #[cfg(feature = "profiling")]
+    #[inline]
+    pub(crate) fn profile_mut(&mut self) -> &mut super::profile::NodeProfile {
+        &mut self.profile
+    }
+
     /// Allocates memory for the processor.
     #[inline]
     pub fn allocate(&mut self, sample_rate: Float, max_block_size: usize) {
@@ -102,6 +169,16 @@ impl ProcessorNode {
         inputs: ProcessorInputs,
         outputs: ProcessorOutputs,
     ) -> Result<(), ProcessorError> {
-        self.processor.process(inputs, outputs)
+        #[cfg(feature = "profiling")]
+        {
+            let start = std::time::Instant::now();
+            let result = self.processor.process(inputs, outputs);
+            self.profile.record(start.elapsed());
+            result
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            self.processor.process(inputs, outputs)
+        }
     }
 }
@@ -0,0 +1,92 @@
+//! Per-node processing time measurements, recorded when the `profiling` feature is enabled.
+
+use std::{fmt, time::Duration};
+
+use super::NodeIndex;
+
+// the number of blocks the rolling average is taken over
+const AVERAGE_WINDOW: u32 = 64;
+
+/// How long a node has been taking to process each block.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NodeProfile {
+    /// The average processing time per block, over roughly the last 64 blocks.
+    pub average: Duration,
+    /// The longest processing time of any block.
+    pub worst: Duration,
+    /// The number of blocks measured.
+    pub blocks: u64,
+
+    // the time spent so far in the current block
+    current: Duration,
+}
+
+impl NodeProfile {
+    // adds the time of a single call to `process`, which may be one of many in a block when processing sample by sample
+    #[inline]
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        self.current += elapsed;
+    }
+
+    // folds the current block's time into the statistics
+    #[inline]
+    pub(crate) fn finish_block(&mut self) {
+        self.blocks += 1;
+        let window = (self.blocks as u32).min(AVERAGE_WINDOW);
+        self.average = self.average - self.average / window + self.current / window;
+        self.worst = self.worst.max(self.current);
+        self.current = Duration::ZERO;
+    }
+}
+
+/// The processing time of a single node in a [`ProfileReport`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileEntry {
+    /// The index of the node.
+    pub node: NodeIndex,
+    /// The name of the node's processor.
+    pub name: String,
+    /// The node's processing time.
+    pub profile: NodeProfile,
+}
+
+/// The processing times of every node in a graph, slowest first.
+///
+/// Displaying the report prints it as a table.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileReport {
+    /// The entry for each node, sorted by average processing time, slowest first.
+    pub entries: Vec<ProfileEntry>,
+}
+
+impl ProfileReport {
+    /// Returns the sum of the average processing times of all nodes.
+    pub fn total_average(&self) -> Duration {
+        self.entries.iter().map(|entry| entry.profile.average).sum()
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>6}  {:<24} {:>12} {:>12}",
+            "node", "processor", "avg (us)", "worst (us)"
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:>6}  {:<24} {:>12.2} {:>12.2}",
+                entry.node.index(),
+                entry.name,
+                entry.profile.average.as_secs_f64() * 1e6,
+                entry.profile.worst.as_secs_f64() * 1e6,
+            )?;
+        }
+        write!(
+            f,
+            "total average: {:.2} us",
+            self.total_average().as_secs_f64() * 1e6
+        )
+    }
+}
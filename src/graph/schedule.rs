@@ -0,0 +1,228 @@
+//! Contains the [`Schedule`] struct, which holds the order a graph's nodes are processed in,
+//! and the [`ParallelSchedule`] struct, which groups the nodes into waves that can be processed concurrently.
+
+use std::ops::Range;
+
+use petgraph::prelude::{Direction, EdgeRef};
+#[cfg(feature = "parallel")]
+use rustc_hash::FxHashMap;
+use rustc_hash::FxHashSet;
+
+#[cfg(feature = "parallel")]
+use super::is_scheduled_edge;
+use super::{DiGraph, NodeIndex};
+
+/// A connection to one of a node's inputs, as `(target_input, source, source_output)`.
+pub(crate) type InputConnection = (u32, NodeIndex, u32);
+
+/// The order a graph's nodes are processed in, along with where each node's inputs come from.
+///
+/// This is rebuilt whenever the structure of the graph changes, so processing a block doesn't need to traverse the graph.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Schedule {
+    // the nodes in processing order
+    pub(crate) nodes: Vec<NodeIndex>,
+
+    // the incoming connections of each node in `nodes`
+    pub(crate) inputs: Vec<Vec<InputConnection>>,
+
+    // the strongly connected components as ranges of `nodes`, in processing order
+    // components of more than one node are feedback loops, which are processed one sample at a time
+    pub(crate) groups: Vec<Range<usize>>,
+
+    // for each node in `nodes` that processes its first input in place, the `(source, source_output)` whose buffer it takes over
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) in_place: Vec<Option<(NodeIndex, u32)>>,
+}
+
+impl Schedule {
+    /// Builds a schedule from the given strongly connected components, which must be in topological order.
+    ///
+    /// The graph's audio input and output nodes are given so they are left out of in-place processing.
+    pub(crate) fn new(
+        digraph: &DiGraph,
+        sccs: &[Vec<NodeIndex>],
+        input_nodes: &[NodeIndex],
+        output_nodes: &[NodeIndex],
+    ) -> Self {
+        let mut nodes = Vec::with_capacity(digraph.node_count());
+        let mut groups = Vec::with_capacity(sccs.len());
+        for scc in sccs {
+            let start = nodes.len();
+            nodes.extend_from_slice(scc);
+            groups.push(start..nodes.len());
+        }
+
+        let inputs: Vec<Vec<InputConnection>> = nodes
+            .iter()
+            .map(|&node| {
+                digraph
+                    .edges_directed(node, Direction::Incoming)
+                    .map(|edge| {
+                        let weight = edge.weight();
+                        (weight.target_input, edge.source(), weight.source_output)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // nodes that are not part of a feedback loop
+        let alone: FxHashSet<NodeIndex> = groups
+            .iter()
+            .filter(|group| group.len() == 1)
+            .map(|group| nodes[group.start])
+            .collect();
+
+        let in_place = nodes
+            .iter()
+            .zip(&inputs)
+            .map(|(&node, connections)| {
+                let &(_, source, source_output) = connections
+                    .iter()
+                    .find(|(target_input, _, _)| *target_input == 0)?;
+
+                // nodes in feedback loops are processed one sample at a time, so there's no block to take over
+                if !alone.contains(&node) || !alone.contains(&source) || source == node {
+                    return None;
+                }
+                // the runtime reads and writes the buffers of the audio inputs and outputs directly
+                let is_io = |node| input_nodes.contains(node) || output_nodes.contains(node);
+                if is_io(&node) || is_io(&source) {
+                    return None;
+                }
+
+                let processor = &digraph[node];
+                if !processor.processor().can_process_in_place() {
+                    return None;
+                }
+
+                // the buffer can only be taken over if nothing else reads it
+                let readers = digraph
+                    .edges_directed(source, Direction::Outgoing)
+                    .filter(|edge| edge.weight().source_output == source_output)
+                    .count();
+                if readers != 1 {
+                    return None;
+                }
+
+                let source_type = digraph[source]
+                    .output_spec()
+                    .get(source_output as usize)?
+                    .signal_type;
+                let output_type = processor.output_spec().first()?.signal_type;
+                (source_type == output_type).then_some((source, source_output))
+            })
+            .collect();
+
+        Self {
+            nodes,
+            inputs,
+            groups,
+            in_place,
+        }
+    }
+
+    /// Returns `true` if the schedule covers every node of the graph, which is not the case for schedules saved by older versions.
+    pub(crate) fn is_complete(&self, digraph: &DiGraph) -> bool {
+        self.nodes.len() == digraph.node_count() && self.in_place.len() == self.nodes.len()
+    }
+}
+
+/// A group of nodes that do not depend on each other, and can therefore be processed at the same time.
+#[cfg(feature = "parallel")]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Wave {
+    // nodes that are not part of a feedback loop, sorted by index
+    pub(crate) nodes: Vec<NodeIndex>,
+
+    // the position of each node in the digraph's node storage, used to pick the nodes out of `node_weights_mut()`
+    pub(crate) ranks: Vec<usize>,
+
+    // the incoming connections of each node
+    pub(crate) inputs: Vec<Vec<InputConnection>>,
+
+    // the buffer each node takes over to process its first input in place, see `Schedule::in_place`
+    pub(crate) in_place: Vec<Option<(NodeIndex, u32)>>,
+
+    // feedback loops as ranges of the `Schedule`'s nodes, which are processed one sample at a time after the rest of the wave
+    pub(crate) feedback: Vec<Range<usize>>,
+}
+
+/// A processing order for a graph where each wave only depends on the waves before it.
+#[cfg(feature = "parallel")]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct ParallelSchedule {
+    pub(crate) waves: Vec<Wave>,
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelSchedule {
+    /// Builds a parallel schedule from the given [`Schedule`].
+    pub(crate) fn new(digraph: &DiGraph, schedule: &Schedule) -> Self {
+        let mut group_of = FxHashMap::default();
+        for (i, group) in schedule.groups.iter().enumerate() {
+            for &node in &schedule.nodes[group.clone()] {
+                group_of.insert(node, i);
+            }
+        }
+
+        // each group is placed one wave after the latest wave of its dependencies
+        let mut levels = vec![0; schedule.groups.len()];
+        for (i, group) in schedule.groups.iter().enumerate() {
+            let mut level = 0;
+            for &node in &schedule.nodes[group.clone()] {
+                for edge in digraph.edges_directed(node, Direction::Incoming) {
+                    if !is_scheduled_edge(digraph, edge) {
+                        continue;
+                    }
+                    let source_group = group_of[&edge.source()];
+                    if source_group != i {
+                        level = level.max(levels[source_group] + 1);
+                    }
+                }
+            }
+            levels[i] = level;
+        }
+
+        let num_waves = levels.iter().max().map_or(0, |max| max + 1);
+        let mut waves = vec![Wave::default(); num_waves];
+        // the positions in the schedule of each wave's nodes that are not part of a feedback loop
+        let mut positions = vec![Vec::new(); num_waves];
+
+        for (group, &level) in schedule.groups.iter().zip(&levels) {
+            if group.len() == 1 {
+                positions[level].push(group.start);
+            } else {
+                waves[level].feedback.push(group.clone());
+            }
+        }
+
+        let ranks: FxHashMap<NodeIndex, usize> = digraph
+            .node_indices()
+            .enumerate()
+            .map(|(rank, node)| (node, rank))
+            .collect();
+
+        for (wave, mut positions) in waves.iter_mut().zip(positions) {
+            positions.sort_by_key(|&position| schedule.nodes[position]);
+            wave.nodes = positions
+                .iter()
+                .map(|&position| schedule.nodes[position])
+                .collect();
+            wave.ranks = wave.nodes.iter().map(|node| ranks[node]).collect();
+            wave.inputs = positions
+                .iter()
+                .map(|&position| schedule.inputs[position].clone())
+                .collect();
+            wave.in_place = positions
+                .iter()
+                .map(|&position| schedule.in_place[position])
+                .collect();
+        }
+
+        Self { waves }
+    }
+}
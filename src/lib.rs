@@ -4,17 +4,24 @@
 #![allow(clippy::unnecessary_cast)]
 #![allow(clippy::excessive_precision)]
 
+pub mod bench;
 pub mod builder;
 pub mod builtins;
+pub mod encode;
 pub mod graph;
+pub mod osc;
 pub mod processor;
 pub mod runtime;
 pub mod signal;
+pub mod transport;
 pub mod util;
 
 #[cfg(feature = "fft")]
 pub mod fft;
 
+#[cfg(feature = "bevy")]
+pub mod bevy;
+
 #[cfg(feature = "fft")]
 pub use fft::builtins as fft_builtins;
 
@@ -28,14 +35,22 @@ pub mod prelude {
         node_builder::{Input, IntoNode, Node, Output},
     };
     pub use crate::builtins::*;
+    pub use crate::encode::{FlacFileOut, FlacSettings, Normalization};
     pub use crate::graph::Graph;
     pub use crate::processor::{
         Processor, ProcessorError, ProcessorInputs, ProcessorOutputs, SignalSpec,
     };
-    pub use crate::runtime::{AudioBackend, AudioDevice, MidiPort, Runtime, RuntimeHandle};
+    #[cfg(all(target_os = "linux", feature = "jack"))]
+    pub use crate::runtime::JackConfig;
+    pub use crate::runtime::{
+        AudioBackend, AudioDevice, Command, MidiPort, MultiOut, OutputConfig,
+        ParseAudioBackendError, Runtime, RuntimeHandle, Xrun, XrunCounts,
+    };
     pub use crate::signal::{
-        AnySignal, Buffer, Float, List, MidiMessage, Signal, SignalBuffer, SignalType, PI, TAU,
+        AnySignal, AudioFileError, Buffer, Float, Frame, List, MidiMessage, Signal, SignalBuffer,
+        SignalType, Spectrum, MAX_CHANNELS, PI, TAU,
     };
+    pub use crate::transport::{Transport, TransportState};
     pub use crate::util::*;
     pub use raug_macros::{iter_proc_io_as, split_outputs};
     pub use std::time::Duration;
@@ -0,0 +1,232 @@
+//! Declarative mapping of OSC (Open Sound Control) addresses to [`Param`]s.
+//!
+//! [`OscMap`] doesn't open any sockets or parse OSC packets itself. Decode the messages with an OSC library of your choice, and pass their addresses and arguments to [`OscMap::dispatch`].
+
+use crate::{
+    prelude::Param,
+    signal::{AnySignal, Float},
+};
+
+/// How the value of an OSC message is converted before it is sent to a [`Param`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OscScaling {
+    /// The value is sent as-is.
+    Identity,
+    /// The value is mapped linearly from the input range to the output range.
+    Linear {
+        /// The range of the incoming values.
+        input: (Float, Float),
+        /// The range of the values sent to the parameter.
+        output: (Float, Float),
+    },
+    /// The value is mapped from the input range to the output range on an exponential curve, which suits frequencies and gains.
+    ///
+    /// The output range must not be zero or cross zero; if it does, the value is mapped linearly instead.
+    Exponential {
+        /// The range of the incoming values.
+        input: (Float, Float),
+        /// The range of the values sent to the parameter.
+        output: (Float, Float),
+    },
+}
+
+impl OscScaling {
+    /// Creates a linear mapping from `input` to `output`.
+    pub fn linear(input: (Float, Float), output: (Float, Float)) -> Self {
+        Self::Linear { input, output }
+    }
+
+    /// Creates an exponential mapping from `input` to `output`.
+    pub fn exponential(input: (Float, Float), output: (Float, Float)) -> Self {
+        Self::Exponential { input, output }
+    }
+
+    /// Converts an incoming value. Values outside the input range are clamped to it.
+    pub fn apply(&self, value: Float) -> Float {
+        match *self {
+            OscScaling::Identity => value,
+            OscScaling::Linear { input, output } => {
+                let t = normalize(value, input);
+                output.0 + (output.1 - output.0) * t
+            }
+            OscScaling::Exponential { input, output } => {
+                let t = normalize(value, input);
+                if output.0 * output.1 <= 0.0 {
+                    output.0 + (output.1 - output.0) * t
+                } else {
+                    output.0 * (output.1 / output.0).powf(t)
+                }
+            }
+        }
+    }
+}
+
+fn normalize(value: Float, (min, max): (Float, Float)) -> Float {
+    if min == max {
+        return 0.0;
+    }
+    ((value - min) / (max - min)).clamp(0.0, 1.0)
+}
+
+#[derive(Debug, Clone)]
+struct OscBinding {
+    pattern: String,
+    param: Param,
+    scaling: OscScaling,
+}
+
+/// A set of bindings from OSC addresses to [`Param`]s.
+///
+/// Addresses may be OSC address patterns, which match several addresses:
+///
+/// | Pattern | Matches |
+/// | --- | --- |
+/// | `?` | Any single character except `/`. |
+/// | `*` | Any sequence of characters, not including `/`. |
+/// | `[abc]`, `[a-z]` | Any of the listed characters, or any character in the range. |
+/// | `[!abc]` | Any character that isn't listed. |
+/// | `{foo,bar}` | Any of the comma-separated strings. |
+#[derive(Debug, Clone, Default)]
+pub struct OscMap {
+    bindings: Vec<OscBinding>,
+}
+
+impl OscMap {
+    /// Creates an empty `OscMap`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the given address pattern to the given parameter, sending it incoming values as-is.
+    pub fn bind(self, pattern: impl Into<String>, param: &Param) -> Self {
+        self.bind_scaled(pattern, param, OscScaling::Identity)
+    }
+
+    /// Binds the given address pattern to the given parameter, converting incoming numbers with the given scaling.
+    pub fn bind_scaled(
+        mut self,
+        pattern: impl Into<String>,
+        param: &Param,
+        scaling: OscScaling,
+    ) -> Self {
+        self.bindings.push(OscBinding {
+            pattern: pattern.into(),
+            param: param.clone(),
+            scaling,
+        });
+        self
+    }
+
+    /// Returns the number of bindings.
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Returns `true` if there are no bindings.
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Sends the first argument of an OSC message to every parameter bound to a pattern matching its address.
+    ///
+    /// Integer and float arguments are scaled by the binding's [`OscScaling`], then every argument is cast to the signal type of the parameter.
+    /// Messages without arguments are ignored. Returns the number of parameters that were sent a value.
+    pub fn dispatch(&self, address: &str, args: &[AnySignal]) -> usize {
+        let Some(arg) = args.first() else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for binding in &self.bindings {
+            if !address_matches(&binding.pattern, address) {
+                continue;
+            }
+
+            let value = match arg {
+                AnySignal::Float(Some(value)) => {
+                    AnySignal::Float(Some(binding.scaling.apply(*value)))
+                }
+                AnySignal::Int(Some(value)) => {
+                    AnySignal::Float(Some(binding.scaling.apply(*value as Float)))
+                }
+                arg => arg.clone(),
+            };
+
+            if let Some(value) = value.cast(binding.param.signal_type()) {
+                binding.param.send_any(value);
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Returns `true` if the OSC address pattern matches the address.
+pub fn address_matches(pattern: &str, address: &str) -> bool {
+    matches(pattern.as_bytes(), address.as_bytes())
+}
+
+fn matches(pattern: &[u8], address: &[u8]) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return address.is_empty();
+    };
+
+    match first {
+        b'?' => match address.split_first() {
+            Some((&c, address)) if c != b'/' => matches(rest, address),
+            _ => false,
+        },
+        b'*' => {
+            // try every possible length of the wildcard, up to the next `/`
+            let segment_len = address
+                .iter()
+                .position(|&c| c == b'/')
+                .unwrap_or(address.len());
+            (0..=segment_len).any(|len| matches(rest, &address[len..]))
+        }
+        b'[' => {
+            let Some(end) = rest.iter().position(|&c| c == b']') else {
+                return false;
+            };
+            let Some((&c, address)) = address.split_first() else {
+                return false;
+            };
+            let (negate, set) = match rest[..end].split_first() {
+                Some((b'!', set)) => (true, set),
+                _ => (false, &rest[..end]),
+            };
+            char_in_set(c, set) != negate && c != b'/' && matches(&rest[end + 1..], address)
+        }
+        b'{' => {
+            let Some(end) = rest.iter().position(|&c| c == b'}') else {
+                return false;
+            };
+            rest[..end].split(|&c| c == b',').any(|alternative| {
+                address.starts_with(alternative)
+                    && matches(&rest[end + 1..], &address[alternative.len()..])
+            })
+        }
+        c => match address.split_first() {
+            Some((&a, address)) if a == c => matches(rest, address),
+            _ => false,
+        },
+    }
+}
+
+fn char_in_set(c: u8, set: &[u8]) -> bool {
+    let mut i = 0;
+    while i < set.len() {
+        if i + 2 < set.len() && set[i + 1] == b'-' {
+            if (set[i]..=set[i + 2]).contains(&c) {
+                return true;
+            }
+            i += 3;
+        } else {
+            if set[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
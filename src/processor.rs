@@ -12,6 +12,7 @@ use crate::{
         AnySignal, AnySignalMut, AnySignalRef, Float, List, MidiMessage, Signal, SignalBuffer,
         SignalType,
     },
+    transport::TransportState,
     GraphSerde,
 };
 
@@ -304,6 +305,12 @@ pub struct ProcessorInputs<'a, 'b> {
 
     /// The current block size.
     pub block_size: usize,
+
+    /// The state of the graph's transport at the start of the block.
+    pub transport: &'a TransportState,
+
+    /// Whether the first input is being processed in place. See [`Processor::can_process_in_place`].
+    pub in_place: bool,
 }
 
 impl<'a, 'b> ProcessorInputs<'a, 'b> {
@@ -316,6 +323,7 @@ impl<'a, 'b> ProcessorInputs<'a, 'b> {
         mode: ProcessMode,
         sample_rate: Float,
         block_size: usize,
+        transport: &'a TransportState,
     ) -> Self {
         Self {
             input_specs,
@@ -324,6 +332,8 @@ impl<'a, 'b> ProcessorInputs<'a, 'b> {
             mode,
             sample_rate,
             block_size,
+            transport,
+            in_place: false,
         }
     }
 
@@ -351,9 +361,15 @@ impl<'a, 'b> ProcessorInputs<'a, 'b> {
         self.block_size
     }
 
+    /// Returns the state of the graph's transport at the start of the block.
+    #[inline]
+    pub fn transport(&self) -> &TransportState {
+        self.transport
+    }
+
     /// Returns the asset with the given name, if it exists.
     #[inline]
-    pub fn asset(&self, name: &str) -> Result<AssetRef, ProcessorError> {
+    pub fn asset(&self, name: &str) -> Result<AssetRef<'_>, ProcessorError> {
         self.assets
             .get(name)
             .ok_or_else(|| ProcessorError::AssetNotFound(name.into()))
@@ -370,7 +386,7 @@ impl<'a, 'b> ProcessorInputs<'a, 'b> {
 
     /// Returns an iterator over the input signal at the given index.
     #[inline]
-    pub fn iter_input(&self, index: usize) -> impl Iterator<Item = Option<AnySignalRef>> {
+    pub fn iter_input(&self, index: usize) -> impl Iterator<Item = Option<AnySignalRef<'_>>> {
         let buffer = &self.inputs[index];
         if let Some(buffer) = buffer.as_ref() {
             if let ProcessMode::Sample(sample_index) = self.mode {
@@ -417,6 +433,33 @@ impl<'a, 'b> ProcessorInputs<'a, 'b> {
         }
     }
 
+    /// Returns the input signal at the given index as a slice, if it is of the given type, or `None` if the input is unconnected.
+    ///
+    /// When processing sample by sample, the slice holds only the current sample.
+    #[inline]
+    pub fn input_as_slice<S: Signal>(
+        &self,
+        index: usize,
+    ) -> Result<Option<&'b [Option<S>]>, ProcessorError> {
+        let Some(buffer) = self.input(index) else {
+            return Ok(None);
+        };
+
+        let slice = buffer
+            .as_type::<S>()
+            .ok_or_else(|| ProcessorError::InputSpecMismatch {
+                index,
+                expected: S::signal_type(),
+                actual: buffer.signal_type(),
+            })?;
+
+        if let ProcessMode::Sample(sample_index) = self.mode {
+            Ok(Some(&slice[sample_index..=sample_index]))
+        } else {
+            Ok(Some(slice))
+        }
+    }
+
     /// Returns an iterator over the input signal at the given index, if it is a [`Float`] signal.
     #[inline]
     pub fn iter_input_as_floats(
@@ -517,7 +560,7 @@ impl<'a> ProcessorOutputs<'a> {
 
     /// Returns an iterator over the output signal at the given index.
     #[inline]
-    pub fn iter_output_mut(&mut self, index: usize) -> impl Iterator<Item = AnySignalMut> {
+    pub fn iter_output_mut(&mut self, index: usize) -> impl Iterator<Item = AnySignalMut<'_>> {
         let output = &mut self.outputs[index];
         if let ProcessMode::Sample(sample_index) = self.mode {
             Either::Left(std::iter::once(output.get_mut(sample_index).unwrap()))
@@ -561,6 +604,32 @@ impl<'a> ProcessorOutputs<'a> {
         }
     }
 
+    /// Returns the output signal at the given index as a mutable slice, if it is of the given type.
+    ///
+    /// When processing sample by sample, the slice holds only the current sample.
+    #[inline]
+    pub fn output_as_slice_mut<S: Signal>(
+        &mut self,
+        index: usize,
+    ) -> Result<&mut [Option<S>], ProcessorError> {
+        let output = &mut self.outputs[index];
+        let actual = output.signal_type();
+        let slice =
+            output
+                .as_type_mut::<S>()
+                .ok_or_else(|| ProcessorError::OutputSpecMismatch {
+                    index,
+                    expected: S::signal_type(),
+                    actual,
+                })?;
+
+        if let ProcessMode::Sample(sample_index) = self.mode {
+            Ok(&mut slice[sample_index..=sample_index])
+        } else {
+            Ok(slice)
+        }
+    }
+
     /// Returns an iterator over the output signal at the given index, if it is a [`Float`] signal.
     #[inline]
     pub fn iter_output_mut_as_floats(
@@ -685,10 +754,56 @@ where
 
     /// Called anytime the sample rate or block size changes.
     ///
+    /// Blocks that the runtime splits around timestamped commands don't change the block size; their parts are processed with a shorter [`ProcessorInputs::block_size`] instead.
+    ///
     /// This function is NOT ALLOWED to allocate memory.
     #[allow(unused)]
     fn resize_buffers(&mut self, sample_rate: Float, block_size: usize) {}
 
+    /// Returns the number of samples the processor delays its signals by, e.g. to look ahead at its input.
+    ///
+    /// This may depend on the sample rate, so it is only meaningful after [`Processor::resize_buffers`] has been called.
+    fn latency(&self) -> usize {
+        0
+    }
+
+    /// Returns `true` if the processor can transform its first input in place, writing the result over it.
+    ///
+    /// When the runtime makes use of this, the first output already holds the values of the first input,
+    /// the first input reads as unconnected, and [`ProcessorInputs::in_place`] is `true`.
+    /// This only happens when nothing else reads the signal connected to the first input, and both are the same type as the first output.
+    fn can_process_in_place(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the processor's outputs depend only on its inputs at the current sample, with no state, assets, or timing involved.
+    ///
+    /// [`Graph::optimize`](crate::graph::Graph::optimize) evaluates pure processors whose inputs are all constant ahead of time, replacing them with constants.
+    fn is_pure(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the processor publishes its measurements outside of the graph, such as through a [`TapReader`](crate::builtins::TapReader).
+    ///
+    /// Taps are kept by [`Graph::garbage_collect`](crate::graph::Graph::garbage_collect) even if nothing is connected to their outputs.
+    /// Processors with no outputs are always treated as taps.
+    fn is_tap(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the processor reads its inputs as they were at the end of the previous block, like [`Feedback`](crate::builtins::Feedback) does.
+    ///
+    /// Edges into such a processor are ignored when scheduling the graph, so a loop through it is not processed one sample at a time.
+    fn reads_previous_block(&self) -> bool {
+        false
+    }
+
+    /// Reseeds the processor's random number generator, if it has one, so its output is reproducible.
+    ///
+    /// [`Graph::set_seed`](crate::graph::Graph::set_seed) calls this on every node with a seed derived from the graph's seed.
+    #[allow(unused)]
+    fn set_seed(&mut self, seed: u64) {}
+
     /// Processes the input signals and writes the output signals.
     ///
     /// This function is NOT ALLOWED to allocate memory.
@@ -1,22 +1,33 @@
 //! The audio graph processing runtime.
 
 use std::{
-    sync::{mpsc, Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
     time::Duration,
 };
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use petgraph::prelude::*;
+use petgraph::visit::NodeIndexable;
 use rustc_hash::{FxBuildHasher, FxHashMap};
 
+#[cfg(feature = "ogg")]
+use crate::encode::{OggFileOut, OggFlacFileOut, VorbisSettings};
 use crate::{
     debug_once,
-    graph::{Graph, GraphRunError, GraphRunErrorType, NodeIndex},
-    prelude::{Param, ProcessorInputs, SignalSpec},
+    encode::{FlacFileOut, FlacSettings, Normalization},
+    graph::node::ProcessorNode,
+    graph::{arena::BufferArena, Graph, GraphRunError, GraphRunErrorType, NodeIndex},
+    prelude::{Param, Processor, ProcessorInputs, SignalSpec},
     processor::{ProcessMode, ProcessorError, ProcessorOutputs},
-    signal::{Float, MidiMessage, SignalBuffer},
+    signal::{AnySignal, Buffer, Float, SignalBuffer, SignalType},
+    transport::{Transport, TransportState},
 };
 
+#[cfg(feature = "midi")]
+use crate::signal::MidiMessage;
+
 /// Errors that can occur related to the runtime.
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -31,6 +42,9 @@ pub enum RuntimeError {
     /// An error occurred while enumerating available hosts.
     Hound(#[from] hound::Error),
 
+    /// An I/O error occurred while writing an audio file.
+    IoError(#[from] std::io::Error),
+
     /// The requested host is unavailable.
     HostUnavailable(#[from] cpal::HostUnavailable),
 
@@ -44,18 +58,27 @@ pub enum RuntimeError {
     /// An error occurred while retrieving the default output config.
     DefaultStreamConfigError(#[from] cpal::DefaultStreamConfigError),
 
+    /// An error occurred while building an audio stream.
+    BuildStreamError(#[from] cpal::BuildStreamError),
+
+    /// An error occurred while starting an audio stream.
+    PlayStreamError(#[from] cpal::PlayStreamError),
+
     /// Output stream sample format is not supported.
     #[error("Unsupported sample format: {0}")]
     UnsupportedSampleFormat(cpal::SampleFormat),
 
     /// An error occurred while initializing MIDI input.
+    #[cfg(feature = "midi")]
     MidirInitError(#[from] midir::InitError),
 
     /// The requested MIDI port is unavailable.
+    #[cfg(feature = "midi")]
     #[error("Requested MIDI port is unavailable: {0:?}")]
     MidiPortUnavailable(MidiPort),
 
     /// An error occurred while connecting to a MIDI port.
+    #[cfg(feature = "midi")]
     MidiConnectError(#[from] midir::ConnectError<midir::MidiInput>),
 
     /// An error occurred while running the audio graph.
@@ -70,6 +93,49 @@ pub enum RuntimeError {
     /// The number of channels in the audio stream does not match the number of outputs in the graph.
     #[error("Channel mismatch: expected {0} channels, got {1}")]
     ChannelMismatch(usize, usize),
+
+    /// A channel map refers to an output that the graph does not have.
+    #[error("Channel map refers to output {0}, but the graph only has {1} outputs")]
+    InvalidChannelMap(usize, usize),
+
+    /// An error occurred in the JACK client.
+    #[cfg(all(target_os = "linux", feature = "jack"))]
+    JackError(#[from] jack::Error),
+
+    /// An error occurred while enumerating the supported configurations of a device.
+    SupportedStreamConfigsError(#[from] cpal::SupportedStreamConfigsError),
+
+    /// The device does not support the requested stream configuration.
+    #[error("Unsupported stream configuration: {0:?}")]
+    UnsupportedStreamConfig(OutputConfig),
+
+    /// A [`MultiOut`] was given without any devices.
+    #[error("No output devices were given")]
+    NoOutputDevices,
+
+    /// The audio thread has not caught up with the [`Command`]s sent to it.
+    #[error("Command queue is full")]
+    CommandQueueFull,
+
+    /// The graph has no parameter with the given name.
+    #[error("Parameter not found: {0}")]
+    ParamNotFound(String),
+
+    /// The parameter can't be set to a value of the given type.
+    #[error("Parameter {0} cannot be set to a value of type {1:?}")]
+    ParamTypeMismatch(String, SignalType),
+
+    /// The graph has no audio input with the given index.
+    #[error("Audio input not found: {0}")]
+    InputNotFound(usize),
+
+    /// The graph has no node with the given index.
+    #[error("Node not found: {0:?}")]
+    NodeNotFound(NodeIndex),
+
+    /// A replacement processor does not have the same inputs and outputs as the node it replaces.
+    #[error("Replacement for node {0:?} has different inputs or outputs")]
+    NodeSpecMismatch(NodeIndex),
 }
 
 /// Result type for runtime operations.
@@ -87,9 +153,103 @@ pub enum AudioBackend {
     #[cfg(target_os = "linux")]
     /// Use the Advanced Linux Sound Architecture audio backend.
     Alsa,
+    #[cfg(target_os = "linux")]
+    /// Use the PipeWire audio server, through its ALSA plugin.
+    ///
+    /// The default device is PipeWire's `pipewire` ALSA device if it is available.
+    PipeWire,
     #[cfg(target_os = "windows")]
     /// Use the Windows Audio Session API audio backend.
     Wasapi,
+    #[cfg(target_os = "macos")]
+    /// Use the Core Audio audio backend.
+    CoreAudio,
+}
+
+/// An error that occurs when parsing an [`AudioBackend`] from a string.
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown or unsupported audio backend: {0}")]
+pub struct ParseAudioBackendError(String);
+
+impl std::str::FromStr for AudioBackend {
+    type Err = ParseAudioBackendError;
+
+    /// Parses a backend name, ignoring case, e.g. `"alsa"` or `"CoreAudio"`.
+    ///
+    /// Names of backends that aren't available on the current platform are rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(AudioBackend::Default),
+            #[cfg(all(target_os = "linux", feature = "jack"))]
+            "jack" => Ok(AudioBackend::Jack),
+            #[cfg(target_os = "linux")]
+            "alsa" => Ok(AudioBackend::Alsa),
+            #[cfg(target_os = "linux")]
+            "pipewire" => Ok(AudioBackend::PipeWire),
+            #[cfg(target_os = "windows")]
+            "wasapi" => Ok(AudioBackend::Wasapi),
+            #[cfg(target_os = "macos")]
+            "coreaudio" => Ok(AudioBackend::CoreAudio),
+            _ => Err(ParseAudioBackendError(s.to_string())),
+        }
+    }
+}
+
+impl AudioBackend {
+    pub(crate) fn host(&self) -> RuntimeResult<cpal::Host> {
+        let host_id = match self {
+            AudioBackend::Default => cpal::default_host().id(),
+            #[cfg(target_os = "linux")]
+            AudioBackend::Alsa | AudioBackend::PipeWire => cpal::available_hosts()
+                .into_iter()
+                .find(|h| *h == cpal::HostId::Alsa)
+                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
+            #[cfg(all(target_os = "linux", feature = "jack"))]
+            AudioBackend::Jack => cpal::available_hosts()
+                .into_iter()
+                .find(|h| *h == cpal::HostId::Jack)
+                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
+            #[cfg(target_os = "windows")]
+            AudioBackend::Wasapi => cpal::available_hosts()
+                .into_iter()
+                .find(|h| *h == cpal::HostId::Wasapi)
+                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
+            #[cfg(target_os = "macos")]
+            AudioBackend::CoreAudio => cpal::available_hosts()
+                .into_iter()
+                .find(|h| *h == cpal::HostId::CoreAudio)
+                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
+        };
+        Ok(cpal::host_from_id(host_id)?)
+    }
+
+    fn default_output_device(&self, host: &cpal::Host) -> Option<cpal::Device> {
+        #[cfg(target_os = "linux")]
+        if let AudioBackend::PipeWire = self {
+            if let Some(device) = find_pipewire_device(host.output_devices().ok()?) {
+                return Some(device);
+            }
+        }
+        host.default_output_device()
+    }
+
+    fn default_input_device(&self, host: &cpal::Host) -> Option<cpal::Device> {
+        #[cfg(target_os = "linux")]
+        if let AudioBackend::PipeWire = self {
+            if let Some(device) = find_pipewire_device(host.input_devices().ok()?) {
+                return Some(device);
+            }
+        }
+        host.default_input_device()
+    }
+}
+
+// finds the ALSA device provided by PipeWire's ALSA plugin
+#[cfg(target_os = "linux")]
+pub(crate) fn find_pipewire_device(
+    mut devices: impl Iterator<Item = cpal::Device>,
+) -> Option<cpal::Device> {
+    devices.find(|d| d.name().is_ok_and(|n| n == "pipewire"))
 }
 
 /// An audio device to use for audio I/O.
@@ -104,7 +264,146 @@ pub enum AudioDevice {
     Name(String),
 }
 
+impl AudioDevice {
+    /// Returns the output configurations supported by the device, as reported by the given backend.
+    pub fn supported_output_configs(
+        &self,
+        backend: &AudioBackend,
+    ) -> RuntimeResult<Vec<cpal::SupportedStreamConfigRange>> {
+        let device = self.output_device(backend, &backend.host()?)?;
+        Ok(device.supported_output_configs()?.collect())
+    }
+
+    fn output_device(
+        &self,
+        backend: &AudioBackend,
+        host: &cpal::Host,
+    ) -> RuntimeResult<cpal::Device> {
+        let device = match self {
+            AudioDevice::Default => backend.default_output_device(host),
+            AudioDevice::Index(index) => host.output_devices()?.nth(*index),
+            AudioDevice::Name(name) => host
+                .output_devices()?
+                .find(|d| d.name().is_ok_and(|n| n.contains(name))),
+        };
+        device.ok_or_else(|| RuntimeError::DeviceUnavailable(self.clone()))
+    }
+
+    fn input_device(
+        &self,
+        backend: &AudioBackend,
+        host: &cpal::Host,
+    ) -> RuntimeResult<cpal::Device> {
+        let device = match self {
+            AudioDevice::Default => backend.default_input_device(host),
+            AudioDevice::Index(index) => host.input_devices()?.nth(*index),
+            AudioDevice::Name(name) => host
+                .input_devices()?
+                .find(|d| d.name().is_ok_and(|n| n.contains(name))),
+        };
+        device.ok_or_else(|| RuntimeError::DeviceUnavailable(self.clone()))
+    }
+}
+
+/// The stream configuration to request from an output device.
+///
+/// Anything that isn't requested is taken from the device's default configuration where possible.
+/// If the device has no configuration that satisfies every request, starting the runtime fails with [`RuntimeError::UnsupportedStreamConfig`].
+/// Use [`AudioDevice::supported_output_configs`] to list the configurations a device supports.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct OutputConfig {
+    sample_rate: Option<u32>,
+    channels: Option<u16>,
+    buffer_size: Option<u32>,
+    sample_format: Option<cpal::SampleFormat>,
+}
+
+impl OutputConfig {
+    /// Creates a new [`OutputConfig`] that uses the device's default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the given sample rate, in Hz.
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// Requests the given number of channels.
+    pub fn with_channels(mut self, channels: u16) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Requests the given buffer size, in frames.
+    ///
+    /// Smaller buffers lower the latency of the stream, but make dropouts more likely.
+    pub fn with_buffer_size(mut self, buffer_size: u32) -> Self {
+        self.buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// Requests the given sample format.
+    pub fn with_sample_format(mut self, sample_format: cpal::SampleFormat) -> Self {
+        self.sample_format = Some(sample_format);
+        self
+    }
+
+    // picks the supported configuration of the device that best matches the request
+    fn negotiate(
+        &self,
+        device: &cpal::Device,
+    ) -> RuntimeResult<(cpal::SupportedStreamConfig, cpal::BufferSize)> {
+        let default = device.default_output_config()?;
+
+        let buffer_size = match self.buffer_size {
+            Some(frames) => cpal::BufferSize::Fixed(frames),
+            None => cpal::BufferSize::Default,
+        };
+
+        if *self == OutputConfig::default() {
+            return Ok((default, buffer_size));
+        }
+
+        let sample_rate = self.sample_rate.unwrap_or(default.sample_rate().0);
+
+        let config = device
+            .supported_output_configs()?
+            .filter(|range| {
+                self.channels.is_none_or(|c| range.channels() == c)
+                    && self
+                        .sample_format
+                        .is_none_or(|f| range.sample_format() == f)
+                    && self.sample_rate.is_none_or(|rate| {
+                        (range.min_sample_rate().0..=range.max_sample_rate().0).contains(&rate)
+                    })
+                    && match (self.buffer_size, range.buffer_size()) {
+                        (Some(frames), cpal::SupportedBufferSize::Range { min, max }) => {
+                            (*min..=*max).contains(&frames)
+                        }
+                        _ => true,
+                    }
+            })
+            // prefer the default channel count and sample format if they weren't requested
+            .max_by_key(|range| {
+                (range.channels() == default.channels()) as u8
+                    + (range.sample_format() == default.sample_format()) as u8
+            })
+            .ok_or_else(|| RuntimeError::UnsupportedStreamConfig(self.clone()))?;
+
+        let sample_rate = sample_rate.clamp(config.min_sample_rate().0, config.max_sample_rate().0);
+
+        Ok((
+            config.with_sample_rate(cpal::SampleRate(sample_rate)),
+            buffer_size,
+        ))
+    }
+}
+
 /// A MIDI port to use for MIDI I/O.
+///
+/// MIDI input requires the `midi` feature.
 #[derive(Default, Debug, Clone)]
 pub enum MidiPort {
     /// Use the default MIDI port.
@@ -116,6 +415,151 @@ pub enum MidiPort {
     Name(String),
 }
 
+/// Settings for running a graph as a standalone JACK client. See [`Runtime::run_jack`].
+#[cfg(all(target_os = "linux", feature = "jack"))]
+#[derive(Debug, Clone)]
+pub struct JackConfig {
+    client_name: String,
+    transport_sync: bool,
+    auto_connect: bool,
+}
+
+#[cfg(all(target_os = "linux", feature = "jack"))]
+impl Default for JackConfig {
+    fn default() -> Self {
+        Self {
+            client_name: "raug".to_string(),
+            transport_sync: true,
+            auto_connect: true,
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "jack"))]
+impl JackConfig {
+    /// Creates a new [`JackConfig`] with the client name `raug`, transport sync enabled, and ports connected to the system's physical ports.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the JACK client. JACK may change it if another client already has this name.
+    pub fn with_client_name(mut self, client_name: impl Into<String>) -> Self {
+        self.client_name = client_name.into();
+        self
+    }
+
+    /// Sets whether the graph's [`Transport`] follows the JACK transport (on by default).
+    ///
+    /// The graph's transport then plays and stops along with the JACK transport, and takes its tempo, time signature and position from the JACK timebase master if there is one.
+    pub fn with_transport_sync(mut self, transport_sync: bool) -> Self {
+        self.transport_sync = transport_sync;
+        self
+    }
+
+    /// Sets whether the client's ports are connected to the system's physical capture and playback ports in order when the client starts (on by default).
+    pub fn with_auto_connect(mut self, auto_connect: bool) -> Self {
+        self.auto_connect = auto_connect;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MultiOutDevice {
+    device: AudioDevice,
+    channel_map: Option<Vec<usize>>,
+    config: OutputConfig,
+}
+
+/// A set of output devices to play a graph's outputs on simultaneously, for example main outputs and a headphone cue mix.
+///
+/// Each device has its own channel map, which lists the graph output to play on each of its channels.
+/// Device channels past the end of the map are left silent, and the same graph output may be played on any number of channels.
+///
+/// The first device drives the graph and is played with the lowest latency.
+/// The other devices run at the same sample rate, and are fed through small ring buffers that absorb differing block sizes and clock drift:
+/// if a device runs slow, whole frames are dropped, and if it runs fast, it plays silence until its buffer has refilled.
+///
+/// Use with [`Runtime::run_multi`].
+#[derive(Debug, Clone)]
+pub struct MultiOut {
+    devices: Vec<MultiOutDevice>,
+    buffer_frames: usize,
+}
+
+impl Default for MultiOut {
+    fn default() -> Self {
+        Self {
+            devices: vec![],
+            buffer_frames: 2048,
+        }
+    }
+}
+
+impl MultiOut {
+    /// Creates a new [`MultiOut`] without any devices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a device that plays every output of the graph, in order.
+    ///
+    /// The device must have as many channels as the graph has outputs.
+    pub fn with_device(mut self, device: AudioDevice) -> Self {
+        self.devices.push(MultiOutDevice {
+            device,
+            channel_map: None,
+            config: OutputConfig::default(),
+        });
+        self
+    }
+
+    /// Adds a device whose channels play the given graph outputs.
+    ///
+    /// For example, a channel map of `[2, 3]` plays the graph's third and fourth outputs on the device's first two channels.
+    pub fn with_mapped_device(
+        mut self,
+        device: AudioDevice,
+        channel_map: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        self.devices.push(MultiOutDevice {
+            device,
+            channel_map: Some(channel_map.into_iter().collect()),
+            config: OutputConfig::default(),
+        });
+        self
+    }
+
+    /// Sets the stream configuration to request from the most recently added device.
+    ///
+    /// The other devices are run at the sample rate of the first one, unless they request a different one (which will fail to start).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no device has been added yet.
+    #[track_caller]
+    pub fn with_config(mut self, config: OutputConfig) -> Self {
+        let device = self
+            .devices
+            .last_mut()
+            .expect("with_config: no device has been added");
+        device.config = config;
+        self
+    }
+
+    /// Sets the size of the ring buffers feeding every device but the first, in frames. Defaults to 2048.
+    ///
+    /// Larger buffers tolerate more drift between the devices, at the cost of latency on the other devices.
+    pub fn with_buffer_frames(mut self, buffer_frames: usize) -> Self {
+        self.buffer_frames = buffer_frames.max(1);
+        self
+    }
+
+    /// Returns the number of devices.
+    pub fn num_devices(&self) -> usize {
+        self.devices.len()
+    }
+}
+
 #[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct NodeBuffers {
@@ -125,10 +569,26 @@ pub(crate) struct NodeBuffers {
 }
 
 impl NodeBuffers {
-    fn resize(&mut self, block_size: usize) {
+    // replaces the output buffers with new ones that can hold the given number of samples
+    fn allocate(&mut self, max_block_size: usize) {
+        for (spec, buffer) in self.output_spec.iter().zip(&mut self.outputs) {
+            *buffer = SignalBuffer::new_of_type(&spec.signal_type, max_block_size);
+        }
+    }
+
+    // resizes the output buffers without allocating, failing if they can't hold the given number of samples
+    fn resize(&mut self, block_size: usize) -> RuntimeResult<()> {
+        if self
+            .outputs
+            .iter()
+            .any(|buffer| buffer.capacity() < block_size)
+        {
+            return Err(RuntimeError::NeedsAlloc);
+        }
         for (spec, buffer) in self.output_spec.iter().zip(&mut self.outputs) {
             buffer.resize_with_hint(block_size, &spec.signal_type);
         }
+        Ok(())
     }
 }
 
@@ -138,9 +598,37 @@ impl NodeBuffers {
 pub struct Runtime {
     graph: Graph,
     buffer_cache: FxHashMap<NodeIndex, NodeBuffers>,
+
     sample_rate: Float,
     block_size: usize,
     max_block_size: usize,
+
+    // how renders written to files are normalized
+    #[cfg_attr(feature = "serde", serde(default))]
+    normalization: Option<Normalization>,
+
+    // the state of the transport for the block currently being processed
+    #[cfg_attr(feature = "serde", serde(skip))]
+    transport_state: TransportState,
+
+    // buffers of the wave currently being processed in parallel
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    wave_buffers: Vec<(NodeIndex, NodeBuffers)>,
+
+    // commands waiting for the transport to reach their sample, in the order they were sent
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending_commands: Vec<(u64, QueuedCommand)>,
+
+    // nodes replaced by commands, to be dropped off the audio thread
+    #[cfg_attr(feature = "serde", serde(skip))]
+    replaced_nodes: Vec<ProcessorNode>,
+
+    // the audio inputs and outputs of a block while it is processed in parts
+    #[cfg_attr(feature = "serde", serde(skip))]
+    split_inputs: Vec<Buffer<Float>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    split_outputs: Vec<Buffer<Float>>,
 }
 
 impl Runtime {
@@ -175,11 +663,19 @@ impl Runtime {
             .unwrap();
 
         Runtime {
+            #[cfg(feature = "parallel")]
+            wave_buffers: Vec::with_capacity(buffer_cache.len()),
             buffer_cache,
             graph,
             sample_rate: 0.0,
             block_size: 0,
             max_block_size: 0,
+            normalization: None,
+            transport_state: TransportState::default(),
+            pending_commands: Vec::new(),
+            replaced_nodes: Vec::new(),
+            split_inputs: Vec::new(),
+            split_outputs: Vec::new(),
         }
     }
 
@@ -210,7 +706,73 @@ impl Runtime {
         self.graph.resize_buffers(sample_rate, max_block_size);
 
         for buffers in self.buffer_cache.values_mut() {
-            buffers.resize(max_block_size);
+            buffers.allocate(max_block_size);
+        }
+
+        self.bind_arena(max_block_size);
+
+        self.split_inputs = vec![Buffer::zeros(max_block_size); self.graph.num_audio_inputs()];
+        self.split_outputs = vec![Buffer::zeros(max_block_size); self.graph.num_audio_outputs()];
+    }
+
+    // a copy of the runtime to move to the audio thread
+    // cloned processors drop the state they set up when allocated (e.g. disk streams and FFT plans), and cloned buffers leave the arena, so the copy is allocated again
+    fn for_audio_thread(&self) -> Runtime {
+        let mut runtime = self.clone();
+        runtime.allocate_for_block_size(self.sample_rate, self.max_block_size);
+        runtime
+    }
+
+    // moves the processors of the nodes an edit left alone over from the running runtime, so they keep their state
+    fn carry_over_processors(&mut self, running: &mut Runtime) {
+        for i in 0..self.graph.digraph().node_bound() {
+            let index = NodeIndex::new(i);
+            let (Some(node), Some(running_node)) = (
+                self.graph.digraph_mut().node_weight_mut(index),
+                running.graph.digraph_mut().node_weight_mut(index),
+            ) else {
+                continue;
+            };
+            if node.instance() == running_node.instance() {
+                node.swap_processor(running_node);
+            }
+        }
+    }
+
+    // makes room for as many commands as the command queue holds, so a running runtime doesn't allocate when receiving them
+    fn reserve_commands(&mut self) {
+        self.pending_commands.reserve(COMMAND_QUEUE_CAPACITY);
+        self.replaced_nodes.reserve(COMMAND_QUEUE_CAPACITY);
+    }
+
+    // moves the float output buffers into a new arena in processing order, so that nodes processed one after another write to neighboring memory
+    fn bind_arena(&mut self, capacity: usize) {
+        let order = &self.graph.schedule.nodes;
+
+        let mut float_outputs = 0;
+        for node in order {
+            if let Some(buffers) = self.buffer_cache.get(node) {
+                float_outputs += buffers
+                    .outputs
+                    .iter()
+                    .filter(|buffer| matches!(buffer, SignalBuffer::Float(_)))
+                    .count();
+            }
+        }
+
+        let arena = BufferArena::new(float_outputs, capacity);
+        let mut index = 0;
+        for node in order {
+            let Some(buffers) = self.buffer_cache.get_mut(node) else {
+                continue;
+            };
+            for buffer in &mut buffers.outputs {
+                if let SignalBuffer::Float(buffer) = buffer {
+                    // SAFETY: each buffer gets its own slice of the arena
+                    unsafe { buffer.bind_to_arena(&arena, index) };
+                    index += 1;
+                }
+            }
         }
     }
 
@@ -227,14 +789,28 @@ impl Runtime {
             return Ok(());
         }
 
-        self.block_size = block_size;
-
         self.graph.resize_buffers(self.sample_rate, block_size);
 
+        self.set_block_len(block_size)
+    }
+
+    // sets the number of samples processed by the following blocks without reconfiguring the processors,
+    // so that parts of a block (see `Runtime::process_split`) don't reset processor state
+    pub(crate) fn set_block_len(&mut self, len: usize) -> RuntimeResult<()> {
+        if len > self.max_block_size {
+            return Err(RuntimeError::NeedsAlloc);
+        }
+
+        if len == self.block_size {
+            return Ok(());
+        }
+
         for buffers in self.buffer_cache.values_mut() {
-            buffers.resize(block_size);
+            buffers.resize(len)?;
         }
 
+        self.block_size = len;
+
         Ok(())
     }
 
@@ -250,90 +826,379 @@ impl Runtime {
         &mut self.graph
     }
 
+    /// Returns the graph's [`Transport`].
+    #[inline]
+    pub fn transport(&self) -> &Transport {
+        self.graph.transport()
+    }
+
     /// Runs the audio graph for one block of samples.
-    #[cfg_attr(feature = "profiling", inline(never))]
+    ///
+    /// Commands scheduled for a sample within the block (see [`Runtime::send_at`]) split it there, and the parts are processed as shorter blocks one after another.
+    /// With the `parallel` feature, nodes that do not depend on each other are processed concurrently on the `rayon` thread pool.
     pub fn process(&mut self) -> RuntimeResult<()> {
-        for i in 0..self.graph.sccs().len() {
-            if self.graph.sccs()[i].len() == 1 {
-                let node_id = self.graph.sccs()[i][0];
-                self.process_node(node_id, ProcessMode::Block)?;
-            } else {
-                let nodes = self.graph.sccs()[i].clone();
-                for sample_index in 0..self.block_size {
-                    for &node_id in &nodes {
-                        self.process_node(node_id, ProcessMode::Sample(sample_index))?;
-                    }
-                }
-            }
+        if self.pending_commands.is_empty() {
+            return self.process_block();
         }
 
-        Ok(())
+        let start = self.graph.transport().sample();
+        self.apply_pending_commands(start);
+
+        let splits = self.graph.transport().is_playing()
+            && self
+                .next_command_offset(start)
+                .is_some_and(|offset| offset < self.block_size);
+        if splits {
+            self.process_split(start)
+        } else {
+            self.process_block()
+        }
     }
 
-    #[cfg_attr(feature = "profiling", inline(never))]
-    fn process_node(&mut self, node_id: NodeIndex, mode: ProcessMode) -> RuntimeResult<()> {
-        let num_inputs = self.buffer_cache[&node_id].input_spec.len();
+    /// Schedules a [`Command`] to be applied when the graph's [`Transport`] reaches the given sample (see [`Transport::sample`]).
+    ///
+    /// This is the counterpart of [`RuntimeHandle::send_at`] for driving a runtime directly, e.g. when rendering offline.
+    /// Unlike the handle, it doesn't keep a separate copy of the graph, so a [`Command::ReplaceNode`] only shows up in [`Runtime::graph`] once it is applied.
+    pub fn send_at(&mut self, command: Command, sample: u64) -> RuntimeResult<()> {
+        let command = prepare_command(&self.graph, command, self.sample_rate, self.max_block_size)?;
+        self.pending_commands.push((sample, command));
+        Ok(())
+    }
 
-        let mut inputs: smallvec::SmallVec<[_; 8]> = smallvec::smallvec![None; num_inputs];
+    // applies the commands that are due at the given sample, keeping the order they were sent in
+    fn apply_pending_commands(&mut self, sample: u64) {
+        let mut i = 0;
+        while i < self.pending_commands.len() {
+            if self.pending_commands[i].0 > sample {
+                i += 1;
+                continue;
+            }
+            let (_, command) = self.pending_commands.remove(i);
+            if let Some(node) = self.apply_command(command) {
+                self.replaced_nodes.push(node);
+            }
+        }
+    }
 
-        let mut buffers = self.buffer_cache.remove(&node_id).unwrap();
+    // the number of samples from the given sample until the next pending command is due
+    fn next_command_offset(&self, sample: u64) -> Option<usize> {
+        self.pending_commands
+            .iter()
+            .map(|&(due, _)| due.saturating_sub(sample) as usize)
+            .min()
+    }
 
-        for (source_id, edge) in self
-            .graph
-            .digraph()
-            .edges_directed(node_id, Direction::Incoming)
-            .map(|edge| (edge.source(), edge.weight()))
-        {
-            let source_buffers = self.buffer_cache.get(&source_id).unwrap();
-            let buffer = &source_buffers.outputs[edge.source_output as usize];
+    // processes a block in parts, applying the commands due at the start of each part
+    fn process_split(&mut self, start: u64) -> RuntimeResult<()> {
+        let block_size = self.block_size;
 
-            inputs[edge.target_input as usize] = Some(buffer);
+        for (split, node) in self.split_inputs.iter_mut().zip(self.graph.input_indices()) {
+            if let SignalBuffer::Float(buffer) = &self.buffer_cache[node].outputs[0] {
+                split[..block_size].copy_from_slice(&buffer[..block_size]);
+            }
         }
 
-        let node = self.graph.digraph.node_weight_mut(node_id).unwrap();
+        let mut offset = 0;
+        while offset < block_size {
+            if offset > 0 {
+                self.apply_pending_commands(start + offset as u64);
+            }
+            let end = match self.next_command_offset(start) {
+                Some(next) if next > offset => next.min(block_size),
+                _ => block_size,
+            };
+            let len = end - offset;
 
-        if inputs.spilled() {
-            debug_once!(format!("{}_spilled", node_id.index()) => "Input array for {} ({}) spilled over to the heap (has {} inputs > 8)", node.name(), node_id.index(), num_inputs);
-        }
+            self.set_block_len(len)?;
+            for (split, node) in self.split_inputs.iter().zip(self.graph.input_indices()) {
+                let buffers = self.buffer_cache.get_mut(node).unwrap();
+                if let SignalBuffer::Float(buffer) = &mut buffers.outputs[0] {
+                    buffer.copy_from_slice(&split[offset..end]);
+                }
+            }
 
-        let result = node.process(
-            ProcessorInputs::new(
-                &buffers.input_spec,
-                &inputs[..],
-                &self.graph.assets,
-                mode,
-                self.sample_rate,
-                self.block_size,
-            ),
-            ProcessorOutputs::new(&buffers.output_spec, &mut buffers.outputs, mode),
-        );
+            self.process_block()?;
 
-        if let Err(err) = result {
-            let node = self.graph.digraph.node_weight(node_id).unwrap();
-            log::error!("Error processing node {}: {:?}", node.name(), err);
-            let error = GraphRunError {
-                node_index: node_id,
-                node_processor: node.name().to_string(),
-                signal_type: GraphRunErrorType::ProcessorError(err),
-            };
-            return Err(RuntimeError::GraphRunError(error));
-        }
+            for (split, node) in self
+                .split_outputs
+                .iter_mut()
+                .zip(self.graph.output_indices())
+            {
+                if let SignalBuffer::Float(buffer) = &self.buffer_cache[node].outputs[0] {
+                    split[offset..end].copy_from_slice(buffer);
+                }
+            }
 
-        drop(inputs);
+            offset = end;
+        }
 
-        self.buffer_cache.insert(node_id, buffers);
+        self.set_block_len(block_size)?;
+        for (split, node) in self.split_inputs.iter().zip(self.graph.input_indices()) {
+            let buffers = self.buffer_cache.get_mut(node).unwrap();
+            if let SignalBuffer::Float(buffer) = &mut buffers.outputs[0] {
+                buffer.copy_from_slice(&split[..block_size]);
+            }
+        }
+        for (split, node) in self.split_outputs.iter().zip(self.graph.output_indices()) {
+            let buffers = self.buffer_cache.get_mut(node).unwrap();
+            if let SignalBuffer::Float(buffer) = &mut buffers.outputs[0] {
+                buffer.copy_from_slice(&split[..block_size]);
+            }
+        }
 
         Ok(())
     }
 
-    /// Returns a reference to the runtime's input buffer for the given input index.
-    #[inline]
-    pub fn get_input_mut(&mut self, input_index: usize) -> Option<&mut SignalBuffer> {
+    // runs the audio graph for one block of samples
+    #[cfg(not(feature = "parallel"))]
+    #[cfg_attr(feature = "profiling", inline(never))]
+    fn process_block(&mut self) -> RuntimeResult<()> {
+        self.transport_state = self.graph.transport().begin_block();
+
+        for i in 0..self.graph.schedule.groups.len() {
+            let group = self.graph.schedule.groups[i].clone();
+            if group.len() == 1 {
+                self.process_node(group.start, ProcessMode::Block)?;
+            } else {
+                for sample_index in 0..self.block_size {
+                    for position in group.clone() {
+                        self.process_node(position, ProcessMode::Sample(sample_index))?;
+                    }
+                }
+            }
+        }
+
+        self.graph
+            .transport()
+            .end_block(&self.transport_state, self.block_size, self.sample_rate);
+
+        #[cfg(feature = "profiling")]
+        self.graph.finish_profile_block();
+
+        Ok(())
+    }
+
+    // runs the audio graph for one block of samples, processing nodes that do not depend on each other concurrently
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(feature = "profiling", inline(never))]
+    fn process_block(&mut self) -> RuntimeResult<()> {
+        self.transport_state = self.graph.transport().begin_block();
+
+        for wave_index in 0..self.graph.parallel_schedule.waves.len() {
+            self.process_wave(wave_index)?;
+
+            for i in 0..self.graph.parallel_schedule.waves[wave_index]
+                .feedback
+                .len()
+            {
+                let group = self.graph.parallel_schedule.waves[wave_index].feedback[i].clone();
+                for sample_index in 0..self.block_size {
+                    for position in group.clone() {
+                        self.process_node(position, ProcessMode::Sample(sample_index))?;
+                    }
+                }
+            }
+        }
+
+        self.graph
+            .transport()
+            .end_block(&self.transport_state, self.block_size, self.sample_rate);
+
+        #[cfg(feature = "profiling")]
+        self.graph.finish_profile_block();
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(feature = "profiling", inline(never))]
+    fn process_wave(&mut self, wave_index: usize) -> RuntimeResult<()> {
+        use rayon::prelude::*;
+
+        let wave = &self.graph.parallel_schedule.waves[wave_index];
+
+        // move the wave's buffers out of the cache so they can be written to while the rest are read from
+        for (node_id, in_place) in wave.nodes.iter().zip(&wave.in_place) {
+            let mut buffers = self.buffer_cache.remove(node_id).unwrap();
+            // take over the buffer of the first input, which nothing else reads
+            if let Some((source_id, source_output)) = *in_place {
+                let source_buffers = self.buffer_cache.get_mut(&source_id).unwrap();
+                std::mem::swap(
+                    &mut buffers.outputs[0],
+                    &mut source_buffers.outputs[source_output as usize],
+                );
+            }
+            self.wave_buffers.push((*node_id, buffers));
+        }
+
+        let buffer_cache = &self.buffer_cache;
+        let assets = &self.graph.assets;
+        let transport = &self.transport_state;
+        let sample_rate = self.sample_rate;
+        let block_size = self.block_size;
+
+        // `node_weights_mut()` yields the nodes in storage order, so pick out the ones in this wave by rank
+        let mut ranks = wave.ranks.iter().peekable();
+        let nodes = self
+            .graph
+            .digraph
+            .node_weights_mut()
+            .enumerate()
+            .filter_map(move |(rank, node)| {
+                if ranks.next_if_eq(&&rank).is_some() {
+                    Some(node)
+                } else {
+                    None
+                }
+            });
+
+        let result = nodes
+            .zip(self.wave_buffers.iter_mut())
+            .zip(wave.inputs.iter().zip(&wave.in_place))
+            .par_bridge()
+            .try_for_each(|((node, (node_id, buffers)), (connections, in_place))| {
+                let mut inputs: smallvec::SmallVec<[_; 8]> =
+                    smallvec::smallvec![None; buffers.input_spec.len()];
+
+                for &(target_input, source_id, source_output) in connections {
+                    if in_place.is_some() && target_input == 0 {
+                        continue;
+                    }
+                    let source_buffers = &buffer_cache[&source_id];
+                    inputs[target_input as usize] =
+                        Some(&source_buffers.outputs[source_output as usize]);
+                }
+
+                let mut processor_inputs = ProcessorInputs::new(
+                    &buffers.input_spec,
+                    &inputs[..],
+                    assets,
+                    ProcessMode::Block,
+                    sample_rate,
+                    block_size,
+                    transport,
+                );
+                processor_inputs.in_place = in_place.is_some();
+
+                node.process(
+                    processor_inputs,
+                    ProcessorOutputs::new(
+                        &buffers.output_spec,
+                        &mut buffers.outputs,
+                        ProcessMode::Block,
+                    ),
+                )
+                .map_err(|err| {
+                    log::error!("Error processing node {}: {:?}", node.name(), err);
+                    GraphRunError {
+                        node_index: *node_id,
+                        node_processor: node.name().to_string(),
+                        signal_type: GraphRunErrorType::ProcessorError(err),
+                    }
+                })
+            });
+
+        for (node_id, buffers) in self.wave_buffers.drain(..) {
+            self.buffer_cache.insert(node_id, buffers);
+        }
+
+        result?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "profiling", inline(never))]
+    // processes the node at the given position in the graph's schedule
+    fn process_node(&mut self, position: usize, mode: ProcessMode) -> RuntimeResult<()> {
+        let node_id = self.graph.schedule.nodes[position];
+        let num_inputs = self.buffer_cache[&node_id].input_spec.len();
+
+        let mut inputs: smallvec::SmallVec<[_; 8]> = smallvec::smallvec![None; num_inputs];
+
+        let mut buffers = self.buffer_cache.remove(&node_id).unwrap();
+
+        // take over the buffer of the first input, which nothing else reads
+        let in_place = self.graph.schedule.in_place[position];
+        if let Some((source_id, source_output)) = in_place {
+            let source_buffers = self.buffer_cache.get_mut(&source_id).unwrap();
+            std::mem::swap(
+                &mut buffers.outputs[0],
+                &mut source_buffers.outputs[source_output as usize],
+            );
+        }
+
+        for &(target_input, source_id, source_output) in &self.graph.schedule.inputs[position] {
+            if in_place.is_some() && target_input == 0 {
+                continue;
+            }
+            let source_buffers = self.buffer_cache.get(&source_id).unwrap();
+            let buffer = &source_buffers.outputs[source_output as usize];
+
+            inputs[target_input as usize] = Some(buffer);
+        }
+
+        let node = self.graph.digraph.node_weight_mut(node_id).unwrap();
+
+        if inputs.spilled() {
+            debug_once!(format!("{}_spilled", node_id.index()) => "Input array for {} ({}) spilled over to the heap (has {} inputs > 8)", node.name(), node_id.index(), num_inputs);
+        }
+
+        let mut processor_inputs = ProcessorInputs::new(
+            &buffers.input_spec,
+            &inputs[..],
+            &self.graph.assets,
+            mode,
+            self.sample_rate,
+            self.block_size,
+            &self.transport_state,
+        );
+        processor_inputs.in_place = in_place.is_some();
+
+        let result = node.process(
+            processor_inputs,
+            ProcessorOutputs::new(&buffers.output_spec, &mut buffers.outputs, mode),
+        );
+
+        if let Err(err) = result {
+            let node = self.graph.digraph.node_weight(node_id).unwrap();
+            log::error!("Error processing node {}: {:?}", node.name(), err);
+            let error = GraphRunError {
+                node_index: node_id,
+                node_processor: node.name().to_string(),
+                signal_type: GraphRunErrorType::ProcessorError(err),
+            };
+            return Err(RuntimeError::GraphRunError(error));
+        }
+
+        drop(inputs);
+
+        self.buffer_cache.insert(node_id, buffers);
+
+        Ok(())
+    }
+
+    /// Returns a reference to the runtime's input buffer for the given input index.
+    #[inline]
+    pub fn get_input_mut(&mut self, input_index: usize) -> Option<&mut SignalBuffer> {
         self.buffer_cache
             .get_mut(self.graph.input_indices().get(input_index)?)
             .map(|buffers| &mut buffers.outputs[0])
     }
 
+    /// Copies the given samples into the runtime's input buffer for the given input index, to be processed by the next call to [`Runtime::process`].
+    ///
+    /// This is how audio captured outside of the runtime (e.g. a microphone stream in a browser) is fed into the graph's audio inputs.
+    /// Samples past the current block size are ignored. If there are fewer samples than the block size, the rest of the block is silent.
+    pub fn set_input(&mut self, input_index: usize, samples: &[Float]) -> RuntimeResult<()> {
+        let Some(SignalBuffer::Float(buffer)) = self.get_input_mut(input_index) else {
+            return Err(RuntimeError::InputNotFound(input_index));
+        };
+        for (i, sample) in buffer.iter_mut().enumerate() {
+            *sample = Some(samples.get(i).copied().unwrap_or_default());
+        }
+        Ok(())
+    }
+
     /// Returns a reference to the runtime's output buffer for the given output index.
     #[inline]
     pub fn get_output(&self, output_index: usize) -> Option<&SignalBuffer> {
@@ -342,6 +1207,51 @@ impl Runtime {
             .map(|buffers| &buffers.outputs[0])
     }
 
+    /// Returns a reference to the buffer holding the given output of the given node, as of the last processed block.
+    ///
+    /// If the output is only connected to a node that processes it in place (see [`Processor::can_process_in_place`]), its buffer is taken over by that node,
+    /// and the buffer returned here holds stale values. Connecting the output to another node as well (e.g. a meter) keeps it intact.
+    #[inline]
+    pub fn get_node_output(&self, node: NodeIndex, output_index: usize) -> Option<&SignalBuffer> {
+        self.buffer_cache.get(&node)?.outputs.get(output_index)
+    }
+
+    /// Returns the peak absolute value of the given output of the given node over the last processed block.
+    ///
+    /// Returns `None` if the output doesn't exist or isn't a [`Float`] output. Empty samples are skipped.
+    /// See [`Runtime::get_node_output`] for outputs that are processed in place.
+    pub fn node_peak(&self, node: NodeIndex, output_index: usize) -> Option<Float> {
+        let Some(SignalBuffer::Float(buffer)) = self.get_node_output(node, output_index) else {
+            return None;
+        };
+        Some(
+            buffer[..self.block_size]
+                .iter()
+                .flatten()
+                .fold(0.0, |peak: Float, sample| peak.max(sample.abs())),
+        )
+    }
+
+    /// Returns the RMS level of the given output of the given node over the last processed block.
+    ///
+    /// Returns `None` if the output doesn't exist or isn't a [`Float`] output. Empty samples are skipped.
+    /// See [`Runtime::get_node_output`] for outputs that are processed in place.
+    pub fn node_rms(&self, node: NodeIndex, output_index: usize) -> Option<Float> {
+        let Some(SignalBuffer::Float(buffer)) = self.get_node_output(node, output_index) else {
+            return None;
+        };
+        let (sum, count) = buffer[..self.block_size]
+            .iter()
+            .flatten()
+            .fold((0.0, 0), |(sum, count), sample| {
+                (sum + sample * sample, count + 1)
+            });
+        if count == 0 {
+            return Some(0.0);
+        }
+        Some((sum / count as Float).sqrt())
+    }
+
     /// Returns a reference to the [`Param`] with the given name.
     #[inline]
     pub fn param_named(&self, name: &str) -> Option<&Param> {
@@ -421,6 +1331,33 @@ impl Runtime {
         Ok(outputs)
     }
 
+    /// Returns how renders written to files are normalized, if at all.
+    pub fn normalization(&self) -> Option<Normalization> {
+        self.normalization
+    }
+
+    /// Sets how renders written to files (such as by [`Runtime::run_offline_to_file`]) are normalized.
+    ///
+    /// The whole render is measured before it is written, so it is scaled by a single gain.
+    /// [`Runtime::run_offline`] never normalizes its output, since it may be called for consecutive parts of a longer render, but [`Normalization::apply`] can be used on the result.
+    pub fn set_normalization(&mut self, normalization: Option<Normalization>) {
+        self.normalization = normalization;
+    }
+
+    // runs the graph offline for a render that is written to a file, normalizing it if requested
+    fn render(
+        &mut self,
+        duration: Duration,
+        sample_rate: Float,
+        block_size: usize,
+    ) -> RuntimeResult<Box<[Box<[Float]>]>> {
+        let mut outputs = self.run_offline(duration, sample_rate, block_size)?;
+        if let Some(normalization) = self.normalization {
+            normalization.apply(&mut outputs, sample_rate);
+        }
+        Ok(outputs)
+    }
+
     /// Runs the audio graph offline for the given duration and sample rate, writing the output to a file.
     pub fn run_offline_to_file(
         &mut self,
@@ -429,7 +1366,7 @@ impl Runtime {
         sample_rate: Float,
         block_size: usize,
     ) -> RuntimeResult<()> {
-        let outputs = self.run_offline(duration, sample_rate, block_size)?;
+        let outputs = self.render(duration, sample_rate, block_size)?;
 
         let num_channels = outputs.len();
 
@@ -467,6 +1404,102 @@ impl Runtime {
         Ok(())
     }
 
+    /// Runs the audio graph offline for the given duration and sample rate, writing the output to a FLAC file.
+    pub fn run_offline_to_flac(
+        &mut self,
+        file_path: impl AsRef<std::path::Path>,
+        settings: FlacSettings,
+        duration: Duration,
+        sample_rate: Float,
+        block_size: usize,
+    ) -> RuntimeResult<()> {
+        let outputs = self.render(duration, sample_rate, block_size)?;
+
+        if outputs.is_empty() {
+            log::warn!("No output channels to write to file");
+            return Ok(());
+        }
+
+        let mut writer =
+            FlacFileOut::create(file_path, outputs.len(), sample_rate as u32, settings)?;
+
+        for sample_index in 0..outputs[0].len() {
+            for output in outputs.iter() {
+                writer.write_sample(output[sample_index])?;
+            }
+        }
+
+        writer.finalize()?;
+
+        Ok(())
+    }
+
+    /// Runs the audio graph offline for the given duration and sample rate, writing the output to an Ogg Vorbis file.
+    ///
+    /// See [`OggFileOut`] for details on the format.
+    #[cfg(feature = "ogg")]
+    pub fn run_offline_to_ogg(
+        &mut self,
+        file_path: impl AsRef<std::path::Path>,
+        settings: VorbisSettings,
+        duration: Duration,
+        sample_rate: Float,
+        block_size: usize,
+    ) -> RuntimeResult<()> {
+        let outputs = self.render(duration, sample_rate, block_size)?;
+
+        if outputs.is_empty() {
+            log::warn!("No output channels to write to file");
+            return Ok(());
+        }
+
+        let mut writer =
+            OggFileOut::create(file_path, outputs.len(), sample_rate as u32, settings)?;
+
+        for sample_index in 0..outputs[0].len() {
+            for output in outputs.iter() {
+                writer.write_sample(output[sample_index])?;
+            }
+        }
+
+        writer.finalize()?;
+
+        Ok(())
+    }
+
+    /// Runs the audio graph offline for the given duration and sample rate, writing the output to an Ogg FLAC file.
+    ///
+    /// See [`OggFlacFileOut`] for details on the format.
+    #[cfg(feature = "ogg")]
+    pub fn run_offline_to_ogg_flac(
+        &mut self,
+        file_path: impl AsRef<std::path::Path>,
+        settings: FlacSettings,
+        duration: Duration,
+        sample_rate: Float,
+        block_size: usize,
+    ) -> RuntimeResult<()> {
+        let outputs = self.render(duration, sample_rate, block_size)?;
+
+        if outputs.is_empty() {
+            log::warn!("No output channels to write to file");
+            return Ok(());
+        }
+
+        let mut writer =
+            OggFlacFileOut::create(file_path, outputs.len(), sample_rate as u32, settings)?;
+
+        for sample_index in 0..outputs[0].len() {
+            for output in outputs.iter() {
+                writer.write_sample(output[sample_index])?;
+            }
+        }
+
+        writer.finalize()?;
+
+        Ok(())
+    }
+
     /// Runs the audio graph in real-time for the given duration.
     pub fn run_for(
         &mut self,
@@ -482,150 +1515,500 @@ impl Runtime {
     }
 
     /// Starts running the audio graph in real-time. Returns a [`RuntimeHandle`] that can be used to stop the runtime.
+    ///
+    /// If the graph has any audio inputs, they will be fed from the backend's default input device.
     pub fn run(
         &mut self,
         backend: AudioBackend,
         device: AudioDevice,
         midi_port: Option<MidiPort>,
     ) -> RuntimeResult<RuntimeHandle> {
-        let (kill_tx, kill_rx) = mpsc::channel();
+        self.run_with_input(backend, device, AudioDevice::Default, midi_port)
+    }
 
-        let host_id = match backend {
-            AudioBackend::Default => cpal::default_host().id(),
-            #[cfg(target_os = "linux")]
-            AudioBackend::Alsa => cpal::available_hosts()
-                .into_iter()
-                .find(|h| *h == cpal::HostId::Alsa)
-                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
-            #[cfg(all(target_os = "linux", feature = "jack"))]
-            AudioBackend::Jack => cpal::available_hosts()
-                .into_iter()
-                .find(|h| *h == cpal::HostId::Jack)
-                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
-            #[cfg(target_os = "windows")]
-            AudioBackend::Wasapi => cpal::available_hosts()
-                .into_iter()
-                .find(|h| *h == cpal::HostId::Wasapi)
-                .ok_or(RuntimeError::HostUnavailable(cpal::HostUnavailable))?,
-        };
-        let host = cpal::host_from_id(host_id)?;
+    /// Starts running the audio graph in real-time, requesting the given stream configuration from the output device. Returns a [`RuntimeHandle`] that can be used to stop the runtime.
+    ///
+    /// If the graph has any audio inputs, they will be fed from the backend's default input device.
+    pub fn run_with_config(
+        &mut self,
+        backend: AudioBackend,
+        device: AudioDevice,
+        config: OutputConfig,
+        midi_port: Option<MidiPort>,
+    ) -> RuntimeResult<RuntimeHandle> {
+        self.start(
+            backend,
+            MultiOut::new().with_device(device).with_config(config),
+            InputSelection::Device(AudioDevice::Default),
+            midi_port,
+        )
+    }
 
-        log::info!("Using host: {:?}", host.id());
+    /// Starts running the audio graph in real-time, capturing audio from the given input device. Returns a [`RuntimeHandle`] that can be used to stop the runtime.
+    ///
+    /// Each input channel of the device is fed into the audio input of the graph with the same index.
+    /// The input device is only opened if the graph has at least one audio input.
+    pub fn run_with_input(
+        &mut self,
+        backend: AudioBackend,
+        device: AudioDevice,
+        input_device: AudioDevice,
+        midi_port: Option<MidiPort>,
+    ) -> RuntimeResult<RuntimeHandle> {
+        self.start(
+            backend,
+            MultiOut::new().with_device(device),
+            InputSelection::Device(input_device),
+            midi_port,
+        )
+    }
 
-        let cpal_device = match &device {
-            AudioDevice::Default => host.default_output_device(),
-            AudioDevice::Index(index) => host.output_devices().unwrap().nth(*index),
-            AudioDevice::Name(name) => host
-                .output_devices()
-                .unwrap()
-                .find(|d| d.name().unwrap().contains(name)),
-        };
+    /// Starts running the audio graph in real-time, capturing audio from and playing audio on the same device. Returns a [`RuntimeHandle`] that can be used to stop the runtime.
+    ///
+    /// The input stream is opened on the input side of the output device, at the same sample rate, so the two streams share a clock.
+    /// Each input channel of the device is fed into the audio input of the graph with the same index (see [`RuntimeHandle::input_channels`]).
+    /// Recorded frames are kept at most one block behind the rendered ones, so the round-trip latency stays constant.
+    pub fn run_duplex(
+        &mut self,
+        backend: AudioBackend,
+        device: AudioDevice,
+        midi_port: Option<MidiPort>,
+    ) -> RuntimeResult<RuntimeHandle> {
+        self.start(
+            backend,
+            MultiOut::new().with_device(device),
+            InputSelection::Duplex,
+            midi_port,
+        )
+    }
+
+    /// Starts running the audio graph in real-time, playing its outputs on several devices at once. Returns a [`RuntimeHandle`] that can be used to stop the runtime.
+    ///
+    /// The first device of the [`MultiOut`] drives the graph, and the others are fed through small ring buffers. See [`MultiOut`] for details.
+    ///
+    /// If the graph has any audio inputs, they will be fed from the backend's default input device.
+    pub fn run_multi(
+        &mut self,
+        backend: AudioBackend,
+        outputs: MultiOut,
+        midi_port: Option<MidiPort>,
+    ) -> RuntimeResult<RuntimeHandle> {
+        self.start(
+            backend,
+            outputs,
+            InputSelection::Device(AudioDevice::Default),
+            midi_port,
+        )
+    }
 
-        let cpal_device = cpal_device.ok_or(RuntimeError::DeviceUnavailable(device))?;
+    /// Starts running the audio graph as a standalone JACK client. Returns a [`RuntimeHandle`] that can be used to stop the runtime.
+    ///
+    /// Unlike [`AudioBackend::Jack`], which goes through cpal's generic devices, this registers a JACK port for every audio input and output of the graph,
+    /// named `in_` or `out_` followed by the name of the graph's input or output, so the graph can be patched freely in a JACK session.
+    /// The graph runs at the sample rate and buffer size of the JACK server. See [`JackConfig`] for the available settings.
+    ///
+    /// [`RuntimeHandle::pause_streams`] only pauses processing, since a JACK client can't be paused.
+    #[cfg(all(target_os = "linux", feature = "jack"))]
+    pub fn run_jack(
+        &mut self,
+        config: JackConfig,
+        midi_port: Option<MidiPort>,
+    ) -> RuntimeResult<RuntimeHandle> {
+        let (client, status) =
+            jack::Client::new(&config.client_name, jack::ClientOptions::NO_START_SERVER)?;
 
-        log::info!("Using device: {}", cpal_device.name()?);
+        log::info!("Opened JACK client {} ({:?})", client.name(), status);
 
-        let config = cpal_device.default_output_config()?;
+        let sample_rate = client.sample_rate() as Float;
+        let buffer_size = client.buffer_size() as usize;
+        self.allocate_for_block_size(sample_rate, buffer_size.max(sample_rate as usize / 10));
 
-        let channels = config.channels();
-        if self.graph.num_audio_outputs() != channels as usize {
-            return Err(RuntimeError::ChannelMismatch(
-                self.graph.num_audio_outputs(),
-                channels as usize,
-            ));
+        let mut inputs = vec![];
+        for i in 0..self.graph.num_audio_inputs() {
+            let name = format!("in_{}", self.graph.audio_input_name(i).unwrap());
+            inputs.push(client.register_port(&name, jack::AudioIn)?);
+        }
+        let mut outputs = vec![];
+        for i in 0..self.graph.num_audio_outputs() {
+            let name = format!("out_{}", self.graph.audio_output_name(i).unwrap());
+            outputs.push(client.register_port(&name, jack::AudioOut)?);
+        }
+        let input_names = inputs
+            .iter()
+            .map(|port| port.name())
+            .collect::<Result<Vec<_>, _>>()?;
+        let output_names = outputs
+            .iter()
+            .map(|port| port.name())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        #[cfg(feature = "midi")]
+        let midi_in = midi_port
+            .map(|midi_port| self.connect_midi(midi_port))
+            .transpose()?;
+
+        #[cfg(not(feature = "midi"))]
+        if midi_port.is_some() {
+            log::warn!("MIDI input was requested, but the `midi` feature is disabled");
         }
 
-        log::info!("Configuration: {:#?}", config);
+        let (kill_tx, kill_rx) = mpsc::channel();
+        let (stream_tx, stream_rx) = mpsc::channel();
+        let (handle, updates, xrun_rx) = self.new_handle(kill_tx, stream_tx, inputs.len());
 
-        let audio_rate = config.sample_rate().0 as Float;
+        #[cfg(feature = "midi")]
+        {
+            *handle.midi_in.lock().unwrap() = midi_in;
+        }
 
-        let midi_connection = midir::MidiInput::new("raug midir input")?;
+        let mut runtime = self.for_audio_thread();
+        runtime.reserve_commands();
+
+        let process = JackProcess {
+            runtime,
+            inputs,
+            outputs,
+            updates,
+            transport_sync: config.transport_sync,
+            last_block_size: 0,
+            latency: handle.latency.clone(),
+            xruns: handle.xruns.clone(),
+            playing: handle.playing.clone(),
+        };
+        let notifications = JackNotifications {
+            xruns: handle.xruns.clone(),
+        };
+        let client = client.activate_async(notifications, process)?;
+
+        if config.auto_connect {
+            let physical = |flags| {
+                client.as_client().ports(
+                    None,
+                    Some("32 bit float mono audio"),
+                    jack::PortFlags::IS_PHYSICAL | flags,
+                )
+            };
+            let captures = physical(jack::PortFlags::IS_OUTPUT);
+            let playbacks = physical(jack::PortFlags::IS_INPUT);
+            let connections = captures
+                .iter()
+                .zip(&input_names)
+                .chain(output_names.iter().zip(&playbacks));
+            for (source, destination) in connections {
+                if let Err(err) = client
+                    .as_client()
+                    .connect_ports_by_name(source, destination)
+                {
+                    log::warn!("Failed to connect {} to {}: {}", source, destination, err);
+                }
+            }
+        }
+
+        let xrun_hook = handle.xrun_hook.clone();
+
+        std::thread::spawn(move || {
+            loop {
+                if kill_rx.try_recv().is_ok() {
+                    if let Err(err) = client.deactivate() {
+                        log::error!("Failed to deactivate JACK client: {}", err);
+                    }
+                    break;
+                }
+
+                // a JACK client can't be paused, so only processing is paused
+                for _ in stream_rx.try_iter() {}
+
+                report_xruns(&xrun_rx, &xrun_hook);
 
-        let midi_port = if let Some(midi_port) = midi_port {
-            let midi_port = match &midi_port {
-                MidiPort::Default => midi_connection.ports().into_iter().next(),
-                MidiPort::Index(index) => midi_connection.ports().into_iter().nth(*index),
-                MidiPort::Name(name) => midi_connection
-                    .ports()
-                    .into_iter()
-                    .find(|port| midi_connection.port_name(port).unwrap().contains(name)),
+                std::thread::yield_now();
             }
-            .ok_or(RuntimeError::MidiPortUnavailable(midi_port))?;
+        });
 
-            log::info!(
-                "Using MIDI port: {:?}",
-                midi_connection
-                    .port_name(&midi_port)
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("unknown")
-            );
+        Ok(handle)
+    }
 
-            Some(midi_port)
-        } else {
-            None
-        };
+    fn start(
+        &mut self,
+        backend: AudioBackend,
+        outputs: MultiOut,
+        input: InputSelection,
+        midi_port: Option<MidiPort>,
+    ) -> RuntimeResult<RuntimeHandle> {
+        let (kill_tx, kill_rx) = mpsc::channel();
+        let (stream_tx, stream_rx) = mpsc::channel();
 
-        self.allocate_for_block_size(audio_rate, audio_rate as usize / 10);
+        let host = backend.host()?;
 
-        let audio_runtime = self.clone();
-        let midi_runtime = self.clone();
+        log::info!("Using host: {:?}", host.id());
+
+        let num_outputs = self.graph.num_audio_outputs();
+
+        let mut devices = vec![];
+        let mut main_sample_rate = None;
+        for MultiOutDevice {
+            device,
+            channel_map,
+            mut config,
+        } in outputs.devices
+        {
+            let cpal_device = device.output_device(&backend, &host)?;
 
-        let midi_in = if let Some(midi_port) = midi_port {
-            let midi_in = midi_connection.connect(
-                &midi_port,
-                "raug midir input",
-                move |_stamp, message, _data| {
-                    log::debug!("MIDI message: {:2x?}", message);
+            log::info!("Using device: {}", cpal_device.name()?);
 
-                    for (_name, param) in midi_runtime.graph().midi_input_iter() {
-                        param.send(MidiMessage::new([message[0], message[1], message[2]]));
+            // every device runs at the sample rate of the first one
+            if config.sample_rate.is_none() {
+                config.sample_rate = main_sample_rate;
+            }
+            let (config, buffer_size) = config.negotiate(&cpal_device)?;
+            main_sample_rate.get_or_insert(config.sample_rate().0);
+            let channels = config.channels() as usize;
+
+            let channel_map = match channel_map {
+                Some(channel_map) => {
+                    if channel_map.len() > channels {
+                        return Err(RuntimeError::ChannelMismatch(channel_map.len(), channels));
+                    }
+                    if let Some(&index) = channel_map.iter().find(|&&index| index >= num_outputs) {
+                        return Err(RuntimeError::InvalidChannelMap(index, num_outputs));
+                    }
+                    channel_map
+                }
+                None => {
+                    if num_outputs != channels {
+                        return Err(RuntimeError::ChannelMismatch(num_outputs, channels));
                     }
-                },
-                (),
-            )?;
+                    (0..num_outputs).collect()
+                }
+            };
+
+            devices.push((cpal_device, config, buffer_size, channel_map));
+        }
+
+        if devices.is_empty() {
+            return Err(RuntimeError::NoOutputDevices);
+        }
+
+        let (cpal_device, config, buffer_size, output_map) = devices.remove(0);
 
-            Some(midi_in)
+        log::info!("Configuration: {:#?}", config);
+
+        let stream_config = cpal::StreamConfig {
+            buffer_size,
+            ..config.config()
+        };
+
+        let audio_rate = config.sample_rate().0 as Float;
+
+        let input = if self.graph.num_audio_inputs() > 0 {
+            let cpal_input_device = match input {
+                InputSelection::Device(input_device) => {
+                    input_device.input_device(&backend, &host)?
+                }
+                InputSelection::Duplex => {
+                    let name = cpal_device.name()?;
+                    host.input_devices()?
+                        .find(|d| d.name().is_ok_and(|n| n == name))
+                        .ok_or(RuntimeError::DeviceUnavailable(AudioDevice::Name(name)))?
+                }
+            };
+
+            log::info!("Using input device: {}", cpal_input_device.name()?);
+
+            let input_config = cpal_input_device.default_input_config()?;
+
+            log::info!("Input configuration: {:#?}", input_config);
+
+            Some((cpal_input_device, input_config))
         } else {
             None
         };
 
-        let handle = RuntimeHandle {
-            kill_tx,
-            midi_in: Arc::new(Mutex::new(midi_in)),
+        let max_block_size = match stream_config.buffer_size {
+            cpal::BufferSize::Fixed(frames) => (frames as usize).max(audio_rate as usize / 10),
+            cpal::BufferSize::Default => audio_rate as usize / 10,
         };
+        self.allocate_for_block_size(audio_rate, max_block_size);
+
+        let audio_runtime = self.for_audio_thread();
+
+        #[cfg(feature = "midi")]
+        let midi_in = midi_port
+            .map(|midi_port| self.connect_midi(midi_port))
+            .transpose()?;
+
+        #[cfg(not(feature = "midi"))]
+        if midi_port.is_some() {
+            log::warn!("MIDI input was requested, but the `midi` feature is disabled");
+        }
+
+        let input_channels = input
+            .as_ref()
+            .map(|(_, input_config)| input_config.channels() as usize)
+            .unwrap_or_default();
+        let (handle, updates, xrun_rx) = self.new_handle(kill_tx, stream_tx, input_channels);
+
+        #[cfg(feature = "midi")]
+        {
+            *handle.midi_in.lock().unwrap() = midi_in;
+        }
+
+        // captured input frames are sent to the output callback through a ring buffer,
+        // with enough room for a few blocks of latency
+        let num_inputs = self.graph.num_audio_inputs();
+        let (input_tx, input_queue) = if input.is_some() && num_inputs > 0 {
+            let (tx, rx) = rtrb::RingBuffer::new(self.max_block_size * num_inputs * 4);
+            (Some(tx), Some(InputQueue::new(num_inputs, rx)))
+        } else {
+            (None, None)
+        };
+
+        // the outputs of the other devices are sent to their own callbacks through ring buffers
+        let mut taps = vec![];
+        let mut tap_devices = vec![];
+        for (device, device_config, buffer_size, channel_map) in devices {
+            let (tx, rx) =
+                crossbeam_channel::bounded(outputs.buffer_frames * channel_map.len().max(1));
+            tap_devices.push((device, device_config, buffer_size, channel_map.len(), rx));
+            taps.push(OutputTap { tx, channel_map });
+        }
+        let prefill_frames = outputs.buffer_frames / 2;
+
+        let input_latency = handle.latency.clone();
+        let latency = handle.latency.clone();
+        let xruns = handle.xruns.clone();
+        let xrun_hook = handle.xrun_hook.clone();
+        let playing = handle.playing.clone();
 
         std::thread::spawn(move || -> RuntimeResult<()> {
+            let input_stream = match (input, input_tx) {
+                (Some((input_device, input_config)), Some(input_tx)) => {
+                    let input = InputTap {
+                        num_inputs,
+                        tx: input_tx,
+                        latency: input_latency,
+                        xruns: xruns.clone(),
+                    };
+                    // run the input stream at the same sample rate as the output stream
+                    let stream_config = cpal::StreamConfig {
+                        channels: input_config.channels(),
+                        sample_rate: config.sample_rate(),
+                        buffer_size: cpal::BufferSize::Default,
+                    };
+                    let stream = match input_config.sample_format() {
+                        cpal::SampleFormat::I8 => {
+                            run_input::<i8>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::I16 => {
+                            run_input::<i16>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::I32 => {
+                            run_input::<i32>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::I64 => {
+                            run_input::<i64>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::U8 => {
+                            run_input::<u8>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::U16 => {
+                            run_input::<u16>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::U32 => {
+                            run_input::<u32>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::U64 => {
+                            run_input::<u64>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::F32 => {
+                            run_input::<f32>(&input_device, &stream_config, input)?
+                        }
+                        cpal::SampleFormat::F64 => {
+                            run_input::<f64>(&input_device, &stream_config, input)?
+                        }
+
+                        sample_format => {
+                            return Err(RuntimeError::UnsupportedSampleFormat(sample_format));
+                        }
+                    };
+                    Some(stream)
+                }
+                _ => None,
+            };
+
+            let mut tap_streams = vec![];
+            for (device_idx, (device, device_config, buffer_size, num_channels, rx)) in
+                tap_devices.into_iter().enumerate()
+            {
+                let stream_config = cpal::StreamConfig {
+                    buffer_size,
+                    ..device_config.config()
+                };
+                let tap = TapStream {
+                    device_idx: device_idx + 1,
+                    num_channels,
+                    prefill_frames,
+                    rx,
+                    xruns: xruns.clone(),
+                };
+                let stream = match device_config.sample_format() {
+                    cpal::SampleFormat::I8 => run_tap::<i8>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::I16 => run_tap::<i16>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::I32 => run_tap::<i32>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::I64 => run_tap::<i64>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::U8 => run_tap::<u8>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::U16 => run_tap::<u16>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::U32 => run_tap::<u32>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::U64 => run_tap::<u64>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::F32 => run_tap::<f32>(&device, &stream_config, tap)?,
+                    cpal::SampleFormat::F64 => run_tap::<f64>(&device, &stream_config, tap)?,
+
+                    sample_format => {
+                        return Err(RuntimeError::UnsupportedSampleFormat(sample_format));
+                    }
+                };
+                tap_streams.push(stream);
+            }
+
+            let channels = StreamChannels {
+                input_queue,
+                updates,
+                output_map,
+                taps,
+                latency,
+                xruns,
+                playing,
+            };
+
             let stream = match config.sample_format() {
                 cpal::SampleFormat::I8 => {
-                    audio_runtime.run_inner::<i8>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<i8>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::I16 => {
-                    audio_runtime.run_inner::<i16>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<i16>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::I32 => {
-                    audio_runtime.run_inner::<i32>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<i32>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::I64 => {
-                    audio_runtime.run_inner::<i64>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<i64>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::U8 => {
-                    audio_runtime.run_inner::<u8>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<u8>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::U16 => {
-                    audio_runtime.run_inner::<u16>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<u16>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::U32 => {
-                    audio_runtime.run_inner::<u32>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<u32>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::U64 => {
-                    audio_runtime.run_inner::<u64>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<u64>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::F32 => {
-                    audio_runtime.run_inner::<f32>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<f32>(&cpal_device, &stream_config, channels)?
                 }
                 cpal::SampleFormat::F64 => {
-                    audio_runtime.run_inner::<f64>(&cpal_device, &config.config())?
+                    audio_runtime.run_inner::<f64>(&cpal_device, &stream_config, channels)?
                 }
 
                 sample_format => {
@@ -636,9 +2019,28 @@ impl Runtime {
             loop {
                 if kill_rx.try_recv().is_ok() {
                     drop(stream);
+                    drop(tap_streams);
+                    drop(input_stream);
                     break;
                 }
 
+                for play in stream_rx.try_iter() {
+                    let streams = std::iter::once(&stream)
+                        .chain(&tap_streams)
+                        .chain(&input_stream);
+                    for stream in streams {
+                        if play {
+                            if let Err(err) = stream.play() {
+                                log::warn!("Failed to resume stream: {}", err);
+                            }
+                        } else if let Err(err) = stream.pause() {
+                            log::warn!("Failed to pause stream: {}", err);
+                        }
+                    }
+                }
+
+                report_xruns(&xrun_rx, &xrun_hook);
+
                 std::thread::yield_now();
             }
 
@@ -648,63 +2050,1068 @@ impl Runtime {
         Ok(handle)
     }
 
+    // creates a handle for controlling this runtime once it is running on an audio thread
+    fn new_handle(
+        &self,
+        kill_tx: mpsc::Sender<()>,
+        stream_tx: mpsc::Sender<bool>,
+        input_channels: usize,
+    ) -> (
+        RuntimeHandle,
+        GraphUpdates,
+        crossbeam_channel::Receiver<Xrun>,
+    ) {
+        let (edit_tx, edit_rx) = crossbeam_channel::bounded(1);
+        let (garbage_tx, garbage_rx) = crossbeam_channel::bounded(8);
+        let (command_tx, command_rx) = crossbeam_channel::bounded(COMMAND_QUEUE_CAPACITY);
+        let (node_garbage_tx, node_garbage_rx) = crossbeam_channel::bounded(COMMAND_QUEUE_CAPACITY);
+
+        let (xrun_tx, xrun_rx) = crossbeam_channel::bounded(64);
+
+        let handle = RuntimeHandle {
+            kill_tx,
+            #[cfg(feature = "midi")]
+            midi_in: Arc::new(Mutex::new(None)),
+            graph: Arc::new(Mutex::new(self.graph.clone())),
+            transport: self.graph.transport().clone(),
+            edit_tx,
+            garbage_rx,
+            command_tx,
+            node_garbage_rx,
+            sample_rate: self.sample_rate,
+            max_block_size: self.max_block_size,
+            input_channels,
+            latency: Arc::new(Latency::default()),
+            xruns: Arc::new(XrunCounter::new(xrun_tx)),
+            xrun_hook: Arc::new(Mutex::new(None)),
+            playing: Arc::new(AtomicBool::new(true)),
+            stream_tx,
+        };
+
+        let updates = GraphUpdates {
+            edit_rx,
+            garbage_tx,
+            command_rx,
+            node_garbage_tx,
+        };
+
+        (handle, updates, xrun_rx)
+    }
+
+    /// Opens the given MIDI port and forwards every incoming message to the graph's MIDI inputs.
+    #[cfg(feature = "midi")]
+    fn connect_midi(&self, midi_port: MidiPort) -> RuntimeResult<midir::MidiInputConnection<()>> {
+        let midi_connection = midir::MidiInput::new("raug midir input")?;
+
+        let port = match &midi_port {
+            MidiPort::Default => midi_connection.ports().into_iter().next(),
+            MidiPort::Index(index) => midi_connection.ports().into_iter().nth(*index),
+            MidiPort::Name(name) => midi_connection.ports().into_iter().find(|port| {
+                midi_connection
+                    .port_name(port)
+                    .map(|port_name| port_name.contains(name))
+                    .unwrap_or(false)
+            }),
+        }
+        .ok_or(RuntimeError::MidiPortUnavailable(midi_port))?;
+
+        log::info!(
+            "Using MIDI port: {:?}",
+            midi_connection
+                .port_name(&port)
+                .as_ref()
+                .map(|s| s.as_str())
+                .unwrap_or("unknown")
+        );
+
+        let midi_inputs: Vec<Param> = self
+            .graph
+            .midi_input_iter()
+            .map(|(_name, param)| param)
+            .collect();
+
+        let midi_in = midi_connection.connect(
+            &port,
+            "raug midir input",
+            move |_stamp, message, _data| {
+                log::debug!("MIDI message: {:2x?}", message);
+
+                // system and running-status messages may be shorter than 3 bytes
+                let mut data = [0; 3];
+                for (byte, &value) in data.iter_mut().zip(message) {
+                    *byte = value;
+                }
+
+                for param in &midi_inputs {
+                    param.send(MidiMessage::new(data));
+                }
+            },
+            (),
+        )?;
+
+        Ok(midi_in)
+    }
+
+    // applies a command sent from a `RuntimeHandle`, returning the node it replaced, if any
+    fn apply_command(&mut self, command: QueuedCommand) -> Option<ProcessorNode> {
+        match command {
+            QueuedCommand::SetParam(index, value) => {
+                if let Some(param) = self.param_at(index) {
+                    param.send_any(value);
+                }
+            }
+            QueuedCommand::Trigger(index) => {
+                if let Some(param) = self.param_at(index) {
+                    param.send_any(AnySignal::Bool(Some(true)));
+                    param.send_any(AnySignal::Bool(None));
+                }
+            }
+            QueuedCommand::ReplaceNode(index, mut node) => {
+                // the graph may have been edited since the command was sent, in which case the edit already replaced the node
+                let old = self.graph.digraph_mut().node_weight_mut(index)?;
+                if old.instance() == node.instance() || !same_signal_types(old, &node) {
+                    return Some(node);
+                }
+                node.resize_buffers(self.sample_rate, self.block_size);
+                return Some(std::mem::replace(old, node));
+            }
+            QueuedCommand::SetTempo(bpm) => self.graph.transport().set_bpm(bpm),
+        }
+        None
+    }
+
+    fn param_at(&self, index: NodeIndex) -> Option<&Param> {
+        self.graph
+            .digraph()
+            .node_weight(index)?
+            .processor()
+            .downcast_ref()
+    }
+
     fn run_inner<T>(
         mut self,
         device: &cpal::Device,
         config: &cpal::StreamConfig,
+        channels: StreamChannels,
     ) -> RuntimeResult<cpal::Stream>
     where
         T: cpal::SizedSample + cpal::FromSample<Float>,
     {
+        let StreamChannels {
+            mut input_queue,
+            updates,
+            output_map,
+            taps,
+            latency,
+            xruns,
+            playing,
+        } = channels;
+
         let channels = config.channels as usize;
+        let sample_rate = config.sample_rate.0 as f64;
+
+        self.reserve_commands();
 
         let mut last_block_size = 0;
-        let stream = device
-            .build_output_stream(
-                config,
-                move |data: &mut [T], _info: &cpal::OutputCallbackInfo| {
-                    let block_size = data.len() / channels;
-                    if block_size != last_block_size {
-                        self.set_block_size(block_size).unwrap();
-                        last_block_size = block_size;
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], info: &cpal::OutputCallbackInfo| {
+                let started = std::time::Instant::now();
+
+                if updates.apply(&mut self) {
+                    last_block_size = 0;
+                }
+
+                let block_size = data.len() / channels;
+                if block_size != last_block_size {
+                    self.set_block_size(block_size).unwrap();
+                    last_block_size = block_size;
+                }
+
+                // the time until this block is played, or the length of the block if the backend doesn't report it
+                let timestamp = info.timestamp();
+                let output_latency = timestamp
+                    .playback
+                    .duration_since(&timestamp.callback)
+                    .unwrap_or_else(|| Duration::from_secs_f64(block_size as f64 / sample_rate));
+                latency.set_output(output_latency);
+
+                if !playing.load(Ordering::Acquire) {
+                    for sample in data.iter_mut() {
+                        *sample = T::from_sample(0.0);
+                    }
+                    // keep the other devices fed, so they don't report dropouts while paused
+                    for tap in &taps {
+                        let free = tap.tx.capacity().unwrap_or_default() - tap.tx.len();
+                        let frames = block_size.min(free / tap.channel_map.len().max(1));
+                        for _ in 0..frames * tap.channel_map.len() {
+                            tap.tx.try_send(0.0).ok();
+                        }
+                    }
+                    // captured input is discarded rather than played late
+                    if let Some(input_queue) = &mut input_queue {
+                        input_queue.clear();
                     }
+                    return;
+                }
+
+                if let Some(input_queue) = &mut input_queue {
+                    let queued_frames = input_queue.read_block(&mut self, block_size, &xruns);
+                    latency.set_input_queue(Duration::from_secs_f64(
+                        queued_frames as f64 / sample_rate,
+                    ));
+                }
 
-                    self.process().unwrap();
+                self.process().unwrap();
+
+                for (frame_idx, frame) in data.chunks_mut(channels).enumerate() {
+                    for (channel_idx, sample) in frame.iter_mut().enumerate() {
+                        // device channels without a graph output are left silent
+                        let Some(&output_idx) = output_map.get(channel_idx) else {
+                            *sample = T::from_sample(0.0);
+                            continue;
+                        };
+                        let buffer = self.get_output(output_idx);
+                        let Some(SignalBuffer::Float(buffer)) = buffer else {
+                            panic!("output {output_idx} signal type mismatch");
+                        };
+                        let value = buffer[frame_idx].unwrap_or_default();
+                        *sample = T::from_sample(value);
+                    }
+                }
 
-                    for (frame_idx, frame) in data.chunks_mut(channels).enumerate() {
-                        for (channel_idx, sample) in frame.iter_mut().enumerate() {
-                            let buffer = self.get_output(channel_idx);
-                            let Some(SignalBuffer::Float(buffer)) = buffer else {
-                                panic!("output {channel_idx} signal type mismatch");
+                for (tap_idx, tap) in taps.iter().enumerate() {
+                    for frame_idx in 0..block_size {
+                        // drop whole frames if the device isn't keeping up, so its channels stay aligned
+                        let free = tap.tx.capacity().unwrap_or_default() - tap.tx.len();
+                        if free < tap.channel_map.len() {
+                            xruns.report(Xrun::OutputOverrun(tap_idx + 1));
+                            break;
+                        }
+                        for &output_idx in &tap.channel_map {
+                            let value = match self.get_output(output_idx) {
+                                Some(SignalBuffer::Float(buffer)) => {
+                                    buffer[frame_idx].unwrap_or_default()
+                                }
+                                _ => 0.0,
                             };
-                            let value = buffer[frame_idx].unwrap_or_default();
-                            *sample = T::from_sample(value);
+                            tap.tx.try_send(value).ok();
                         }
                     }
-                },
-                |err| eprintln!("an error occurred on output: {}", err),
-                None,
-            )
-            .unwrap();
+                }
+
+                if started.elapsed().as_secs_f64() > block_size as f64 / sample_rate {
+                    xruns.report(Xrun::LateBlock);
+                }
+            },
+            |err| eprintln!("an error occurred on output: {}", err),
+            None,
+        )?;
 
-        stream.play().unwrap();
+        stream.play()?;
 
         Ok(stream)
     }
 }
 
-/// A handle to the runtime that can be used to stop it.
+// latency measurements shared between the audio callbacks and the runtime handle, in nanoseconds
+#[derive(Default)]
+struct Latency {
+    output: AtomicU64,
+    input_device: AtomicU64,
+    input_queue: AtomicU64,
+}
+
+impl Latency {
+    fn set_output(&self, latency: Duration) {
+        self.output
+            .store(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn set_input_device(&self, latency: Duration) {
+        self.input_device
+            .store(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn set_input_queue(&self, latency: Duration) {
+        self.input_queue
+            .store(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn output(&self) -> Duration {
+        Duration::from_nanos(self.output.load(Ordering::Relaxed))
+    }
+
+    fn input(&self) -> Duration {
+        Duration::from_nanos(
+            self.input_device.load(Ordering::Relaxed) + self.input_queue.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// The number of [`Command`]s that can be waiting for the audio thread at once.
+pub const COMMAND_QUEUE_CAPACITY: usize = 1024;
+
+/// A message for the audio thread, sent with [`RuntimeHandle::send`] or [`RuntimeHandle::send_at`].
+///
+/// Commands are applied in the order they were sent, between blocks, so commands sent together take effect on the same block.
+/// Commands sent with [`RuntimeHandle::send_at`] take effect on a given sample instead, splitting the block they fall in.
+/// Unlike [`RuntimeHandle::edit`], they don't rebuild the graph, so processors keep their state.
+#[derive(Debug)]
+pub enum Command {
+    /// Sets the [`Param`] with the given name to the given value. The value is cast to the signal type of the parameter.
+    SetParam(String, AnySignal),
+
+    /// Makes the boolean [`Param`] with the given name output `true` for the first sample after the command is applied, and nothing after that.
+    Trigger(String),
+
+    /// Replaces the processor of the given node. The new processor must have the same input and output types as the old one.
+    ReplaceNode(NodeIndex, Box<dyn Processor>),
+
+    /// Sets the tempo of the graph's [`Transport`], in beats per minute.
+    SetTempo(Float),
+}
+
+// a command that has been prepared on the sending thread, so that applying it doesn't allocate
+#[derive(Clone)]
+enum QueuedCommand {
+    SetParam(NodeIndex, AnySignal),
+    Trigger(NodeIndex),
+    ReplaceNode(NodeIndex, ProcessorNode),
+    SetTempo(Float),
+}
+
+// resolves a command against the given graph and allocates anything it needs
+fn prepare_command(
+    graph: &Graph,
+    command: Command,
+    sample_rate: Float,
+    max_block_size: usize,
+) -> RuntimeResult<QueuedCommand> {
+    let command = match command {
+        Command::SetParam(name, value) => {
+            let index = graph
+                .param_index(&name)
+                .ok_or_else(|| RuntimeError::ParamNotFound(name.clone()))?;
+            let signal_type = graph.param_named(&name).unwrap().signal_type();
+            let value = value
+                .cast(signal_type)
+                .ok_or(RuntimeError::ParamTypeMismatch(name, value.signal_type()))?;
+            QueuedCommand::SetParam(index, value)
+        }
+        Command::Trigger(name) => {
+            let index = graph
+                .param_index(&name)
+                .ok_or_else(|| RuntimeError::ParamNotFound(name.clone()))?;
+            if graph.param_named(&name).unwrap().signal_type() != SignalType::Bool {
+                return Err(RuntimeError::ParamTypeMismatch(name, SignalType::Bool));
+            }
+            QueuedCommand::Trigger(index)
+        }
+        Command::ReplaceNode(index, processor) => {
+            let old = graph
+                .digraph()
+                .node_weight(index)
+                .ok_or(RuntimeError::NodeNotFound(index))?;
+            let mut node = ProcessorNode::new_from_boxed(processor);
+            if !same_signal_types(old, &node) {
+                return Err(RuntimeError::NodeSpecMismatch(index));
+            }
+            node.allocate(sample_rate, max_block_size);
+            QueuedCommand::ReplaceNode(index, node)
+        }
+        Command::SetTempo(bpm) => QueuedCommand::SetTempo(bpm),
+    };
+    Ok(command)
+}
+
+// whether a node can replace another without reconnecting or reallocating its buffers
+fn same_signal_types(a: &ProcessorNode, b: &ProcessorNode) -> bool {
+    a.num_inputs() == b.num_inputs()
+        && a.num_outputs() == b.num_outputs()
+        && a.input_spec()
+            .iter()
+            .zip(b.input_spec())
+            .chain(a.output_spec().iter().zip(b.output_spec()))
+            .all(|(a, b)| a.signal_type == b.signal_type)
+}
+
+/// A dropout in one of the runtime's audio streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Xrun {
+    /// Processing a block took longer than the block lasts, so the output device probably ran out of audio to play.
+    LateBlock,
+    /// The input device hadn't captured enough frames for a block, and the missing frames were replaced by silence.
+    InputUnderrun,
+    /// Captured frames were dropped because the graph wasn't processing them fast enough.
+    InputOverrun,
+    /// The output device at the given index of a [`MultiOut`] ran out of audio and played silence until its buffer refilled.
+    OutputUnderrun(usize),
+    /// Frames for the output device at the given index of a [`MultiOut`] were dropped because it wasn't playing them fast enough.
+    OutputOverrun(usize),
+}
+
+impl Xrun {
+    /// Returns `true` if a stream ran out of audio, or `false` if audio was dropped.
+    pub fn is_underrun(&self) -> bool {
+        matches!(
+            self,
+            Xrun::LateBlock | Xrun::InputUnderrun | Xrun::OutputUnderrun(_)
+        )
+    }
+}
+
+/// The number of dropouts that have occurred in a running runtime. See [`RuntimeHandle::xruns`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XrunCounts {
+    /// The number of times a stream ran out of audio.
+    pub underruns: u64,
+    /// The number of times audio was dropped.
+    pub overruns: u64,
+}
+
+impl XrunCounts {
+    /// Returns the total number of dropouts.
+    pub fn total(&self) -> u64 {
+        self.underruns + self.overruns
+    }
+}
+
+type XrunHook = Box<dyn FnMut(Xrun) + Send>;
+
+// logs dropouts and passes them to the hook, off the audio thread
+fn report_xruns(xrun_rx: &crossbeam_channel::Receiver<Xrun>, xrun_hook: &Mutex<Option<XrunHook>>) {
+    for xrun in xrun_rx.try_iter() {
+        log::warn!("Audio dropout: {:?}", xrun);
+        if let Some(hook) = xrun_hook.lock().unwrap().as_mut() {
+            hook(xrun);
+        }
+    }
+}
+
+// the audio thread's ends of the channels a `RuntimeHandle` uses to update the running graph
+struct GraphUpdates {
+    edit_rx: crossbeam_channel::Receiver<Runtime>,
+    garbage_tx: crossbeam_channel::Sender<Runtime>,
+    command_rx: crossbeam_channel::Receiver<(u64, QueuedCommand)>,
+    node_garbage_tx: crossbeam_channel::Sender<ProcessorNode>,
+}
+
+impl GraphUpdates {
+    // applies pending edits between blocks and hands commands to the runtime, returning `true` if the runtime was replaced
+    fn apply(&self, runtime: &mut Runtime) -> bool {
+        // swap in an edited runtime, and send the old one back to be dropped off the audio thread
+        let replaced = if let Ok(mut edited) = self.edit_rx.try_recv() {
+            edited.carry_over_processors(runtime);
+            std::mem::swap(runtime, &mut edited);
+            // commands that aren't due yet carry over to the edited graph, swapping the queues so that neither has to grow
+            std::mem::swap(&mut runtime.pending_commands, &mut edited.pending_commands);
+            std::mem::swap(&mut runtime.replaced_nodes, &mut edited.replaced_nodes);
+            self.garbage_tx.try_send(edited).ok();
+            true
+        } else {
+            false
+        };
+
+        // only take as many commands as the runtime has room for, the rest wait in the queue
+        while runtime.pending_commands.len() < runtime.pending_commands.capacity() {
+            let Ok(command) = self.command_rx.try_recv() else {
+                break;
+            };
+            runtime.pending_commands.push(command);
+        }
+
+        for node in runtime.replaced_nodes.drain(..) {
+            self.node_garbage_tx.try_send(node).ok();
+        }
+
+        replaced
+    }
+}
+
+// dropout counters shared between the audio callbacks and the runtime handle
+struct XrunCounter {
+    underruns: AtomicU64,
+    overruns: AtomicU64,
+    tx: crossbeam_channel::Sender<Xrun>,
+}
+
+impl XrunCounter {
+    fn new(tx: crossbeam_channel::Sender<Xrun>) -> Self {
+        Self {
+            underruns: AtomicU64::new(0),
+            overruns: AtomicU64::new(0),
+            tx,
+        }
+    }
+
+    fn report(&self, xrun: Xrun) {
+        if xrun.is_underrun() {
+            self.underruns.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+        self.tx.try_send(xrun).ok();
+    }
+
+    fn counts(&self) -> XrunCounts {
+        XrunCounts {
+            underruns: self.underruns.load(Ordering::Relaxed),
+            overruns: self.overruns.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// where to capture audio from
+enum InputSelection {
+    Device(AudioDevice),
+    // the input side of the first output device
+    Duplex,
+}
+
+// channels used to communicate with the audio thread
+struct StreamChannels {
+    input_queue: Option<InputQueue>,
+    updates: GraphUpdates,
+    // the graph output played on each channel of the device
+    output_map: Vec<usize>,
+    taps: Vec<OutputTap>,
+    latency: Arc<Latency>,
+    xruns: Arc<XrunCounter>,
+    playing: Arc<AtomicBool>,
+}
+
+// the sending end of the ring buffer feeding one of the other devices of a `MultiOut`
+struct OutputTap {
+    tx: crossbeam_channel::Sender<Float>,
+    channel_map: Vec<usize>,
+}
+
+// the process callback of a standalone JACK client
+#[cfg(all(target_os = "linux", feature = "jack"))]
+struct JackProcess {
+    runtime: Runtime,
+    inputs: Vec<jack::Port<jack::AudioIn>>,
+    outputs: Vec<jack::Port<jack::AudioOut>>,
+    updates: GraphUpdates,
+    transport_sync: bool,
+    last_block_size: usize,
+    latency: Arc<Latency>,
+    xruns: Arc<XrunCounter>,
+    playing: Arc<AtomicBool>,
+}
+
+#[cfg(all(target_os = "linux", feature = "jack"))]
+impl JackProcess {
+    // copies the state of the JACK transport into the graph's transport
+    fn sync_transport(&self, client: &jack::Client) {
+        let Ok(jack::TransportStatePosition { pos, state }) = client.transport().query() else {
+            return;
+        };
+        let transport = self.runtime.transport();
+
+        if state == jack::TransportState::Rolling {
+            transport.play();
+        } else {
+            transport.stop();
+        }
+
+        if let Some(bbt) = pos.bbt() {
+            transport.set_bpm(bbt.bpm as Float);
+            transport.set_beats_per_bar(bbt.sig_num as u32);
+            let beat = (bbt.bar - 1) as Float * bbt.sig_num as Float
+                + (bbt.beat - 1) as Float
+                + bbt.tick as Float / bbt.ticks_per_beat as Float;
+            transport.seek(beat);
+        } else {
+            // without a timebase master, follow the frame position at the graph's own tempo
+            let seconds = pos.frame() as Float / self.runtime.sample_rate();
+            transport.seek(seconds * transport.bpm() / 60.0);
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "jack"))]
+impl jack::ProcessHandler for JackProcess {
+    fn process(&mut self, client: &jack::Client, ps: &jack::ProcessScope) -> jack::Control {
+        let started = std::time::Instant::now();
+
+        if self.updates.apply(&mut self.runtime) {
+            self.last_block_size = 0;
+        }
+
+        let block_size = ps.n_frames() as usize;
+        let sample_rate = self.runtime.sample_rate();
+        if block_size != self.last_block_size {
+            if self.runtime.set_block_size(block_size).is_err() {
+                // only happens to edited graphs after the JACK buffer size has grown
+                self.runtime
+                    .allocate_for_block_size(sample_rate, block_size);
+            }
+            self.last_block_size = block_size;
+        }
+
+        self.latency.set_output(Duration::from_secs_f64(
+            block_size as f64 / sample_rate as f64,
+        ));
+
+        if !self.playing.load(Ordering::Acquire) {
+            for port in &mut self.outputs {
+                port.as_mut_slice(ps).fill(0.0);
+            }
+            return jack::Control::Continue;
+        }
+
+        if self.transport_sync {
+            self.sync_transport(client);
+        }
+
+        for (input_idx, port) in self.inputs.iter().enumerate() {
+            if let Some(SignalBuffer::Float(buffer)) = self.runtime.get_input_mut(input_idx) {
+                for (sample, &value) in buffer.iter_mut().zip(port.as_slice(ps)) {
+                    *sample = Some(value as Float);
+                }
+            }
+        }
+
+        if let Err(err) = self.runtime.process() {
+            log::error!("Error processing graph: {}", err);
+        }
+
+        for (output_idx, port) in self.outputs.iter_mut().enumerate() {
+            let out = port.as_mut_slice(ps);
+            match self.runtime.get_output(output_idx) {
+                Some(SignalBuffer::Float(buffer)) => {
+                    for (sample, value) in out.iter_mut().zip(buffer.iter()) {
+                        *sample = value.unwrap_or_default() as f32;
+                    }
+                }
+                _ => out.fill(0.0),
+            }
+        }
+
+        if started.elapsed().as_secs_f64() > block_size as f64 / sample_rate as f64 {
+            self.xruns.report(Xrun::LateBlock);
+        }
+
+        jack::Control::Continue
+    }
+
+    fn buffer_size(&mut self, _: &jack::Client, size: jack::Frames) -> jack::Control {
+        // unlike `process`, this callback is allowed to allocate
+        if size as usize > self.runtime.max_block_size {
+            let sample_rate = self.runtime.sample_rate();
+            self.runtime
+                .allocate_for_block_size(sample_rate, size as usize);
+            self.last_block_size = 0;
+        }
+        jack::Control::Continue
+    }
+}
+
+// the notification callbacks of a standalone JACK client
+#[cfg(all(target_os = "linux", feature = "jack"))]
+struct JackNotifications {
+    xruns: Arc<XrunCounter>,
+}
+
+#[cfg(all(target_os = "linux", feature = "jack"))]
+impl jack::NotificationHandler for JackNotifications {
+    fn xrun(&mut self, _: &jack::Client) -> jack::Control {
+        self.xruns.report(Xrun::OutputUnderrun(0));
+        jack::Control::Continue
+    }
+}
+
+// the receiving end of the ring buffer feeding one of the other devices of a `MultiOut`
+struct TapStream {
+    // the index of the device in the `MultiOut`
+    device_idx: usize,
+    num_channels: usize,
+    prefill_frames: usize,
+    rx: crossbeam_channel::Receiver<Float>,
+    xruns: Arc<XrunCounter>,
+}
+
+fn run_tap<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    tap: TapStream,
+) -> RuntimeResult<cpal::Stream>
+where
+    T: cpal::SizedSample + cpal::FromSample<Float>,
+{
+    let channels = config.channels as usize;
+    let TapStream {
+        device_idx,
+        num_channels,
+        prefill_frames,
+        rx,
+        xruns,
+    } = tap;
+
+    // wait for the ring buffer to fill up halfway before playing, and again after it runs dry,
+    // so that differing block sizes and clock drift between the devices are absorbed
+    let mut primed = false;
+
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _info: &cpal::OutputCallbackInfo| {
+            for frame in data.chunks_mut(channels) {
+                if !primed && rx.len() >= prefill_frames.max(1) * num_channels {
+                    primed = true;
+                }
+                if primed && rx.len() < num_channels {
+                    primed = false;
+                    xruns.report(Xrun::OutputUnderrun(device_idx));
+                }
+
+                for (channel_idx, sample) in frame.iter_mut().enumerate() {
+                    let value = if primed && channel_idx < num_channels {
+                        rx.try_recv().unwrap_or_default()
+                    } else {
+                        0.0
+                    };
+                    *sample = T::from_sample(value);
+                }
+            }
+        },
+        |err| eprintln!("an error occurred on output: {}", err),
+        None,
+    )?;
+
+    stream.play()?;
+
+    Ok(stream)
+}
+
+// the producing end of the ring buffer feeding captured frames to the output callback
+struct InputTap {
+    num_inputs: usize,
+    tx: rtrb::Producer<Float>,
+    latency: Arc<Latency>,
+    xruns: Arc<XrunCounter>,
+}
+
+impl InputTap {
+    // queues the graph inputs of the given interleaved device frames in a single chunk
+    fn capture<T>(&mut self, data: &[T], channels: usize)
+    where
+        T: cpal::Sample,
+        Float: cpal::FromSample<T>,
+    {
+        let num_inputs = self.num_inputs;
+        let frames = data.len() / channels;
+
+        // drop whole frames if the output callback isn't keeping up, so the inputs stay aligned
+        let fits = frames.min(self.tx.slots() / num_inputs);
+        if fits < frames {
+            self.xruns.report(Xrun::InputOverrun);
+        }
+
+        let Ok(chunk) = self.tx.write_chunk_uninit(fits * num_inputs) else {
+            return;
+        };
+        chunk.fill_from_iter(data.chunks(channels).take(fits).flat_map(|frame| {
+            // graph inputs without a matching device channel receive silence
+            (0..num_inputs).map(move |input_idx| {
+                frame
+                    .get(input_idx)
+                    .map(|sample| sample.to_sample::<Float>())
+                    .unwrap_or_default()
+            })
+        }));
+    }
+}
+
+// the consuming end of the ring buffer of captured frames, read by the output callback
+struct InputQueue {
+    num_inputs: usize,
+    rx: rtrb::Consumer<Float>,
+    // the input stream may start a little after the output stream
+    started: bool,
+}
+
+impl InputQueue {
+    fn new(num_inputs: usize, rx: rtrb::Consumer<Float>) -> Self {
+        Self {
+            num_inputs,
+            rx,
+            started: false,
+        }
+    }
+
+    // drops every queued frame
+    fn clear(&mut self) {
+        if let Ok(chunk) = self.rx.read_chunk(self.rx.slots()) {
+            chunk.commit_all();
+        }
+    }
+
+    // copies the next block of captured frames into the graph's audio inputs, returning how many frames were queued
+    fn read_block(
+        &mut self,
+        runtime: &mut Runtime,
+        block_size: usize,
+        xruns: &XrunCounter,
+    ) -> usize {
+        let num_inputs = self.num_inputs;
+
+        // skip whole frames that piled up while the output was busy,
+        // so that the input stays at most one block behind and the round-trip latency stays constant
+        let mut queued_frames = self.rx.slots() / num_inputs;
+        if queued_frames > 2 * block_size {
+            xruns.report(Xrun::InputOverrun);
+            let skipped = queued_frames - 2 * block_size;
+            if let Ok(chunk) = self.rx.read_chunk(skipped * num_inputs) {
+                chunk.commit_all();
+            }
+            queued_frames -= skipped;
+        }
+
+        let frames = queued_frames.min(block_size);
+        let Ok(chunk) = self.rx.read_chunk(frames * num_inputs) else {
+            return queued_frames;
+        };
+        let (first, second) = chunk.as_slices();
+        for input_idx in 0..num_inputs {
+            let Some(SignalBuffer::Float(buffer)) = runtime.get_input_mut(input_idx) else {
+                continue;
+            };
+            let samples = first
+                .iter()
+                .chain(second)
+                .skip(input_idx)
+                .step_by(num_inputs);
+            for (sample, &value) in buffer.iter_mut().zip(samples) {
+                *sample = Some(value);
+            }
+            // if the input stream fell behind, fill the rest of the block with silence
+            for sample in &mut buffer[frames..block_size] {
+                *sample = Some(0.0);
+            }
+        }
+        chunk.commit_all();
+
+        self.started |= frames > 0;
+        if frames < block_size && self.started {
+            xruns.report(Xrun::InputUnderrun);
+        }
+
+        queued_frames
+    }
+}
+
+fn run_input<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut input: InputTap,
+) -> RuntimeResult<cpal::Stream>
+where
+    T: cpal::SizedSample,
+    Float: cpal::FromSample<T>,
+{
+    let channels = config.channels as usize;
+
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], info: &cpal::InputCallbackInfo| {
+            let timestamp = info.timestamp();
+            if let Some(input_latency) = timestamp.callback.duration_since(&timestamp.capture) {
+                input.latency.set_input_device(input_latency);
+            }
+
+            input.capture(data, channels);
+        },
+        |err| eprintln!("an error occurred on input: {}", err),
+        None,
+    )?;
+
+    stream.play()?;
+
+    Ok(stream)
+}
+
+/// A handle to the runtime that can be used to stop or edit it.
+///
+/// The handle and the audio thread don't share any locks. The audio thread owns the running [`Runtime`], while the handle keeps its own copy of the graph that edits are applied to.
+/// Edited runtimes and [`Command`]s reach the audio thread through bounded lock-free queues, which it polls between blocks without waiting,
+/// and whatever it replaces is sent back to be dropped on the handle's side.
 #[must_use = "The runtime handle must be kept alive for the runtime to continue running"]
 #[derive(Clone)]
 pub struct RuntimeHandle {
+    #[cfg(feature = "midi")]
     midi_in: Arc<Mutex<Option<midir::MidiInputConnection<()>>>>,
     kill_tx: mpsc::Sender<()>,
+
+    // the latest version of the graph, which edits are applied to
+    graph: Arc<Mutex<Graph>>,
+    transport: Transport,
+    edit_tx: crossbeam_channel::Sender<Runtime>,
+    garbage_rx: crossbeam_channel::Receiver<Runtime>,
+    command_tx: crossbeam_channel::Sender<(u64, QueuedCommand)>,
+    node_garbage_rx: crossbeam_channel::Receiver<ProcessorNode>,
+    sample_rate: Float,
+    max_block_size: usize,
+    input_channels: usize,
+    latency: Arc<Latency>,
+    xruns: Arc<XrunCounter>,
+    xrun_hook: Arc<Mutex<Option<XrunHook>>>,
+    playing: Arc<AtomicBool>,
+    // `true` to play the device streams, `false` to pause them
+    stream_tx: mpsc::Sender<bool>,
 }
 
 impl RuntimeHandle {
+    /// Pauses processing of the graph. The devices keep running, but play silence.
+    ///
+    /// Processors keep their state, so [`RuntimeHandle::resume`] picks up where the graph left off.
+    /// Edits made while paused are still applied.
+    pub fn pause(&self) {
+        self.playing.store(false, Ordering::Release);
+    }
+
+    /// Pauses processing of the graph like [`RuntimeHandle::pause`], and also pauses the device streams.
+    ///
+    /// This saves the CPU time spent on the silent callbacks, but not all backends support pausing a stream, and some may take a moment to start it again.
+    pub fn pause_streams(&self) {
+        self.pause();
+        self.stream_tx.send(false).ok();
+    }
+
+    /// Resumes processing of the graph after [`RuntimeHandle::pause`] or [`RuntimeHandle::pause_streams`].
+    pub fn resume(&self) {
+        self.stream_tx.send(true).ok();
+        self.playing.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if the graph is being processed, i.e. the runtime hasn't been paused.
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of dropouts that have occurred since the runtime was started.
+    pub fn xruns(&self) -> XrunCounts {
+        self.xruns.counts()
+    }
+
+    /// Sets a function to call whenever a dropout occurs, replacing any previous one.
+    ///
+    /// The function is called on a background thread shortly after the dropout, not on the audio thread, so it may block or allocate.
+    /// This can be used to warn the user or to restart the runtime with a larger block size.
+    /// Dropouts are also logged as warnings.
+    pub fn on_xrun<F>(&self, f: F)
+    where
+        F: FnMut(Xrun) + Send + 'static,
+    {
+        *self.xrun_hook.lock().unwrap() = Some(Box::new(f));
+    }
+
+    /// Returns an estimate of the output latency: the time between the graph processing a block and the first device playing it.
+    ///
+    /// The estimate is updated every block. It is based on the timestamps reported by the backend, or on the block size if the backend doesn't report them.
+    /// Devices other than the first one of a [`MultiOut`] have additional latency from their ring buffers.
+    pub fn output_latency(&self) -> Duration {
+        self.latency.output()
+    }
+
+    /// Returns an estimate of the input latency: the time between the input device capturing a frame and the graph processing it.
+    ///
+    /// This includes the captured frames that are waiting to be processed. It is zero if no input device was opened.
+    pub fn input_latency(&self) -> Duration {
+        self.latency.input()
+    }
+
+    /// Returns an estimate of the round-trip latency, the sum of [`RuntimeHandle::input_latency`] and [`RuntimeHandle::output_latency`].
+    ///
+    /// This is how far behind the played audio a recording of it made through the graph's inputs will be, for example when overdubbing.
+    pub fn round_trip_latency(&self) -> Duration {
+        self.input_latency() + self.output_latency()
+    }
+
+    /// Returns the number of channels of the input device that is being captured from, or 0 if no input device was opened.
+    ///
+    /// Channels past the number of audio inputs of the graph are ignored.
+    pub fn input_channels(&self) -> usize {
+        self.input_channels
+    }
+
+    /// Returns the running graph's [`Transport`].
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    /// Edits the running graph.
+    ///
+    /// The closure is applied to a copy of the graph, which is then allocated on the calling thread and swapped in by the audio thread between blocks.
+    /// Nodes the edit left alone keep their processors from the running graph, along with any internal state they have (e.g. oscillator phases).
+    /// Nodes that were added, replaced, or changed through [`ProcessorNode::processor_mut`] start from the state they have in the edited graph.
+    /// [`Command::ReplaceNode`]s that are still waiting for their sample take effect as soon as the edit is swapped in, since the edited graph already contains them.
+    ///
+    /// If a previous edit has not been picked up by the audio thread yet, this blocks until it has.
+    pub fn edit<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Graph) -> R,
+    {
+        // drop any runtimes the audio thread has replaced
+        for runtime in self.garbage_rx.try_iter() {
+            drop(runtime);
+        }
+
+        let mut graph = self.graph.lock().unwrap();
+        let result = f(&mut graph);
+
+        let mut runtime = Runtime::new(graph.clone());
+        runtime.allocate_for_block_size(self.sample_rate, self.max_block_size);
+        runtime.reserve_commands();
+
+        self.edit_tx.send(runtime).ok();
+
+        result
+    }
+
+    /// Sends a [`Command`] to the audio thread, which applies it before processing the next block.
+    ///
+    /// This doesn't block or wait for the audio thread. Anything that needs allocating, such as the processor of a [`Command::ReplaceNode`], is allocated on the calling thread.
+    /// Returns [`RuntimeError::CommandQueueFull`] if [`COMMAND_QUEUE_CAPACITY`] commands are already waiting.
+    pub fn send(&self, command: Command) -> RuntimeResult<()> {
+        self.send_at(command, 0)
+    }
+
+    /// Sends a [`Command`] to the audio thread, which applies it when the graph's [`Transport`] reaches the given sample (see [`Transport::sample`]).
+    ///
+    /// The block containing that sample is processed in two parts, so the command takes effect on exactly that sample rather than at the start of the block.
+    /// Commands for samples that have already passed are applied before processing the next block, like [`RuntimeHandle::send`].
+    /// While the transport is stopped, its sample doesn't advance, so commands for later samples wait until it plays again.
+    pub fn send_at(&self, command: Command, sample: u64) -> RuntimeResult<()> {
+        // drop any nodes the audio thread has replaced
+        for node in self.node_garbage_rx.try_iter() {
+            drop(node);
+        }
+
+        if self.command_tx.is_full() {
+            return Err(RuntimeError::CommandQueueFull);
+        }
+
+        let command = {
+            let mut graph = self.graph.lock().unwrap();
+            let command = prepare_command(&graph, command, self.sample_rate, self.max_block_size)?;
+            // keep the latest version of the graph up to date for later edits
+            if let QueuedCommand::ReplaceNode(index, node) = &command {
+                graph.digraph_mut()[*index] = node.clone();
+            }
+            command
+        };
+
+        self.command_tx
+            .try_send((sample, command))
+            .map_err(|_| RuntimeError::CommandQueueFull)
+    }
+
     /// Stops the runtime. This will close the audio stream and MIDI input.
     pub fn stop(&self) {
         self.kill_tx.send(()).ok();
+        #[cfg(feature = "midi")]
         if let Ok(mut midi_in) = self.midi_in.lock() {
             if let Some(midi_in) = midi_in.take() {
                 midi_in.close();
@@ -718,3 +3125,294 @@ impl Drop for RuntimeHandle {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{GraphBuilder, SineOscillator, WavFileIn};
+
+    const BLOCK_SIZE: usize = 64;
+
+    // a sine at 440 Hz, returned with the index of its oscillator
+    fn sine() -> (Graph, NodeIndex) {
+        let graph = GraphBuilder::new();
+        let out = graph.add_audio_output();
+        let sine = graph.add(SineOscillator::default());
+        sine.input("frequency").connect(440.0);
+        sine.output(0).connect(&out.input(0));
+        (graph.build(), sine.id())
+    }
+
+    fn allocated(graph: Graph) -> Runtime {
+        let mut runtime = Runtime::new(graph);
+        runtime.allocate_for_block_size(48_000.0, BLOCK_SIZE);
+        runtime.reserve_commands();
+        runtime.transport().play();
+        runtime
+    }
+
+    // a runtime and a handle to it, as if it were running on an audio thread
+    fn running(graph: Graph) -> (Runtime, RuntimeHandle, GraphUpdates) {
+        let runtime = allocated(graph);
+        let (kill_tx, _) = mpsc::channel();
+        let (stream_tx, _) = mpsc::channel();
+        let (handle, updates, _) = runtime.new_handle(kill_tx, stream_tx, 0);
+        (runtime, handle, updates)
+    }
+
+    // processes blocks until the first output has a sample, giving background threads (e.g. disk streams) time to catch up
+    fn first_output(runtime: &mut Runtime) -> Option<Float> {
+        for _ in 0..100 {
+            runtime.process().unwrap();
+            let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+            if let Some(&sample) = buffer.iter().flatten().next() {
+                return Some(sample);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        None
+    }
+
+    fn render(runtime: &mut Runtime, updates: Option<&GraphUpdates>, blocks: usize) -> Vec<Float> {
+        let mut output = Vec::new();
+        for _ in 0..blocks {
+            if let Some(updates) = updates {
+                updates.apply(runtime);
+            }
+            runtime.process().unwrap();
+            let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+            output.extend(buffer.iter().map(|sample| sample.unwrap()));
+        }
+        output
+    }
+
+    #[test]
+    fn edit_keeps_state_of_untouched_nodes() {
+        let (graph, _) = sine();
+        let mut reference = allocated(graph.clone());
+        let (mut runtime, handle, updates) = running(graph);
+
+        let mut output = render(&mut runtime, Some(&updates), 10);
+        handle.edit(|graph| graph.add_processor(SineOscillator::default()));
+        assert!(updates.apply(&mut runtime));
+        output.extend(render(&mut runtime, Some(&updates), 10));
+
+        assert_eq!(output, render(&mut reference, None, 20));
+    }
+
+    #[test]
+    fn edit_applies_pending_replacements_once() {
+        let (graph, sine) = sine();
+        let mut reference = allocated(graph.clone());
+        let (mut runtime, handle, updates) = running(graph);
+
+        let mut output = render(&mut runtime, Some(&updates), 10);
+        // the edit swaps the replacement in before it is due
+        let due = 15 * BLOCK_SIZE as u64;
+        handle
+            .send_at(
+                Command::ReplaceNode(sine, Box::new(SineOscillator::default())),
+                due,
+            )
+            .unwrap();
+        updates.apply(&mut runtime);
+        handle.edit(|_| ());
+        output.extend(render(&mut runtime, Some(&updates), 10));
+
+        let mut expected = render(&mut reference, None, 10);
+        reference
+            .send_at(
+                Command::ReplaceNode(sine, Box::new(SineOscillator::default())),
+                0,
+            )
+            .unwrap();
+        expected.extend(render(&mut reference, None, 10));
+
+        assert_eq!(output, expected);
+    }
+
+    // the frames of the given input in the runtime's last block
+    fn input(runtime: &mut Runtime, input_idx: usize) -> Vec<Float> {
+        let buffer = runtime.get_input_mut(input_idx).unwrap();
+        let buffer = buffer.as_type::<Float>().unwrap();
+        buffer.iter().map(|sample| sample.unwrap()).collect()
+    }
+
+    // interleaved frames of a three-channel device, where each sample encodes its frame and channel
+    fn device_frames(frames: std::ops::Range<usize>) -> Vec<f32> {
+        frames
+            .flat_map(|frame| (0..3).map(move |channel| (frame * 10 + channel) as f32))
+            .collect()
+    }
+
+    fn expected_input(frames: std::ops::Range<usize>, input_idx: usize) -> Vec<Float> {
+        frames
+            .map(|frame| (frame * 10 + input_idx) as Float)
+            .collect()
+    }
+
+    #[test]
+    fn captured_input_is_read_in_whole_blocks() {
+        let graph = GraphBuilder::new();
+        graph.add_audio_input();
+        graph.add_audio_input();
+        let mut runtime = allocated(graph.build());
+
+        let (xrun_tx, _xrun_rx) = crossbeam_channel::unbounded();
+        let xruns = Arc::new(XrunCounter::new(xrun_tx));
+        let (tx, rx) = rtrb::RingBuffer::new(BLOCK_SIZE * 2 * 4);
+        let mut tap = InputTap {
+            num_inputs: 2,
+            tx,
+            latency: Arc::new(Latency::default()),
+            xruns: xruns.clone(),
+        };
+        let mut queue = InputQueue::new(2, rx);
+
+        // the third device channel has no graph input
+        tap.capture(&device_frames(0..BLOCK_SIZE * 3 / 2), 3);
+        assert_eq!(
+            queue.read_block(&mut runtime, BLOCK_SIZE, &xruns),
+            BLOCK_SIZE * 3 / 2
+        );
+        for input_idx in 0..2 {
+            assert_eq!(
+                input(&mut runtime, input_idx),
+                expected_input(0..BLOCK_SIZE, input_idx)
+            );
+        }
+        assert_eq!(xruns.counts(), XrunCounts::default());
+
+        // the rest of a starved block is silent
+        queue.read_block(&mut runtime, BLOCK_SIZE, &xruns);
+        let mut expected = expected_input(BLOCK_SIZE..BLOCK_SIZE * 3 / 2, 0);
+        expected.resize(BLOCK_SIZE, 0.0);
+        assert_eq!(input(&mut runtime, 0), expected);
+        assert_eq!(
+            xruns.counts(),
+            XrunCounts {
+                underruns: 1,
+                overruns: 0
+            }
+        );
+
+        // frames that don't fit in the ring buffer are dropped, and the backlog is skipped down to two blocks
+        tap.capture(&device_frames(0..BLOCK_SIZE * 5), 3);
+        assert_eq!(
+            queue.read_block(&mut runtime, BLOCK_SIZE, &xruns),
+            BLOCK_SIZE * 2
+        );
+        assert_eq!(
+            input(&mut runtime, 1),
+            expected_input(BLOCK_SIZE * 2..BLOCK_SIZE * 3, 1)
+        );
+        assert_eq!(
+            xruns.counts(),
+            XrunCounts {
+                underruns: 1,
+                overruns: 2
+            }
+        );
+
+        queue.clear();
+        assert_eq!(queue.read_block(&mut runtime, BLOCK_SIZE, &xruns), 0);
+    }
+
+    #[test]
+    fn audio_thread_runtime_streams_files() {
+        let path = std::env::temp_dir().join(format!("raug-{}-stream.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..BLOCK_SIZE * 4 {
+            writer.write_sample(0.5_f32).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let graph = GraphBuilder::new();
+        let out = graph.add_audio_output();
+        let file = graph.add(WavFileIn::open(&path).unwrap());
+        file.output(0).connect(&out.input(0));
+
+        let runtime = allocated(graph.build());
+        let mut audio_runtime = runtime.for_audio_thread();
+        assert_eq!(first_output(&mut audio_runtime), Some(0.5));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[cfg(feature = "fft")]
+    #[test]
+    fn audio_thread_runtime_runs_spectral_processors() {
+        use crate::prelude::{FftAnalyze, IfftSynthesize, WindowFunction};
+
+        let graph = GraphBuilder::new();
+        let out = graph.add_audio_output();
+        let sine = graph.add(SineOscillator::default());
+        sine.input("frequency").connect(440.0);
+        let analyze = graph.add(FftAnalyze::new(256, 64, WindowFunction::Hann));
+        let synthesize = graph.add(IfftSynthesize::new(256, 64, WindowFunction::Hann));
+        analyze.input(0).connect(sine);
+        synthesize.input(0).connect(analyze);
+        synthesize.output(0).connect(&out.input(0));
+
+        let mut audio_runtime = allocated(graph.build()).for_audio_thread();
+        let mut loudest: Float = 0.0;
+        for _ in 0..10 {
+            audio_runtime.process().unwrap();
+            let buffer = audio_runtime.get_output(0).unwrap();
+            let buffer = buffer.as_type::<Float>().unwrap();
+            loudest = buffer
+                .iter()
+                .flatten()
+                .fold(loudest, |max, s| max.max(s.abs()));
+        }
+        assert!(loudest > 0.5);
+    }
+
+    #[cfg(feature = "rubato")]
+    #[test]
+    fn audio_thread_runtime_resamples() {
+        use crate::prelude::Resample;
+
+        let graph = GraphBuilder::new();
+        let out = graph.add_audio_output();
+        let resample = graph.add(Resample::build(44_100.0, |graph| {
+            let out = graph.add_audio_output();
+            let sine = graph.add(SineOscillator::default());
+            sine.input("frequency").connect(440.0);
+            sine.output(0).connect(&out.input(0));
+        }));
+        resample.output(0).connect(&out.input(0));
+
+        let mut audio_runtime = allocated(graph.build()).for_audio_thread();
+        let output = render(&mut audio_runtime, None, 10);
+        assert!(output.iter().any(|sample| sample.abs() > 0.5));
+    }
+
+    // whether the float output buffers of every node are slices of the runtime's arena
+    fn uses_arena(runtime: &Runtime) -> bool {
+        runtime
+            .buffer_cache
+            .values()
+            .flat_map(|buffers| &buffers.outputs)
+            .all(|buffer| match buffer {
+                SignalBuffer::Float(buffer) => buffer.is_in_arena(),
+                _ => true,
+            })
+    }
+
+    #[test]
+    fn audio_thread_runtime_uses_arena() {
+        let (graph, _) = sine();
+        let runtime = allocated(graph);
+        assert!(uses_arena(&runtime));
+        // clones own their buffers, so the copy for the audio thread binds a new arena
+        assert!(!uses_arena(&runtime.clone()));
+        assert!(uses_arena(&runtime.for_audio_thread()));
+    }
+}
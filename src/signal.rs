@@ -1,11 +1,36 @@
 //! Signal types and operations.
+//!
+//! # Missing values
+//!
+//! Every sample of a signal is an `Option`: `Some` carries a value, and `None` means the signal has no value on that sample.
+//!
+//! - Continuous signals, such as audio, are `Some` on every sample.
+//! - Sparse signals, such as triggers, MIDI messages and parameter changes, are `Some` only on the samples where something happens.
+//! - Unconnected inputs read as `None` on every sample.
+//!
+//! Processors generally treat a `None` input as "unchanged", holding the last value they received (or their initial value).
+//! Processors that output events only output `Some` when one occurs.
+//!
+//! The following processors convert between the two kinds of signal:
+//!
+//! - [`IsSome`](crate::builtins::IsSome) and [`IsNone`](crate::builtins::IsNone) test for a value.
+//! - [`OrElse`](crate::builtins::OrElse) replaces `None` with a default value.
+//! - [`Register`](crate::builtins::Register) holds the last value, making a sparse signal continuous.
+//! - [`Dedup`](crate::builtins::Dedup) outputs only changes, making a continuous signal sparse.
+//! - [`Filter`](crate::builtins::Filter) passes values only while a condition holds.
 
 use std::{
     fmt::Debug,
     ops::{Deref, DerefMut},
     path::Path,
+    ptr::NonNull,
+    sync::Arc,
 };
 
+use crate::graph::arena::{ArenaMemory, BufferArena};
+
+use num::Complex;
+
 #[cfg(feature = "f32_samples")]
 /// The floating-point sample type.
 pub type Float = f32;
@@ -27,6 +52,151 @@ pub const TAU: Float = std::f32::consts::TAU;
 /// The value of TAU (2*PI) for the floating-point sample type.
 pub const TAU: Float = std::f64::consts::TAU;
 
+/// An error that occurred while loading an audio file.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum AudioFileError {
+    /// An error occurred while reading a WAV file.
+    #[error("WAV error: {0}")]
+    Hound(#[from] hound::Error),
+
+    /// An error occurred while decoding an audio file.
+    #[cfg(feature = "symphonia")]
+    #[error("Decoding error: {0}")]
+    Symphonia(#[from] symphonia::core::errors::Error),
+
+    /// The file does not contain any audio tracks.
+    #[error("No audio track found")]
+    NoAudioTrack,
+}
+
+// the storage behind a `Buffer`, which is either owned or a slice of a runtime's `BufferArena`
+enum BufferStorage<T> {
+    Owned(Vec<Option<T>>),
+    // `capacity` initialized samples in `_memory` starting at `ptr`, of which the first `len` are in use
+    // nothing else accesses the slice while the buffer uses it, and holding on to `_memory` keeps it allocated
+    Arena {
+        ptr: NonNull<Option<T>>,
+        len: usize,
+        capacity: usize,
+        _memory: Arc<ArenaMemory>,
+    },
+}
+
+// SAFETY: an arena slice is owned by a single buffer, just like a `Vec`'s allocation is
+unsafe impl<T: Send> Send for BufferStorage<T> {}
+// SAFETY: shared access to the storage only ever reads from the slice
+unsafe impl<T: Sync> Sync for BufferStorage<T> {}
+
+impl<T> BufferStorage<T> {
+    // the number of samples the storage can hold without allocating
+    #[inline]
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Owned(buf) => buf.capacity(),
+            Self::Arena { capacity, .. } => *capacity,
+        }
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: Option<T>)
+    where
+        T: Clone,
+    {
+        match self {
+            Self::Owned(buf) => buf.resize(new_len, value),
+            Self::Arena {
+                ptr, len, capacity, ..
+            } => {
+                // growing would need a new allocation, which the arena exists to avoid, and the runtime checks for before resizing
+                assert!(
+                    new_len <= *capacity,
+                    "cannot grow a buffer past the capacity of its arena slice"
+                );
+                // SAFETY: all `capacity` signals of the slice are initialized, and the buffer has exclusive access to them
+                let slice = unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), new_len) };
+                if new_len > *len {
+                    slice[*len..].fill(value);
+                }
+                *len = new_len;
+            }
+        }
+    }
+}
+
+impl<T> Deref for BufferStorage<T> {
+    type Target = [Option<T>];
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Owned(buf) => buf,
+            // SAFETY: the first `len` signals of the slice are initialized, and nothing else writes to them while `self` is borrowed
+            Self::Arena { ptr, len, .. } => unsafe {
+                std::slice::from_raw_parts(ptr.as_ptr(), *len)
+            },
+        }
+    }
+}
+
+impl<T> DerefMut for BufferStorage<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Owned(buf) => buf,
+            // SAFETY: the first `len` signals of the slice are initialized, and nothing else accesses them while `self` is borrowed mutably
+            Self::Arena { ptr, len, .. } => unsafe {
+                std::slice::from_raw_parts_mut(ptr.as_ptr(), *len)
+            },
+        }
+    }
+}
+
+impl<T: Clone> Clone for BufferStorage<T> {
+    // clones are always owned, keeping the arena slice's capacity so they can grow just as far without allocating
+    fn clone(&self) -> Self {
+        match self {
+            Self::Owned(buf) => Self::Owned(buf.clone()),
+            Self::Arena { capacity, .. } => {
+                let mut buf = Vec::with_capacity(*capacity);
+                buf.extend_from_slice(self);
+                Self::Owned(buf)
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for BufferStorage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T> From<Vec<Option<T>>> for BufferStorage<T> {
+    fn from(buf: Vec<Option<T>>) -> Self {
+        Self::Owned(buf)
+    }
+}
+
+impl<T> FromIterator<Option<T>> for BufferStorage<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        Self::Owned(iter.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for BufferStorage<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for BufferStorage<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::Owned)
+    }
+}
+
 /// A contiguous buffer of signals.
 ///
 /// The signals are stored as a [`Vec`] of [`Option<T>`] to allow for missing values.
@@ -35,7 +205,7 @@ pub const TAU: Float = std::f64::consts::TAU;
 #[derive(PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Buffer<T: Signal> {
-    buf: Vec<Option<T>>,
+    buf: BufferStorage<T>,
 }
 
 impl<T: Signal> Debug for Buffer<T> {
@@ -52,7 +222,7 @@ impl<T: Signal> Buffer<T> {
         for _ in 0..length {
             buf.push(None);
         }
-        Buffer { buf }
+        Buffer { buf: buf.into() }
     }
 
     /// Clones the slice into a new buffer. All elements are wrapped in `Some`.
@@ -81,6 +251,232 @@ impl<T: Signal> Buffer<T> {
 }
 
 impl Buffer<Float> {
+    /// Moves the buffer's contents into the given slice of an arena, which the buffer uses from then on.
+    ///
+    /// Contents that don't fit in the slice are dropped.
+    ///
+    /// # Safety
+    ///
+    /// The slice must not be used by any other buffer.
+    #[inline]
+    pub(crate) unsafe fn bind_to_arena(&mut self, arena: &BufferArena, index: usize) {
+        let (ptr, memory) = arena.slice(index);
+        let capacity = arena.capacity();
+        let len = self.buf.len().min(capacity);
+        // SAFETY: the slice holds `capacity` initialized signals, and the caller guarantees nothing else uses it
+        let slice = unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), capacity) };
+        slice[..len].copy_from_slice(&self.buf[..len]);
+        self.buf = BufferStorage::Arena {
+            ptr,
+            len,
+            capacity,
+            _memory: memory,
+        };
+    }
+
+    // whether the buffer is a slice of an arena rather than owning its signals
+    #[cfg(test)]
+    pub(crate) fn is_in_arena(&self) -> bool {
+        matches!(self.buf, BufferStorage::Arena { .. })
+    }
+
+    /// Loads a buffer from an audio file, keeping only the first channel.
+    ///
+    /// With the `symphonia` feature, MP3, FLAC, and OGG Vorbis files are supported in addition to WAV files.
+    /// Without it, this is equivalent to [`Buffer::load_wav`].
+    ///
+    /// The samples are not resampled; see [`Buffer::load_resampled`] to convert them to a given sample rate.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AudioFileError> {
+        let (buffer, _sample_rate) = Self::load_with_sample_rate(path)?;
+        Ok(buffer)
+    }
+
+    /// Loads a buffer from an audio file like [`Buffer::load`], resampling it to the given sample rate.
+    pub fn load_resampled(
+        path: impl AsRef<Path>,
+        sample_rate: Float,
+    ) -> Result<Self, AudioFileError> {
+        let (buffer, file_sample_rate) = Self::load_with_sample_rate(path)?;
+        Ok(buffer.resample(file_sample_rate as Float, sample_rate))
+    }
+
+    /// Loads a buffer from an audio file like [`Buffer::load`], returning it along with the file's sample rate.
+    #[cfg(feature = "symphonia")]
+    pub fn load_with_sample_rate(path: impl AsRef<Path>) -> Result<(Self, u32), AudioFileError> {
+        use symphonia::core::{
+            audio::SampleBuffer, codecs::DecoderOptions, errors::Error, formats::FormatOptions,
+            io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+        };
+
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(Error::IoError)?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+            hint.with_extension(extension);
+        }
+
+        let probed = symphonia::default::get_probe().format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?;
+        let mut format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+            .ok_or(AudioFileError::NoAudioTrack)?;
+        let track_id = track.id;
+        let mut sample_rate = track.codec_params.sample_rate;
+
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+
+        let mut samples = Vec::new();
+        let mut sample_buffer: Option<SampleBuffer<f32>> = None;
+
+        loop {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            if packet.track_id() != track_id {
+                continue;
+            }
+
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // corrupted packets are skipped
+                Err(Error::DecodeError(e)) => {
+                    log::warn!("Skipping undecodable packet in {}: {}", path.display(), e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let spec = *decoded.spec();
+            sample_rate.get_or_insert(spec.rate);
+            let channels = spec.channels.count();
+
+            if sample_buffer
+                .as_ref()
+                .is_none_or(|buffer| buffer.capacity() < decoded.capacity() * channels)
+            {
+                sample_buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+            }
+
+            let sample_buffer = sample_buffer.as_mut().unwrap();
+            sample_buffer.copy_interleaved_ref(decoded);
+            samples.extend(
+                sample_buffer
+                    .samples()
+                    .iter()
+                    .step_by(channels)
+                    .map(|&sample| sample as Float),
+            );
+        }
+
+        let sample_rate = sample_rate.ok_or(AudioFileError::NoAudioTrack)?;
+
+        Ok((Buffer::from_slice(&samples), sample_rate))
+    }
+
+    /// Loads a buffer from an audio file like [`Buffer::load`], returning it along with the file's sample rate.
+    #[cfg(not(feature = "symphonia"))]
+    pub fn load_with_sample_rate(path: impl AsRef<Path>) -> Result<(Self, u32), AudioFileError> {
+        let sample_rate = hound::WavReader::open(path.as_ref())?.spec().sample_rate;
+        Ok((Self::load_wav(path)?, sample_rate))
+    }
+
+    /// Resamples the buffer from one sample rate to another using linear interpolation.
+    ///
+    /// [`None`] entries are treated as silence.
+    #[cfg(not(feature = "rubato"))]
+    pub fn resample(&self, from_sample_rate: Float, to_sample_rate: Float) -> Self {
+        if from_sample_rate == to_sample_rate || self.buf.is_empty() {
+            return self.clone();
+        }
+
+        let ratio = from_sample_rate / to_sample_rate;
+        let length = (self.buf.len() as Float / ratio).round() as usize;
+        let last = self.buf.len() - 1;
+
+        let buf = (0..length)
+            .map(|i| {
+                let position = i as Float * ratio;
+                let index = (position.floor() as usize).min(last);
+                let next = (index + 1).min(last);
+                let t = position - index as Float;
+
+                let a = self.buf[index].unwrap_or_default();
+                let b = self.buf[next].unwrap_or_default();
+                Some(a + (b - a) * t.clamp(0.0, 1.0))
+            })
+            .collect();
+
+        Buffer { buf }
+    }
+
+    /// Resamples the buffer from one sample rate to another using a windowed sinc interpolator.
+    ///
+    /// [`None`] entries are treated as silence.
+    #[cfg(feature = "rubato")]
+    pub fn resample(&self, from_sample_rate: Float, to_sample_rate: Float) -> Self {
+        use rubato::{Resampler, SincFixedIn};
+
+        if from_sample_rate == to_sample_rate || self.buf.is_empty() {
+            return self.clone();
+        }
+
+        let ratio = to_sample_rate as f64 / from_sample_rate as f64;
+        let mut resampler = SincFixedIn::<Float>::new(
+            ratio,
+            1.0,
+            crate::builtins::resample::sinc_parameters(),
+            1024,
+            1,
+        )
+        .unwrap();
+
+        let input: Vec<Float> = self.buf.iter().map(|s| s.unwrap_or_default()).collect();
+        let length = (input.len() as f64 * ratio).round() as usize;
+
+        let mut output = Vec::with_capacity(length + resampler.output_frames_max());
+        let mut chunk = vec![vec![0.0; resampler.output_frames_max()]];
+        let mut position = 0;
+
+        while position + resampler.input_frames_next() <= input.len() {
+            let frames = resampler.input_frames_next();
+            let (_, resampled) = resampler
+                .process_into_buffer(&[&input[position..position + frames]], &mut chunk, None)
+                .unwrap();
+            output.extend_from_slice(&chunk[0][..resampled]);
+            position += frames;
+        }
+
+        // process the remaining input, then flush the resampler until the whole buffer has been output
+        let remaining = [&input[position..]];
+        let (_, resampled) = resampler
+            .process_partial_into_buffer(Some(&remaining), &mut chunk, None)
+            .unwrap();
+        output.extend_from_slice(&chunk[0][..resampled]);
+
+        while output.len() < length {
+            let (_, resampled) = resampler
+                .process_partial_into_buffer(None::<&[&[Float]]>, &mut chunk, None)
+                .unwrap();
+            output.extend_from_slice(&chunk[0][..resampled]);
+        }
+
+        Buffer::from_slice(&output[..length])
+    }
+
     /// Loads a buffer from a WAV file.
     pub fn load_wav(path: impl AsRef<Path>) -> Result<Self, hound::Error> {
         let reader = hound::WavReader::open(path)?;
@@ -208,21 +604,21 @@ impl<T: Signal> Deref for Buffer<T> {
     type Target = [Option<T>];
     #[inline]
     fn deref(&self) -> &Self::Target {
-        self.buf.as_ref()
+        &self.buf
     }
 }
 
 impl<T: Signal> DerefMut for Buffer<T> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.buf.as_mut()
+        &mut self.buf
     }
 }
 
 impl<T: Signal> AsRef<[Option<T>]> for Buffer<T> {
     #[inline]
     fn as_ref(&self) -> &[Option<T>] {
-        self.buf.as_ref()
+        &self.buf
     }
 }
 
@@ -292,12 +688,12 @@ impl List {
     }
 
     /// Returns a reference to the signal at the given index.
-    pub fn get(&self, index: usize) -> Option<AnySignalRef> {
+    pub fn get(&self, index: usize) -> Option<AnySignalRef<'_>> {
         self.0.get(index).map(AnySignal::as_ref)
     }
 
     /// Returns a mutable reference to the signal at the given index.
-    pub fn get_mut(&mut self, index: usize) -> Option<AnySignalMut> {
+    pub fn get_mut(&mut self, index: usize) -> Option<AnySignalMut<'_>> {
         self.0.get_mut(index).map(AnySignal::as_mut)
     }
 
@@ -357,6 +753,21 @@ impl MidiMessage {
         Self { data }
     }
 
+    /// Creates a note on message for the given channel (`0..16`), note number and velocity.
+    pub fn note_on(channel: u8, note: u8, velocity: u8) -> Self {
+        Self::new([0x90 | (channel & 0x0F), note & 0x7F, velocity & 0x7F])
+    }
+
+    /// Creates a note off message for the given channel (`0..16`) and note number.
+    pub fn note_off(channel: u8, note: u8) -> Self {
+        Self::new([0x80 | (channel & 0x0F), note & 0x7F, 0])
+    }
+
+    /// Creates a control change message for the given channel (`0..16`), controller number and value.
+    pub fn control_change(channel: u8, controller: u8, value: u8) -> Self {
+        Self::new([0xB0 | (channel & 0x0F), controller & 0x7F, value & 0x7F])
+    }
+
     /// Returns the status byte of the MIDI message.
     pub fn status(&self) -> u8 {
         self.data[0] & 0xF0
@@ -376,6 +787,36 @@ impl MidiMessage {
     pub fn data2(&self) -> u8 {
         self.data[2]
     }
+
+    /// Returns `true` if this is a note on message with a nonzero velocity.
+    pub fn is_note_on(&self) -> bool {
+        self.status() == 0x90 && self.data2() > 0
+    }
+
+    /// Returns `true` if this is a note off message, or a note on message with zero velocity.
+    pub fn is_note_off(&self) -> bool {
+        self.status() == 0x80 || (self.status() == 0x90 && self.data2() == 0)
+    }
+
+    /// Returns `true` if this is a control change message.
+    pub fn is_control_change(&self) -> bool {
+        self.status() == 0xB0
+    }
+
+    /// Returns `true` if this is a channel pressure (aftertouch) message.
+    pub fn is_channel_pressure(&self) -> bool {
+        self.status() == 0xD0
+    }
+
+    /// Returns `true` if this is a pitch bend message.
+    pub fn is_pitch_bend(&self) -> bool {
+        self.status() == 0xE0
+    }
+
+    /// Returns the 14-bit pitch bend value of the MIDI message, centered at `8192`.
+    pub fn pitch_bend(&self) -> u16 {
+        ((self.data2() as u16 & 0x7F) << 7) | (self.data1() as u16 & 0x7F)
+    }
 }
 
 impl Deref for MidiMessage {
@@ -391,6 +832,183 @@ impl DerefMut for MidiMessage {
     }
 }
 
+/// A frame of complex frequency bins, as produced by a real-to-complex FFT.
+///
+/// A spectrum for an FFT of length `n` has `n / 2 + 1` bins, from DC up to the Nyquist frequency.
+#[derive(Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spectrum(Box<[Complex<Float>]>);
+
+impl Clone for Spectrum {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    // reuses the allocation if the spectra have the same length, so spectra can be overwritten on the audio thread
+    fn clone_from(&mut self, source: &Self) {
+        self.0.clone_from(&source.0);
+    }
+}
+
+impl Spectrum {
+    /// Creates a new spectrum of zeros for an FFT of the given length.
+    pub fn new(fft_length: usize) -> Self {
+        Self(vec![Complex::default(); fft_length / 2 + 1].into_boxed_slice())
+    }
+
+    /// Creates a new spectrum from a slice of bins.
+    pub fn from_slice(bins: &[Complex<Float>]) -> Self {
+        Self(bins.into())
+    }
+
+    /// Returns the length of the FFT this spectrum was produced by.
+    pub fn fft_length(&self) -> usize {
+        self.0.len().saturating_sub(1) * 2
+    }
+
+    /// Returns the frequency in Hz of the given bin at the given sample rate.
+    pub fn bin_frequency(&self, bin: usize, sample_rate: Float) -> Float {
+        bin as Float * sample_rate / self.fft_length().max(1) as Float
+    }
+}
+
+impl Deref for Spectrum {
+    type Target = [Complex<Float>];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Spectrum {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// The maximum number of channels in a [`Frame`].
+pub const MAX_CHANNELS: usize = 8;
+
+/// A frame of samples of a multichannel signal, one per channel.
+///
+/// Frames let stereo, quad or ambisonic signals travel over a single connection. The math processors apply to each channel separately.
+/// A stereo signal is a frame of two channels, left then right.
+///
+/// Frames hold up to [`MAX_CHANNELS`] channels inline, so creating and copying them never allocates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Frame {
+    samples: [Float; MAX_CHANNELS],
+    channels: usize,
+}
+
+impl Frame {
+    /// Creates a new frame of silence with the given number of channels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channels` is greater than [`MAX_CHANNELS`].
+    pub fn new(channels: usize) -> Self {
+        assert!(
+            channels <= MAX_CHANNELS,
+            "frames can have at most {MAX_CHANNELS} channels, got {channels}"
+        );
+        Self {
+            samples: [0.0; MAX_CHANNELS],
+            channels,
+        }
+    }
+
+    /// Creates a new stereo frame from the given left and right samples.
+    pub fn stereo(left: Float, right: Float) -> Self {
+        Self::from_slice(&[left, right])
+    }
+
+    /// Creates a new frame from a slice of samples, one per channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than [`MAX_CHANNELS`] samples.
+    pub fn from_slice(samples: &[Float]) -> Self {
+        let mut frame = Self::new(samples.len());
+        frame.copy_from_slice(samples);
+        frame
+    }
+
+    /// Creates a new frame with the given number of channels, each set to the same value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channels` is greater than [`MAX_CHANNELS`].
+    pub fn splat(value: Float, channels: usize) -> Self {
+        let mut frame = Self::new(channels);
+        frame.fill(value);
+        frame
+    }
+
+    /// Returns the number of channels in the frame.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Returns the left (first) channel of a stereo frame, or `0.0` if the frame has no channels.
+    pub fn left(&self) -> Float {
+        self.first().copied().unwrap_or(0.0)
+    }
+
+    /// Returns the right (second) channel of a stereo frame, or `0.0` if the frame has fewer than two channels.
+    pub fn right(&self) -> Float {
+        self.get(1).copied().unwrap_or(0.0)
+    }
+
+    /// Returns a new frame with the given function applied to each channel.
+    pub fn map(&self, f: impl Fn(Float) -> Float) -> Self {
+        let mut frame = *self;
+        for sample in frame.iter_mut() {
+            *sample = f(*sample);
+        }
+        frame
+    }
+}
+
+impl PartialEq for Frame {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Deref for Frame {
+    type Target = [Float];
+    fn deref(&self) -> &Self::Target {
+        &self.samples[..self.channels]
+    }
+}
+
+impl DerefMut for Frame {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.samples[..self.channels]
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Frame {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Frame {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let samples = Vec::<Float>::deserialize(deserializer)?;
+        if samples.len() > MAX_CHANNELS {
+            return Err(serde::de::Error::invalid_length(
+                samples.len(),
+                &"at most MAX_CHANNELS channels",
+            ));
+        }
+        Ok(Self::from_slice(&samples))
+    }
+}
+
 /// A type that can be stored in a [`Buffer`] and processed by a [`Processor`](crate::processor::Processor).
 pub trait Signal: Sized + Debug + Send + Sync + PartialEq + 'static {
     /// The type of the signal.
@@ -403,10 +1021,12 @@ pub trait Signal: Sized + Debug + Send + Sync + PartialEq + 'static {
     fn try_from_any_signal(signal: AnySignal) -> Option<Self>;
 
     /// Attempts to convert an [`AnySignal`] into the signal type.
-    fn try_from_any_signal_ref(signal: AnySignalRef) -> Option<&Option<Self>>;
+    fn try_from_any_signal_ref(signal: AnySignalRef<'_>) -> Option<&Option<Self>>;
 
     /// Attempts to convert a mutable [`AnySignal`] into a mutable signal of the signal type.
-    fn try_from_any_signal_mut(signal: AnySignalMut) -> Result<&mut Option<Self>, AnySignalMut>;
+    fn try_from_any_signal_mut(
+        signal: AnySignalMut<'_>,
+    ) -> Result<&mut Option<Self>, AnySignalMut<'_>>;
 
     /// Attempts to convert a [`SignalBuffer`] into a buffer of the signal type.
     fn try_convert_buffer(buffer: &SignalBuffer) -> Option<&Buffer<Self>>;
@@ -436,7 +1056,7 @@ macro_rules! impl_signal {
             }
 
             #[inline]
-            fn try_from_any_signal_ref(signal: AnySignalRef) -> Option<&Option<Self>>
+            fn try_from_any_signal_ref(signal: AnySignalRef<'_>) -> Option<&Option<Self>>
             where
                 Self: Sized,
             {
@@ -448,8 +1068,8 @@ macro_rules! impl_signal {
 
             #[inline]
             fn try_from_any_signal_mut(
-                signal: AnySignalMut,
-            ) -> Result<&mut Option<Self>, AnySignalMut>
+                signal: AnySignalMut<'_>,
+            ) -> Result<&mut Option<Self>, AnySignalMut<'_>>
             where
                 Self: Sized,
             {
@@ -484,6 +1104,8 @@ impl_signal!(i64, SignalType::Int, Int);
 impl_signal!(String, SignalType::String, String);
 impl_signal!(List, SignalType::List, List);
 impl_signal!(MidiMessage, SignalType::Midi, Midi);
+impl_signal!(Spectrum, SignalType::Spectrum, Spectrum);
+impl_signal!(Frame, SignalType::Frame, Frame);
 
 /// A type that can hold any signal type.
 #[derive(Debug, Clone, PartialEq)]
@@ -506,6 +1128,12 @@ pub enum AnySignal {
 
     /// A MIDI message.
     Midi(Option<MidiMessage>),
+
+    /// A frame of frequency bins.
+    Spectrum(Option<Spectrum>),
+
+    /// A frame of samples, one per channel.
+    Frame(Option<Frame>),
 }
 
 impl AnySignal {
@@ -516,8 +1144,10 @@ impl AnySignal {
             SignalType::Int => AnySignal::Int(None),
             SignalType::Bool => AnySignal::Bool(None),
             SignalType::String => AnySignal::String(None),
-            SignalType::List { .. } => AnySignal::List(None),
+            SignalType::List => AnySignal::List(None),
             SignalType::Midi => AnySignal::Midi(None),
+            SignalType::Spectrum => AnySignal::Spectrum(None),
+            SignalType::Frame => AnySignal::Frame(None),
         }
     }
 
@@ -530,6 +1160,8 @@ impl AnySignal {
             Self::String(string) => string.is_some(),
             Self::List(list) => list.is_some(),
             Self::Midi(midi) => midi.is_some(),
+            Self::Spectrum(spectrum) => spectrum.is_some(),
+            Self::Frame(frame) => frame.is_some(),
         }
     }
 
@@ -553,6 +1185,8 @@ impl AnySignal {
                 | (Self::String(_), Self::String(_))
                 | (Self::List(_), Self::List(_))
                 | (Self::Midi(_), Self::Midi(_))
+                | (Self::Spectrum(_), Self::Spectrum(_))
+                | (Self::Frame(_), Self::Frame(_))
         )
     }
 
@@ -566,6 +1200,8 @@ impl AnySignal {
             Self::String(_) => SignalType::String,
             Self::List(_) => SignalType::List,
             Self::Midi(_) => SignalType::Midi,
+            Self::Spectrum(_) => SignalType::Spectrum,
+            Self::Frame(_) => SignalType::Frame,
         }
     }
 
@@ -573,14 +1209,18 @@ impl AnySignal {
     ///
     /// Currently, the following conversions are supported:
     ///
-    /// | From \ To | Float | Int | Bool | String | List | Midi |
-    /// |-----------|-------|-----|------|--------|------|------|
-    /// | Float     | -     | Yes | Yes  | Yes    | -    | -    |
-    /// | Int       | Yes   | -   | Yes  | Yes    | -    | -    |
-    /// | Bool      | Yes   | Yes | -    | Yes    | -    | -    |
-    /// | String    | Yes   | Yes | Yes  | -      | -    | -    |
-    /// | List      | -     | -   | -    | -      | -    | -    |
-    /// | Midi      | -     | -   | -    | -      | -    | -    |
+    /// | From \ To | Float | Int | Bool | String | List | Midi | Spectrum | Frame |
+    /// |-----------|-------|-----|------|--------|------|------|----------|-------|
+    /// | Float     | -     | Yes | Yes  | Yes    | -    | -    | -        | -     |
+    /// | Int       | Yes   | -   | Yes  | Yes    | -    | -    | -        | -     |
+    /// | Bool      | Yes   | Yes | -    | Yes    | -    | -    | -        | -     |
+    /// | String    | Yes   | Yes | Yes  | -      | -    | -    | -        | -     |
+    /// | List      | -     | -   | -    | -      | -    | -    | -        | -     |
+    /// | Midi      | -     | -   | -    | -      | -    | -    | -        | -     |
+    /// | Spectrum  | -     | -   | -    | -      | -    | -    | -        | -     |
+    /// | Frame     | -     | -   | -    | -      | -    | -    | -        | -     |
+    ///
+    /// Numbers cast to `Bool` are `true` when nonzero, so a `Float` of `1e-9` or `NaN` casts to `true`.
     #[inline]
     pub fn cast(&self, target: SignalType) -> Option<Self> {
         if self.signal_type() == target {
@@ -619,7 +1259,7 @@ impl AnySignal {
 
     /// Returns a reference to the signal.
     #[inline]
-    pub fn as_ref(&self) -> AnySignalRef {
+    pub fn as_ref(&self) -> AnySignalRef<'_> {
         match self {
             Self::Float(float) => AnySignalRef::Float(float),
             Self::Int(int) => AnySignalRef::Int(int),
@@ -627,12 +1267,14 @@ impl AnySignal {
             Self::String(string) => AnySignalRef::String(string),
             Self::List(list) => AnySignalRef::List(list),
             Self::Midi(midi) => AnySignalRef::Midi(midi),
+            Self::Spectrum(spectrum) => AnySignalRef::Spectrum(spectrum),
+            Self::Frame(frame) => AnySignalRef::Frame(frame),
         }
     }
 
     /// Returns a mutable reference to the signal.
     #[inline]
-    pub fn as_mut(&mut self) -> AnySignalMut {
+    pub fn as_mut(&mut self) -> AnySignalMut<'_> {
         match self {
             Self::Float(float) => AnySignalMut::Float(float),
             Self::Int(int) => AnySignalMut::Int(int),
@@ -640,6 +1282,8 @@ impl AnySignal {
             Self::String(string) => AnySignalMut::String(string),
             Self::List(list) => AnySignalMut::List(list),
             Self::Midi(midi) => AnySignalMut::Midi(midi),
+            Self::Spectrum(spectrum) => AnySignalMut::Spectrum(spectrum),
+            Self::Frame(frame) => AnySignalMut::Frame(frame),
         }
     }
 
@@ -677,6 +1321,8 @@ impl AnySignal {
             (Self::String(string), AnySignalRef::String(other)) => string.clone_from(other),
             (Self::List(list), AnySignalRef::List(other)) => list.clone_from(other),
             (Self::Midi(midi), AnySignalRef::Midi(other)) => *midi = *other,
+            (Self::Spectrum(spectrum), AnySignalRef::Spectrum(other)) => spectrum.clone_from(other),
+            (Self::Frame(frame), AnySignalRef::Frame(other)) => frame.clone_from(other),
             (this, other) => {
                 panic!(
                     "Signal types do not match: {:?} and {:?}",
@@ -703,6 +1349,10 @@ pub enum AnySignalRef<'a> {
     List(&'a Option<List>),
     /// A MIDI message.
     Midi(&'a Option<MidiMessage>),
+    /// A frame of frequency bins.
+    Spectrum(&'a Option<Spectrum>),
+    /// A frame of samples, one per channel.
+    Frame(&'a Option<Frame>),
 }
 
 impl<'a> AnySignalRef<'a> {
@@ -716,6 +1366,8 @@ impl<'a> AnySignalRef<'a> {
             Self::String(_) => SignalType::String,
             Self::List(_) => SignalType::List,
             Self::Midi(_) => SignalType::Midi,
+            Self::Spectrum(_) => SignalType::Spectrum,
+            Self::Frame(_) => SignalType::Frame,
         }
     }
 
@@ -739,6 +1391,8 @@ impl<'a> AnySignalRef<'a> {
             Self::String(string) => AnySignal::String((*string).clone()),
             Self::List(list) => AnySignal::List((*list).clone()),
             Self::Midi(midi) => AnySignal::Midi(**midi),
+            Self::Spectrum(spectrum) => AnySignal::Spectrum((*spectrum).clone()),
+            Self::Frame(frame) => AnySignal::Frame(**frame),
         }
     }
 
@@ -752,6 +1406,8 @@ impl<'a> AnySignalRef<'a> {
             Self::String(string) => string.is_some(),
             Self::List(list) => list.is_some(),
             Self::Midi(midi) => midi.is_some(),
+            Self::Spectrum(spectrum) => spectrum.is_some(),
+            Self::Frame(frame) => frame.is_some(),
         }
     }
 
@@ -777,6 +1433,10 @@ pub enum AnySignalMut<'a> {
     List(&'a mut Option<List>),
     /// A MIDI message.
     Midi(&'a mut Option<MidiMessage>),
+    /// A frame of frequency bins.
+    Spectrum(&'a mut Option<Spectrum>),
+    /// A frame of samples, one per channel.
+    Frame(&'a mut Option<Frame>),
 }
 
 impl<'a> AnySignalMut<'a> {
@@ -790,6 +1450,8 @@ impl<'a> AnySignalMut<'a> {
             Self::String(_) => SignalType::String,
             Self::List(_) => SignalType::List,
             Self::Midi(_) => SignalType::Midi,
+            Self::Spectrum(_) => SignalType::Spectrum,
+            Self::Frame(_) => SignalType::Frame,
         }
     }
 
@@ -813,6 +1475,8 @@ impl<'a> AnySignalMut<'a> {
             Self::String(string) => string.is_some(),
             Self::List(list) => list.is_some(),
             Self::Midi(midi) => midi.is_some(),
+            Self::Spectrum(spectrum) => spectrum.is_some(),
+            Self::Frame(frame) => frame.is_some(),
         }
     }
 
@@ -832,6 +1496,8 @@ impl<'a> AnySignalMut<'a> {
             Self::String(string) => *string = None,
             Self::List(list) => *list = None,
             Self::Midi(midi) => *midi = None,
+            Self::Spectrum(spectrum) => *spectrum = None,
+            Self::Frame(frame) => *frame = None,
         }
     }
 
@@ -857,6 +1523,8 @@ impl<'a> AnySignalMut<'a> {
             Self::String(string) => AnySignal::String((*string).clone()),
             Self::List(list) => AnySignal::List((*list).clone()),
             Self::Midi(midi) => AnySignal::Midi(**midi),
+            Self::Spectrum(spectrum) => AnySignal::Spectrum((*spectrum).clone()),
+            Self::Frame(frame) => AnySignal::Frame(**frame),
         }
     }
 
@@ -870,6 +1538,8 @@ impl<'a> AnySignalMut<'a> {
             (Self::String(string), AnySignalRef::String(other)) => string.clone_from(other),
             (Self::List(list), AnySignalRef::List(other)) => list.clone_from(other),
             (Self::Midi(midi), AnySignalRef::Midi(other)) => **midi = *other,
+            (Self::Spectrum(spectrum), AnySignalRef::Spectrum(other)) => spectrum.clone_from(other),
+            (Self::Frame(frame), AnySignalRef::Frame(other)) => frame.clone_from(other),
             (this, other) => {
                 panic!(
                     "Signal types do not match: {:?} and {:?}",
@@ -902,6 +1572,12 @@ pub enum SignalType {
 
     /// A MIDI signal.
     Midi,
+
+    /// A spectrum signal.
+    Spectrum,
+
+    /// A multichannel frame signal.
+    Frame,
 }
 
 impl SignalType {
@@ -916,8 +1592,17 @@ impl SignalType {
                 | (Self::String, Self::String)
                 | (Self::List, Self::List)
                 | (Self::Midi, Self::Midi)
+                | (Self::Spectrum, Self::Spectrum)
+                | (Self::Frame, Self::Frame)
         )
     }
+
+    /// Returns `true` if signals of this type can be cast to the other signal type, either because the types are compatible or by [`AnySignal::cast`].
+    #[inline]
+    pub fn can_cast_to(&self, other: &Self) -> bool {
+        let scalar = |ty: &Self| matches!(ty, Self::Float | Self::Int | Self::Bool | Self::String);
+        self.is_compatible_with(other) || (scalar(self) && scalar(other))
+    }
 }
 
 /// A buffer of signals that can hold any signal type.
@@ -941,6 +1626,12 @@ pub enum SignalBuffer {
 
     /// A buffer of MIDI signals.
     Midi(Buffer<MidiMessage>),
+
+    /// A buffer of spectrum signals.
+    Spectrum(Buffer<Spectrum>),
+
+    /// A buffer of frame signals.
+    Frame(Buffer<Frame>),
 }
 
 impl SignalBuffer {
@@ -953,6 +1644,8 @@ impl SignalBuffer {
             SignalType::String => Self::String(Buffer::zeros(length)),
             SignalType::List => Self::List(Buffer::zeros(length)),
             SignalType::Midi => Self::Midi(Buffer::zeros(length)),
+            SignalType::Spectrum => Self::Spectrum(Buffer::zeros(length)),
+            SignalType::Frame => Self::Frame(Buffer::zeros(length)),
         }
     }
 
@@ -966,6 +1659,8 @@ impl SignalBuffer {
             Self::String(_) => SignalType::String,
             Self::List(_) => SignalType::List,
             Self::Midi(_) => SignalType::Midi,
+            Self::Spectrum(_) => SignalType::Spectrum,
+            Self::Frame(_) => SignalType::Frame,
         }
     }
 
@@ -997,6 +1692,8 @@ impl SignalBuffer {
             Self::String(buffer) => buffer.len(),
             Self::List(buffer) => buffer.len(),
             Self::Midi(buffer) => buffer.len(),
+            Self::Spectrum(buffer) => buffer.len(),
+            Self::Frame(buffer) => buffer.len(),
         }
     }
 
@@ -1006,11 +1703,26 @@ impl SignalBuffer {
         self.len() == 0
     }
 
+    /// Returns the number of signals the buffer can hold without allocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match self {
+            Self::Float(buffer) => buffer.buf.capacity(),
+            Self::Int(buffer) => buffer.buf.capacity(),
+            Self::Bool(buffer) => buffer.buf.capacity(),
+            Self::String(buffer) => buffer.buf.capacity(),
+            Self::List(buffer) => buffer.buf.capacity(),
+            Self::Midi(buffer) => buffer.buf.capacity(),
+            Self::Spectrum(buffer) => buffer.buf.capacity(),
+            Self::Frame(buffer) => buffer.buf.capacity(),
+        }
+    }
+
     /// Resizes the buffer to the given length, filling the new elements with the given value.
     ///
     /// # Panics
     ///
-    /// Panics if the value type does not match the buffer type.
+    /// Panics if the value type does not match the buffer type, or if the buffer is one of a [`Runtime`](crate::runtime::Runtime)'s output buffers and would grow past its [capacity](SignalBuffer::capacity).
     pub fn resize(&mut self, length: usize, value: impl Into<AnySignal>) {
         let value = value.into();
         match (self, value) {
@@ -1020,6 +1732,10 @@ impl SignalBuffer {
             (Self::String(buffer), AnySignal::String(value)) => buffer.buf.resize(length, value),
             (Self::List(buffer), AnySignal::List(value)) => buffer.buf.resize(length, value),
             (Self::Midi(buffer), AnySignal::Midi(value)) => buffer.buf.resize(length, value),
+            (Self::Spectrum(buffer), AnySignal::Spectrum(value)) => {
+                buffer.buf.resize(length, value)
+            }
+            (Self::Frame(buffer), AnySignal::Frame(value)) => buffer.buf.resize(length, value),
             _ => panic!("Cannot resize buffer with value of different type"),
         }
     }
@@ -1038,11 +1754,17 @@ impl SignalBuffer {
             (Self::String(buffer), AnySignal::String(value)) => buffer.fill(value),
             (Self::List(buffer), AnySignal::List(value)) => buffer.fill(value),
             (Self::Midi(buffer), AnySignal::Midi(value)) => buffer.fill(value),
+            (Self::Spectrum(buffer), AnySignal::Spectrum(value)) => buffer.fill(value),
+            (Self::Frame(buffer), AnySignal::Frame(value)) => buffer.fill(value),
             _ => panic!("Cannot fill buffer with value of different type"),
         }
     }
 
     /// Resizes the buffer to the given length, filling the new elements with `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is one of a [`Runtime`](crate::runtime::Runtime)'s output buffers and would grow past its [capacity](SignalBuffer::capacity).
     pub fn resize_default(&mut self, length: usize) {
         match self {
             Self::Float(buffer) => buffer.buf.resize(length, None),
@@ -1051,6 +1773,8 @@ impl SignalBuffer {
             Self::String(buffer) => buffer.buf.resize(length, None),
             Self::List(buffer) => buffer.buf.resize(length, None),
             Self::Midi(buffer) => buffer.buf.resize(length, None),
+            Self::Spectrum(buffer) => buffer.buf.resize(length, None),
+            Self::Frame(buffer) => buffer.buf.resize(length, None),
         }
     }
 
@@ -1073,6 +1797,8 @@ impl SignalBuffer {
             Self::String(buffer) => buffer.fill(None),
             Self::List(buffer) => buffer.fill(None),
             Self::Midi(buffer) => buffer.fill(None),
+            Self::Spectrum(buffer) => buffer.fill(None),
+            Self::Frame(buffer) => buffer.fill(None),
         }
     }
 
@@ -1088,7 +1814,7 @@ impl SignalBuffer {
 
     /// Returns a reference to the signal at the given index.
     #[inline]
-    pub fn get(&self, index: usize) -> Option<AnySignalRef> {
+    pub fn get(&self, index: usize) -> Option<AnySignalRef<'_>> {
         match self {
             Self::Float(buffer) => buffer.get(index).map(AnySignalRef::Float),
             Self::Int(buffer) => buffer.get(index).map(AnySignalRef::Int),
@@ -1096,12 +1822,14 @@ impl SignalBuffer {
             Self::String(buffer) => buffer.get(index).map(AnySignalRef::String),
             Self::List(buffer) => buffer.get(index).map(AnySignalRef::List),
             Self::Midi(buffer) => buffer.get(index).map(AnySignalRef::Midi),
+            Self::Spectrum(buffer) => buffer.get(index).map(AnySignalRef::Spectrum),
+            Self::Frame(buffer) => buffer.get(index).map(AnySignalRef::Frame),
         }
     }
 
     /// Returns a mutable reference to the signal at the given index.
     #[inline]
-    pub fn get_mut(&mut self, index: usize) -> Option<AnySignalMut> {
+    pub fn get_mut(&mut self, index: usize) -> Option<AnySignalMut<'_>> {
         match self {
             Self::Float(buffer) => buffer.get_mut(index).map(AnySignalMut::Float),
             Self::Int(buffer) => buffer.get_mut(index).map(AnySignalMut::Int),
@@ -1109,6 +1837,8 @@ impl SignalBuffer {
             Self::String(buffer) => buffer.get_mut(index).map(AnySignalMut::String),
             Self::List(buffer) => buffer.get_mut(index).map(AnySignalMut::List),
             Self::Midi(buffer) => buffer.get_mut(index).map(AnySignalMut::Midi),
+            Self::Spectrum(buffer) => buffer.get_mut(index).map(AnySignalMut::Spectrum),
+            Self::Frame(buffer) => buffer.get_mut(index).map(AnySignalMut::Frame),
         }
     }
 
@@ -1144,6 +1874,10 @@ impl SignalBuffer {
             (Self::String(buffer), AnySignalRef::String(value)) => buffer[index].clone_from(value),
             (Self::List(buffer), AnySignalRef::List(value)) => buffer[index].clone_from(value),
             (Self::Midi(buffer), AnySignalRef::Midi(value)) => buffer[index] = *value,
+            (Self::Spectrum(buffer), AnySignalRef::Spectrum(value)) => {
+                buffer[index].clone_from(value)
+            }
+            (Self::Frame(buffer), AnySignalRef::Frame(value)) => buffer[index].clone_from(value),
             (this, value) => {
                 panic!(
                     "Cannot set signal of different type: {:?} != {:?}",
@@ -1178,6 +1912,8 @@ impl SignalBuffer {
             Self::String(buffer) => buffer[index] = None,
             Self::List(buffer) => buffer[index] = None,
             Self::Midi(buffer) => buffer[index] = None,
+            Self::Spectrum(buffer) => buffer[index] = None,
+            Self::Frame(buffer) => buffer[index] = None,
         }
     }
 
@@ -1207,6 +1943,12 @@ impl SignalBuffer {
             (Self::Midi(this), Self::Midi(other)) => {
                 this.clone_from_slice(other);
             }
+            (Self::Spectrum(this), Self::Spectrum(other)) => {
+                this.clone_from_slice(other);
+            }
+            (Self::Frame(this), Self::Frame(other)) => {
+                this.copy_from_slice(other);
+            }
             _ => panic!("Cannot copy buffer of different type"),
         }
     }
@@ -1231,19 +1973,25 @@ impl SignalBuffer {
             (Self::Midi(this), Self::Midi(other)) => {
                 this.copy_from_slice(other);
             }
+            (Self::Frame(this), Self::Frame(other)) => {
+                this.copy_from_slice(other);
+            }
             (Self::String(_), Self::String(_)) => {
                 panic!("Cannot copy string buffer; use `clone_from` instead");
             }
             (Self::List(_), Self::List(_)) => {
                 panic!("Cannot copy list buffer; use `clone_from` instead");
             }
+            (Self::Spectrum(_), Self::Spectrum(_)) => {
+                panic!("Cannot copy spectrum buffer; use `clone_from` instead");
+            }
             _ => panic!("Cannot copy buffer of different type"),
         }
     }
 
     /// Returns an iterator over the signals in the buffer.
     #[inline]
-    pub fn iter(&self) -> SignalBufferIter {
+    pub fn iter(&self) -> SignalBufferIter<'_> {
         SignalBufferIter {
             buffer: self,
             index: 0,
@@ -1253,7 +2001,7 @@ impl SignalBuffer {
 
     /// Returns a mutable iterator over the signals in the buffer.
     #[inline]
-    pub fn iter_mut(&mut self) -> SignalBufferIterMut {
+    pub fn iter_mut(&mut self) -> SignalBufferIterMut<'_> {
         SignalBufferIterMut {
             buffer: self,
             index: 0,
@@ -1282,6 +2030,8 @@ impl<'a> Iterator for SignalBufferIter<'a> {
                 SignalBuffer::String(buffer) => AnySignalRef::String(&buffer[self.index]),
                 SignalBuffer::List(buffer) => AnySignalRef::List(&buffer[self.index]),
                 SignalBuffer::Midi(buffer) => AnySignalRef::Midi(&buffer[self.index]),
+                SignalBuffer::Spectrum(buffer) => AnySignalRef::Spectrum(&buffer[self.index]),
+                SignalBuffer::Frame(buffer) => AnySignalRef::Frame(&buffer[self.index]),
             };
             self.index += 1;
             Some(signal)
@@ -1342,6 +2092,12 @@ impl<'a> Iterator for SignalBufferIterMut<'a> {
                     SignalBuffer::Midi(buffer) => AnySignalMut::Midi(
                         &mut *(&mut buffer[self.index] as *mut Option<MidiMessage>),
                     ),
+                    SignalBuffer::Spectrum(buffer) => AnySignalMut::Spectrum(
+                        &mut *(&mut buffer[self.index] as *mut Option<Spectrum>),
+                    ),
+                    SignalBuffer::Frame(buffer) => {
+                        AnySignalMut::Frame(&mut *(&mut buffer[self.index] as *mut Option<Frame>))
+                    }
                 };
                 self.index += 1;
                 Some(signal)
@@ -1418,3 +2174,21 @@ impl FromIterator<MidiMessage> for SignalBuffer {
         })
     }
 }
+
+impl FromIterator<Spectrum> for SignalBuffer {
+    fn from_iter<T: IntoIterator<Item = Spectrum>>(iter: T) -> Self {
+        let iter = iter.into_iter().map(Some);
+        Self::Spectrum(Buffer {
+            buf: iter.collect(),
+        })
+    }
+}
+
+impl FromIterator<Frame> for SignalBuffer {
+    fn from_iter<T: IntoIterator<Item = Frame>>(iter: T) -> Self {
+        let iter = iter.into_iter().map(Some);
+        Self::Frame(Buffer {
+            buf: iter.collect(),
+        })
+    }
+}
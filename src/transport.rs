@@ -0,0 +1,225 @@
+//! A shared musical clock for the audio graph.
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::signal::Float;
+
+/// The state of a [`Transport`] at the start of the block currently being processed.
+///
+/// This is made available to processors through [`ProcessorInputs::transport`](crate::processor::ProcessorInputs::transport).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransportState {
+    /// The tempo in beats per minute.
+    pub bpm: Float,
+    /// The number of beats in a bar.
+    pub beats_per_bar: u32,
+    /// Whether the transport is playing.
+    pub playing: bool,
+    /// The musical position in beats at the start of the block.
+    pub beat: Float,
+    /// The number of samples the transport has played at the start of the block.
+    pub sample: u64,
+}
+
+impl Default for TransportState {
+    fn default() -> Self {
+        Self {
+            bpm: 120.0,
+            beats_per_bar: 4,
+            playing: true,
+            beat: 0.0,
+            sample: 0,
+        }
+    }
+}
+
+impl TransportState {
+    /// Returns the number of beats that pass every sample at the given sample rate, or `0` if the transport is stopped.
+    #[inline]
+    pub fn beats_per_sample(&self, sample_rate: Float) -> Float {
+        if self.playing {
+            self.bpm / (60.0 * sample_rate)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the musical position in beats at the given sample index of the current block.
+    #[inline]
+    pub fn beat_at(&self, sample_index: usize, sample_rate: Float) -> Float {
+        self.beat + sample_index as Float * self.beats_per_sample(sample_rate)
+    }
+
+    /// Returns the bar containing the given position in beats.
+    #[inline]
+    pub fn bar_of(&self, beat: Float) -> Float {
+        (beat / self.beats_per_bar.max(1) as Float).floor()
+    }
+}
+
+#[derive(Debug)]
+struct TransportInner {
+    bpm: AtomicU64,
+    beats_per_bar: AtomicU32,
+    playing: AtomicBool,
+    beat: AtomicU64,
+    sample: AtomicU64,
+    // a position requested by `seek`, applied at the start of the next block (NaN if none)
+    seek: AtomicU64,
+}
+
+/// A musical clock shared between a [`Graph`](crate::graph::Graph), its [`Runtime`](crate::runtime::Runtime), and any handles to it.
+///
+/// The transport keeps track of the tempo, time signature, play/stop state, and current position in beats.
+/// The runtime advances the transport after every block it processes, and processors (such as a synced [`Metro`](crate::builtins::Metro)) can read its state at the start of each block.
+///
+/// Changes made from other threads take effect at the start of the next block.
+#[derive(Debug, Clone)]
+pub struct Transport {
+    inner: Arc<TransportInner>,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::new(120.0)
+    }
+}
+
+impl Transport {
+    /// Creates a new, playing transport with the given tempo in beats per minute, in 4/4 time.
+    pub fn new(bpm: Float) -> Self {
+        Self {
+            inner: Arc::new(TransportInner {
+                bpm: AtomicU64::new(to_bits(bpm)),
+                beats_per_bar: AtomicU32::new(4),
+                playing: AtomicBool::new(true),
+                beat: AtomicU64::new(to_bits(0.0)),
+                sample: AtomicU64::new(0),
+                seek: AtomicU64::new(to_bits(Float::NAN)),
+            }),
+        }
+    }
+
+    /// Returns the tempo in beats per minute.
+    pub fn bpm(&self) -> Float {
+        from_bits(self.inner.bpm.load(Ordering::Relaxed))
+    }
+
+    /// Sets the tempo in beats per minute.
+    pub fn set_bpm(&self, bpm: Float) {
+        self.inner.bpm.store(to_bits(bpm), Ordering::Relaxed);
+    }
+
+    /// Returns the number of beats in a bar.
+    pub fn beats_per_bar(&self) -> u32 {
+        self.inner.beats_per_bar.load(Ordering::Relaxed)
+    }
+
+    /// Sets the number of beats in a bar.
+    pub fn set_beats_per_bar(&self, beats_per_bar: u32) {
+        self.inner
+            .beats_per_bar
+            .store(beats_per_bar, Ordering::Relaxed);
+    }
+
+    /// Returns whether the transport is playing.
+    pub fn is_playing(&self) -> bool {
+        self.inner.playing.load(Ordering::Relaxed)
+    }
+
+    /// Starts (or resumes) the transport.
+    pub fn play(&self) {
+        self.inner.playing.store(true, Ordering::Relaxed);
+    }
+
+    /// Stops the transport, keeping its current position.
+    pub fn stop(&self) {
+        self.inner.playing.store(false, Ordering::Relaxed);
+    }
+
+    /// Moves the transport to the given position in beats.
+    pub fn seek(&self, beat: Float) {
+        self.inner.seek.store(to_bits(beat), Ordering::Relaxed);
+    }
+
+    /// Returns the current position in beats.
+    pub fn beat(&self) -> Float {
+        from_bits(self.inner.beat.load(Ordering::Relaxed))
+    }
+
+    /// Returns the current bar, starting from `0`.
+    pub fn bar(&self) -> u64 {
+        (self.beat() / self.beats_per_bar().max(1) as Float).floor() as u64
+    }
+
+    /// Returns the number of samples the transport has played.
+    pub fn sample(&self) -> u64 {
+        self.inner.sample.load(Ordering::Relaxed)
+    }
+
+    /// Applies any pending seek and returns the state of the transport for the next block.
+    pub(crate) fn begin_block(&self) -> TransportState {
+        let seek = from_bits(self.inner.seek.swap(to_bits(Float::NAN), Ordering::Relaxed));
+        if !seek.is_nan() {
+            self.inner.beat.store(to_bits(seek), Ordering::Relaxed);
+        }
+
+        self.state()
+    }
+
+    /// Advances the transport by the given number of samples, if it is playing.
+    pub(crate) fn end_block(&self, state: &TransportState, num_samples: usize, sample_rate: Float) {
+        if !state.playing {
+            return;
+        }
+
+        // only advance from the position the block started at, so runtimes sharing the transport (such as the voices of a `PolyVoice`) only advance it once
+        let beat = state.beat_at(num_samples, sample_rate);
+        let advanced = self.inner.beat.compare_exchange(
+            to_bits(state.beat),
+            to_bits(beat),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+        if advanced.is_ok() {
+            self.inner
+                .sample
+                .store(state.sample + num_samples as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Copies the given state into this transport, e.g. to keep a sub-graph in sync with its parent graph.
+    pub(crate) fn sync_to(&self, state: &TransportState) {
+        self.set_bpm(state.bpm);
+        self.set_beats_per_bar(state.beats_per_bar);
+        self.inner.playing.store(state.playing, Ordering::Relaxed);
+        self.inner
+            .beat
+            .store(to_bits(state.beat), Ordering::Relaxed);
+        self.inner.sample.store(state.sample, Ordering::Relaxed);
+    }
+
+    /// Returns the current state of the transport.
+    pub fn state(&self) -> TransportState {
+        TransportState {
+            bpm: self.bpm(),
+            beats_per_bar: self.beats_per_bar(),
+            playing: self.is_playing(),
+            beat: self.beat(),
+            sample: self.sample(),
+        }
+    }
+}
+
+#[inline]
+fn to_bits(value: Float) -> u64 {
+    (value as f64).to_bits()
+}
+
+#[inline]
+fn from_bits(bits: u64) -> Float {
+    f64::from_bits(bits) as Float
+}
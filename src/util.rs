@@ -16,11 +16,23 @@ pub fn available_audio_backends() -> Vec<AudioBackend> {
             #[cfg(target_os = "linux")]
             cpal::HostId::Alsa => {
                 backends.push(AudioBackend::Alsa);
+
+                let pipewire = cpal::host_from_id(host)
+                    .ok()
+                    .and_then(|host| host.output_devices().ok())
+                    .and_then(crate::runtime::find_pipewire_device);
+                if pipewire.is_some() {
+                    backends.push(AudioBackend::PipeWire);
+                }
             }
             #[cfg(target_os = "windows")]
             cpal::HostId::Wasapi => {
                 backends.push(AudioBackend::Wasapi);
             }
+            #[cfg(target_os = "macos")]
+            cpal::HostId::CoreAudio => {
+                backends.push(AudioBackend::CoreAudio);
+            }
             #[allow(unreachable_patterns)]
             _ => {}
         }
@@ -40,21 +52,23 @@ pub fn list_audio_backends() {
 /// Prints a list of available audio devices for the given backend to the console.
 pub fn list_audio_devices(backend: AudioBackend) {
     println!("Listing devices for backend: {:?}", backend);
-    let host = match backend {
-        AudioBackend::Default => cpal::default_host(),
-        #[cfg(all(target_os = "linux", feature = "jack"))]
-        AudioBackend::Jack => cpal::host_from_id(cpal::HostId::Jack).unwrap(),
-        #[cfg(target_os = "linux")]
-        AudioBackend::Alsa => cpal::host_from_id(cpal::HostId::Alsa).unwrap(),
-        #[cfg(target_os = "windows")]
-        AudioBackend::Wasapi => cpal::host_from_id(cpal::HostId::Wasapi).unwrap(),
-    };
+    let host = backend.host().unwrap();
     for (i, device) in host.output_devices().unwrap().enumerate() {
         println!("  {}: {:?}", i, device.name());
     }
 }
 
+/// Prints a list of available audio input devices for the given backend to the console.
+pub fn list_audio_input_devices(backend: AudioBackend) {
+    println!("Listing input devices for backend: {:?}", backend);
+    let host = backend.host().unwrap();
+    for (i, device) in host.input_devices().unwrap().enumerate() {
+        println!("  {}: {:?}", i, device.name());
+    }
+}
+
 /// Prints a list of available MIDI ports to the console.
+#[cfg(feature = "midi")]
 pub fn list_midi_ports() {
     let input = midir::MidiInput::new("raug").unwrap();
     println!("Listing available MIDI ports:");
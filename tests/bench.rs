@@ -0,0 +1,18 @@
+use raug::{bench, prelude::*};
+
+#[test]
+fn measure_processes_without_allocating() {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency").connect(440.0);
+    (sine * 0.5).output(0).connect(&out.input(0));
+
+    let report = bench::measure(&graph.build(), 16);
+    assert_eq!(report.blocks, 16);
+
+    #[cfg(feature = "profiling")]
+    assert_eq!(report.allocations, Some(0));
+    #[cfg(not(feature = "profiling"))]
+    assert_eq!(report.allocations, None);
+}
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use raug::{
+    encode::{FlacFileOut, FlacSettings},
+    prelude::*,
+};
+
+const SAMPLE_RATE: u32 = 48_000;
+
+// a path in the temporary directory that no other test writes to
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("raug-{}-{}", std::process::id(), name))
+}
+
+// two seconds of a stereo sine, louder on the left, with a quiet tail that doesn't fill the last block
+fn stereo_sine() -> Vec<[Float; 2]> {
+    (0..2 * SAMPLE_RATE as usize + 123)
+        .map(|i| {
+            let sample = (TAU * 440.0 * i as Float / SAMPLE_RATE as Float).sin();
+            [sample * 0.8, sample * 0.3]
+        })
+        .collect()
+}
+
+#[test]
+fn flac_round_trip() {
+    let path = temp_path("round-trip.flac");
+    let frames = stereo_sine();
+
+    let mut writer = FlacFileOut::create(&path, 2, SAMPLE_RATE, FlacSettings::new(16, 5)).unwrap();
+    for frame in &frames {
+        for &sample in frame {
+            writer.write_sample(sample).unwrap();
+        }
+    }
+    writer.finalize().unwrap();
+
+    let mut reader = claxon::FlacReader::open(&path).unwrap();
+    let info = reader.streaminfo();
+    assert_eq!(info.channels, 2);
+    assert_eq!(info.sample_rate, SAMPLE_RATE);
+    assert_eq!(info.bits_per_sample, 16);
+    assert_eq!(info.samples, Some(frames.len() as u64));
+    // the MD5 signature is left unknown
+    assert_eq!(info.md5sum, [0; 16]);
+
+    let decoded = reader
+        .samples()
+        .map(|sample| sample.unwrap())
+        .collect::<Vec<_>>();
+    let expected = frames
+        .iter()
+        .flatten()
+        .map(|sample| ((sample * 32768.0).round() as i32).clamp(-32768, 32767))
+        .collect::<Vec<_>>();
+    assert_eq!(decoded, expected);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[cfg(feature = "ogg")]
+#[test]
+fn vorbis_round_trip() {
+    use raug::encode::{OggFileOut, VorbisSettings};
+
+    let path = temp_path("round-trip.ogg");
+    let frames = stereo_sine();
+
+    let mut writer = OggFileOut::create(&path, 2, SAMPLE_RATE, VorbisSettings::new(0.8)).unwrap();
+    for frame in &frames {
+        for &sample in frame {
+            writer.write_sample(sample).unwrap();
+        }
+    }
+    assert_eq!(writer.len(), frames.len() as u64);
+    writer.finalize().unwrap();
+
+    let file = std::fs::File::open(&path).unwrap();
+    let mut decoder = vorbis_rs::VorbisDecoder::new(std::io::BufReader::new(file)).unwrap();
+    assert_eq!(decoder.channels().get(), 2);
+    assert_eq!(decoder.sampling_frequency().get(), SAMPLE_RATE);
+
+    let mut decoded = Vec::new();
+    while let Some(block) = decoder.decode_audio_block().unwrap() {
+        let [left, right] = block.samples() else {
+            panic!("expected two channels");
+        };
+        decoded.extend(left.iter().zip(right.iter()).map(|(&l, &r)| [l, r]));
+    }
+    assert_eq!(decoded.len(), frames.len());
+
+    // lossy, but close to the original
+    let error = decoded
+        .iter()
+        .zip(&frames)
+        .flat_map(|(decoded, frame)| decoded.iter().zip(frame))
+        .map(|(&decoded, &sample)| (decoded as Float - sample).powi(2))
+        .sum::<Float>()
+        / (frames.len() * 2) as Float;
+    assert!(error.sqrt() < 0.01, "RMS error {}", error.sqrt());
+
+    std::fs::remove_file(path).ok();
+}
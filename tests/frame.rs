@@ -0,0 +1,121 @@
+use raug::{bench, prelude::*};
+
+// a stereo frame of two constants, scaled by a float and offset by another stereo frame, then split back into channels
+fn scaled_stereo() -> Graph {
+    let graph = GraphBuilder::new();
+    let left_out = graph.add_audio_output();
+    let right_out = graph.add_audio_output();
+
+    let frame = graph.stereo(0.5, -1.0);
+    let offset = graph.stereo(0.25, 0.125);
+    let scaled = frame * 2.0 + offset;
+
+    let split = graph.add(FrameSplit::new(2));
+    split.input(0).connect(scaled);
+    split.output(0).connect(&left_out.input(0));
+    split.output(1).connect(&right_out.input(0));
+
+    graph.build()
+}
+
+#[test]
+fn floats_broadcast_over_frames() {
+    let mut runtime = Runtime::new(scaled_stereo());
+    runtime.allocate_for_block_size(48_000.0, 64);
+    runtime.process().unwrap();
+
+    for (output, expected) in [(0, 1.25), (1, -1.875)] {
+        let buffer = runtime
+            .get_output(output)
+            .unwrap()
+            .as_type::<Float>()
+            .unwrap();
+        assert!(buffer.iter().all(|sample| *sample == Some(expected)));
+    }
+}
+
+#[test]
+fn frame_math_processes_without_allocating() {
+    let report = bench::measure(&scaled_stereo(), 16);
+
+    #[cfg(feature = "profiling")]
+    assert_eq!(report.allocations, Some(0));
+    #[cfg(not(feature = "profiling"))]
+    assert_eq!(report.allocations, None);
+}
+
+// renders the first block of the given outputs of a graph
+fn render(graph: Graph, outputs: usize) -> Vec<Vec<Float>> {
+    let mut runtime = Runtime::new(graph);
+    runtime.allocate_for_block_size(48_000.0, 512);
+    runtime.process().unwrap();
+
+    (0..outputs)
+        .map(|output| {
+            let buffer = runtime
+                .get_output(output)
+                .unwrap()
+                .as_type::<Float>()
+                .unwrap();
+            buffer.iter().map(|sample| sample.unwrap()).collect()
+        })
+        .collect()
+}
+
+// pans a sine with the given panner, widens it and sends it through a ping-pong delay, all either on separate channels or on stereo frames
+fn stereo_chain(framed: bool) -> Graph {
+    let graph = GraphBuilder::new();
+    let left_out = graph.add_audio_output();
+    let right_out = graph.add_audio_output();
+
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency").connect(440.0);
+
+    let pan = Pan::new(-0.5);
+    let width = StereoWidth::new(1.5);
+    let delay = PingPongDelay::new(0.001);
+
+    if framed {
+        let pan = graph.add(pan.framed());
+        pan.input("in").connect(sine);
+        let width = graph.add(width.framed());
+        width.input("in").connect(pan);
+        let delay = graph.add(delay.framed());
+        delay.input("in").connect(width);
+
+        let split = graph.add(FrameSplit::new(2));
+        split.input(0).connect(delay);
+        split.output(0).connect(&left_out.input(0));
+        split.output(1).connect(&right_out.input(0));
+    } else {
+        let pan = graph.add(pan);
+        pan.input("in").connect(sine);
+        let width = graph.add(width);
+        width.input("left").connect(pan.output("left"));
+        width.input("right").connect(pan.output("right"));
+        let delay = graph.add(delay);
+        delay.input("left").connect(width.output("left"));
+        delay.input("right").connect(width.output("right"));
+
+        delay.output("left").connect(&left_out.input(0));
+        delay.output("right").connect(&right_out.input(0));
+    }
+
+    graph.build()
+}
+
+#[test]
+fn framed_stereo_processors_match_separate_channels() {
+    let framed = render(stereo_chain(true), 2);
+    let separate = render(stereo_chain(false), 2);
+
+    // the delay has had time to echo
+    assert!(separate[1].iter().any(|sample| *sample != 0.0));
+    assert_eq!(framed, separate);
+}
+
+#[test]
+#[should_panic(expected = "Add: cannot combine Float and Int inputs")]
+fn mismatched_binary_types_are_rejected() {
+    Add::with_types(SignalType::Float, SignalType::Int);
+}
@@ -0,0 +1,189 @@
+use raug::{
+    graph::{GraphConstructionError, NodeIndex},
+    prelude::*,
+};
+
+#[test]
+fn connect_missing_node_is_an_error() {
+    let mut graph = Graph::new();
+    let sine = graph.add_processor(SineOscillator::default());
+    let missing = NodeIndex::new(100);
+
+    assert!(matches!(
+        graph.connect(sine, 0, missing, 0),
+        Err(GraphConstructionError::NodeNotFound(index)) if index == missing
+    ));
+    assert!(matches!(
+        graph.connect(missing, 0, sine, 0),
+        Err(GraphConstructionError::NodeNotFound(index)) if index == missing
+    ));
+}
+
+#[test]
+fn try_connect_node_from_another_graph_is_an_error() {
+    let other = GraphBuilder::new();
+    other.add(SineOscillator::default());
+    other.add(SineOscillator::default());
+    let foreign = other.add(SineOscillator::default());
+
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+
+    assert!(matches!(
+        graph.try_connect(&foreign, 0, &out, 0),
+        Err(GraphConstructionError::NodeNotFound(_))
+    ));
+    assert!(matches!(
+        graph.try_connect(&foreign, "out", &out, "in"),
+        Err(GraphConstructionError::NodeNotFound(_))
+    ));
+}
+
+fn render(graph: Graph, blocks: usize) -> Vec<Float> {
+    let mut runtime = Runtime::new(graph);
+    runtime.allocate_for_block_size(48_000.0, 64);
+    let mut output = Vec::new();
+    for _ in 0..blocks {
+        runtime.process().unwrap();
+        let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+        output.extend(buffer.iter().map(|sample| sample.unwrap()));
+    }
+    output
+}
+
+#[test]
+fn optimize_preserves_output() {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+    let through = graph.add(Passthrough::new(SignalType::Float));
+
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency")
+        .connect((graph.constant(2.0) + 3.0) * 88.0);
+    through.input(0).connect(sine);
+    let gain = graph.constant(0.25) + 0.25;
+    (through * gain).output(0).connect(&out.input(0));
+
+    let mut graph = graph.build();
+    let expected = render(graph.clone(), 8);
+
+    let node_count = graph.digraph().node_count();
+    let removed = graph.optimize();
+    assert!(!removed.is_empty());
+    assert!(graph.digraph().node_count() < node_count);
+    // the constants added by folding don't take the indices of removed nodes
+    for (index, _) in &removed {
+        assert!(!graph.digraph().contains_node(*index));
+    }
+
+    assert_eq!(render(graph, 8), expected);
+}
+
+// a chain of math processors on a sine, where each stage can process its input in place
+// unless `fan_out` gives every stage a second reader, which makes the runtime copy its buffer instead
+fn math_chain(fan_out: bool) -> Graph {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency").connect(440.0);
+    let other = graph.add(SineOscillator::default());
+    other.input("frequency").connect(110.0);
+
+    let mut stages = vec![sine.clone()];
+    stages.push(stages.last().unwrap() * 0.5);
+    stages.push(stages.last().unwrap() + &other);
+    stages.push(stages.last().unwrap().neg());
+    stages.push(stages.last().unwrap().tanh());
+    stages.push(stages.last().unwrap() - 0.25);
+    let through = graph.add(Passthrough::new(SignalType::Float));
+    through.input(0).connect(stages.last().unwrap());
+    stages.push(through);
+
+    if fan_out {
+        for stage in &stages {
+            let reader = graph.add(Passthrough::new(SignalType::Float));
+            reader.input(0).connect(stage);
+        }
+    }
+    stages.last().unwrap().output(0).connect(&out.input(0));
+
+    graph.build()
+}
+
+#[test]
+fn in_place_processing_matches_copying() {
+    assert_eq!(render(math_chain(false), 8), render(math_chain(true), 8));
+}
+
+#[test]
+fn unparsable_strings_cast_to_none() {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+    let text = Param::new::<String>("text", Some("not a number".to_string()));
+    out.input(0).connect_cast(graph.add_param(text.clone()));
+
+    let mut runtime = Runtime::new(graph.build());
+    runtime.allocate_for_block_size(48_000.0, 64);
+    runtime.process().unwrap();
+    let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+    assert!(buffer.iter().all(Option::is_none));
+
+    text.send("0.5".to_string());
+    runtime.process().unwrap();
+    let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+    assert!(buffer.iter().all(|sample| *sample == Some(0.5)));
+}
+
+#[test]
+fn track_and_hold_holds_through_gaps() {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+    // a message sent once a second, so the input is `None` between messages
+    let gappy = graph.add(Message::new(0.5 as Float));
+    gappy.input("trig").connect(graph.add(Metro::new(1.0)));
+    let hold = graph.add(TrackAndHold::default());
+    hold.input("in").connect(gappy);
+    hold.input("gate").connect(graph.constant(true));
+    hold.output(0).connect(&out.input(0));
+
+    assert!(render(graph.build(), 2).iter().all(|&sample| sample == 0.5));
+}
+
+#[test]
+fn empty_chain_is_none() {
+    let graph = GraphBuilder::new();
+    assert!(graph.chain([]).is_none());
+}
+
+#[test]
+fn failed_chain_adds_no_nodes() {
+    let graph = GraphBuilder::new();
+    let processors: [Box<dyn Processor>; 3] = [
+        Box::new(SineOscillator::default()),
+        Box::new(Passthrough::new(SignalType::Float)),
+        Box::new(Passthrough::new(SignalType::Int)),
+    ];
+
+    assert!(matches!(
+        graph.try_chain(processors),
+        Err(GraphConstructionError::IncompatibleSignalTypes { .. })
+    ));
+    assert_eq!(graph.build().digraph().node_count(), 0);
+}
+
+#[test]
+fn failed_pipe_adds_no_node() {
+    let graph = GraphBuilder::new();
+    let sine = graph.add(SineOscillator::default());
+
+    assert!(matches!(
+        sine.try_then(Passthrough::new(SignalType::Int)),
+        Err(GraphConstructionError::IncompatibleSignalTypes { .. })
+    ));
+    assert!(matches!(
+        sine.output(0).try_pipe(Null),
+        Err(GraphConstructionError::InputNotFound { .. })
+    ));
+    assert_eq!(graph.build().digraph().node_count(), 1);
+}
@@ -0,0 +1,58 @@
+use raug::prelude::*;
+
+const BLOCK_SIZE: usize = 64;
+
+// a graph that outputs the given parameter
+fn param_graph(param: &Param) -> Graph {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+    graph
+        .add_param(param.clone())
+        .output(0)
+        .connect(&out.input(0));
+    graph.build()
+}
+
+fn render(runtime: &mut Runtime, blocks: usize) -> Vec<Float> {
+    let mut output = Vec::new();
+    for _ in 0..blocks {
+        runtime.process().unwrap();
+        let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+        output.extend(buffer.iter().map(|sample| sample.unwrap()));
+    }
+    output
+}
+
+#[test]
+fn clones_each_follow_the_automation() {
+    let param = Param::new::<Float>("gain", Some(0.0));
+    let graph = param_graph(&param);
+
+    let mut first = Runtime::new(graph.clone());
+    first.allocate_for_block_size(48_000.0, BLOCK_SIZE);
+    let mut second = Runtime::new(graph);
+    second.allocate_for_block_size(48_000.0, BLOCK_SIZE);
+
+    param.linear_ramp_to(2 * BLOCK_SIZE as u64, 1.0);
+
+    let output = render(&mut first, 4);
+    assert_eq!(render(&mut second, 4), output);
+    assert_eq!(output[BLOCK_SIZE], 0.5);
+    assert_eq!(output[3 * BLOCK_SIZE], 1.0);
+    // the clones process side by side, so the parameter's time doesn't run ahead
+    assert_eq!(param.time(), 4 * BLOCK_SIZE as u64);
+}
+
+#[test]
+fn events_past_the_limit_are_dropped() {
+    let param = Param::new::<Float>("gain", Some(0.0));
+    let mut runtime = Runtime::new(param_graph(&param));
+    runtime.allocate_for_block_size(48_000.0, BLOCK_SIZE);
+
+    for i in 0..100 {
+        param.set_at(i + 1, i as Float);
+    }
+
+    let output = render(&mut runtime, 2);
+    assert_eq!(output.last(), Some(&63.0));
+}
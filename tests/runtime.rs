@@ -0,0 +1,95 @@
+use raug::prelude::*;
+
+const SAMPLE_RATE: Float = 48_000.0;
+const BLOCK_SIZE: usize = 64;
+
+// a sine through a tempo-synced delay with a feedback parameter, so any processor state lost between parts of a block shows up in the output
+fn delayed_sine() -> Graph {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency").connect(440.0);
+
+    let delay = graph.add(SyncDelay::new(1.0 / 64.0, NoteFeel::Straight, 1.0));
+    delay.input("in").connect(sine);
+    delay
+        .input("feedback")
+        .connect(graph.add_param(Param::new::<Float>("feedback", Some(0.5))));
+    delay.output(0).connect(&out.input(0));
+
+    graph.build()
+}
+
+fn render(runtime: &mut Runtime, blocks: usize) -> Vec<Float> {
+    let mut output = Vec::new();
+    for _ in 0..blocks {
+        runtime.process().unwrap();
+        let buffer = runtime.get_output(0).unwrap().as_type::<Float>().unwrap();
+        output.extend(buffer.iter().map(|sample| sample.unwrap()));
+    }
+    output
+}
+
+#[test]
+fn split_block_matches_unsplit_block() {
+    let mut unsplit = Runtime::new(delayed_sine());
+    unsplit.allocate_for_block_size(SAMPLE_RATE, BLOCK_SIZE);
+    unsplit.transport().play();
+
+    let mut split = Runtime::new(delayed_sine());
+    split.allocate_for_block_size(SAMPLE_RATE, BLOCK_SIZE);
+    split.transport().play();
+
+    // a tempo change on a block boundary starts the delay crossfading without splitting the block
+    let tempo_change = 40 * BLOCK_SIZE as u64;
+    unsplit
+        .send_at(Command::SetTempo(90.0), tempo_change)
+        .unwrap();
+    split
+        .send_at(Command::SetTempo(90.0), tempo_change)
+        .unwrap();
+
+    // setting the parameter to the value it already has splits blocks during the crossfade without changing what they sound like
+    for sample in [10, 2600, 2601, 3000, 4000] {
+        split
+            .send_at(
+                Command::SetParam("feedback".into(), AnySignal::Float(Some(0.5))),
+                sample,
+            )
+            .unwrap();
+    }
+
+    let blocks = 100;
+    assert_eq!(render(&mut split, blocks), render(&mut unsplit, blocks));
+}
+
+#[test]
+fn block_size_change_keeps_processor_state() {
+    let mut fixed = Runtime::new(delayed_sine());
+    fixed.allocate_for_block_size(SAMPLE_RATE, BLOCK_SIZE);
+    fixed.transport().play();
+
+    let mut changing = Runtime::new(delayed_sine());
+    changing.allocate_for_block_size(SAMPLE_RATE, BLOCK_SIZE);
+    changing.transport().play();
+
+    // change the tempo once the delay line is full, so the delay crossfades to the new delay time
+    let tempo_change = 40 * BLOCK_SIZE as u64;
+    fixed
+        .send_at(Command::SetTempo(90.0), tempo_change)
+        .unwrap();
+    changing
+        .send_at(Command::SetTempo(90.0), tempo_change)
+        .unwrap();
+
+    // change the block size in the middle of the crossfade
+    let mut expected = render(&mut fixed, 42);
+    expected.extend(render(&mut fixed, 4));
+
+    let mut output = render(&mut changing, 42);
+    changing.set_block_size(BLOCK_SIZE / 2).unwrap();
+    output.extend(render(&mut changing, 8));
+
+    assert_eq!(output, expected);
+}
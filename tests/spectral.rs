@@ -0,0 +1,25 @@
+#![cfg(feature = "fft")]
+
+use raug::{bench, prelude::*};
+
+#[test]
+fn stft_processes_without_allocating() {
+    let graph = GraphBuilder::new();
+    let out = graph.add_audio_output();
+    let sine = graph.add(SineOscillator::default());
+    sine.input("frequency").connect(440.0);
+
+    // a hop that doesn't divide the block size, so frames land on different samples of each block
+    let analyze = graph.add(FftAnalyze::new(256, 96, WindowFunction::Hann));
+    let synthesize = graph.add(IfftSynthesize::new(256, 96, WindowFunction::Hann));
+    analyze.input(0).connect(sine);
+    synthesize.input(0).connect(analyze);
+    synthesize.output(0).connect(&out.input(0));
+
+    let report = bench::measure(&graph.build(), 16);
+
+    #[cfg(feature = "profiling")]
+    assert_eq!(report.allocations, Some(0));
+    #[cfg(not(feature = "profiling"))]
+    assert_eq!(report.allocations, None);
+}